@@ -1,4 +1,4 @@
-use tauri::{command, State};
+use tauri::{command, AppHandle, State};
 use log::*;
 use crate::{context::AppContext, models::*, notifier::SetupEndedNotifier, services::*};
 
@@ -20,24 +20,43 @@ pub async fn load(
 
 #[command]
 pub async fn get_dashboard_stats(
-    installed_programs_service: State<'_, InstalledProgramsService>,
+    app_handle: AppHandle,
     process_manager: State<'_, ProcessManager>,
     memory_service: State<'_, MemoryService>,
-    disk_service: State<'_, DiskService>) -> Result<DashboardStats> {
+    disk_service: State<'_, DiskService>,
+    disk_io_service: State<'_, DiskIoService>,
+    net_interface_service: State<'_, NetInterfaceService>) -> Result<DashboardStats> {
 
-    let programs_count = installed_programs_service.get_count()?;
+    let programs_count = programs_count(&app_handle)?;
     let active_processes = process_manager.get_count()?;
     let memory = memory_service.get_stats()?;
     let disks = disk_service.get_disks()?;
+    let disk_io = disk_io_service.get_disk_io()?;
+    let network = net_interface_service.get_throughput()?;
 
     let stats = DashboardStats {
         programs_count,
         active_processes,
         memory,
-        disks
+        disks,
+        disk_io,
+        network
     };
 
     info!("{:?}", stats);
 
     Ok(stats)
-}
\ No newline at end of file
+}
+
+#[cfg(windows)]
+fn programs_count(app_handle: &AppHandle) -> Result<u32> {
+    use tauri::Manager;
+
+    Ok(app_handle.state::<InstalledProgramsService>().get_count()?)
+}
+
+/// Installed programs are read from the Windows registry only
+#[cfg(not(windows))]
+fn programs_count(_app_handle: &AppHandle) -> Result<u32> {
+    Ok(0)
+}
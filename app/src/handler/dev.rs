@@ -1,5 +1,4 @@
 use tauri::{command, State};
-use log::*;
 use crate::services::SaveScreenshotService;
 
 use super::error::*;
@@ -0,0 +1,202 @@
+use std::time::Duration;
+
+use anyhow::anyhow;
+use tauri::{async_runtime::spawn_blocking, command, State};
+use uuid::Uuid;
+use crate::{models::*, services::*};
+
+use super::error::*;
+
+#[command]
+pub fn get_disk_io(disk_io_service: State<DiskIoService>) -> Result<Vec<DiskIoStats>> {
+
+    let disks = disk_io_service.get_disk_io()?;
+
+    Ok(disks)
+}
+
+#[command]
+pub fn get_process_io(disk_io_service: State<DiskIoService>, limit: Option<u32>) -> Result<Vec<ProcessIoStats>> {
+
+    let processes = disk_io_service.get_process_io(limit.unwrap_or(20) as usize)?;
+
+    Ok(processes)
+}
+
+#[command]
+pub fn get_disk_forecasts(disk_forecast_service: State<DiskForecastService>) -> Result<Vec<DiskForecast>> {
+
+    let forecasts = disk_forecast_service.get_forecasts()?;
+
+    Ok(forecasts)
+}
+
+#[command]
+pub fn set_disk_forecast_notifications(disk_forecast_service: State<DiskForecastService>, enabled: bool, horizon_days: Option<u64>) -> Result<()> {
+
+    let horizon = horizon_days
+        .map(|days| days.checked_mul(24 * 60 * 60).map(Duration::from_secs).ok_or_else(|| anyhow!("horizon of {} days is too long", days)))
+        .transpose()?;
+
+    disk_forecast_service.set_notifications(enabled, horizon);
+
+    Ok(())
+}
+
+#[command]
+pub fn start_disk_usage_scan(disk_usage_service: State<DiskUsageService>, root: String) -> Result<Uuid> {
+
+    let scan_id = disk_usage_service.start_scan(&root)?;
+
+    Ok(scan_id)
+}
+
+#[command]
+pub fn cancel_disk_usage_scan(disk_usage_service: State<DiskUsageService>, scan_id: Uuid) -> Result<()> {
+
+    disk_usage_service.cancel_scan(scan_id)?;
+
+    Ok(())
+}
+
+#[command]
+pub fn get_disk_usage_report(disk_usage_service: State<DiskUsageService>, scan_id: Uuid) -> Result<DiskUsageReport> {
+
+    let report = disk_usage_service.get_report(scan_id)?;
+
+    Ok(report)
+}
+
+#[command]
+pub fn get_disk_usage_children(disk_usage_service: State<DiskUsageService>, scan_id: Uuid, path: String) -> Result<Vec<UsageEntry>> {
+
+    let children = disk_usage_service.get_children(scan_id, &path)?;
+
+    Ok(children)
+}
+
+#[command]
+pub fn get_disk_usage_scans(disk_usage_service: State<DiskUsageService>) -> Result<Vec<SavedScanInfo>> {
+
+    let scans = disk_usage_service.get_saved_scans()?;
+
+    Ok(scans)
+}
+
+#[command]
+pub fn diff_disk_usage_scans(disk_usage_service: State<DiskUsageService>, previous_scan_id: Uuid, current_scan_id: Uuid, limit: Option<u32>) -> Result<DiskUsageDiff> {
+
+    let diff = disk_usage_service.diff(previous_scan_id, current_scan_id, limit.unwrap_or(100) as usize)?;
+
+    Ok(diff)
+}
+
+#[command]
+pub fn delete_disk_usage_scan(disk_usage_service: State<DiskUsageService>, scan_id: Uuid) -> Result<()> {
+
+    disk_usage_service.delete_scan(scan_id)?;
+
+    Ok(())
+}
+
+#[command]
+pub async fn find_duplicate_files(duplicate_service: State<'_, DuplicateService>, args: FindDuplicatesArgs) -> Result<DuplicateReport> {
+
+    let service = duplicate_service.inner().clone();
+    let report = spawn_blocking(move || service.find(&args)).await??;
+
+    Ok(report)
+}
+
+#[command]
+pub async fn resolve_duplicate_files(duplicate_service: State<'_, DuplicateService>, args: ResolveDuplicatesArgs) -> Result<ResolveDuplicatesResult> {
+
+    let service = duplicate_service.inner().clone();
+    let result = spawn_blocking(move || service.resolve(&args)).await?;
+
+    Ok(result)
+}
+
+#[command]
+pub async fn get_partition_table(partition_table_service: State<'_, PartitionTableService>, path: String) -> Result<PartitionTable> {
+
+    let service = partition_table_service.inner().clone();
+    let table = spawn_blocking(move || service.read(&path)).await??;
+
+    Ok(table)
+}
+
+#[command]
+pub async fn start_benchmark(benchmark_service: State<'_, BenchmarkService>, args: BenchmarkArgs) -> Result<Uuid> {
+
+    let service = benchmark_service.inner().clone();
+    let run_id = spawn_blocking(move || service.start(args)).await??;
+
+    Ok(run_id)
+}
+
+#[command]
+pub fn cancel_benchmark(benchmark_service: State<BenchmarkService>, run_id: Uuid) -> Result<()> {
+
+    benchmark_service.cancel(run_id)?;
+
+    Ok(())
+}
+
+#[command]
+pub fn get_benchmark_run(benchmark_service: State<BenchmarkService>, run_id: Uuid) -> Result<BenchmarkRun> {
+
+    let run = benchmark_service.get_run(run_id)?;
+
+    Ok(run)
+}
+
+#[command]
+pub fn get_benchmark_runs(benchmark_service: State<BenchmarkService>) -> Result<Vec<BenchmarkRun>> {
+
+    let runs = benchmark_service.get_runs()?;
+
+    Ok(runs)
+}
+
+#[command]
+pub fn compare_benchmark_runs(benchmark_service: State<BenchmarkService>, previous_run_id: Uuid, current_run_id: Uuid) -> Result<BenchmarkComparison> {
+
+    let comparison = benchmark_service.compare(previous_run_id, current_run_id)?;
+
+    Ok(comparison)
+}
+
+#[command]
+pub fn delete_benchmark_run(benchmark_service: State<BenchmarkService>, run_id: Uuid) -> Result<()> {
+
+    benchmark_service.delete_run(run_id)?;
+
+    Ok(())
+}
+
+#[command]
+pub fn get_cleanup_categories(cleanup_service: State<CleanupService>) -> Result<Vec<CleanupCategory>> {
+
+    let categories = cleanup_service.get_categories();
+
+    Ok(categories)
+}
+
+#[command]
+pub async fn preview_cleanup(cleanup_service: State<'_, CleanupService>, categories: Vec<String>, limit: Option<u32>) -> Result<CleanupPreview> {
+
+    let service = cleanup_service.inner().clone();
+    let preview = spawn_blocking(move || service.preview(&categories, limit.unwrap_or(100) as usize)).await??;
+
+    Ok(preview)
+}
+
+#[command]
+pub async fn run_cleanup(cleanup_service: State<'_, CleanupService>, categories: Vec<String>) -> Result<CleanupResult> {
+
+    let service = cleanup_service.inner().clone();
+    let result = spawn_blocking(move || service.run(&categories)).await??;
+
+    Ok(result)
+}
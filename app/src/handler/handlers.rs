@@ -9,13 +9,65 @@ pub fn generate_handlers() -> Box<dyn Fn(Invoke) -> bool + Send + Sync> {
         app::load,
         app::get_dashboard_stats,
         handles::get_system_handles,
+        handles::find_open_handles,
+        handles::get_dangerous_handles,
+        handles::get_handle_counts,
+        handles::get_handle_top_growers,
+        handles::get_handle_access_denied,
         memory::get_memory_info,
+        memory::get_process_memory,
+        memory::get_process_memory_summary,
+        memory::get_memory_growth,
+        memory::set_memory_leak_notifications,
+        disk::get_disk_io,
+        disk::get_process_io,
+        disk::get_disk_forecasts,
+        disk::set_disk_forecast_notifications,
+        disk::start_disk_usage_scan,
+        disk::cancel_disk_usage_scan,
+        disk::get_disk_usage_report,
+        disk::get_disk_usage_children,
+        disk::get_disk_usage_scans,
+        disk::diff_disk_usage_scans,
+        disk::delete_disk_usage_scan,
+        disk::find_duplicate_files,
+        disk::resolve_duplicate_files,
+        disk::get_partition_table,
+        disk::start_benchmark,
+        disk::cancel_benchmark,
+        disk::get_benchmark_run,
+        disk::get_benchmark_runs,
+        disk::compare_benchmark_runs,
+        disk::delete_benchmark_run,
+        disk::get_cleanup_categories,
+        disk::preview_cleanup,
+        disk::run_cleanup,
+        integrity::get_integrity_status,
+        integrity::get_default_integrity_paths,
+        integrity::create_integrity_baseline,
+        integrity::delete_integrity_baseline,
+        integrity::verify_integrity,
+        integrity::get_integrity_changes,
+        integrity::clear_integrity_changes,
+        #[cfg(windows)]
         driver::get_installed_drivers,
+        #[cfg(windows)]
         driver::get_loaded_drivers,
         process::get_processes,
         process::get_process,
+        process::kill_process,
+        #[cfg(windows)]
         program::get_programs,
+        #[cfg(windows)]
         program::get_programs_count,
         network::get_net_table,
+        network::get_net_table_aggregate,
+        network::get_network_interfaces,
+        network::get_geoip_databases,
+        network::set_geoip_databases,
+        network::get_routes,
+        network::get_neighbours,
+        network::lookup_route,
+        ipc::get_ipc_endpoints,
     ])
 }
\ No newline at end of file
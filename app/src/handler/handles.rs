@@ -1,13 +1,58 @@
 use tauri::{command, State};
-use log::*;
-use crate::{models::HandleInfo, services::*};
+use std::time::Duration;
+
+use crate::{models::{DangerousHandle, GetSystemHandlesArgs, HandleAccessDenied, HandleGrowth, HandleInfo, OpenFileProcess, Paged, ProcessHandleCounts}, services::*};
 
 use super::error::*;
 
+/// the sampler keeps two hours, longer windows are clamped before they can overflow
+const MAX_WINDOW_MINUTES: u64 = 2 * 60;
+
+#[command]
+pub fn get_system_handles(handle_manager: State<HandleManager>, args: GetSystemHandlesArgs) -> Result<Paged<HandleInfo>> {
+
+    let handles = handle_manager.get_handles(&args)?;
+
+    Ok(handles)
+}
+
+#[command]
+pub fn find_open_handles(handle_manager: State<HandleManager>, query: String) -> Result<Vec<OpenFileProcess>> {
+
+    let processes = handle_manager.find_open_handles(&query)?;
+
+    Ok(processes)
+}
+
 #[command]
-pub fn get_system_handles(handle_manager: State<HandleManager>) -> Result<Vec<HandleInfo>> {
+pub fn get_dangerous_handles(handle_manager: State<HandleManager>) -> Result<Vec<DangerousHandle>> {
 
-    let handles = handle_manager.get_handles()?;
+    let handles = handle_manager.get_dangerous_handles()?;
 
     Ok(handles)
+}
+
+#[command]
+pub fn get_handle_counts(handle_manager: State<HandleManager>) -> Result<Vec<ProcessHandleCounts>> {
+
+    let counts = handle_manager.get_counts()?;
+
+    Ok(counts)
+}
+
+#[command]
+pub fn get_handle_top_growers(handle_manager: State<HandleManager>, window_minutes: Option<u64>, limit: Option<u32>) -> Result<Vec<HandleGrowth>> {
+
+    let window = Duration::from_mins(window_minutes.unwrap_or(30).min(MAX_WINDOW_MINUTES));
+    let growers = handle_manager.get_top_growers(window, limit.unwrap_or(20) as usize)?;
+
+    Ok(growers)
+}
+
+#[command]
+pub fn get_handle_access_denied(handle_manager: State<HandleManager>) -> Result<Vec<HandleAccessDenied>> {
+
+    let denied = handle_manager.get_access_denied()?;
+
+    Ok(denied)
 }
\ No newline at end of file
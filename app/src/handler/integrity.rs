@@ -0,0 +1,62 @@
+use tauri::{async_runtime::spawn_blocking, command, State};
+use crate::{models::*, services::*};
+
+use super::error::*;
+
+#[command]
+pub fn get_integrity_status(integrity_service: State<IntegrityService>) -> Result<IntegrityStatus> {
+
+    let status = integrity_service.get_status();
+
+    Ok(status)
+}
+
+#[command]
+pub fn get_default_integrity_paths(integrity_service: State<IntegrityService>) -> Result<Vec<String>> {
+
+    let paths = integrity_service.get_default_paths();
+
+    Ok(paths)
+}
+
+#[command]
+pub async fn create_integrity_baseline(integrity_service: State<'_, IntegrityService>, paths: Vec<String>) -> Result<IntegrityStatus> {
+
+    let service = integrity_service.inner().clone();
+    let status = spawn_blocking(move || service.create_baseline(&paths)).await??;
+
+    Ok(status)
+}
+
+#[command]
+pub fn delete_integrity_baseline(integrity_service: State<IntegrityService>) -> Result<()> {
+
+    integrity_service.delete_baseline()?;
+
+    Ok(())
+}
+
+#[command]
+pub async fn verify_integrity(integrity_service: State<'_, IntegrityService>) -> Result<IntegrityVerification> {
+
+    let service = integrity_service.inner().clone();
+    let verification = spawn_blocking(move || service.verify()).await??;
+
+    Ok(verification)
+}
+
+#[command]
+pub fn get_integrity_changes(integrity_service: State<IntegrityService>, limit: Option<u32>) -> Result<Vec<IntegrityChange>> {
+
+    let changes = integrity_service.get_changes(limit.unwrap_or(100) as usize);
+
+    Ok(changes)
+}
+
+#[command]
+pub fn clear_integrity_changes(integrity_service: State<IntegrityService>) -> Result<()> {
+
+    integrity_service.clear_changes()?;
+
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use tauri::{command, State};
+
+use crate::{models::{GetIpcEndpointsArgs, IpcEndpoint, PageArgs, Paged}, services::*};
+
+use super::error::*;
+
+#[command]
+pub fn get_ipc_endpoints(ipc_service: State<IpcService>, args: GetIpcEndpointsArgs) -> Result<Paged<IpcEndpoint>> {
+
+    let endpoints = ipc_service.query(&args.filter)?;
+
+    let PageArgs { page, page_size } = args.page;
+    let total = endpoints.len() as u32;
+    let start = (page * page_size) as usize;
+    let end = (start + page_size as usize).min(endpoints.len());
+    let items = if start < endpoints.len() { endpoints[start..end].to_vec() } else { Vec::new() };
+
+    Ok(Paged {
+        items,
+        page,
+        page_size,
+        total
+    })
+}
@@ -1,13 +1,66 @@
+use std::time::Duration;
+
 use tauri::{command, State};
-use log::*;
 use crate::{models::*, services::*};
 
 use super::error::*;
 
+/// the leak sampler keeps two hours, longer windows are clamped before they can overflow
+const MAX_WINDOW_MINUTES: u64 = 2 * 60;
+
 #[command]
 pub fn get_memory_info(memory_service: State<MemoryService>) -> Result<MemoryStats> {
 
     let stats = memory_service.get_stats()?;
 
     Ok(stats)
-}
\ No newline at end of file
+}
+
+#[command]
+pub fn get_process_memory(process_memory_service: State<ProcessMemoryService>, args: GetProcessMemoryArgs) -> Result<Paged<ProcessMemory>> {
+
+    let items = process_memory_service.query(args.name.as_deref(), args.sort.as_ref())?;
+
+    let PageArgs { page, page_size } = args.page;
+    let total = items.len() as u32;
+    let start = (page * page_size) as usize;
+    let end = (start + page_size as usize).min(items.len());
+    let items = if start < items.len() { items[start..end].to_vec() } else { Vec::new() };
+
+    Ok(Paged {
+        items,
+        page,
+        page_size,
+        total
+    })
+}
+
+#[command]
+pub fn get_process_memory_summary(
+    memory_service: State<MemoryService>,
+    process_memory_service: State<ProcessMemoryService>,
+    top: Option<u32>,
+) -> Result<ProcessMemorySummary> {
+
+    let stats = memory_service.get_stats()?;
+    let summary = process_memory_service.summary(&stats, top.unwrap_or(10) as usize)?;
+
+    Ok(summary)
+}
+
+#[command]
+pub fn get_memory_growth(memory_leak_service: State<MemoryLeakService>, window_minutes: Option<u64>, limit: Option<u32>) -> Result<Vec<MemoryGrowth>> {
+
+    let window = Duration::from_mins(window_minutes.unwrap_or(30).min(MAX_WINDOW_MINUTES));
+    let growth = memory_leak_service.get_growth(window, limit.unwrap_or(20) as usize)?;
+
+    Ok(growth)
+}
+
+#[command]
+pub fn set_memory_leak_notifications(memory_leak_service: State<MemoryLeakService>, enabled: bool) -> Result<()> {
+
+    memory_leak_service.set_notifications(enabled);
+
+    Ok(())
+}
@@ -1,12 +1,17 @@
 mod handlers;
 mod app;
 mod process;
+#[cfg(windows)]
 mod program;
+#[cfg(windows)]
 mod driver;
 mod memory;
+mod disk;
+mod integrity;
 mod dev;
 mod handles;
 mod network;
+mod ipc;
 mod error;
 
 pub use handlers::*;
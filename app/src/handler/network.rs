@@ -1,49 +1,16 @@
 use tauri::{command, State};
-use log::*;
-use crate::{models::{GetNetTableArgs, NetTableEntry, PageArgs, Paged, ProtocolInfo, TransportProtocol}, services::*};
+use std::{net::IpAddr, path::PathBuf};
+
+use crate::{models::{GeoIpDatabase, GetNetTableAggregateArgs, GetNetTableArgs, NetTableAggregate, Neighbour, NetTableEntry, NetworkInterface, PageArgs, Paged, Route, RouteLookup}, services::*};
 
 use super::error::*;
 
 #[command]
 pub fn get_net_table(net_table_service: State<NetTableService>, args: GetNetTableArgs) -> Result<Paged<NetTableEntry>> {
 
-    let GetNetTableArgs {
-        page: PageArgs { page, page_size },
-        protocols,
-        local_ip_addr,
-        local_port,
-        process_name,
-        remote_ip_addr,
-        remote_port
-    } = args;
-
-    let mut entries = Vec::new();
-
-    if protocols.contains(&TransportProtocol::Tcp) {
-        entries.extend(net_table_service.get_tcp_table()?);
-    }
-
-    if protocols.contains(&TransportProtocol::Udp) {
-        entries.extend(net_table_service.get_udp_table()?);
-    }
-
-    let entries: Vec<NetTableEntry> = entries.into_iter()
-        .filter(|e| protocols.contains(match &e.protocol {
-            ProtocolInfo::Tcp { .. } => &TransportProtocol::Tcp,
-            ProtocolInfo::Udp => &TransportProtocol::Udp,
-        }))
-        .filter(|e| local_ip_addr.map_or(true, |ip| e.local_ip_address == ip))
-        .filter(|e| local_port.map_or(true, |p| e.local_port == p))
-        .filter(|e| process_name.as_ref().map_or(true, |name| e.process_name.contains(name)))
-        .filter(|e| match &e.protocol {
-            ProtocolInfo::Tcp { remote_ip_address, remote_port, .. } => {
-                remote_ip_addr.map_or(true, |ip| remote_ip_address.map_or(false, |r| r == ip)) &&
-                remote_port.map_or(true, |p| remote_port.map_or(false, |r| r == p))
-            },
-            ProtocolInfo::Udp => remote_ip_addr.is_none() && remote_port.is_none(),
-        })
-        .collect();
+    let entries = net_table_service.query(&args)?;
 
+    let PageArgs { page, page_size } = args.page;
     let total = entries.len() as u32;
     let start = (page * page_size) as usize;
     let end = (start + page_size as usize).min(entries.len());
@@ -55,4 +22,60 @@ pub fn get_net_table(net_table_service: State<NetTableService>, args: GetNetTabl
         page_size,
         total
     })
-}
\ No newline at end of file
+}
+
+#[command]
+pub fn get_net_table_aggregate(net_table_service: State<NetTableService>, args: GetNetTableAggregateArgs) -> Result<NetTableAggregate> {
+
+    let aggregate = net_table_service.aggregate(&args)?;
+
+    Ok(aggregate)
+}
+
+#[command]
+pub fn get_network_interfaces(net_interface_service: State<NetInterfaceService>) -> Result<Vec<NetworkInterface>> {
+
+    let interfaces = net_interface_service.get_interfaces()?;
+
+    Ok(interfaces)
+}
+
+#[command]
+pub fn get_geoip_databases(geoip_service: State<GeoIpService>) -> Result<Vec<GeoIpDatabase>> {
+
+    let databases = geoip_service.get_databases();
+
+    Ok(databases)
+}
+
+#[command]
+pub fn set_geoip_databases(geoip_service: State<GeoIpService>, paths: Vec<PathBuf>) -> Result<Vec<GeoIpDatabase>> {
+
+    let databases = geoip_service.set_paths(paths);
+
+    Ok(databases)
+}
+
+#[command]
+pub fn get_routes(route_service: State<RouteService>) -> Result<Vec<Route>> {
+
+    let routes = route_service.get_routes()?;
+
+    Ok(routes)
+}
+
+#[command]
+pub fn get_neighbours(route_service: State<RouteService>) -> Result<Vec<Neighbour>> {
+
+    let neighbours = route_service.get_neighbours()?;
+
+    Ok(neighbours)
+}
+
+#[command]
+pub fn lookup_route(route_service: State<RouteService>, destination: IpAddr) -> Result<RouteLookup> {
+
+    let lookup = route_service.lookup(destination)?;
+
+    Ok(lookup)
+}
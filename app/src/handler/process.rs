@@ -40,9 +40,7 @@ pub fn get_processes(
         ProcessResult::Hierarchy(mut nodes) => {
             // filter top-level nodes by name
             if let Some(ref name) = args.name {
-                nodes = nodes.into_iter()
-                    .filter(|n| n.process.name.to_lowercase().contains(&name.to_lowercase()))
-                    .collect();
+                nodes.retain(|n| n.process.name.to_lowercase().contains(&name.to_lowercase()));
             }
 
             let total = nodes.len() as u32;
@@ -69,9 +67,9 @@ pub fn get_process(process_manager: State<ProcessManager>, id: u32) -> Result<Op
 }
 
 #[command]
-pub fn kill_process(process_manager: State<ProcessManager>, id: u32) -> Result<Option<Process>> {
+pub fn kill_process(process_manager: State<ProcessManager>, id: u32) -> Result<()> {
   
-    let process = process_manager.get_by_id(id)?;
+    process_manager.kill_process(id)?;
 
-    Ok(process)
+    Ok(())
 }
\ No newline at end of file
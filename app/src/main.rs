@@ -1,7 +1,5 @@
 #![allow(unsafe_op_in_unsafe_fn)]
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
-#![feature(duration_constructors_lite)]
-#![feature(mapped_lock_guards)]
 
 mod setup;
 mod handler;
@@ -17,32 +15,45 @@ use anyhow::Result;
 use log::LevelFilter;
 use crate::context::AppContext;
 use crate::services::*;
-use crate::{handler::generate_handlers, notifier::*, panic::set_hook};
+use crate::{handler::generate_handlers, panic::set_hook};
 use crate::notifier::SetupEndedNotifier;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     set_hook();
     let tauri_context: tauri::Context<_> = tauri::generate_context!();
-    let package_info = tauri_context.package_info();
 
     tauri::async_runtime::set(tokio::runtime::Handle::current());
        
     let app_path = std::env::current_exe()?;
     let current_dir = app_path.parent().unwrap().to_owned();
     let process_manager = ProcessManager::new();
+    let geoip_service = GeoIpService::new(&current_dir.join("geoip"));
 
-    tauri::Builder::default()
+    let builder = tauri::Builder::default();
+
+    #[cfg(windows)]
+    let builder = builder
+        .manage(InstalledProgramsService::new())
+        .manage(LoadedDriverService::new())
+        .manage(InstalledDriverService::new());
+
+    builder
         .manage(AppContext::new())
         .manage(SetupEndedNotifier::new())
         .manage(process_manager.clone())
-        .manage(InstalledProgramsService::new())
         .manage(MemoryService::new())
-        .manage(DiskService::new())
-        .manage(LoadedDriverService::new())
-        .manage(InstalledDriverService::new())
+        .manage(ProcessMemoryService::new())
+        .manage(DiskService::new(&current_dir))
+        .manage(DiskIoService::new())
+        .manage(DuplicateService::new())
+        .manage(PartitionTableService::new())
         .manage(HandleManager::new(process_manager.clone()))
-        .manage(NetTableService::new(process_manager))
+        .manage(NetTableService::new(process_manager, ReverseDnsResolver::new(ResolverConfig::default()), geoip_service.clone()))
+        .manage(geoip_service)
+        .manage(NetInterfaceService::new())
+        .manage(RouteService::new())
+        .manage(IpcService::new())
         .plugin(tauri_plugin_log::Builder::new()
             .level_for("tauri_plugin_updater", LevelFilter::Error)
             .level_for("updater", LevelFilter::Error)
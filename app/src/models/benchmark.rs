@@ -0,0 +1,144 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BenchmarkTest {
+    /// 1 MiB blocks
+    SequentialRead,
+    SequentialWrite,
+    /// 4 KiB blocks at random offsets
+    RandomRead,
+    RandomWrite,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BenchmarkArgs {
+    /// `DiskPartition::name` of the volume to test
+    pub partition: String,
+    /// folder on the partition for the test file, the partition root otherwise
+    pub directory: Option<String>,
+    /// size of the temporary test file
+    pub file_size: u64,
+    /// requests kept in flight, each by its own thread
+    pub queue_depth: u32,
+    /// seconds each test runs for
+    pub duration_secs: u64,
+    pub tests: Vec<BenchmarkTest>,
+}
+
+impl Default for BenchmarkArgs {
+    fn default() -> Self {
+        Self {
+            partition: String::new(),
+            directory: None,
+            file_size: 1024 * 1024 * 1024,
+            queue_depth: 4,
+            duration_secs: 5,
+            tests: vec![
+                BenchmarkTest::SequentialRead,
+                BenchmarkTest::SequentialWrite,
+                BenchmarkTest::RandomRead,
+                BenchmarkTest::RandomWrite,
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BenchmarkState {
+    /// writing the test file
+    Preparing,
+    Running,
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+/// Emitted as `on-benchmark-progress` while a run is going and once when it ends
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkProgress {
+    pub run_id: Uuid,
+    pub partition: String,
+    pub state: BenchmarkState,
+    pub test: Option<BenchmarkTest>,
+    /// 0 to 1 over the whole run
+    pub progress: f64,
+    /// throughput of the current test or preparation so far
+    pub bytes_per_sec: u64,
+    pub bytes_per_sec_formatted: String,
+    pub error: Option<String>,
+}
+
+/// Request latencies in microseconds
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyStats {
+    pub min: u64,
+    pub mean: f64,
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+    pub p999: u64,
+    pub max: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkResult {
+    pub test: BenchmarkTest,
+    pub block_size: u64,
+    pub queue_depth: u32,
+    pub operations: u64,
+    pub bytes: u64,
+    pub duration_ms: u64,
+    pub bytes_per_sec: u64,
+    pub bytes_per_sec_formatted: String,
+    pub iops: f64,
+    pub latency: LatencyStats,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkRun {
+    pub run_id: Uuid,
+    pub partition: String,
+    pub state: BenchmarkState,
+    pub started_on: DateTime<Utc>,
+    pub finished_on: DateTime<Utc>,
+    pub file_size: u64,
+    pub file_size_formatted: String,
+    pub queue_depth: u32,
+    pub duration_secs: u64,
+    /// the OS cache was bypassed, reads otherwise may have been served from memory
+    pub cache_bypassed: bool,
+    pub results: Vec<BenchmarkResult>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkChange {
+    pub test: BenchmarkTest,
+    pub previous_bytes_per_sec: u64,
+    pub current_bytes_per_sec: u64,
+    /// relative throughput change in percent
+    pub throughput_change: f64,
+    pub previous_iops: f64,
+    pub current_iops: f64,
+    pub previous_p99: u64,
+    pub current_p99: u64,
+}
+
+/// Tests present in both runs
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkComparison {
+    pub previous_run_id: Uuid,
+    pub current_run_id: Uuid,
+    pub changes: Vec<BenchmarkChange>,
+}
@@ -0,0 +1,87 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A group of cleanup locations, read from `categories.json` or a `cleanup.json` next to the executable
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupCategory {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    /// `windows`, `linux` or `macos`, every platform when empty
+    #[serde(default)]
+    pub platforms: Vec<String>,
+    /// paths starting with a placeholder such as `{temp}`, `{cache}` or `{exec_dir}`,
+    /// `*` and `?` match within a name and `**` any number of folders
+    pub patterns: Vec<String>,
+    /// file names never removed even when a pattern matches them, same wildcards
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// files modified more recently are kept
+    #[serde(default)]
+    pub min_age_days: u64,
+    /// ticked when the tool opens
+    #[serde(default)]
+    pub selected: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupEntry {
+    pub path: String,
+    pub size: u64,
+    pub size_formatted: String,
+    pub modified_on: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupCategoryPreview {
+    pub id: String,
+    pub name: String,
+    /// files that would be removed, in use ones excluded
+    pub files: u64,
+    pub bytes: u64,
+    pub bytes_formatted: String,
+    /// largest first, up to the requested limit
+    pub entries: Vec<CleanupEntry>,
+    /// held open by a process, these are skipped
+    pub in_use: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupPreview {
+    pub categories: Vec<CleanupCategoryPreview>,
+    pub total_bytes: u64,
+    pub total_bytes_formatted: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupFailure {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupCategoryResult {
+    pub id: String,
+    pub name: String,
+    pub deleted: u64,
+    pub bytes_freed: u64,
+    pub bytes_freed_formatted: String,
+    /// held open by a process and left alone
+    pub in_use: Vec<String>,
+    pub failed: Vec<CleanupFailure>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupResult {
+    pub categories: Vec<CleanupCategoryResult>,
+    pub deleted: u64,
+    pub bytes_freed: u64,
+    pub bytes_freed_formatted: String,
+}
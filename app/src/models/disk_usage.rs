@@ -0,0 +1,100 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ScanState {
+    Running,
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+/// Emitted as `on-disk-usage-progress` while a scan runs and once when it ends
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanProgress {
+    pub scan_id: Uuid,
+    pub root: String,
+    pub state: ScanState,
+    pub files: u64,
+    pub directories: u64,
+    pub bytes: u64,
+    pub bytes_formatted: String,
+    /// directory a worker picked up last
+    pub current_path: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageEntry {
+    pub path: String,
+    pub bytes: u64,
+    pub bytes_formatted: String,
+    /// files below the entry, 1 for a file
+    pub files: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionUsage {
+    /// lowercase without the dot, empty for files without one
+    pub extension: String,
+    pub bytes: u64,
+    pub bytes_formatted: String,
+    pub files: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskUsageReport {
+    pub scan_id: Uuid,
+    pub root: String,
+    pub state: ScanState,
+    pub started_on: DateTime<Utc>,
+    pub finished_on: DateTime<Utc>,
+    pub total_bytes: u64,
+    pub total_bytes_formatted: String,
+    pub total_files: u64,
+    pub total_directories: u64,
+    /// entries that could not be read
+    pub errors: u64,
+    pub top_directories: Vec<UsageEntry>,
+    pub top_files: Vec<UsageEntry>,
+    pub extensions: Vec<ExtensionUsage>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedScanInfo {
+    pub scan_id: Uuid,
+    pub root: String,
+    pub state: ScanState,
+    pub finished_on: DateTime<Utc>,
+    pub total_bytes: u64,
+    pub total_bytes_formatted: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageChange {
+    pub path: String,
+    /// `None` when the directory did not exist in that scan
+    pub before: Option<u64>,
+    pub after: Option<u64>,
+    pub delta: i64,
+    pub delta_formatted: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskUsageDiff {
+    pub previous_scan_id: Uuid,
+    pub current_scan_id: Uuid,
+    pub total_delta: i64,
+    pub total_delta_formatted: String,
+    /// directories ordered by how much they grew, shrinking ones last
+    pub changes: Vec<UsageChange>,
+}
@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FindDuplicatesArgs {
+    pub roots: Vec<String>,
+    /// smaller files are ignored, empty files always are
+    pub min_size: u64,
+}
+
+impl Default for FindDuplicatesArgs {
+    fn default() -> Self {
+        Self {
+            roots: vec![],
+            min_size: 1,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    /// SHA-256 of the content
+    pub hash: String,
+    pub size: u64,
+    pub size_formatted: String,
+    pub files: Vec<String>,
+    /// space held by every copy but one
+    pub wasted: u64,
+    pub wasted_formatted: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateReport {
+    /// largest waste first
+    pub groups: Vec<DuplicateGroup>,
+    pub files_scanned: u64,
+    pub files_hashed: u64,
+    pub total_wasted: u64,
+    pub total_wasted_formatted: String,
+    /// files or directories that could not be read
+    pub errors: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DuplicateAction {
+    Delete,
+    /// replace the duplicate with a hard link to the kept file, same volume only
+    HardLink,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateResolution {
+    pub keep: String,
+    pub remove: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveDuplicatesArgs {
+    pub action: DuplicateAction,
+    pub groups: Vec<DuplicateResolution>,
+    /// check every file and report what would happen without touching anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ActionOutcome {
+    Done,
+    WouldDo,
+    Skipped,
+    Failed,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateActionResult {
+    pub path: String,
+    pub keep: String,
+    pub outcome: ActionOutcome,
+    pub reason: Option<String>,
+    pub bytes_freed: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveDuplicatesResult {
+    pub action: DuplicateAction,
+    pub dry_run: bool,
+    pub results: Vec<DuplicateActionResult>,
+    pub bytes_freed: u64,
+    pub bytes_freed_formatted: String,
+}
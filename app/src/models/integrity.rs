@@ -0,0 +1,64 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A watched file as recorded in the baseline or seen afterwards
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityEntry {
+    pub path: String,
+    pub size: u64,
+    pub modified_on: Option<DateTime<Utc>>,
+    /// SHA-256 of the content, missing when the file could not be read
+    pub hash: Option<String>,
+    /// unix mode bits, the read-only, hidden and system attributes on Windows
+    pub permissions: u32,
+    /// SHA-256 of the owner and DACL on Windows or of the owning uid and gid on unix, missing when they could not be read
+    #[serde(default)]
+    pub security_hash: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum IntegrityChangeKind {
+    Added,
+    Modified,
+    Deleted,
+    /// same content, different permissions
+    PermissionsChanged,
+}
+
+/// Emitted as `on-integrity-change` when the watcher notices it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityChange {
+    pub path: String,
+    pub kind: IntegrityChangeKind,
+    pub detected_on: DateTime<Utc>,
+    pub before_hash: Option<String>,
+    pub after_hash: Option<String>,
+    /// missing for added files
+    pub before: Option<IntegrityEntry>,
+    /// missing for deleted files
+    pub after: Option<IntegrityEntry>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityStatus {
+    /// files and folders the baseline covers, empty without one
+    pub roots: Vec<String>,
+    pub files: u64,
+    pub created_on: Option<DateTime<Utc>>,
+    pub watching: bool,
+    /// changes recorded since the baseline was taken
+    pub changes: u64,
+}
+
+/// Every difference between the baseline and the files on disk right now
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityVerification {
+    pub verified_on: DateTime<Utc>,
+    pub files_checked: u64,
+    pub changes: Vec<IntegrityChange>,
+}
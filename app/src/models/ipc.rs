@@ -0,0 +1,69 @@
+use hashbrown::HashSet;
+use serde::{Deserialize, Serialize};
+
+use crate::models::PageArgs;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum IpcKind {
+    UnixSocket,
+    NamedPipe,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum IpcState {
+    Listening,
+    Connected,
+    Connecting,
+    Disconnecting,
+    Unconnected,
+    Unknown,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IpcEndpoint {
+    pub kind: IpcKind,
+    /// socket path (`@` prefixed when abstract) or pipe name
+    pub path: Option<String>,
+    /// stream, dgram or seqpacket
+    pub socket_type: Option<String>,
+    pub state: IpcState,
+    pub inode: Option<u64>,
+    pub process_id: Option<u32>,
+    pub process_name: Option<String>,
+    /// processes holding the other end of the connection
+    pub peers: Vec<IpcPeer>,
+    pub instances: Option<u32>,
+    pub max_instances: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IpcPeer {
+    pub inode: Option<u64>,
+    pub process_id: Option<u32>,
+    pub process_name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GetIpcEndpointsArgs {
+    #[serde(flatten)]
+    pub filter: IpcFilter,
+    #[serde(flatten)]
+    pub page: PageArgs
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct IpcFilter {
+    pub kinds: Option<HashSet<IpcKind>>,
+    pub path: Option<String>,
+    pub process_name: Option<String>,
+    pub process_ids: Option<HashSet<u32>>,
+    pub states: Option<HashSet<IpcState>>,
+    /// only endpoints with a known peer process
+    pub connected_only: bool,
+}
@@ -1,5 +1,19 @@
 mod services;
 mod network;
+mod ipc;
+mod disk_usage;
+mod benchmark;
+mod duplicates;
+mod partition_table;
+mod cleanup;
+mod integrity;
 
 pub use services::*;
-pub use network::*;
\ No newline at end of file
+pub use network::*;
+pub use ipc::*;
+pub use disk_usage::*;
+pub use benchmark::*;
+pub use duplicates::*;
+pub use partition_table::*;
+pub use cleanup::*;
+pub use integrity::*;
\ No newline at end of file
@@ -1,7 +1,8 @@
-use std::{net::Ipv4Addr, path::PathBuf};
+use std::{net::IpAddr, path::PathBuf};
 
+use hashbrown::HashMap;
+use ipnetwork::IpNetwork;
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
 
 #[derive(Debug, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -9,17 +10,24 @@ pub struct NetTableEntry {
     pub process_id: u32,
     pub process_name: String,
     pub local_port: u16,
-    pub local_ip_address: Ipv4Addr,
+    pub local_ip_address: IpAddr,
     pub protocol: ProtocolInfo
 }
 
 #[derive(Debug, Serialize, Clone)]
 #[serde(tag = "protocol", rename_all = "lowercase")]
+#[allow(clippy::large_enum_variant)]
 pub enum ProtocolInfo {
     Tcp {
         remote_port: Option<u16>,
-        remote_ip_address: Option<Ipv4Addr>,
+        remote_ip_address: Option<IpAddr>,
+        /// filled in from the reverse DNS cache when requested
+        remote_host_name: Option<String>,
+        /// filled in when a GeoIP database is loaded
+        remote_geo: Option<GeoInfo>,
         state: TcpState,
+        /// rate between the last two refreshes, when the platform exposes per connection byte counters
+        throughput: Option<NetworkThroughput>,
     },
     Udp,
 }
@@ -31,7 +39,7 @@ impl From<u32> for TcpState {
 }
 
 #[repr(u32)]
-#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 pub enum TcpState {
     Closed = 1,
@@ -47,3 +55,186 @@ pub enum TcpState {
     TimeWait = 11,
     DeleteTcb = 12,
 }
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkInterface {
+    pub name: String,
+    pub description: Option<String>,
+    pub mac_address: Option<String>,
+    pub mtu: Option<u32>,
+    pub link_state: LinkState,
+    /// link speed in Mbit/s
+    pub speed_mbps: Option<u64>,
+    pub is_loopback: bool,
+    pub addresses: Vec<IpNetwork>,
+    pub gateways: Vec<IpAddr>,
+    pub dns_servers: Vec<IpAddr>,
+    pub traffic: InterfaceTraffic,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum LinkState {
+    Up,
+    Down,
+    Testing,
+    Dormant,
+    NotPresent,
+    LowerLayerDown,
+    Unknown,
+}
+
+#[derive(Debug, Default, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InterfaceTraffic {
+    pub total: InterfaceCounters,
+    pub per_second: InterfaceCounters,
+}
+
+#[derive(Debug, Default, Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct InterfaceCounters {
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+    pub rx_dropped: u64,
+    pub tx_dropped: u64,
+}
+
+#[derive(Debug, Default, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkThroughput {
+    pub rx_bytes_per_sec: u64,
+    pub rx_formatted: String,
+    pub tx_bytes_per_sec: u64,
+    pub tx_formatted: String,
+}
+
+#[derive(Debug, Default, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GeoInfo {
+    pub country_code: Option<String>,
+    pub country: Option<String>,
+    pub city: Option<String>,
+    pub asn: Option<u32>,
+    pub organization: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GeoIpDatabase {
+    pub path: PathBuf,
+    pub database_type: String,
+    pub build_epoch: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum NetTableGroupBy {
+    Process,
+    RemoteAddress,
+    RemotePort,
+    State,
+    Country,
+    Asn,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NetTableAggregate {
+    pub groups: Vec<ConnectionGroup>,
+    pub graph: ConnectionGraph,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionGroup {
+    pub key: String,
+    pub label: String,
+    pub count: u32,
+    pub process_count: u32,
+    pub states: HashMap<TcpState, u32>,
+    /// sum over the connections that have one, missing when none has
+    pub throughput: Option<NetworkThroughput>,
+}
+
+#[derive(Debug, Default, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionGraph {
+    pub nodes: Vec<ConnectionNode>,
+    pub edges: Vec<ConnectionEdge>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ConnectionNode {
+    #[serde(rename_all = "camelCase")]
+    Process {
+        id: String,
+        process_id: u32,
+        process_name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Endpoint {
+        id: String,
+        ip_address: IpAddr,
+        port: Option<u16>,
+        geo: Option<GeoInfo>,
+    },
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionEdge {
+    pub source: String,
+    pub target: String,
+    /// number of connections between the process and the endpoint
+    pub weight: u32,
+    pub states: HashMap<TcpState, u32>,
+    /// sum over the connections that have one, missing when none has
+    pub throughput: Option<NetworkThroughput>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Route {
+    pub destination: IpNetwork,
+    pub gateway: Option<IpAddr>,
+    pub interface: String,
+    pub metric: u32,
+    pub protocol: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Neighbour {
+    pub ip_address: IpAddr,
+    pub mac_address: Option<String>,
+    pub interface: String,
+    pub state: NeighbourState,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum NeighbourState {
+    Unreachable,
+    Incomplete,
+    Probe,
+    Delay,
+    Stale,
+    Reachable,
+    Permanent,
+    Unknown,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RouteLookup {
+    pub destination: IpAddr,
+    /// most specific matching route, ties are broken by the lowest metric
+    pub route: Option<Route>,
+    /// neighbour entry of the next hop (gateway or the destination itself when on-link)
+    pub next_hop: Option<Neighbour>,
+}
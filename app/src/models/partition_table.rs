@@ -0,0 +1,50 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PartitionScheme {
+    Mbr,
+    Gpt,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PartitionTableEntry {
+    /// slot in the GPT array or MBR, logical MBR partitions count from 5
+    pub number: u32,
+    /// type GUID for GPT, `0x83` style type byte for MBR
+    pub type_id: String,
+    pub type_name: Option<String>,
+    /// GPT only
+    pub unique_guid: Option<Uuid>,
+    pub first_lba: u64,
+    pub last_lba: u64,
+    pub size: u64,
+    pub size_formatted: String,
+    /// raw GPT attribute bits, 0 for MBR
+    pub attributes: u64,
+    pub attribute_names: Vec<String>,
+    /// GPT partition name
+    pub name: Option<String>,
+    pub bootable: bool,
+    /// inside an MBR extended partition
+    pub logical: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PartitionTable {
+    pub scheme: PartitionScheme,
+    pub sector_size: u32,
+    /// `None` when the device does not report its size
+    pub disk_size: Option<u64>,
+    pub disk_size_formatted: Option<String>,
+    /// GPT only
+    pub disk_guid: Option<Uuid>,
+    pub first_usable_lba: Option<u64>,
+    pub last_usable_lba: Option<u64>,
+    pub partitions: Vec<PartitionTableEntry>,
+    /// checksum failures and differences between the primary and backup GPT, empty when both agree
+    pub issues: Vec<String>,
+}
@@ -1,15 +1,20 @@
-use std::{net::{IpAddr, Ipv4Addr}, path::PathBuf};
+use std::path::PathBuf;
 
-use hashbrown::HashSet;
+use chrono::{DateTime, Utc};
+use hashbrown::{HashMap, HashSet};
+use ipnetwork::IpNetwork;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::models::{NetTableGroupBy, NetworkThroughput, TcpState};
+
 #[derive(Debug, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct LoadResult {
     pub session_id: Uuid
 }
 
+#[cfg(windows)]
 #[derive(Debug, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct LoadedDriver {
@@ -18,6 +23,7 @@ pub struct LoadedDriver {
     pub base: usize,
 }
 
+#[cfg(windows)]
 #[derive(Debug, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct DriverInfo {
@@ -36,9 +42,12 @@ pub struct DashboardStats {
     pub programs_count: u32,
     pub active_processes: u32,
     pub memory: MemoryStats,
-    pub disks: Vec<DiskInfo>
+    pub disks: Vec<DiskInfo>,
+    pub disk_io: Vec<DiskIoStats>,
+    pub network: NetworkThroughput
 }
 
+#[cfg(windows)]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Program {
@@ -65,6 +74,7 @@ pub struct GetProcessArgs {
     pub page_size: u32
 }
 
+#[cfg(windows)]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct GetProgramArgs {
@@ -84,15 +94,78 @@ pub enum TransportProtocol {
 #[serde(rename_all = "camelCase")]
 pub struct GetNetTableArgs {
     pub protocols: HashSet<TransportProtocol>,
-    pub process_name: Option<String>,
-    pub local_port: Option<u16>,
-    pub remote_port: Option<u16>,
-    pub local_ip_addr: Option<IpAddr>,
-    pub remote_ip_addr: Option<IpAddr>,
+    #[serde(flatten)]
+    pub filter: NetTableFilter,
+    /// rows matching every criterion set here are dropped, e.g. `{ "loopback": true }`
+    #[serde(default)]
+    pub exclude: Option<NetTableFilter>,
+    #[serde(default)]
+    pub sort: Option<NetTableSort>,
+    #[serde(default)]
+    pub resolve_host_names: bool,
     #[serde(flatten)]
     pub page: PageArgs
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GetNetTableAggregateArgs {
+    pub protocols: HashSet<TransportProtocol>,
+    #[serde(flatten)]
+    pub filter: NetTableFilter,
+    #[serde(default)]
+    pub exclude: Option<NetTableFilter>,
+    pub group_by: NetTableGroupBy
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NetTableFilter {
+    pub process_name: Option<String>,
+    pub process_ids: Option<HashSet<u32>>,
+    pub local_port: Option<PortFilter>,
+    pub remote_port: Option<PortFilter>,
+    /// CIDR such as `192.168.0.0/16` or `fe80::/10`, rows of the other address family never match
+    pub local_ip_addr: Option<IpNetwork>,
+    pub remote_ip_addr: Option<IpNetwork>,
+    pub states: Option<HashSet<TcpState>>,
+    pub loopback: Option<bool>,
+    /// ISO country codes of the remote address
+    pub countries: Option<HashSet<String>>,
+    pub asns: Option<HashSet<u32>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[serde(untagged)]
+pub enum PortFilter {
+    Single(u16),
+    Range {
+        start: u16,
+        end: u16
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NetTableSort {
+    pub column: NetTableColumn,
+    #[serde(default)]
+    pub descending: bool
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum NetTableColumn {
+    ProcessId,
+    ProcessName,
+    Protocol,
+    LocalIpAddress,
+    LocalPort,
+    RemoteIpAddress,
+    RemotePort,
+    State
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct PageArgs {
@@ -157,7 +230,125 @@ pub struct HandleInfo {
     pub process_name: String,
     pub handle: u32,
     pub object_type: u16,
+    /// File, Key, Event, Section...
+    pub type_name: Option<String>,
+    /// object name, DOS path for files
+    pub object_name: Option<String>,
     pub granted_access: u32,
+    /// `granted_access` decoded for the object type
+    pub access_rights: Vec<String>,
+    /// process a Process or Thread handle refers to
+    pub target_process_id: Option<u32>,
+    /// `st_mode` of the open object on Linux, where `granted_access` holds the open flags
+    pub file_mode: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HandleAccessDenied {
+    pub process_id: u32,
+    pub process_name: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DangerousHandle {
+    pub handle: HandleInfo,
+    pub target_process_name: Option<String>,
+    /// the subset of rights that allow tampering with the target
+    pub rights: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSystemHandlesArgs {
+    #[serde(flatten)]
+    pub filter: HandleFilter,
+    #[serde(flatten)]
+    pub page: PageArgs
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HandleFilter {
+    pub process_ids: Option<HashSet<u32>>,
+    pub process_name: Option<String>,
+    pub type_names: Option<HashSet<String>>,
+    pub object_name: Option<String>,
+    /// any of the decoded rights, e.g. `PROCESS_VM_WRITE`
+    pub access_rights: Option<HashSet<String>>,
+    /// all of the bits
+    pub access_mask: Option<u32>,
+    pub dangerous_only: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessHandleCounts {
+    pub process_id: u32,
+    pub process_name: String,
+    pub total: u32,
+    pub by_type: HashMap<String, u32>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HandleCountPoint {
+    pub timestamp: DateTime<Utc>,
+    pub count: u32,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HandleTypeGrowth {
+    pub type_name: String,
+    pub first_count: u32,
+    pub last_count: u32,
+    pub growth: i64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HandleGrowth {
+    pub process_id: u32,
+    pub process_name: String,
+    pub first_count: u32,
+    pub last_count: u32,
+    pub growth: i64,
+    /// least squares trend over the window
+    pub slope_per_minute: f64,
+    /// the count grew steadily across the window
+    pub suspected_leak: bool,
+    pub growing_types: Vec<HandleTypeGrowth>,
+    pub samples: Vec<HandleCountPoint>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenFileProcess {
+    pub process_id: u32,
+    pub process_name: String,
+    pub handles: Vec<OpenFileHandle>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenFileHandle {
+    /// handle value on Windows, descriptor number on Linux, `None` for the working directory
+    pub handle: Option<u64>,
+    pub path: String,
+    pub access: FileAccess,
+    pub is_directory: bool,
+}
+
+#[derive(Debug, Default, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileAccess {
+    pub read: bool,
+    pub write: bool,
+    pub append: bool,
+    pub delete: bool,
 }
 
 #[derive(Debug, Default, Serialize, Clone)]
@@ -176,6 +367,167 @@ pub struct MemoryStats {
     pub avail_virtual: u64,
     pub avail_virtual_formatted: String,
     pub memory_load: u32,
+    /// file cache, standby list on Windows
+    pub cached: Option<u64>,
+    pub cached_formatted: Option<String>,
+    pub buffers: Option<u64>,
+    pub buffers_formatted: Option<String>,
+    pub committed: Option<u64>,
+    pub committed_formatted: Option<String>,
+    pub commit_limit: Option<u64>,
+    pub commit_limit_formatted: Option<String>,
+    pub swap_used: Option<u64>,
+    pub swap_used_formatted: Option<String>,
+    pub huge_pages_total: Option<u64>,
+    pub huge_pages_total_formatted: Option<String>,
+    pub huge_pages_free: Option<u64>,
+    pub huge_pages_free_formatted: Option<String>,
+    pub paged_pool: Option<u64>,
+    pub paged_pool_formatted: Option<String>,
+    pub non_paged_pool: Option<u64>,
+    pub non_paged_pool_formatted: Option<String>,
+    /// compressed store on Windows, zswap pool on Linux
+    pub compressed: Option<u64>,
+    pub compressed_formatted: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessMemory {
+    pub process_id: u32,
+    pub process_name: String,
+    pub working_set: Option<u64>,
+    /// PrivateUsage on Windows, private resident pages on Linux
+    pub private_bytes: Option<u64>,
+    /// resident pages mapped by other processes as well
+    pub shared_bytes: Option<u64>,
+    /// resident memory with shared pages split between their users,
+    /// Pss on Linux and the private working set on Windows
+    pub proportional_bytes: Option<u64>,
+    /// PagefileUsage on Windows, private writable mappings (data and stack) on Linux
+    pub commit_charge: Option<u64>,
+    pub peak_working_set: Option<u64>,
+    pub page_faults: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ProcessMemoryColumn {
+    Id,
+    Name,
+    WorkingSet,
+    PrivateBytes,
+    SharedBytes,
+    ProportionalBytes,
+    CommitCharge,
+    PeakWorkingSet,
+    PageFaults,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessMemorySort {
+    pub column: ProcessMemoryColumn,
+    #[serde(default)]
+    pub descending: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GetProcessMemoryArgs {
+    pub name: Option<String>,
+    pub sort: Option<ProcessMemorySort>,
+    #[serde(flatten)]
+    pub page: PageArgs
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryContributor {
+    pub process_id: u32,
+    pub process_name: String,
+    pub bytes: u64,
+    pub bytes_formatted: String,
+    /// percentage of used physical memory
+    pub share: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessMemorySummary {
+    pub used_phys: u64,
+    pub used_phys_formatted: String,
+    /// sum of `proportional_bytes` over all readable processes
+    pub attributed: u64,
+    pub attributed_formatted: String,
+    /// used memory not attributed to any process: kernel, caches, unreadable processes
+    pub unattributed: u64,
+    pub unattributed_formatted: String,
+    pub top: Vec<MemoryContributor>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryPoint {
+    pub timestamp: DateTime<Utc>,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryGrowth {
+    pub process_id: u32,
+    pub process_name: String,
+    pub current: u64,
+    pub current_formatted: String,
+    pub growth: i64,
+    /// least squares trend over the window
+    pub bytes_per_minute: f64,
+    pub bytes_per_minute_formatted: String,
+    /// how well the samples follow a straight line, 0 to 1
+    pub r_squared: f64,
+    /// the private memory grew steadily across the window
+    pub suspected_leak: bool,
+    /// seconds until the threshold is reached at the current rate, `None` when not growing
+    pub time_to_threshold: Option<u64>,
+    pub threshold: u64,
+    pub threshold_formatted: String,
+    pub samples: Vec<MemoryPoint>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskIoStats {
+    /// `PhysicalDrive0` on Windows, `sda` on Linux
+    pub name: String,
+    pub read_bytes_per_sec: u64,
+    pub read_formatted: String,
+    pub write_bytes_per_sec: u64,
+    pub write_formatted: String,
+    pub read_iops: f64,
+    pub write_iops: f64,
+    /// average number of requests in flight
+    pub queue_depth: f64,
+    /// share of the interval the disk was busy, 0 to 100
+    pub utilisation: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessIoStats {
+    pub process_id: u32,
+    pub process_name: String,
+    pub read_bytes_per_sec: u64,
+    pub read_formatted: String,
+    pub write_bytes_per_sec: u64,
+    pub write_formatted: String,
+    /// read and write calls per second on any file, pipe or socket, not requests that reached the disk
+    /// (`syscr`/`syscw` on Linux, I/O operation counts on Windows)
+    pub read_ops_per_sec: f64,
+    pub write_ops_per_sec: f64,
+    /// bytes since the process started
+    pub read_bytes: u64,
+    pub write_bytes: u64,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -183,5 +535,46 @@ pub struct MemoryStats {
 pub struct DiskInfo {
     pub model: Option<String>,
     pub disk_type: Option<String>, // HDD / SSD
+    pub serial: Option<String>,
+    pub interface_type: Option<String>,
+    pub size: Option<u64>,
+    pub size_formatted: Option<String>,
+    /// disk size not covered by any partition
+    pub unpartitioned: Option<u64>,
+    pub unpartitioned_formatted: Option<String>,
+    pub removable: Option<bool>,
+    /// mounted partitions, unmounted ones only count towards `unpartitioned`
     pub partitions: Vec<DiskPartition>,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskUsagePoint {
+    pub timestamp: DateTime<Utc>,
+    pub used: u64,
+    pub total: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskForecast {
+    /// mount point on Linux, drive letter on Windows
+    pub name: String,
+    pub total: u64,
+    pub total_formatted: String,
+    pub used: u64,
+    pub used_formatted: String,
+    pub free: u64,
+    pub free_formatted: String,
+    /// least squares trend over the window, negative while the partition shrinks
+    pub bytes_per_day: f64,
+    pub bytes_per_day_formatted: String,
+    /// how well the samples follow a straight line, 0 to 1
+    pub r_squared: f64,
+    /// projected date the partition runs full, `None` when not growing or too few samples
+    pub full_on: Option<DateTime<Utc>>,
+    pub days_left: Option<f64>,
+    /// `full_on` falls inside the notification horizon
+    pub within_horizon: bool,
+    pub samples: Vec<DiskUsagePoint>,
+}
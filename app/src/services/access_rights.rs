@@ -0,0 +1,285 @@
+//! Access mask decoding tables for the kernel object types a handle can refer to.
+//! Values come from winnt.h and the WDK headers.
+
+type RightsTable = &'static [(u32, &'static str)];
+
+struct ObjectRights {
+    /// `*_ALL_ACCESS` value and name, reported in place of the individual rights
+    all_access: (u32, &'static str),
+    specific: RightsTable,
+}
+
+const GENERIC_RIGHTS: RightsTable = &[
+    (0x8000_0000, "GENERIC_READ"),
+    (0x4000_0000, "GENERIC_WRITE"),
+    (0x2000_0000, "GENERIC_EXECUTE"),
+    (0x1000_0000, "GENERIC_ALL"),
+    (0x0200_0000, "MAXIMUM_ALLOWED"),
+    (0x0100_0000, "ACCESS_SYSTEM_SECURITY"),
+];
+
+const STANDARD_RIGHTS: RightsTable = &[
+    (0x0001_0000, "DELETE"),
+    (0x0002_0000, "READ_CONTROL"),
+    (0x0004_0000, "WRITE_DAC"),
+    (0x0008_0000, "WRITE_OWNER"),
+    (0x0010_0000, "SYNCHRONIZE"),
+];
+
+const PROCESS_RIGHTS: ObjectRights = ObjectRights {
+    all_access: (0x001f_ffff, "PROCESS_ALL_ACCESS"),
+    specific: &[
+        (0x0001, "PROCESS_TERMINATE"),
+        (0x0002, "PROCESS_CREATE_THREAD"),
+        (0x0004, "PROCESS_SET_SESSIONID"),
+        (0x0008, "PROCESS_VM_OPERATION"),
+        (0x0010, "PROCESS_VM_READ"),
+        (0x0020, "PROCESS_VM_WRITE"),
+        (0x0040, "PROCESS_DUP_HANDLE"),
+        (0x0080, "PROCESS_CREATE_PROCESS"),
+        (0x0100, "PROCESS_SET_QUOTA"),
+        (0x0200, "PROCESS_SET_INFORMATION"),
+        (0x0400, "PROCESS_QUERY_INFORMATION"),
+        (0x0800, "PROCESS_SUSPEND_RESUME"),
+        (0x1000, "PROCESS_QUERY_LIMITED_INFORMATION"),
+        (0x2000, "PROCESS_SET_LIMITED_INFORMATION"),
+    ],
+};
+
+const THREAD_RIGHTS: ObjectRights = ObjectRights {
+    all_access: (0x001f_ffff, "THREAD_ALL_ACCESS"),
+    specific: &[
+        (0x0001, "THREAD_TERMINATE"),
+        (0x0002, "THREAD_SUSPEND_RESUME"),
+        (0x0008, "THREAD_GET_CONTEXT"),
+        (0x0010, "THREAD_SET_CONTEXT"),
+        (0x0020, "THREAD_SET_INFORMATION"),
+        (0x0040, "THREAD_QUERY_INFORMATION"),
+        (0x0080, "THREAD_SET_THREAD_TOKEN"),
+        (0x0100, "THREAD_IMPERSONATE"),
+        (0x0200, "THREAD_DIRECT_IMPERSONATION"),
+        (0x0400, "THREAD_SET_LIMITED_INFORMATION"),
+        (0x0800, "THREAD_QUERY_LIMITED_INFORMATION"),
+        (0x1000, "THREAD_RESUME"),
+    ],
+};
+
+const FILE_RIGHTS: ObjectRights = ObjectRights {
+    all_access: (0x001f_01ff, "FILE_ALL_ACCESS"),
+    specific: &[
+        (0x0001, "FILE_READ_DATA"),
+        (0x0002, "FILE_WRITE_DATA"),
+        (0x0004, "FILE_APPEND_DATA"),
+        (0x0008, "FILE_READ_EA"),
+        (0x0010, "FILE_WRITE_EA"),
+        (0x0020, "FILE_EXECUTE"),
+        (0x0040, "FILE_DELETE_CHILD"),
+        (0x0080, "FILE_READ_ATTRIBUTES"),
+        (0x0100, "FILE_WRITE_ATTRIBUTES"),
+    ],
+};
+
+const KEY_RIGHTS: ObjectRights = ObjectRights {
+    all_access: (0x000f_003f, "KEY_ALL_ACCESS"),
+    specific: &[
+        (0x0001, "KEY_QUERY_VALUE"),
+        (0x0002, "KEY_SET_VALUE"),
+        (0x0004, "KEY_CREATE_SUB_KEY"),
+        (0x0008, "KEY_ENUMERATE_SUB_KEYS"),
+        (0x0010, "KEY_NOTIFY"),
+        (0x0020, "KEY_CREATE_LINK"),
+        (0x0100, "KEY_WOW64_64KEY"),
+        (0x0200, "KEY_WOW64_32KEY"),
+    ],
+};
+
+const TOKEN_RIGHTS: ObjectRights = ObjectRights {
+    all_access: (0x000f_01ff, "TOKEN_ALL_ACCESS"),
+    specific: &[
+        (0x0001, "TOKEN_ASSIGN_PRIMARY"),
+        (0x0002, "TOKEN_DUPLICATE"),
+        (0x0004, "TOKEN_IMPERSONATE"),
+        (0x0008, "TOKEN_QUERY"),
+        (0x0010, "TOKEN_QUERY_SOURCE"),
+        (0x0020, "TOKEN_ADJUST_PRIVILEGES"),
+        (0x0040, "TOKEN_ADJUST_GROUPS"),
+        (0x0080, "TOKEN_ADJUST_DEFAULT"),
+        (0x0100, "TOKEN_ADJUST_SESSIONID"),
+    ],
+};
+
+const SECTION_RIGHTS: ObjectRights = ObjectRights {
+    all_access: (0x000f_001f, "SECTION_ALL_ACCESS"),
+    specific: &[
+        (0x0001, "SECTION_QUERY"),
+        (0x0002, "SECTION_MAP_WRITE"),
+        (0x0004, "SECTION_MAP_READ"),
+        (0x0008, "SECTION_MAP_EXECUTE"),
+        (0x0010, "SECTION_EXTEND_SIZE"),
+        (0x0020, "SECTION_MAP_EXECUTE_EXPLICIT"),
+    ],
+};
+
+const EVENT_RIGHTS: ObjectRights = ObjectRights {
+    all_access: (0x001f_0003, "EVENT_ALL_ACCESS"),
+    specific: &[(0x0001, "EVENT_QUERY_STATE"), (0x0002, "EVENT_MODIFY_STATE")],
+};
+
+const MUTANT_RIGHTS: ObjectRights = ObjectRights {
+    all_access: (0x001f_0001, "MUTANT_ALL_ACCESS"),
+    specific: &[(0x0001, "MUTANT_QUERY_STATE")],
+};
+
+const SEMAPHORE_RIGHTS: ObjectRights = ObjectRights {
+    all_access: (0x001f_0003, "SEMAPHORE_ALL_ACCESS"),
+    specific: &[(0x0001, "SEMAPHORE_QUERY_STATE"), (0x0002, "SEMAPHORE_MODIFY_STATE")],
+};
+
+const TIMER_RIGHTS: ObjectRights = ObjectRights {
+    all_access: (0x001f_0003, "TIMER_ALL_ACCESS"),
+    specific: &[(0x0001, "TIMER_QUERY_STATE"), (0x0002, "TIMER_MODIFY_STATE")],
+};
+
+const JOB_RIGHTS: ObjectRights = ObjectRights {
+    all_access: (0x001f_003f, "JOB_OBJECT_ALL_ACCESS"),
+    specific: &[
+        (0x0001, "JOB_OBJECT_ASSIGN_PROCESS"),
+        (0x0002, "JOB_OBJECT_SET_ATTRIBUTES"),
+        (0x0004, "JOB_OBJECT_QUERY"),
+        (0x0008, "JOB_OBJECT_TERMINATE"),
+        (0x0010, "JOB_OBJECT_SET_SECURITY_ATTRIBUTES"),
+        (0x0020, "JOB_OBJECT_IMPERSONATE"),
+    ],
+};
+
+const DIRECTORY_RIGHTS: ObjectRights = ObjectRights {
+    all_access: (0x000f_000f, "DIRECTORY_ALL_ACCESS"),
+    specific: &[
+        (0x0001, "DIRECTORY_QUERY"),
+        (0x0002, "DIRECTORY_TRAVERSE"),
+        (0x0004, "DIRECTORY_CREATE_OBJECT"),
+        (0x0008, "DIRECTORY_CREATE_SUBDIRECTORY"),
+    ],
+};
+
+const SYMBOLIC_LINK_RIGHTS: ObjectRights = ObjectRights {
+    all_access: (0x000f_0001, "SYMBOLIC_LINK_ALL_ACCESS"),
+    specific: &[(0x0001, "SYMBOLIC_LINK_QUERY")],
+};
+
+/// Rights on a process or thread that allow code injection or taking it over
+const DANGEROUS_PROCESS_RIGHTS: u32 = 0x0002 | 0x0008 | 0x0020 | 0x0040;
+const DANGEROUS_THREAD_RIGHTS: u32 = 0x0010 | 0x0080 | 0x0200;
+const DANGEROUS_STANDARD_RIGHTS: u32 = 0x0004_0000 | 0x0008_0000;
+const DANGEROUS_GENERIC_RIGHTS: u32 = 0x4000_0000 | 0x1000_0000;
+
+fn object_rights(type_name: &str) -> Option<&'static ObjectRights> {
+    match type_name {
+        "Process" => Some(&PROCESS_RIGHTS),
+        "Thread" => Some(&THREAD_RIGHTS),
+        "File" => Some(&FILE_RIGHTS),
+        "Key" => Some(&KEY_RIGHTS),
+        "Token" => Some(&TOKEN_RIGHTS),
+        "Section" => Some(&SECTION_RIGHTS),
+        "Event" => Some(&EVENT_RIGHTS),
+        "Mutant" => Some(&MUTANT_RIGHTS),
+        "Semaphore" => Some(&SEMAPHORE_RIGHTS),
+        "Timer" => Some(&TIMER_RIGHTS),
+        "Job" => Some(&JOB_RIGHTS),
+        "Directory" => Some(&DIRECTORY_RIGHTS),
+        "SymbolicLink" => Some(&SYMBOLIC_LINK_RIGHTS),
+        _ => None,
+    }
+}
+
+fn push_rights(names: &mut Vec<String>, table: RightsTable, mask: u32) {
+    names.extend(table.iter().filter(|(right, _)| mask & right != 0).map(|(_, name)| name.to_string()));
+}
+
+/// Decodes `granted_access` into right names for the given object type.
+/// A full `*_ALL_ACCESS` grant is reported by its name alone, specific bits of
+/// unknown object types are reported as hex.
+#[cfg(any(windows, test))]
+pub fn decode_access_mask(type_name: Option<&str>, mask: u32) -> Vec<String> {
+    let mut names = Vec::new();
+    let rights = type_name.and_then(object_rights);
+
+    push_rights(&mut names, GENERIC_RIGHTS, mask);
+
+    if let Some(rights) = rights {
+        let (all, name) = rights.all_access;
+
+        if mask & all == all {
+            names.push(name.to_string());
+            return names;
+        }
+    }
+
+    push_rights(&mut names, STANDARD_RIGHTS, mask);
+
+    match rights {
+        Some(rights) => push_rights(&mut names, rights.specific, mask),
+        None if mask & 0xffff != 0 => names.push(format!("SPECIFIC_RIGHTS(0x{:x})", mask & 0xffff)),
+        None => {}
+    }
+
+    names
+}
+
+/// Rights a handle to a process or thread grants that let its holder tamper with the target.
+/// Empty for other object types.
+pub fn dangerous_rights(type_name: Option<&str>, mask: u32) -> Vec<String> {
+    let specific = match type_name {
+        Some("Process") => DANGEROUS_PROCESS_RIGHTS,
+        Some("Thread") => DANGEROUS_THREAD_RIGHTS,
+        _ => return vec![],
+    };
+
+    let mut names = Vec::new();
+    let rights = type_name.and_then(object_rights).unwrap();
+
+    if mask & rights.all_access.0 == rights.all_access.0 {
+        names.push(rights.all_access.1.to_string());
+    }
+
+    push_rights(&mut names, GENERIC_RIGHTS, mask & DANGEROUS_GENERIC_RIGHTS);
+    push_rights(&mut names, STANDARD_RIGHTS, mask & DANGEROUS_STANDARD_RIGHTS);
+    push_rights(&mut names, rights.specific, mask & specific);
+
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_access_is_reported_by_name() {
+        assert_eq!(decode_access_mask(Some("Process"), 0x001f_ffff), vec!["PROCESS_ALL_ACCESS"]);
+        assert_eq!(decode_access_mask(Some("File"), 0x001f_01ff), vec!["FILE_ALL_ACCESS"]);
+    }
+
+    #[test]
+    fn generic_standard_and_specific_rights_are_decoded() {
+        assert_eq!(
+            decode_access_mask(Some("Process"), 0x8000_0000 | 0x0010_0000 | 0x0020 | 0x1000),
+            vec!["GENERIC_READ", "SYNCHRONIZE", "PROCESS_VM_WRITE", "PROCESS_QUERY_LIMITED_INFORMATION"]
+        );
+        assert_eq!(decode_access_mask(Some("Key"), 0x0002_0019), vec!["READ_CONTROL", "KEY_QUERY_VALUE", "KEY_ENUMERATE_SUB_KEYS", "KEY_NOTIFY"]);
+    }
+
+    #[test]
+    fn unknown_types_report_specific_bits_as_hex() {
+        assert_eq!(decode_access_mask(Some("ALPC Port"), 0x0001_0003), vec!["DELETE", "SPECIFIC_RIGHTS(0x3)"]);
+        assert_eq!(decode_access_mask(None, 0x0010_0000), vec!["SYNCHRONIZE"]);
+        assert!(decode_access_mask(None, 0).is_empty());
+    }
+
+    #[test]
+    fn dangerous_rights_only_cover_processes_and_threads() {
+        assert_eq!(dangerous_rights(Some("Process"), 0x1000 | 0x0020 | 0x0008), vec!["PROCESS_VM_OPERATION", "PROCESS_VM_WRITE"]);
+        assert_eq!(dangerous_rights(Some("Thread"), 0x0010 | 0x0040), vec!["THREAD_SET_CONTEXT"]);
+        assert!(dangerous_rights(Some("Process"), 0x1000 | 0x0010_0000).is_empty());
+        assert!(dangerous_rights(Some("File"), 0x001f_01ff).is_empty());
+    }
+}
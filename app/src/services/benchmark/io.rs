@@ -0,0 +1,185 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io,
+    path::{Path, PathBuf},
+    slice,
+};
+
+use rand::RngCore;
+
+/// Alignment unbuffered I/O asks for, covers 512 byte and 4K sector disks
+pub const PAGE_SIZE: usize = 4096;
+
+#[repr(C, align(4096))]
+#[derive(Clone, Copy)]
+struct Page([u8; PAGE_SIZE]);
+
+/// Page aligned memory, `O_DIRECT` and `FILE_FLAG_NO_BUFFERING` reject unaligned buffers
+pub struct AlignedBuffer {
+    pages: Vec<Page>,
+    len: usize,
+}
+
+impl AlignedBuffer {
+    pub fn new(len: usize) -> Self {
+        Self {
+            pages: vec![Page([0; PAGE_SIZE]); len.div_ceil(PAGE_SIZE)],
+            len,
+        }
+    }
+
+    /// Random content, compressing or deduplicating storage would otherwise report inflated numbers
+    pub fn random(len: usize) -> Self {
+        let mut buffer = Self::new(len);
+        rand::rng().fill_bytes(buffer.as_mut_slice());
+        buffer
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.pages.as_ptr() as *const u8, self.len) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { slice::from_raw_parts_mut(self.pages.as_mut_ptr() as *mut u8, self.len) }
+    }
+
+    /// Writes `value` into the start of every page so repeated writes of the buffer never repeat a page
+    pub fn stamp(&mut self, value: u64) {
+        for page in self.pages.iter_mut() {
+            page.0[..8].copy_from_slice(&value.to_le_bytes());
+        }
+    }
+}
+
+/// The benchmark file, removed again when dropped
+pub struct TestFile {
+    pub path: PathBuf,
+    /// opened without the OS cache
+    pub cache_bypassed: bool,
+}
+
+impl TestFile {
+    /// Creates the file and reports whether the cache could be bypassed,
+    /// filesystems such as tmpfs refuse direct I/O
+    pub fn create(path: PathBuf) -> io::Result<(Self, File)> {
+        let direct = open(&path, true, true);
+        Self::create_or_fall_back(path, direct)
+    }
+
+    /// Falls back to a buffered file when the direct open was refused
+    fn create_or_fall_back(path: PathBuf, direct: io::Result<File>) -> io::Result<(Self, File)> {
+        let (file, cache_bypassed) = match direct {
+            Ok(file) => (file, true),
+            // the failed open may already have created the file
+            Err(err) if err.kind() == io::ErrorKind::InvalidInput => {
+                (OpenOptions::new().write(true).create(true).truncate(true).open(&path)?, false)
+            }
+            Err(err) => return Err(err),
+        };
+
+        Ok((Self { path, cache_bypassed }, file))
+    }
+
+    /// A separate handle per worker, requests on one handle may be serialized
+    pub fn open(&self, write: bool) -> io::Result<File> {
+        if self.cache_bypassed {
+            open(&self.path, write, false)
+        } else {
+            OpenOptions::new().read(true).write(write).open(&self.path)
+        }
+    }
+}
+
+impl Drop for TestFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn open(path: &Path, write: bool, create: bool) -> io::Result<File> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    OpenOptions::new()
+        .read(true)
+        .write(write)
+        .create_new(create)
+        .custom_flags(libc::O_DIRECT)
+        .open(path)
+}
+
+#[cfg(windows)]
+fn open(path: &Path, write: bool, create: bool) -> io::Result<File> {
+    use std::os::windows::fs::OpenOptionsExt;
+    use winapi::um::winbase::{FILE_FLAG_NO_BUFFERING, FILE_FLAG_WRITE_THROUGH};
+
+    OpenOptions::new()
+        .read(true)
+        .write(write)
+        .create_new(create)
+        .custom_flags(FILE_FLAG_NO_BUFFERING | FILE_FLAG_WRITE_THROUGH)
+        .open(path)
+}
+
+#[cfg(unix)]
+pub fn read_at(file: &File, buffer: &mut [u8], offset: u64) -> io::Result<()> {
+    use std::os::unix::fs::FileExt;
+
+    file.read_exact_at(buffer, offset)
+}
+
+#[cfg(unix)]
+pub fn write_at(file: &File, buffer: &[u8], offset: u64) -> io::Result<()> {
+    use std::os::unix::fs::FileExt;
+
+    file.write_all_at(buffer, offset)
+}
+
+#[cfg(windows)]
+pub fn read_at(file: &File, buffer: &mut [u8], offset: u64) -> io::Result<()> {
+    use std::os::windows::fs::FileExt;
+
+    match file.seek_read(buffer, offset)? {
+        read if read == buffer.len() => Ok(()),
+        _ => Err(io::ErrorKind::UnexpectedEof.into()),
+    }
+}
+
+#[cfg(windows)]
+pub fn write_at(file: &File, buffer: &[u8], offset: u64) -> io::Result<()> {
+    use std::os::windows::fs::FileExt;
+
+    match file.seek_write(buffer, offset)? {
+        written if written == buffer.len() => Ok(()),
+        _ => Err(io::ErrorKind::WriteZero.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn refused_direct_io_falls_back_to_a_buffered_file() {
+        let path = std::env::temp_dir().join(format!("benchmark-fallback-{}.tmp", Uuid::now_v7()));
+
+        let (test_file, file) = TestFile::create_or_fall_back(path.clone(), Err(io::ErrorKind::InvalidInput.into())).unwrap();
+        write_at(&file, AlignedBuffer::random(PAGE_SIZE).as_slice(), 0).unwrap();
+
+        assert!(!test_file.cache_bypassed);
+        assert!(test_file.open(false).is_ok());
+
+        drop(test_file);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn other_open_errors_are_reported() {
+        let path = std::env::temp_dir().join(format!("benchmark-denied-{}.tmp", Uuid::now_v7()));
+
+        assert!(TestFile::create_or_fall_back(path.clone(), Err(io::ErrorKind::PermissionDenied.into())).is_err());
+        assert!(!path.exists());
+    }
+}
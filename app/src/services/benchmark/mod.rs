@@ -0,0 +1,278 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
+    thread,
+    time::Duration,
+};
+
+use anyhow::{bail, Result};
+use chrono::Utc;
+use hashbrown::HashMap;
+use log::*;
+use tauri::{AppHandle, Emitter};
+use uuid::Uuid;
+
+use crate::{
+    models::{BenchmarkArgs, BenchmarkComparison, BenchmarkProgress, BenchmarkRun, BenchmarkState},
+    services::DiskService,
+    utils::format_bytes,
+};
+
+mod io;
+mod runner;
+mod store;
+
+pub use self::runner::{run_benchmark, BenchmarkConfig, BenchmarkCounters, SEQUENTIAL_BLOCK};
+pub use self::store::{compare_runs, RunStore};
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+/// Free space left on the partition besides the test file
+const FREE_SPACE_MARGIN: u64 = 256 * 1024 * 1024;
+const MAX_QUEUE_DEPTH: u32 = 64;
+const MAX_DURATION_SECS: u64 = 120;
+
+/// Disk benchmarks on a temporary file, reporting progress as `on-benchmark-progress` events
+#[derive(Clone)]
+pub struct BenchmarkService {
+    app_handle: AppHandle,
+    disk_service: DiskService,
+    store: Arc<RunStore>,
+    /// cancel flags of the runs still going
+    running: Arc<RwLock<HashMap<Uuid, Arc<AtomicBool>>>>,
+}
+
+impl BenchmarkService {
+    pub fn new(app_handle: AppHandle, disk_service: DiskService, storage_dir: &Path) -> Self {
+        Self {
+            app_handle,
+            disk_service,
+            store: Arc::new(RunStore::new(storage_dir.join("benchmarks"))),
+            running: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub fn start(&self, args: BenchmarkArgs) -> Result<Uuid> {
+        let run_id = Uuid::now_v7();
+        let config = self.config_for(run_id, &args)?;
+
+        // one run at a time, concurrent runs would measure each other
+        let mut running = self.running.write().unwrap();
+        if !running.is_empty() {
+            bail!("a benchmark is already running");
+        }
+
+        let cancel = Arc::new(AtomicBool::new(false));
+        running.insert(run_id, cancel.clone());
+
+        let service = self.clone();
+        let spawned = thread::Builder::new()
+            .name("disk-benchmark".into())
+            .spawn(move || service.run(run_id, args.partition, config, cancel));
+
+        if let Err(err) = spawned {
+            running.remove(&run_id);
+            return Err(err.into());
+        }
+
+        Ok(run_id)
+    }
+
+    pub fn cancel(&self, run_id: Uuid) -> Result<()> {
+        match self.running.read().unwrap().get(&run_id) {
+            Some(cancel) => cancel.store(true, Ordering::Relaxed),
+            None => bail!("benchmark {} is not running", run_id),
+        }
+
+        Ok(())
+    }
+
+    pub fn get_run(&self, run_id: Uuid) -> Result<BenchmarkRun> {
+        self.store.load(run_id)
+    }
+
+    pub fn get_runs(&self) -> Result<Vec<BenchmarkRun>> {
+        self.store.list()
+    }
+
+    pub fn compare(&self, previous: Uuid, current: Uuid) -> Result<BenchmarkComparison> {
+        Ok(compare_runs(&self.store.load(previous)?, &self.store.load(current)?))
+    }
+
+    pub fn delete_run(&self, run_id: Uuid) -> Result<()> {
+        self.store.delete(run_id)
+    }
+
+    /// Checks the partition has room for the test file and clamps the settings
+    fn config_for(&self, run_id: Uuid, args: &BenchmarkArgs) -> Result<BenchmarkConfig> {
+        let disks = self.disk_service.get_disks()?;
+
+        let Some(partition) = disks.iter().flat_map(|disk| &disk.partitions).find(|partition| partition.name == args.partition) else {
+            bail!("partition {} not found", args.partition);
+        };
+
+        let directory = PathBuf::from(args.directory.as_deref().unwrap_or(&partition.name));
+
+        if !directory.is_dir() || !is_on_partition(&directory, Path::new(&partition.name)) {
+            bail!("{} is not a folder on {}", directory.display(), partition.name);
+        }
+
+        let file_size = args.file_size / SEQUENTIAL_BLOCK * SEQUENTIAL_BLOCK;
+
+        if file_size == 0 {
+            bail!("the test file must be at least {}", format_bytes(SEQUENTIAL_BLOCK));
+        }
+
+        let Some(needed) = file_size.checked_add(FREE_SPACE_MARGIN) else {
+            bail!("a test file of {} does not fit on any disk", format_bytes(file_size));
+        };
+
+        if partition.free < needed {
+            bail!("{} free on {}, the test needs {}", partition.free_formatted, partition.name, format_bytes(needed));
+        }
+
+        if args.tests.is_empty() {
+            bail!("no test selected");
+        }
+
+        Ok(BenchmarkConfig {
+            path: directory.join(format!(".benchmark-{}.tmp", run_id)),
+            file_size,
+            queue_depth: args.queue_depth.clamp(1, MAX_QUEUE_DEPTH),
+            duration: Duration::from_secs(args.duration_secs.clamp(1, MAX_DURATION_SECS)),
+            tests: args.tests.clone(),
+        })
+    }
+
+    fn run(&self, run_id: Uuid, partition: String, config: BenchmarkConfig, cancel: Arc<AtomicBool>) {
+        let started_on = Utc::now();
+        let counters = BenchmarkCounters::default();
+        let done = AtomicBool::new(false);
+
+        let outcome = thread::scope(|scope| {
+            scope.spawn(|| {
+                while !done.load(Ordering::Relaxed) {
+                    self.emit_progress(run_id, &partition, &config, &counters, None, None);
+                    thread::sleep(PROGRESS_INTERVAL);
+                }
+            });
+
+            let outcome = run_benchmark(&config, &counters, &cancel);
+            done.store(true, Ordering::Relaxed);
+            outcome
+        });
+
+        let (mut state, results, cache_bypassed, mut error) = match outcome {
+            Ok(_) if cancel.load(Ordering::Relaxed) => (BenchmarkState::Cancelled, vec![], false, None),
+            Ok((results, cache_bypassed)) => (BenchmarkState::Completed, results, cache_bypassed, None),
+            Err(err) => (BenchmarkState::Failed, vec![], false, Some(err.to_string())),
+        };
+
+        // partial runs are not comparable, only completed ones are kept
+        if state == BenchmarkState::Completed {
+            let run = BenchmarkRun {
+                run_id,
+                partition: partition.clone(),
+                state,
+                started_on,
+                finished_on: Utc::now(),
+                file_size: config.file_size,
+                file_size_formatted: format_bytes(config.file_size),
+                queue_depth: config.queue_depth,
+                duration_secs: config.duration.as_secs(),
+                cache_bypassed,
+                results,
+                error: None,
+            };
+
+            if let Err(err) = self.store.save(&run) {
+                warn!("could not save benchmark {}: {}", run_id, err);
+                state = BenchmarkState::Failed;
+                error = Some(err.to_string());
+            }
+        }
+
+        self.running.write().unwrap().remove(&run_id);
+        self.emit_progress(run_id, &partition, &config, &counters, Some(state), error);
+    }
+
+    fn emit_progress(
+        &self,
+        run_id: Uuid,
+        partition: &str,
+        config: &BenchmarkConfig,
+        counters: &BenchmarkCounters,
+        finished: Option<BenchmarkState>,
+        error: Option<String>,
+    ) {
+        let phase = *counters.phase.lock().unwrap();
+        let bytes = counters.bytes.load(Ordering::Relaxed);
+        let elapsed = phase.started.elapsed();
+
+        // preparing counts as one more step in front of the tests
+        let steps = config.tests.len() as f64 + 1.0;
+        let step_progress = match phase.state {
+            BenchmarkState::Preparing => bytes as f64 / config.file_size as f64,
+            _ => elapsed.as_secs_f64() / config.duration.as_secs_f64(),
+        };
+        let step = if phase.state == BenchmarkState::Preparing { 0.0 } else { phase.index as f64 + 1.0 };
+
+        let progress = match finished {
+            Some(BenchmarkState::Completed) => 1.0,
+            _ => ((step + step_progress.min(1.0)) / steps).min(1.0),
+        };
+
+        let bytes_per_sec = (bytes as f64 / elapsed.as_secs_f64().max(f64::EPSILON)) as u64;
+
+        let progress = BenchmarkProgress {
+            run_id,
+            partition: partition.to_string(),
+            state: finished.unwrap_or(phase.state),
+            test: phase.test,
+            progress,
+            bytes_per_sec,
+            bytes_per_sec_formatted: format!("{}/s", format_bytes(bytes_per_sec)),
+            error,
+        };
+
+        if let Err(err) = self.app_handle.emit("on-benchmark-progress", progress) {
+            debug!("could not emit benchmark progress: {}", err);
+        }
+    }
+}
+
+/// Every path starts with `/`, only the device tells whether another filesystem is mounted in between
+#[cfg(unix)]
+fn is_on_partition(directory: &Path, mount_point: &Path) -> bool {
+    use std::{fs, os::unix::fs::MetadataExt};
+
+    match (fs::metadata(directory), fs::metadata(mount_point)) {
+        (Ok(directory), Ok(mount_point)) => directory.dev() == mount_point.dev(),
+        _ => false,
+    }
+}
+
+/// Partitions are drive letters here
+#[cfg(not(unix))]
+fn is_on_partition(directory: &Path, mount_point: &Path) -> bool {
+    directory.starts_with(mount_point)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn directories_on_other_filesystems_are_not_on_the_partition() {
+        let current = std::env::current_dir().unwrap();
+
+        assert!(is_on_partition(&current, &current));
+        assert!(is_on_partition(&current.join("src"), &current));
+        // procfs is always its own filesystem
+        assert!(!is_on_partition(Path::new("/proc/self"), Path::new("/")));
+        assert!(!is_on_partition(Path::new("/no/such/folder"), Path::new("/")));
+    }
+}
@@ -0,0 +1,329 @@
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Result};
+use rand::Rng;
+
+use crate::{
+    models::{BenchmarkResult, BenchmarkState, BenchmarkTest, LatencyStats},
+    utils::format_bytes,
+};
+
+use super::io::{read_at, write_at, AlignedBuffer, TestFile, PAGE_SIZE};
+
+pub const SEQUENTIAL_BLOCK: u64 = 1024 * 1024;
+pub const RANDOM_BLOCK: u64 = PAGE_SIZE as u64;
+
+#[derive(Debug, Clone)]
+pub struct BenchmarkConfig {
+    pub path: PathBuf,
+    /// a multiple of `SEQUENTIAL_BLOCK`
+    pub file_size: u64,
+    pub queue_depth: u32,
+    pub duration: Duration,
+    pub tests: Vec<BenchmarkTest>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BenchmarkPhase {
+    pub state: BenchmarkState,
+    pub test: Option<BenchmarkTest>,
+    /// index of the current test
+    pub index: usize,
+    pub started: Instant,
+}
+
+/// Shared with the progress reporter while a run is going
+#[derive(Debug)]
+pub struct BenchmarkCounters {
+    pub phase: Mutex<BenchmarkPhase>,
+    /// bytes moved in the current phase
+    pub bytes: AtomicU64,
+}
+
+impl Default for BenchmarkCounters {
+    fn default() -> Self {
+        Self {
+            phase: Mutex::new(BenchmarkPhase {
+                state: BenchmarkState::Preparing,
+                test: None,
+                index: 0,
+                started: Instant::now(),
+            }),
+            bytes: AtomicU64::new(0),
+        }
+    }
+}
+
+impl BenchmarkCounters {
+    fn enter(&self, state: BenchmarkState, test: Option<BenchmarkTest>, index: usize) {
+        self.bytes.store(0, Ordering::Relaxed);
+        *self.phase.lock().unwrap() = BenchmarkPhase { state, test, index, started: Instant::now() };
+    }
+}
+
+/// Writes the test file, then runs every test against it. The file is removed afterwards,
+/// a cancelled run stops at the next request and its results are incomplete.
+pub fn run_benchmark(config: &BenchmarkConfig, counters: &BenchmarkCounters, cancel: &AtomicBool) -> Result<(Vec<BenchmarkResult>, bool)> {
+    let test_file = prepare(config, counters, cancel)?;
+    let mut results = Vec::new();
+
+    for (index, test) in config.tests.iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+
+        counters.enter(BenchmarkState::Running, Some(*test), index);
+        results.push(run_test(&test_file, *test, config, counters, cancel)?);
+    }
+
+    Ok((results, test_file.cache_bypassed))
+}
+
+/// The read tests need real data on disk, a sparse file would be answered without touching it
+fn prepare(config: &BenchmarkConfig, counters: &BenchmarkCounters, cancel: &AtomicBool) -> Result<TestFile> {
+    counters.enter(BenchmarkState::Preparing, None, 0);
+
+    let (test_file, file) = TestFile::create(config.path.clone())
+        .map_err(|err| anyhow!("could not create {}: {}", config.path.display(), err))?;
+
+    let mut buffer = AlignedBuffer::random(SEQUENTIAL_BLOCK as usize);
+    let mut offset = 0;
+
+    while offset < config.file_size && !cancel.load(Ordering::Relaxed) {
+        buffer.stamp(offset);
+        write_at(&file, buffer.as_slice(), offset)?;
+
+        offset += SEQUENTIAL_BLOCK;
+        counters.bytes.fetch_add(SEQUENTIAL_BLOCK, Ordering::Relaxed);
+    }
+
+    file.sync_all()?;
+
+    Ok(test_file)
+}
+
+fn run_test(test_file: &TestFile, test: BenchmarkTest, config: &BenchmarkConfig, counters: &BenchmarkCounters, cancel: &AtomicBool) -> Result<BenchmarkResult> {
+    let (block_size, sequential, write) = match test {
+        BenchmarkTest::SequentialRead => (SEQUENTIAL_BLOCK, true, false),
+        BenchmarkTest::SequentialWrite => (SEQUENTIAL_BLOCK, true, true),
+        BenchmarkTest::RandomRead => (RANDOM_BLOCK, false, false),
+        BenchmarkTest::RandomWrite => (RANDOM_BLOCK, false, true),
+    };
+
+    let blocks = config.file_size / block_size;
+    // workers take turns on sequential blocks, together they sweep the file front to back
+    let next_block = AtomicU64::new(0);
+    let started = Instant::now();
+    let deadline = started + config.duration;
+
+    let latencies: Vec<Vec<u32>> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..config.queue_depth)
+            .map(|_| {
+                let next_block = &next_block;
+
+                scope.spawn(move || -> Result<Vec<u32>> {
+                    let file = test_file.open(write)?;
+                    let mut buffer = AlignedBuffer::random(block_size as usize);
+                    let mut rng = rand::rng();
+                    let mut latencies = Vec::new();
+
+                    while Instant::now() < deadline && !cancel.load(Ordering::Relaxed) {
+                        let block = if sequential {
+                            next_block.fetch_add(1, Ordering::Relaxed) % blocks
+                        } else {
+                            rng.random_range(0..blocks)
+                        };
+
+                        let offset = block * block_size;
+                        let request = Instant::now();
+
+                        if write {
+                            buffer.stamp(offset ^ latencies.len() as u64);
+                            write_at(&file, buffer.as_slice(), offset)?;
+                        } else {
+                            read_at(&file, buffer.as_mut_slice(), offset)?;
+                        }
+
+                        latencies.push(request.elapsed().as_micros().min(u32::MAX as u128) as u32);
+                        counters.bytes.fetch_add(block_size, Ordering::Relaxed);
+                    }
+
+                    // without direct I/O the writes may still sit in the cache
+                    if write && !test_file.cache_bypassed {
+                        file.sync_data()?;
+                    }
+
+                    Ok(latencies)
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .map(|worker| worker.join().map_err(|_| anyhow!("benchmark worker panicked"))?)
+            .collect::<Result<_>>()
+    })?;
+
+    let elapsed = started.elapsed();
+    let mut latencies: Vec<u32> = latencies.into_iter().flatten().collect();
+
+    let operations = latencies.len() as u64;
+    let bytes = operations * block_size;
+    let seconds = elapsed.as_secs_f64().max(f64::EPSILON);
+    let bytes_per_sec = (bytes as f64 / seconds) as u64;
+
+    Ok(BenchmarkResult {
+        test,
+        block_size,
+        queue_depth: config.queue_depth,
+        operations,
+        bytes,
+        duration_ms: elapsed.as_millis() as u64,
+        bytes_per_sec,
+        bytes_per_sec_formatted: format!("{}/s", format_bytes(bytes_per_sec)),
+        iops: operations as f64 / seconds,
+        latency: latency_stats(&mut latencies),
+    })
+}
+
+/// Percentiles by nearest rank, all zero without samples
+pub fn latency_stats(samples: &mut [u32]) -> LatencyStats {
+    if samples.is_empty() {
+        return LatencyStats::default();
+    }
+
+    samples.sort_unstable();
+
+    let percentile = |quantile: f64| samples[((samples.len() as f64 * quantile).ceil() as usize).clamp(1, samples.len()) - 1] as u64;
+
+    LatencyStats {
+        min: samples[0] as u64,
+        mean: samples.iter().map(|sample| *sample as f64).sum::<f64>() / samples.len() as f64,
+        p50: percentile(0.5),
+        p90: percentile(0.9),
+        p99: percentile(0.99),
+        p999: percentile(0.999),
+        max: samples[samples.len() - 1] as u64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, sync::Arc};
+
+    use uuid::Uuid;
+
+    use super::*;
+
+    const ALL_TESTS: [BenchmarkTest; 4] = [
+        BenchmarkTest::SequentialRead,
+        BenchmarkTest::SequentialWrite,
+        BenchmarkTest::RandomRead,
+        BenchmarkTest::RandomWrite,
+    ];
+
+    /// long enough for the unoptimized workers to fill their buffers before the deadline
+    fn config(name: &str, duration: Duration) -> BenchmarkConfig {
+        BenchmarkConfig {
+            path: std::env::temp_dir().join(format!("{}-{}.tmp", name, Uuid::now_v7())),
+            file_size: 2 * SEQUENTIAL_BLOCK,
+            queue_depth: 2,
+            duration,
+            tests: ALL_TESTS.to_vec(),
+        }
+    }
+
+    #[test]
+    fn percentiles_use_the_nearest_rank() {
+        let mut samples: Vec<u32> = (1..=1000).rev().collect();
+
+        let stats = latency_stats(&mut samples);
+
+        assert_eq!((stats.min, stats.max), (1, 1000));
+        assert_eq!(stats.mean, 500.5);
+        assert_eq!((stats.p50, stats.p90, stats.p99, stats.p999), (500, 900, 990, 999));
+    }
+
+    #[test]
+    fn few_samples_give_their_own_percentiles() {
+        let stats = latency_stats(&mut [7]);
+        assert_eq!((stats.min, stats.p50, stats.p999, stats.max), (7, 7, 7, 7));
+
+        let stats = latency_stats(&mut [30, 10]);
+        assert_eq!((stats.p50, stats.p90), (10, 30));
+
+        let stats = latency_stats(&mut []);
+        assert_eq!((stats.min, stats.max, stats.mean), (0, 0, 0.0));
+    }
+
+    #[test]
+    fn runs_every_test_and_removes_the_file() {
+        let config = config("benchmark-run", Duration::from_secs(1));
+
+        let (results, _) = run_benchmark(&config, &BenchmarkCounters::default(), &AtomicBool::new(false)).unwrap();
+
+        assert_eq!(results.iter().map(|result| result.test).collect::<Vec<_>>(), ALL_TESTS);
+        assert!(results.iter().all(|result| result.operations > 0 && result.bytes == result.operations * result.block_size));
+        assert!(results.iter().all(|result| result.latency.min <= result.latency.p50 && result.latency.p50 <= result.latency.max));
+        assert!(!config.path.exists());
+    }
+
+    /// tmpfs used to refuse `O_DIRECT`, the tests then run on a buffered file
+    #[test]
+    fn tests_run_on_a_buffered_file() {
+        let config = config("benchmark-buffered", Duration::from_secs(1));
+        fs::write(&config.path, vec![0u8; config.file_size as usize]).unwrap();
+        let test_file = TestFile { path: config.path.clone(), cache_bypassed: false };
+
+        for test in ALL_TESTS {
+            let result = run_test(&test_file, test, &config, &BenchmarkCounters::default(), &AtomicBool::new(false)).unwrap();
+
+            assert!(result.operations > 0);
+        }
+
+        drop(test_file);
+        assert!(!config.path.exists());
+    }
+
+    #[test]
+    fn cancelled_runs_stop_early() {
+        let config = config("benchmark-cancel", Duration::from_secs(30));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let started = Instant::now();
+
+        let canceller = {
+            let cancel = cancel.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(200));
+                cancel.store(true, Ordering::Relaxed);
+            })
+        };
+
+        let (results, _) = run_benchmark(&config, &BenchmarkCounters::default(), &cancel).unwrap();
+        canceller.join().unwrap();
+
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(results.len() < ALL_TESTS.len());
+        assert!(!config.path.exists());
+    }
+
+    #[test]
+    fn runs_cancelled_before_the_start_write_nothing() {
+        let config = config("benchmark-cancelled", Duration::from_secs(30));
+        let counters = BenchmarkCounters::default();
+
+        let (results, _) = run_benchmark(&config, &counters, &AtomicBool::new(true)).unwrap();
+
+        assert!(results.is_empty());
+        assert_eq!(counters.bytes.load(Ordering::Relaxed), 0);
+        assert!(!config.path.exists());
+    }
+}
@@ -0,0 +1,92 @@
+use std::{cmp::Reverse, fs, path::PathBuf};
+
+use anyhow::{anyhow, Result};
+use uuid::Uuid;
+
+use crate::models::{BenchmarkChange, BenchmarkComparison, BenchmarkRun};
+
+/// One JSON file per completed run under `<exec dir>/benchmarks`
+pub struct RunStore {
+    directory: PathBuf,
+}
+
+impl RunStore {
+    pub fn new(directory: PathBuf) -> Self {
+        Self { directory }
+    }
+
+    pub fn save(&self, run: &BenchmarkRun) -> Result<()> {
+        fs::create_dir_all(&self.directory)?;
+        fs::write(self.path_of(run.run_id), serde_json::to_vec(run)?)?;
+
+        Ok(())
+    }
+
+    pub fn load(&self, run_id: Uuid) -> Result<BenchmarkRun> {
+        let content = fs::read(self.path_of(run_id)).map_err(|err| anyhow!("benchmark {} not found: {}", run_id, err))?;
+
+        Ok(serde_json::from_slice(&content)?)
+    }
+
+    /// Saved runs, newest first
+    pub fn list(&self) -> Result<Vec<BenchmarkRun>> {
+        let Ok(entries) = fs::read_dir(&self.directory) else {
+            return Ok(vec![]);
+        };
+
+        let mut runs: Vec<BenchmarkRun> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let run_id = entry.path().file_stem()?.to_str()?.parse::<Uuid>().ok()?;
+                self.load(run_id).ok()
+            })
+            .collect();
+
+        runs.sort_by_key(|item| Reverse(item.finished_on));
+        Ok(runs)
+    }
+
+    pub fn delete(&self, run_id: Uuid) -> Result<()> {
+        fs::remove_file(self.path_of(run_id))?;
+
+        Ok(())
+    }
+
+    fn path_of(&self, run_id: Uuid) -> PathBuf {
+        self.directory.join(format!("{}.json", run_id))
+    }
+}
+
+/// Throughput, IOPS and tail latency of the tests both runs share
+pub fn compare_runs(previous: &BenchmarkRun, current: &BenchmarkRun) -> BenchmarkComparison {
+    let changes = current
+        .results
+        .iter()
+        .filter_map(|current| {
+            let previous = previous.results.iter().find(|result| result.test == current.test)?;
+
+            let throughput_change = if previous.bytes_per_sec == 0 {
+                0.0
+            } else {
+                (current.bytes_per_sec as f64 / previous.bytes_per_sec as f64 - 1.0) * 100.0
+            };
+
+            Some(BenchmarkChange {
+                test: current.test,
+                previous_bytes_per_sec: previous.bytes_per_sec,
+                current_bytes_per_sec: current.bytes_per_sec,
+                throughput_change,
+                previous_iops: previous.iops,
+                current_iops: current.iops,
+                previous_p99: previous.latency.p99,
+                current_p99: current.latency.p99,
+            })
+        })
+        .collect();
+
+    BenchmarkComparison {
+        previous_run_id: previous.run_id,
+        current_run_id: current.run_id,
+        changes,
+    }
+}
@@ -0,0 +1,116 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use hashbrown::HashMap;
+use log::*;
+
+use crate::models::CleanupCategory;
+
+const BUILT_IN: &str = include_str!("categories.json");
+
+/// Categories for this platform, a `cleanup.json` in `directory` replaces the built-in list
+pub fn load_categories(directory: &Path) -> Vec<CleanupCategory> {
+    let path = directory.join("cleanup.json");
+
+    let categories = match fs::read_to_string(&path) {
+        Ok(content) => parse_categories(&content).unwrap_or_else(|err| {
+            warn!("ignoring {}: {}", path.display(), err);
+            parse_categories(BUILT_IN).expect("built-in cleanup categories")
+        }),
+        Err(_) => parse_categories(BUILT_IN).expect("built-in cleanup categories"),
+    };
+
+    categories
+        .into_iter()
+        .filter(|category| category.platforms.is_empty() || category.platforms.iter().any(|platform| platform == env::consts::OS))
+        .collect()
+}
+
+pub fn parse_categories(content: &str) -> Result<Vec<CleanupCategory>> {
+    Ok(serde_json::from_str(content)?)
+}
+
+/// Folders the `{name}` placeholders of a pattern stand for
+pub struct Placeholders {
+    values: HashMap<&'static str, PathBuf>,
+}
+
+impl Placeholders {
+    pub fn new(exec_dir: &Path) -> Self {
+        let mut values = HashMap::new();
+
+        values.insert("temp", env::temp_dir());
+        values.insert("exec_dir", exec_dir.to_owned());
+
+        let known = [
+            ("home", dirs::home_dir()),
+            ("cache", dirs::cache_dir()),
+            ("local_app_data", dirs::data_local_dir()),
+            ("app_data", dirs::data_dir()),
+            ("windows", env::var_os("SystemRoot").map(PathBuf::from)),
+            ("program_data", env::var_os("ProgramData").map(PathBuf::from)),
+        ];
+
+        for (name, value) in known {
+            if let Some(value) = value {
+                values.insert(name, value);
+            }
+        }
+
+        Self { values }
+    }
+
+    /// The pattern with its placeholder replaced, `None` when the folder does not exist on this
+    /// system or the pattern would not be anchored to one
+    pub fn expand(&self, pattern: &str) -> Option<PathBuf> {
+        let expanded = match pattern.strip_prefix('{') {
+            Some(rest) => {
+                let (name, rest) = rest.split_once('}')?;
+                let base = self.values.get(name)?;
+
+                base.join(rest.trim_start_matches(['/', '\\']))
+            }
+            None => PathBuf::from(pattern),
+        };
+
+        // a relative pattern would resolve against whatever the working directory is
+        expanded.is_absolute().then_some(expanded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_categories_parse() {
+        let categories = parse_categories(BUILT_IN).unwrap();
+
+        assert!(!categories.is_empty());
+        assert!(categories.iter().all(|category| !category.patterns.is_empty()));
+    }
+
+    #[test]
+    fn expands_known_placeholders_only() {
+        let exec_dir = env::temp_dir().join("toolbox");
+        let placeholders = Placeholders::new(&exec_dir);
+
+        assert_eq!(placeholders.expand("{exec_dir}/logs/*.log"), Some(exec_dir.join("logs/*.log")));
+        assert_eq!(placeholders.expand("{temp}\\*.tmp"), Some(env::temp_dir().join("*.tmp")));
+        assert_eq!(placeholders.expand("{nowhere}/*.tmp"), None);
+        assert_eq!(placeholders.expand("{temp"), None);
+    }
+
+    #[test]
+    fn rejects_relative_patterns() {
+        let placeholders = Placeholders::new(&env::temp_dir());
+        let absolute = env::temp_dir().join("*.tmp");
+
+        assert_eq!(placeholders.expand("cache/*.tmp"), None);
+        assert_eq!(placeholders.expand("../*.tmp"), None);
+        assert_eq!(placeholders.expand(&absolute.to_string_lossy()), Some(absolute));
+    }
+}
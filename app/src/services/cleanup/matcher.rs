@@ -0,0 +1,250 @@
+use std::{
+    fs::{self, Metadata},
+    path::{Component, Path, PathBuf},
+};
+
+/// Calls `visit` for every regular file matching `pattern`. Symbolic links are neither followed
+/// nor reported, so a match never leads outside the folders the pattern names.
+pub fn find_files(pattern: &Path, visit: &mut impl FnMut(PathBuf, Metadata)) {
+    let mut base = PathBuf::new();
+    let mut segments = Vec::new();
+
+    for component in pattern.components() {
+        match component {
+            Component::Normal(name) if !segments.is_empty() || is_wildcard(&name.to_string_lossy()) => {
+                segments.push(name.to_string_lossy().into_owned());
+            }
+            // `..` after a wildcard could climb out of the matched folder
+            Component::ParentDir if !segments.is_empty() => return,
+            component => base.push(component),
+        }
+    }
+
+    if segments.is_empty() {
+        // a single file named in full
+        match fs::symlink_metadata(&base) {
+            Ok(metadata) if metadata.is_file() => visit(base, metadata),
+            _ => {}
+        }
+
+        return;
+    }
+
+    walk(&base, &segments, visit);
+}
+
+fn walk(directory: &Path, segments: &[String], visit: &mut impl FnMut(PathBuf, Metadata)) {
+    let Some((segment, rest)) = segments.split_first() else {
+        return;
+    };
+
+    if segment == "**" {
+        // zero folders deep, then one more level with `**` still in front
+        if !rest.is_empty() {
+            walk(directory, rest, visit);
+        }
+    } else if !is_wildcard(segment) {
+        let path = directory.join(segment);
+
+        match fs::symlink_metadata(&path) {
+            Ok(metadata) if rest.is_empty() && metadata.is_file() => visit(path, metadata),
+            Ok(metadata) if !rest.is_empty() && metadata.is_dir() => walk(&path, rest, visit),
+            _ => {}
+        }
+
+        return;
+    }
+
+    let Ok(entries) = fs::read_dir(directory) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+
+        let last = rest.is_empty();
+
+        if segment == "**" {
+            // `**` also matches the files at every depth when nothing follows it
+            match (file_type.is_dir(), file_type.is_file() && last) {
+                (true, _) => walk(&entry.path(), segments, visit),
+                (_, true) => visit_entry(&entry, visit),
+                _ => {}
+            }
+
+            continue;
+        }
+
+        if !matches_name(segment, &entry.file_name().to_string_lossy()) {
+            continue;
+        }
+
+        if last && file_type.is_file() {
+            visit_entry(&entry, visit);
+        } else if !last && file_type.is_dir() {
+            walk(&entry.path(), rest, visit);
+        }
+    }
+}
+
+fn visit_entry(entry: &fs::DirEntry, visit: &mut impl FnMut(PathBuf, Metadata)) {
+    if let Ok(metadata) = entry.metadata() {
+        visit(entry.path(), metadata);
+    }
+}
+
+fn is_wildcard(segment: &str) -> bool {
+    segment.contains(['*', '?'])
+}
+
+/// `*` matches any run of characters and `?` a single one, case insensitive on Windows
+pub fn matches_name(pattern: &str, name: &str) -> bool {
+    let (pattern, name): (Vec<char>, Vec<char>) = if cfg!(windows) {
+        (pattern.to_lowercase().chars().collect(), name.to_lowercase().chars().collect())
+    } else {
+        (pattern.chars().collect(), name.chars().collect())
+    };
+
+    let (mut p, mut n) = (0, 0);
+    // position after the last `*` and the name position it was tried at
+    let mut star: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p + 1, n));
+            p += 1;
+        } else if let Some((after, tried)) = star {
+            // let the last `*` swallow one more character
+            p = after;
+            n = tried + 1;
+            star = Some((after, n));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn tree() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("cleanup-matcher-{}", Uuid::now_v7()));
+
+        for file in ["a.tmp", "b.log", "one/c.tmp", "one/two/d.tmp", "one/two/e.txt", "other/f.tmp"] {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, b"x").unwrap();
+        }
+
+        root
+    }
+
+    fn find(root: &Path, pattern: &str) -> Vec<String> {
+        let mut found = Vec::new();
+
+        find_files(&root.join(pattern), &mut |path, metadata| {
+            assert!(metadata.is_file());
+            found.push(path.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/"));
+        });
+
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn star_and_question_mark_match_within_a_name() {
+        assert!(matches_name("*.tmp", "a.tmp"));
+        assert!(matches_name("*.tmp", ".tmp"));
+        assert!(!matches_name("*.tmp", "a.tmp.bak"));
+        assert!(matches_name("?.log", "a.log"));
+        assert!(!matches_name("?.log", "ab.log"));
+        assert!(matches_name("*", ""));
+        assert!(!matches_name("?", ""));
+        assert!(matches_name("**", "anything"));
+    }
+
+    #[test]
+    fn star_backtracks_to_later_matches() {
+        assert!(matches_name("a*b*c", "aXbYbZc"));
+        assert!(matches_name("*ab", "aab"));
+        assert!(matches_name("*a*a", "banana"));
+        assert!(!matches_name("a*b*c", "aXbYcZ"));
+        assert!(matches_name("crash*.dmp", "crash-2024-01-01.dmp"));
+    }
+
+    #[test]
+    fn double_star_spans_any_number_of_folders() {
+        let root = tree();
+
+        let shallow = find(&root, "*.tmp");
+        let nested = find(&root, "one/*/*.tmp");
+        let deep = find(&root, "one/**/*.tmp");
+        let everything = find(&root, "one/**");
+
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(shallow, vec!["a.tmp"]);
+        assert_eq!(nested, vec!["one/two/d.tmp"]);
+        assert_eq!(deep, vec!["one/c.tmp", "one/two/d.tmp"]);
+        assert_eq!(everything, vec!["one/c.tmp", "one/two/d.tmp", "one/two/e.txt"]);
+    }
+
+    #[test]
+    fn plain_paths_name_a_single_file() {
+        let root = tree();
+
+        let file = find(&root, "one/c.tmp");
+        let folder = find(&root, "one");
+        let below_wildcard = find(&root, "*/two/d.tmp");
+
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(file, vec!["one/c.tmp"]);
+        assert!(folder.is_empty());
+        assert_eq!(below_wildcard, vec!["one/two/d.tmp"]);
+    }
+
+    #[test]
+    fn parent_folder_only_before_the_first_wildcard() {
+        let root = tree();
+
+        let before = find(&root, "one/../*.tmp");
+        let after = find(&root, "*/../a.tmp");
+
+        fs::remove_dir_all(&root).unwrap();
+
+        // the folder is kept as written
+        assert_eq!(before, vec!["one/../a.tmp"]);
+        assert!(after.is_empty());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn symbolic_links_are_neither_followed_nor_reported() {
+        use std::os::unix::fs::symlink;
+
+        let root = tree();
+        symlink(root.join("other"), root.join("one/link")).unwrap();
+        symlink(root.join("a.tmp"), root.join("one/a-link.tmp")).unwrap();
+
+        let deep = find(&root, "one/**/*.tmp");
+        let through_link = find(&root, "one/*/f.tmp");
+        let file = find(&root, "one/a-link.tmp");
+
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(deep, vec!["one/c.tmp", "one/two/d.tmp"]);
+        assert!(through_link.is_empty());
+        assert!(file.is_empty());
+    }
+}
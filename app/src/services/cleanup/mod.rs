@@ -0,0 +1,258 @@
+use std::{cmp::Reverse, path::Path, sync::Arc, time::SystemTime};
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use hashbrown::HashSet;
+use log::*;
+
+use crate::{
+    models::{
+        CleanupCategory, CleanupCategoryPreview, CleanupCategoryResult, CleanupEntry, CleanupFailure, CleanupPreview, CleanupResult,
+    },
+    services::{normalize_path, HandleManager},
+    utils::format_bytes,
+};
+
+mod catalog;
+mod matcher;
+mod sweep;
+
+pub use self::catalog::{load_categories, Placeholders};
+pub use self::sweep::{collect_candidates, remove_candidate, Candidate, Removal};
+
+/// Removes old files from temporary folders, caches and crash dump locations.
+/// Files a process holds open are skipped and reported on their own.
+#[derive(Clone)]
+pub struct CleanupService {
+    handle_manager: HandleManager,
+    categories: Arc<Vec<CleanupCategory>>,
+    placeholders: Arc<Placeholders>,
+}
+
+impl CleanupService {
+    pub fn new(handle_manager: HandleManager, exec_dir: &Path) -> Self {
+        Self {
+            handle_manager,
+            categories: Arc::new(load_categories(exec_dir)),
+            placeholders: Arc::new(Placeholders::new(exec_dir)),
+        }
+    }
+
+    pub fn get_categories(&self) -> Vec<CleanupCategory> {
+        self.categories.to_vec()
+    }
+
+    /// What deleting the categories would remove, every category when `ids` is empty
+    pub fn preview(&self, ids: &[String], limit: usize) -> Result<CleanupPreview> {
+        let categories = if ids.is_empty() {
+            self.categories.iter().collect()
+        } else {
+            self.find_categories(ids)?
+        };
+
+        let open_files = self.open_files();
+        let now = SystemTime::now();
+
+        let categories: Vec<CleanupCategoryPreview> = categories
+            .into_iter()
+            .map(|category| {
+                let (in_use, mut candidates): (Vec<Candidate>, Vec<Candidate>) = collect_candidates(category, &self.placeholders, now)
+                    .into_iter()
+                    .partition(|candidate| is_open(&open_files, candidate));
+
+                candidates.sort_by_key(|item| Reverse(item.size));
+
+                let bytes = candidates.iter().map(|candidate| candidate.size).sum();
+
+                CleanupCategoryPreview {
+                    id: category.id.clone(),
+                    name: category.name.clone(),
+                    files: candidates.len() as u64,
+                    bytes,
+                    bytes_formatted: format_bytes(bytes),
+                    entries: candidates.iter().take(limit).map(to_entry).collect(),
+                    in_use: in_use.iter().map(|candidate| candidate.path.to_string_lossy().into_owned()).collect(),
+                }
+            })
+            .collect();
+
+        let total_bytes = categories.iter().map(|category| category.bytes).sum();
+
+        Ok(CleanupPreview {
+            categories,
+            total_bytes,
+            total_bytes_formatted: format_bytes(total_bytes),
+        })
+    }
+
+    /// Deletes the files of the chosen categories. Matches are looked up again,
+    /// nothing the preview listed is removed unless it still qualifies.
+    pub fn run(&self, ids: &[String]) -> Result<CleanupResult> {
+        if ids.is_empty() {
+            bail!("no category selected");
+        }
+
+        let categories = self.find_categories(ids)?;
+        let open_files = self.open_files();
+        let now = SystemTime::now();
+
+        let categories: Vec<CleanupCategoryResult> = categories
+            .into_iter()
+            .map(|category| {
+                let mut result = CleanupCategoryResult {
+                    id: category.id.clone(),
+                    name: category.name.clone(),
+                    deleted: 0,
+                    bytes_freed: 0,
+                    bytes_freed_formatted: String::new(),
+                    in_use: vec![],
+                    failed: vec![],
+                };
+
+                for candidate in collect_candidates(category, &self.placeholders, now) {
+                    let path = candidate.path.to_string_lossy().into_owned();
+
+                    let removal = if is_open(&open_files, &candidate) {
+                        Removal::InUse
+                    } else {
+                        remove_candidate(&candidate)
+                    };
+
+                    match removal {
+                        Removal::Deleted => {
+                            result.deleted += 1;
+                            result.bytes_freed += candidate.size;
+                        }
+                        Removal::InUse => result.in_use.push(path),
+                        Removal::Gone => {}
+                        Removal::Failed(err) => result.failed.push(CleanupFailure { path, error: err.to_string() }),
+                    }
+                }
+
+                result.bytes_freed_formatted = format_bytes(result.bytes_freed);
+                info!("cleanup {}: {} files, {} freed, {} in use, {} failed", category.id, result.deleted, result.bytes_freed_formatted, result.in_use.len(), result.failed.len());
+
+                result
+            })
+            .collect();
+
+        let deleted = categories.iter().map(|category| category.deleted).sum();
+        let bytes_freed = categories.iter().map(|category| category.bytes_freed).sum();
+
+        Ok(CleanupResult {
+            categories,
+            deleted,
+            bytes_freed,
+            bytes_freed_formatted: format_bytes(bytes_freed),
+        })
+    }
+
+    fn find_categories(&self, ids: &[String]) -> Result<Vec<&CleanupCategory>> {
+        ids.iter()
+            .map(|id| match self.categories.iter().find(|category| &category.id == id) {
+                Some(category) => Ok(category),
+                None => bail!("unknown cleanup category {}", id),
+            })
+            .collect()
+    }
+
+    /// Without the handle list only the errors of the delete itself reveal files in use
+    fn open_files(&self) -> HashSet<String> {
+        self.handle_manager.get_open_file_paths().unwrap_or_else(|err| {
+            warn!("could not list open files: {}", err);
+            HashSet::new()
+        })
+    }
+}
+
+fn is_open(open_files: &HashSet<String>, candidate: &Candidate) -> bool {
+    open_files.contains(&normalize_path(&candidate.path.to_string_lossy()))
+}
+
+fn to_entry(candidate: &Candidate) -> CleanupEntry {
+    CleanupEntry {
+        path: candidate.path.to_string_lossy().into_owned(),
+        size: candidate.size,
+        size_formatted: format_bytes(candidate.size),
+        modified_on: candidate.modified.map(DateTime::<Utc>::from),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use uuid::Uuid;
+
+    use crate::services::ProcessManager;
+
+    use super::*;
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn skips_files_held_open() {
+        use std::{fs::File, process::{Command, Stdio}};
+
+        let root = std::env::temp_dir().join(format!("cleanup-{}", Uuid::now_v7()));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("free.tmp"), b"free").unwrap();
+        fs::write(root.join("held.tmp"), b"held").unwrap();
+
+        let mut child = Command::new("sleep")
+            .arg("30")
+            .stdin(File::open(root.join("held.tmp")).unwrap())
+            .stdout(Stdio::null())
+            .spawn()
+            .unwrap();
+
+        let service = CleanupService {
+            handle_manager: HandleManager::new(ProcessManager::new()),
+            categories: Arc::new(vec![CleanupCategory {
+                id: "test".to_string(),
+                name: "Test".to_string(),
+                description: String::new(),
+                platforms: vec![],
+                patterns: vec![root.join("*.tmp").to_string_lossy().into_owned()],
+                exclude: vec![],
+                min_age_days: 0,
+                selected: true,
+            }]),
+            placeholders: Arc::new(Placeholders::new(&root)),
+        };
+
+        let preview = service.preview(&[], 10);
+        let result = service.run(&["test".to_string()]);
+        let held_remains = root.join("held.tmp").exists();
+        let free_remains = root.join("free.tmp").exists();
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        let held = root.join("held.tmp").to_string_lossy().into_owned();
+        let preview = preview.unwrap();
+        let result = result.unwrap();
+
+        assert_eq!(preview.categories[0].files, 1);
+        assert_eq!(preview.categories[0].in_use, vec![held.clone()]);
+        assert_eq!(result.deleted, 1);
+        assert_eq!(result.bytes_freed, 4);
+        assert_eq!(result.categories[0].in_use, vec![held]);
+        assert!(held_remains);
+        assert!(!free_remains);
+    }
+
+    #[test]
+    fn unknown_categories_are_rejected() {
+        let root = std::env::temp_dir().join(format!("cleanup-{}", Uuid::now_v7()));
+        let service = CleanupService {
+            handle_manager: HandleManager::new(ProcessManager::new()),
+            categories: Arc::new(vec![]),
+            placeholders: Arc::new(Placeholders::new(&root)),
+        };
+
+        assert!(service.run(&[]).is_err());
+        assert!(service.run(&["missing".to_string()]).is_err());
+        assert!(service.preview(&["missing".to_string()], 10).is_err());
+    }
+}
@@ -0,0 +1,205 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use hashbrown::HashSet;
+use log::*;
+
+use crate::models::CleanupCategory;
+
+use super::{catalog::Placeholders, matcher::{find_files, matches_name}};
+
+/// A file old enough to be removed
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+}
+
+pub enum Removal {
+    Deleted,
+    InUse,
+    /// removed by someone else in the meantime
+    Gone,
+    Failed(io::Error),
+}
+
+/// Files matching any pattern but no `exclude` name of the category and last modified
+/// at least `min_age_days` ago. A category whose age does not fit a `Duration` matches nothing.
+pub fn collect_candidates(category: &CleanupCategory, placeholders: &Placeholders, now: SystemTime) -> Vec<Candidate> {
+    let Some(min_age) = category.min_age_days.checked_mul(24 * 60 * 60).map(Duration::from_secs) else {
+        warn!("skipping cleanup category {}: minAgeDays {} is out of range", category.id, category.min_age_days);
+        return vec![];
+    };
+
+    let mut seen = HashSet::new();
+    let mut candidates = Vec::new();
+
+    for pattern in category.patterns.iter().filter_map(|pattern| placeholders.expand(pattern)) {
+        find_files(&pattern, &mut |path, metadata| {
+            if is_excluded(category, &path) {
+                return;
+            }
+
+            let modified = metadata.modified().ok();
+            // unknown or future times count as brand new
+            let age = modified.and_then(|modified| now.duration_since(modified).ok()).unwrap_or_default();
+
+            if age >= min_age && seen.insert(path.clone()) {
+                candidates.push(Candidate { path, size: metadata.len(), modified });
+            }
+        });
+    }
+
+    candidates
+}
+
+fn is_excluded(category: &CleanupCategory, path: &Path) -> bool {
+    let Some(name) = path.file_name() else {
+        return false;
+    };
+
+    let name = name.to_string_lossy();
+    category.exclude.iter().any(|pattern| matches_name(pattern, &name))
+}
+
+pub fn remove_candidate(candidate: &Candidate) -> Removal {
+    match fs::remove_file(&candidate.path) {
+        Ok(()) => Removal::Deleted,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Removal::Gone,
+        Err(err) if is_in_use(&err) => Removal::InUse,
+        Err(err) => Removal::Failed(err),
+    }
+}
+
+/// Windows refuses to delete files opened without delete sharing
+#[cfg(windows)]
+fn is_in_use(err: &io::Error) -> bool {
+    use winapi::shared::winerror::{ERROR_LOCK_VIOLATION, ERROR_SHARING_VIOLATION};
+
+    matches!(err.raw_os_error(), Some(code) if code as u32 == ERROR_SHARING_VIOLATION || code as u32 == ERROR_LOCK_VIOLATION)
+}
+
+/// Unlinking open files succeeds, open ones are caught beforehand through the process handles
+#[cfg(unix)]
+fn is_in_use(err: &io::Error) -> bool {
+    matches!(err.raw_os_error(), Some(libc::EBUSY | libc::ETXTBSY))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use uuid::Uuid;
+
+    use super::*;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn category(patterns: &[PathBuf], exclude: &[&str], min_age_days: u64) -> CleanupCategory {
+        CleanupCategory {
+            id: "test".to_string(),
+            name: "Test".to_string(),
+            description: String::new(),
+            platforms: vec![],
+            patterns: patterns.iter().map(|pattern| pattern.to_string_lossy().into_owned()).collect(),
+            exclude: exclude.iter().map(|pattern| pattern.to_string()).collect(),
+            min_age_days,
+            selected: false,
+        }
+    }
+
+    fn create(path: &Path, modified: SystemTime) {
+        fs::write(path, b"data").unwrap();
+        File::options().write(true).open(path).unwrap().set_modified(modified).unwrap();
+    }
+
+    fn names(candidates: &[Candidate]) -> Vec<String> {
+        let mut names: Vec<String> = candidates.iter().map(|candidate| candidate.path.file_name().unwrap().to_string_lossy().into_owned()).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn keeps_recent_and_excluded_files() {
+        let root = std::env::temp_dir().join(format!("cleanup-sweep-{}", Uuid::now_v7()));
+        fs::create_dir_all(&root).unwrap();
+
+        let now = SystemTime::now();
+        create(&root.join("old.tmp"), now - DAY * 10);
+        create(&root.join("new.tmp"), now - DAY);
+        create(&root.join("keep.tmp"), now - DAY * 10);
+        create(&root.join("old.log"), now - DAY * 10);
+
+        let placeholders = Placeholders::new(&root);
+        // both patterns match old.tmp, it is listed once
+        let category = category(&[root.join("*.tmp"), root.join("old.*")], &["keep*"], 7);
+
+        let candidates = collect_candidates(&category, &placeholders, now);
+
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(names(&candidates), vec!["old.log", "old.tmp"]);
+        assert_eq!(candidates[0].size, 4);
+    }
+
+    #[test]
+    fn zero_age_takes_everything_and_future_times_count_as_new() {
+        let root = std::env::temp_dir().join(format!("cleanup-sweep-{}", Uuid::now_v7()));
+        fs::create_dir_all(&root).unwrap();
+
+        let now = SystemTime::now();
+        create(&root.join("a.tmp"), now);
+        create(&root.join("b.tmp"), now + DAY);
+
+        let placeholders = Placeholders::new(&root);
+
+        let everything = collect_candidates(&category(&[root.join("*.tmp")], &[], 0), &placeholders, now);
+        let aged = collect_candidates(&category(&[root.join("*.tmp")], &[], 1), &placeholders, now);
+
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(names(&everything), vec!["a.tmp", "b.tmp"]);
+        assert!(aged.is_empty());
+    }
+
+    #[test]
+    fn out_of_range_age_skips_the_category() {
+        let root = std::env::temp_dir().join(format!("cleanup-sweep-{}", Uuid::now_v7()));
+        fs::create_dir_all(&root).unwrap();
+        create(&root.join("a.tmp"), SystemTime::UNIX_EPOCH);
+
+        let placeholders = Placeholders::new(&root);
+        let candidates = collect_candidates(&category(&[root.join("*.tmp")], &[], u64::MAX / 2), &placeholders, SystemTime::now());
+
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(candidates.is_empty());
+    }
+
+    #[test]
+    fn relative_and_unknown_patterns_match_nothing() {
+        let root = std::env::temp_dir().join(format!("cleanup-sweep-{}", Uuid::now_v7()));
+        let placeholders = Placeholders::new(&root);
+        let category = CleanupCategory {
+            patterns: vec!["*.tmp".to_string(), "{nowhere}/*.tmp".to_string()],
+            ..category(&[], &[], 0)
+        };
+
+        assert!(collect_candidates(&category, &placeholders, SystemTime::now()).is_empty());
+    }
+
+    #[test]
+    fn removing_a_missing_file_reports_it_gone() {
+        let candidate = Candidate {
+            path: std::env::temp_dir().join(format!("cleanup-sweep-{}", Uuid::now_v7())),
+            size: 0,
+            modified: None,
+        };
+
+        assert!(matches!(remove_candidate(&candidate), Removal::Gone));
+    }
+}
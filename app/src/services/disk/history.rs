@@ -0,0 +1,306 @@
+use std::{fs, path::PathBuf, time::Duration};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    models::{DiskForecast, DiskPartition, DiskUsagePoint},
+    services::{fit_trend, time_to_threshold},
+    utils::format_bytes,
+};
+
+/// Samples older than this are dropped from the store
+const RETENTION: Duration = Duration::from_hours(90 * 24);
+
+/// How much history a projection needs and how far ahead it warns
+#[derive(Debug, Clone)]
+pub struct DiskForecastConfig {
+    /// only the samples within this window are fitted, older growth says little about next week
+    pub window: Duration,
+    pub min_samples: usize,
+    /// time between the first and last sample, a few minutes of history would project wildly
+    pub min_span: Duration,
+    /// partitions projected to run full within this are flagged
+    pub horizon: Duration,
+}
+
+impl Default for DiskForecastConfig {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_hours(30 * 24),
+            min_samples: 6,
+            min_span: Duration::from_hours(24),
+            horizon: Duration::from_hours(14 * 24),
+        }
+    }
+}
+
+/// Fitted growth of one partition
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Projection {
+    pub bytes_per_day: f64,
+    pub r_squared: f64,
+    /// `None` when usage is flat or shrinking
+    pub full_in: Option<Duration>,
+}
+
+/// Used bytes per partition over time, kept in a JSON file next to the executable
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageHistory {
+    #[serde(skip)]
+    path: PathBuf,
+    partitions: HashMap<String, Vec<DiskUsagePoint>>,
+}
+
+impl UsageHistory {
+    /// Reads the store at `path`, a missing or unreadable file starts an empty history
+    pub fn load(path: PathBuf) -> Self {
+        let mut history: Self = fs::read(&path)
+            .ok()
+            .and_then(|content| serde_json::from_slice(&content).ok())
+            .unwrap_or_default();
+
+        history.path = path;
+        history
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(&self.path, serde_json::to_vec(self)?)?;
+
+        Ok(())
+    }
+
+    pub fn record(&mut self, taken_on: DateTime<Utc>, partitions: &[DiskPartition]) {
+        let oldest = taken_on - chrono::Duration::from_std(RETENTION).unwrap_or(chrono::Duration::zero());
+
+        for partition in partitions.iter().filter(|partition| partition.total > 0) {
+            let points = self.partitions.entry(partition.name.clone()).or_default();
+
+            // a resized or replaced volume restarts the series
+            if points.last().is_some_and(|point| point.total != partition.total) {
+                points.clear();
+            }
+
+            points.push(DiskUsagePoint {
+                timestamp: taken_on,
+                used: partition.used,
+                total: partition.total,
+            });
+        }
+
+        for points in self.partitions.values_mut() {
+            points.retain(|point| point.timestamp >= oldest);
+        }
+
+        self.partitions.retain(|_, points| !points.is_empty());
+    }
+
+    pub fn points(&self, name: &str) -> &[DiskUsagePoint] {
+        self.partitions.get(name).map(Vec::as_slice).unwrap_or_default()
+    }
+}
+
+/// Projects when `partition` runs full from the samples within the configured window
+pub fn forecast(partition: &DiskPartition, points: &[DiskUsagePoint], config: &DiskForecastConfig, now: DateTime<Utc>) -> DiskForecast {
+    let since = now - chrono::Duration::from_std(config.window).unwrap_or(chrono::Duration::zero());
+    let samples: Vec<DiskUsagePoint> = points.iter().filter(|point| point.timestamp >= since).cloned().collect();
+
+    let Projection { bytes_per_day, r_squared, full_in } = project(&samples, partition.total, config).unwrap_or(Projection {
+        bytes_per_day: 0.0,
+        r_squared: 0.0,
+        full_in: None,
+    });
+
+    // the projection counts from the newest sample, which may be older than now
+    let latest = samples.last().map(|point| point.timestamp).unwrap_or(now);
+    let full_on = full_in.and_then(|duration| chrono::Duration::from_std(duration).ok()).map(|duration| latest + duration);
+    let days_left = full_on.map(|full_on| ((full_on - now).num_seconds() as f64 / 86_400.0).max(0.0));
+    let horizon = chrono::Duration::from_std(config.horizon).unwrap_or(chrono::Duration::zero());
+
+    DiskForecast {
+        name: partition.name.clone(),
+        total: partition.total,
+        total_formatted: partition.total_formatted.clone(),
+        used: partition.used,
+        used_formatted: partition.used_formatted.clone(),
+        free: partition.free,
+        free_formatted: partition.free_formatted.clone(),
+        bytes_per_day,
+        bytes_per_day_formatted: format_bytes(bytes_per_day.max(0.0) as u64),
+        r_squared,
+        full_on,
+        days_left,
+        within_horizon: full_on.is_some_and(|full_on| full_on <= now + horizon),
+        samples,
+    }
+}
+
+/// Growth per day and time until `total` is reached, `None` while the history is too short
+pub fn project(samples: &[DiskUsagePoint], total: u64, config: &DiskForecastConfig) -> Option<Projection> {
+    let first = samples.first()?;
+    let last = samples.last()?;
+
+    let span = (last.timestamp - first.timestamp).to_std().unwrap_or_default();
+    if samples.len() < config.min_samples || span < config.min_span {
+        return None;
+    }
+
+    let xy: Vec<(f64, f64)> = samples
+        .iter()
+        .map(|point| ((point.timestamp - first.timestamp).num_seconds() as f64 / 86_400.0, point.used as f64))
+        .collect();
+
+    let trend = fit_trend(&xy)?;
+
+    Some(Projection {
+        bytes_per_day: trend.slope,
+        r_squared: trend.r_squared,
+        full_in: time_to_threshold(last.used, total, trend.slope / 1440.0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GIB: u64 = 1024 * 1024 * 1024;
+
+    fn day(day: f64) -> DateTime<Utc> {
+        DateTime::from_timestamp((day * 86_400.0) as i64, 0).unwrap()
+    }
+
+    fn partition(used: u64, total: u64) -> DiskPartition {
+        DiskPartition {
+            name: "/".into(),
+            fs_type: None,
+            total,
+            total_formatted: format_bytes(total),
+            free: total - used,
+            free_formatted: format_bytes(total - used),
+            used,
+            used_formatted: format_bytes(used),
+        }
+    }
+
+    /// One sample a day with `used(day)` bytes of 200 GiB
+    fn daily(days: usize, used: impl Fn(u64) -> u64) -> Vec<DiskUsagePoint> {
+        (0..days as u64)
+            .map(|index| DiskUsagePoint {
+                timestamp: day(index as f64),
+                used: used(index),
+                total: 200 * GIB,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn linear_growth_projects_when_the_disk_fills() {
+        let samples = daily(10, |index| 100 * GIB + index * GIB);
+
+        let projection = project(&samples, 200 * GIB, &DiskForecastConfig::default()).unwrap();
+
+        assert!((projection.bytes_per_day - GIB as f64).abs() < 1.0);
+        assert!((projection.r_squared - 1.0).abs() < 1e-9);
+        // 91 GiB left at 1 GiB a day
+        assert!(projection.full_in.unwrap().abs_diff(Duration::from_hours(91 * 24)) < Duration::from_secs(60));
+    }
+
+    #[test]
+    fn flat_usage_never_fills() {
+        let projection = project(&daily(10, |_| 100 * GIB), 200 * GIB, &DiskForecastConfig::default()).unwrap();
+
+        assert_eq!(projection.bytes_per_day, 0.0);
+        assert_eq!(projection.full_in, None);
+    }
+
+    #[test]
+    fn shrinking_usage_never_fills() {
+        let projection = project(&daily(10, |index| 150 * GIB - index * GIB), 200 * GIB, &DiskForecastConfig::default()).unwrap();
+
+        assert!(projection.bytes_per_day < 0.0);
+        assert_eq!(projection.full_in, None);
+    }
+
+    #[test]
+    fn too_few_samples_are_not_projected() {
+        assert_eq!(project(&daily(5, |index| 100 * GIB + index * GIB), 200 * GIB, &DiskForecastConfig::default()), None);
+        assert_eq!(project(&[], 200 * GIB, &DiskForecastConfig::default()), None);
+    }
+
+    #[test]
+    fn too_short_a_span_is_not_projected() {
+        let samples: Vec<DiskUsagePoint> = (0..20)
+            .map(|index| DiskUsagePoint {
+                timestamp: day(index as f64 / 24.0),
+                used: 100 * GIB + index * GIB,
+                total: 200 * GIB,
+            })
+            .collect();
+
+        assert_eq!(project(&samples, 200 * GIB, &DiskForecastConfig::default()), None);
+    }
+
+    #[test]
+    fn forecast_flags_partitions_full_within_the_horizon() {
+        let config = DiskForecastConfig::default();
+        let samples = daily(10, |index| 150 * GIB + index * 5 * GIB);
+        let now = day(9.0);
+
+        let forecast = forecast(&partition(195 * GIB, 200 * GIB), &samples, &config, now);
+
+        assert!((forecast.full_on.unwrap() - day(10.0)).num_seconds().abs() < 60);
+        assert!((forecast.days_left.unwrap() - 1.0).abs() < 1e-3);
+        assert!(forecast.within_horizon);
+
+        let slow = daily(10, |index| 100 * GIB + index * GIB);
+        assert!(!super::forecast(&partition(109 * GIB, 200 * GIB), &slow, &config, now).within_horizon);
+    }
+
+    #[test]
+    fn forecast_only_fits_samples_within_the_window() {
+        let config = DiskForecastConfig {
+            window: Duration::from_hours(5 * 24),
+            min_samples: 3,
+            ..Default::default()
+        };
+        // shrank for a month, then grows
+        let samples = daily(40, |index| if index < 34 { 150 * GIB - index * GIB } else { 116 * GIB + (index - 34) * 2 * GIB });
+
+        let forecast = forecast(&partition(126 * GIB, 200 * GIB), &samples, &config, day(39.0));
+
+        assert_eq!(forecast.samples.len(), 6);
+        assert!((forecast.bytes_per_day - (2 * GIB) as f64).abs() < 1.0);
+    }
+
+    #[test]
+    fn resized_volume_restarts_the_series() {
+        let mut history = UsageHistory::default();
+
+        history.record(day(0.0), &[partition(50 * GIB, 100 * GIB)]);
+        history.record(day(1.0), &[partition(60 * GIB, 100 * GIB)]);
+        assert_eq!(history.points("/").len(), 2);
+
+        history.record(day(2.0), &[partition(60 * GIB, 200 * GIB)]);
+
+        assert_eq!(history.points("/").len(), 1);
+        assert_eq!(history.points("/")[0].total, 200 * GIB);
+    }
+
+    #[test]
+    fn samples_past_the_retention_are_dropped() {
+        let mut history = UsageHistory::default();
+
+        history.record(day(0.0), &[partition(50 * GIB, 100 * GIB)]);
+        history.record(day(100.0), &[partition(60 * GIB, 100 * GIB)]);
+
+        assert_eq!(history.points("/").len(), 1);
+        assert!(history.points("missing").is_empty());
+    }
+}
@@ -0,0 +1,340 @@
+use std::{
+    ffi::CString,
+    fs,
+    mem,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use hashbrown::HashSet;
+use log::*;
+
+use crate::{
+    models::{DiskInfo, DiskPartition},
+    utils::format_bytes,
+};
+
+/// Kernel and virtual filesystems that never hold user data
+const PSEUDO_FILESYSTEMS: &[&str] = &[
+    "autofs", "binfmt_misc", "bpf", "cgroup", "cgroup2", "configfs", "debugfs", "devpts", "devtmpfs",
+    "efivarfs", "fusectl", "hugetlbfs", "mqueue", "nsfs", "overlay", "proc", "pstore", "ramfs",
+    "rpc_pipefs", "securityfs", "selinuxfs", "squashfs", "sysfs", "tmpfs", "tracefs",
+];
+
+/// One line of `/proc/self/mountinfo`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountEntry {
+    pub major: u32,
+    pub minor: u32,
+    /// path inside the filesystem that is mounted, `/` unless it is a bind mount
+    pub root: String,
+    pub mount_point: String,
+    pub fs_type: String,
+    pub source: String,
+}
+
+/// Block device metadata from `/sys/block/<disk>`
+#[derive(Debug, Clone, Default)]
+struct BlockDisk {
+    model: Option<String>,
+    serial: Option<String>,
+    interface_type: Option<String>,
+    rotational: Option<bool>,
+    removable: Option<bool>,
+    size: Option<u64>,
+    /// disk size not covered by a partition, zero for a filesystem on the whole disk
+    unpartitioned: Option<u64>,
+}
+
+/// Mounted filesystems grouped under the physical disk backing them
+pub struct DiskSource {
+    include_pseudo: bool,
+}
+
+impl DiskSource {
+    pub fn new() -> Self {
+        Self { include_pseudo: false }
+    }
+
+    pub fn disks(&self) -> Result<Vec<DiskInfo>> {
+        let mountinfo = fs::read_to_string("/proc/self/mountinfo")?;
+        let mut seen = HashSet::new();
+        let mut disks: Vec<(Option<String>, DiskInfo)> = Vec::new();
+
+        for mount in parse_mountinfo(&mountinfo) {
+            if !self.include_pseudo && is_pseudo_filesystem(&mount.fs_type) {
+                continue;
+            }
+
+            // bind mounts and btrfs subvolumes show the same filesystem again
+            if !seen.insert((mount.major, mount.minor, mount.source.clone())) {
+                continue;
+            }
+
+            let Some(partition) = statvfs_partition(&mount) else {
+                continue;
+            };
+
+            let disk_name = parent_disk(&mount);
+
+            if let Some((_, disk)) = disks.iter_mut().find(|(name, _)| disk_name.is_some() && *name == disk_name) {
+                disk.partitions.push(partition);
+                continue;
+            }
+
+            let block = disk_name.as_deref().map(read_block_disk).unwrap_or_default();
+
+            disks.push((disk_name, DiskInfo {
+                model: block.model,
+                disk_type: block.rotational.map(|rotational| if rotational { "HDD" } else { "SSD" }.to_string()),
+                serial: block.serial,
+                interface_type: block.interface_type,
+                size: block.size,
+                size_formatted: block.size.map(format_bytes),
+                unpartitioned: block.unpartitioned,
+                unpartitioned_formatted: block.unpartitioned.map(format_bytes),
+                removable: block.removable,
+                partitions: vec![partition],
+            }));
+        }
+
+        Ok(disks.into_iter().map(|(_, disk)| disk).collect())
+    }
+}
+
+pub fn is_pseudo_filesystem(fs_type: &str) -> bool {
+    // fuse helpers such as fuse.gvfsd-fuse and fuse.portal expose other mounts or nothing at all
+    PSEUDO_FILESYSTEMS.contains(&fs_type) || fs_type == "fuse.gvfsd-fuse" || fs_type == "fuse.portal"
+}
+
+/// Parses `/proc/self/mountinfo`
+///
+/// ```text
+/// 36 35 98:0 /mnt1 /mnt2 rw,noatime master:1 - ext3 /dev/root rw,errors=continue
+/// ```
+pub fn parse_mountinfo(content: &str) -> Vec<MountEntry> {
+    content
+        .lines()
+        .filter_map(|line| {
+            // optional fields before the separator vary in number
+            let (left, right) = line.split_once(" - ")?;
+            let left: Vec<&str> = left.split(' ').collect();
+            let mut right = right.split(' ');
+
+            let (major, minor) = left.get(2)?.split_once(':')?;
+
+            Some(MountEntry {
+                major: major.parse().ok()?,
+                minor: minor.parse().ok()?,
+                root: unescape(left.get(3)?),
+                mount_point: unescape(left.get(4)?),
+                fs_type: right.next()?.to_string(),
+                source: unescape(right.next()?),
+            })
+        })
+        .collect()
+}
+
+/// Undoes the octal escaping of space, tab, newline and backslash, e.g. `\040`
+fn unescape(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let escaped = bytes
+            .get(index + 1..index + 4)
+            .filter(|_| bytes[index] == b'\\')
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u8::from_str_radix(digits, 8).ok());
+
+        match escaped {
+            Some(byte) => {
+                result.push(byte);
+                index += 4;
+            }
+            None => {
+                result.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&result).into_owned()
+}
+
+fn statvfs_partition(mount: &MountEntry) -> Option<DiskPartition> {
+    let path = CString::new(mount.mount_point.as_str()).ok()?;
+
+    let stat = unsafe {
+        let mut stat: libc::statvfs = mem::zeroed();
+        if libc::statvfs(path.as_ptr(), &mut stat) != 0 {
+            debug!("statvfs {} failed", mount.mount_point);
+            return None;
+        }
+        stat
+    };
+
+    let block_size = stat.f_frsize as u64;
+    let total = stat.f_blocks as u64 * block_size;
+
+    // sizeless mounts are pseudo filesystems missing from the list
+    if total == 0 {
+        return None;
+    }
+
+    let free = stat.f_bavail as u64 * block_size;
+    let used = total - stat.f_bfree as u64 * block_size;
+
+    Some(DiskPartition {
+        name: mount.mount_point.clone(),
+        fs_type: Some(mount.fs_type.clone()),
+        total,
+        total_formatted: format_bytes(total),
+        free,
+        free_formatted: format_bytes(free),
+        used,
+        used_formatted: format_bytes(used),
+    })
+}
+
+/// Name under `/sys/block` of the disk holding the mount, `sda` for `sda1`.
+/// Filesystems on anonymous devices (btrfs, nfs) are looked up by their source instead.
+fn parent_disk(mount: &MountEntry) -> Option<String> {
+    let by_number = fs::canonicalize(format!("/sys/dev/block/{}:{}", mount.major, mount.minor)).ok();
+    let by_source = || {
+        let device = fs::canonicalize(&mount.source).ok()?;
+        let name = device.strip_prefix("/dev").ok()?.file_name()?.to_owned();
+        fs::canonicalize(Path::new("/sys/class/block").join(name)).ok()
+    };
+
+    let device = by_number.or_else(by_source)?;
+
+    disk_of(device)
+}
+
+/// Partitions live in a directory of their disk and carry a `partition` file
+fn disk_of(device: PathBuf) -> Option<String> {
+    let disk = if device.join("partition").exists() { device.parent()?.to_path_buf() } else { device };
+
+    disk.file_name().map(|name| name.to_string_lossy().into_owned())
+}
+
+fn read_block_disk(name: &str) -> BlockDisk {
+    let base = Path::new("/sys/block").join(name);
+    let read = |file: &str| fs::read_to_string(base.join(file)).ok().map(|value| value.trim().to_string());
+    let flag = |file: &str| read(file).map(|value| value == "1");
+    // always counted in 512 byte sectors regardless of the logical block size
+    let sectors = |path: PathBuf| fs::read_to_string(path).ok().and_then(|value| value.trim().parse::<u64>().ok()).map(|sectors| sectors * 512);
+
+    let size = sectors(base.join("size"));
+    let partitions: Vec<u64> = fs::read_dir(&base)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().join("partition").exists())
+                .filter_map(|entry| sectors(entry.path().join("size")))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let unpartitioned = size.map(|size| {
+        if partitions.is_empty() { 0 } else { size.saturating_sub(partitions.iter().sum()) }
+    });
+
+    BlockDisk {
+        // device-mapper and md devices have no device/model, their name is the best we have
+        model: read("device/model")
+            .filter(|model| !model.is_empty())
+            .or_else(|| read("dm/name"))
+            .or_else(|| read("md/level").map(|_| name.to_string())),
+        // nvme and mmc expose the serial directly, scsi and sata disks only through udev
+        serial: read("device/serial").filter(|serial| !serial.is_empty()),
+        interface_type: fs::canonicalize(&base).ok().and_then(|path| interface_type(&path)),
+        rotational: flag("queue/rotational"),
+        removable: flag("removable"),
+        size,
+        unpartitioned,
+    }
+}
+
+/// Bus the disk hangs off, read from its position in the device tree
+///
+/// ```text
+/// /sys/devices/pci0000:00/0000:00:14.0/usb2/2-1/2-1:1.0/host0/target0:0:0/0:0:0:0/block/sda
+/// ```
+pub fn interface_type(device_path: &Path) -> Option<String> {
+    let path = device_path.to_string_lossy();
+
+    let interface = if path.contains("/usb") {
+        "USB"
+    } else if path.contains("/nvme") {
+        "NVMe"
+    } else if path.contains("/ata") {
+        "SATA"
+    } else if path.contains("/mmc") {
+        "MMC"
+    } else if path.contains("/virtio") {
+        "VirtIO"
+    } else if path.contains("/host") && path.contains("/target") {
+        "SCSI"
+    } else if path.contains("/virtual/") {
+        return None;
+    } else {
+        "Other"
+    };
+
+    Some(interface.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mountinfo_skips_optional_fields_and_unescapes_paths() {
+        let content = "22 1 8:2 / / rw,relatime shared:1 - ext4 /dev/sda2 rw,errors=remount-ro\n\
+            45 22 8:17 /backups /media/my\\040disk rw,nosuid shared:27 master:3 propagate_from:5 - vfat /dev/sdb1 rw,fmask=0022\n\
+            46 22 0:44 / /run/user/1000 rw,nosuid,nodev - tmpfs tmpfs rw,size=1631692k\n\
+            47 22 0:45 / /mnt/with\\011tab\\134slash rw - fuse.sshfs user@host:/a\\040b rw\n";
+
+        let mounts = parse_mountinfo(content);
+
+        assert_eq!(mounts, vec![
+            MountEntry { major: 8, minor: 2, root: "/".into(), mount_point: "/".into(), fs_type: "ext4".into(), source: "/dev/sda2".into() },
+            MountEntry {
+                major: 8,
+                minor: 17,
+                root: "/backups".into(),
+                mount_point: "/media/my disk".into(),
+                fs_type: "vfat".into(),
+                source: "/dev/sdb1".into(),
+            },
+            MountEntry { major: 0, minor: 44, root: "/".into(), mount_point: "/run/user/1000".into(), fs_type: "tmpfs".into(), source: "tmpfs".into() },
+            MountEntry {
+                major: 0,
+                minor: 45,
+                root: "/".into(),
+                mount_point: "/mnt/with\ttab\\slash".into(),
+                fs_type: "fuse.sshfs".into(),
+                source: "user@host:/a b".into(),
+            },
+        ]);
+    }
+
+    #[test]
+    fn malformed_mountinfo_lines_are_skipped() {
+        let content = "22 1 8:2 / / rw,relatime shared:1 ext4 /dev/sda2 rw\n\
+            23 1 bad / / rw - ext4 /dev/sda3 rw\n\
+            24 1 8:4 / /home rw -\n";
+
+        assert!(parse_mountinfo(content).is_empty());
+    }
+
+    #[test]
+    fn incomplete_escapes_are_kept_as_is() {
+        assert_eq!(unescape("a\\04"), "a\\04");
+        assert_eq!(unescape("a\\09x"), "a\\09x");
+        assert_eq!(unescape("\\040\\040"), "  ");
+    }
+}
@@ -0,0 +1,107 @@
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use anyhow::Result;
+use chrono::Utc;
+use log::*;
+
+use crate::models::*;
+
+mod history;
+
+pub use self::history::{forecast, DiskForecastConfig, UsageHistory};
+
+#[cfg(windows)]
+mod windows;
+#[cfg(windows)]
+use self::windows as platform;
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "linux")]
+use self::linux as platform;
+
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    refreshed_on: Instant,
+    disks: Vec<DiskInfo>,
+}
+
+#[derive(Clone)]
+pub struct DiskService {
+    query_interval: Duration,
+    cache: Arc<RwLock<CacheEntry>>,
+    source: Arc<platform::DiskSource>,
+    /// used bytes per partition, sampled on every refresh
+    history: Arc<RwLock<UsageHistory>>,
+}
+
+impl DiskService {
+    pub fn new(storage_dir: &Path) -> Self {
+        let query_interval = Duration::from_hours(1);
+
+        Self {
+            query_interval,
+            cache: Arc::new(RwLock::new(CacheEntry {
+                refreshed_on: Instant::now() - query_interval,
+                disks: vec![],
+            })),
+            source: Arc::new(platform::DiskSource::new()),
+            history: Arc::new(RwLock::new(UsageHistory::load(storage_dir.join("disk_history.json")))),
+        }
+    }
+
+    pub fn get_disks(&self) -> Result<Vec<DiskInfo>> {
+        {
+            let guard = self.cache.read().unwrap();
+            if guard.refreshed_on.elapsed() < self.query_interval {
+                return Ok(guard.disks.clone());
+            }
+        }
+
+        debug!("refresh_disks");
+
+        let disks = self.source.disks()?;
+        self.record_usage(&disks);
+
+        let mut guard = self.cache.write().unwrap();
+        guard.disks = disks.clone();
+        guard.refreshed_on = Instant::now();
+
+        Ok(disks)
+    }
+
+    /// Projected date each mounted partition runs full, soonest first
+    pub fn get_forecasts(&self, config: &DiskForecastConfig) -> Result<Vec<DiskForecast>> {
+        let disks = self.get_disks()?;
+        let history = self.history.read().unwrap();
+        let now = Utc::now();
+
+        let mut forecasts: Vec<DiskForecast> = disks
+            .iter()
+            .flat_map(|disk| &disk.partitions)
+            .filter(|partition| partition.total > 0)
+            .map(|partition| forecast(partition, history.points(&partition.name), config, now))
+            .collect();
+
+        forecasts.sort_by(|a, b| match (a.days_left, b.days_left) {
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => b.used.cmp(&a.used),
+        });
+
+        Ok(forecasts)
+    }
+
+    fn record_usage(&self, disks: &[DiskInfo]) {
+        let partitions: Vec<DiskPartition> = disks.iter().flat_map(|disk| disk.partitions.iter().cloned()).collect();
+
+        let mut history = self.history.write().unwrap();
+        history.record(Utc::now(), &partitions);
+
+        if let Err(err) = history.save() {
+            warn!("could not save disk usage history: {}", err);
+        }
+    }
+}
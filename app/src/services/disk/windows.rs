@@ -0,0 +1,161 @@
+use std::ffi::OsStr;
+use std::os::windows::ffi::OsStrExt;
+use std::ptr;
+use winapi::shared::minwindef::{DWORD, FALSE};
+use winapi::um::fileapi::{GetDiskFreeSpaceExW, GetLogicalDrives, GetVolumeInformationW};
+use winapi::um::winnt::ULARGE_INTEGER;
+use anyhow::Result;
+use log::*;
+
+use crate::models::*;
+use crate::services::{WmiPartition, WmiService};
+use crate::utils::format_bytes;
+
+/// Physical disks from WMI with their lettered partitions nested under them
+pub struct DiskSource {
+    wmi_service: WmiService
+}
+
+impl DiskSource {
+    pub fn new() -> Self {
+        Self {
+            wmi_service: WmiService::new()
+        }
+    }
+
+    pub fn disks(&self) -> Result<Vec<DiskInfo>> {
+        let mut drives = self.drives();
+        let layout = self.wmi_service.get_disk_layout()?;
+
+        debug!("{layout:?}");
+
+        let mut disks: Vec<DiskInfo> = layout
+            .disks
+            .iter()
+            .map(|disk| {
+                let partitions: Vec<&WmiPartition> = layout.partitions.iter().filter(|p| p.disk_index == disk.index).collect();
+                // Win32_DiskDrive.Size only counts whole cylinders and leaves out the
+                // partition table and gaps, only MSFT_Disk knows what is really unallocated
+                let storage = layout.storage_disks.iter().find(|storage| storage.number == disk.index);
+                let size = storage.map(|storage| storage.size).or(disk.size);
+                let unpartitioned = storage.map(|storage| storage.size.saturating_sub(storage.allocated_size));
+
+                let mut mounted = partitions
+                    .iter()
+                    .flat_map(|partition| {
+                        layout
+                            .logical_map
+                            .iter()
+                            .filter(|(_, device_id)| **device_id == partition.device_id)
+                            .map(|(letter, _)| format!("{}\\", letter))
+                    })
+                    .collect::<Vec<_>>();
+                mounted.sort();
+
+                drives.retain(|drive| !mounted.contains(drive));
+
+                DiskInfo {
+                    model: disk.model.clone(),
+                    disk_type: disk.media_type.clone(),
+                    // padded with spaces by some drivers
+                    serial: disk.serial_number.as_ref().map(|serial| serial.trim().to_string()).filter(|serial| !serial.is_empty()),
+                    interface_type: disk.interface_type.clone(),
+                    size,
+                    size_formatted: size.map(format_bytes),
+                    unpartitioned,
+                    unpartitioned_formatted: unpartitioned.map(format_bytes),
+                    removable: disk.media_type.as_deref().map(|media| media.starts_with("Removable")),
+                    partitions: mounted.iter().filter_map(|drive| Self::partition(drive)).collect(),
+                }
+            })
+            .collect();
+
+        // network shares, subst and virtual drives have no physical disk
+        disks.extend(drives.into_iter().filter_map(|drive| {
+            let partition = Self::partition(&drive)?;
+
+            Some(DiskInfo {
+                model: None,
+                disk_type: None,
+                serial: None,
+                interface_type: None,
+                size: None,
+                size_formatted: None,
+                unpartitioned: None,
+                unpartitioned_formatted: None,
+                removable: None,
+                partitions: vec![partition],
+            })
+        }));
+
+        Ok(disks)
+    }
+
+    fn drives(&self) -> Vec<String> {
+        let mut drives = Vec::new();
+        unsafe {
+            let mask = GetLogicalDrives();
+            for i in 0..26 {
+                if mask & (1 << i) != 0 {
+                    drives.push(format!("{}:\\", (b'A' + i) as char));
+                }
+            }
+        }
+        drives
+    }
+
+    fn partition(drive: &str) -> Option<DiskPartition> {
+        let mut total_bytes: ULARGE_INTEGER = unsafe { std::mem::zeroed() };
+        let mut free_bytes: ULARGE_INTEGER = unsafe { std::mem::zeroed() };
+        let mut avail_bytes: ULARGE_INTEGER = unsafe { std::mem::zeroed() };
+
+        let mut fs_name = [0u16; 32];
+
+        let drive_wide: Vec<u16> = OsStr::new(drive).encode_wide().chain(Some(0)).collect();
+
+        unsafe {
+            let result = GetDiskFreeSpaceExW(
+                drive_wide.as_ptr(),
+                &mut avail_bytes as *mut ULARGE_INTEGER,
+                &mut total_bytes as *mut ULARGE_INTEGER,
+                &mut free_bytes as *mut ULARGE_INTEGER,
+            );
+
+            if result == FALSE {
+                return None;
+            }
+
+            let total = *total_bytes.QuadPart();
+            let free = *free_bytes.QuadPart();
+            let used = total - free;
+
+            let fs_type = if GetVolumeInformationW(
+                drive_wide.as_ptr(),
+                ptr::null_mut(),
+                0,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+                fs_name.as_mut_ptr(),
+                fs_name.len() as u32,
+            ) != FALSE
+            {
+                let len = fs_name.iter().position(|&c| c == 0).unwrap_or(fs_name.len());
+                Some(String::from_utf16_lossy(&fs_name[..len]))
+            } else {
+                None
+            };
+
+            Some(DiskPartition {
+                name: drive.to_string(),
+                fs_type,
+                total,
+                total_formatted: format_bytes(total),
+                free,
+                free_formatted: format_bytes(free),
+                used,
+                used_formatted: format_bytes(used),
+            })
+        }
+    }
+}
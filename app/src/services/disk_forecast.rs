@@ -0,0 +1,145 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{atomic::{AtomicBool, Ordering}, Arc, RwLock},
+    thread,
+    time::Duration,
+};
+
+use anyhow::Result;
+use hashbrown::HashSet;
+use log::*;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::{models::DiskForecast, services::{DiskForecastConfig, DiskService}};
+
+/// Checks hit the disk cache, usage is only sampled when it refreshes every hour
+const CHECK_INTERVAL: Duration = Duration::from_mins(10);
+
+/// Notification choice kept in `disk_forecast.json` next to `disk_history.json`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NotificationSettings {
+    enabled: bool,
+    horizon: Duration,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            horizon: DiskForecastConfig::default().horizon,
+        }
+    }
+}
+
+/// Keeps the usage history growing in the background and warns about partitions about to run full
+#[derive(Clone)]
+pub struct DiskForecastService {
+    app_handle: AppHandle,
+    disk_service: DiskService,
+    settings_path: PathBuf,
+    config: Arc<RwLock<DiskForecastConfig>>,
+    notifications: Arc<AtomicBool>,
+    /// partitions already reported, so a warning is sent once rather than every check
+    notified: Arc<RwLock<HashSet<String>>>,
+}
+
+impl DiskForecastService {
+    pub fn new(app_handle: AppHandle, disk_service: DiskService, storage_dir: &Path) -> Self {
+        let settings_path = storage_dir.join("disk_forecast.json");
+        let settings: NotificationSettings = fs::read(&settings_path)
+            .ok()
+            .and_then(|content| serde_json::from_slice(&content).ok())
+            .unwrap_or_default();
+
+        let service = Self {
+            app_handle,
+            disk_service,
+            settings_path,
+            config: Arc::new(RwLock::new(DiskForecastConfig {
+                horizon: settings.horizon,
+                ..Default::default()
+            })),
+            notifications: Arc::new(AtomicBool::new(settings.enabled)),
+            notified: Arc::new(RwLock::new(HashSet::new())),
+        };
+
+        {
+            let service = service.clone();
+            thread::Builder::new()
+                .name("disk-forecast".into())
+                .spawn(move || service.run_checks())
+                .expect("could not spawn disk forecast");
+        }
+
+        service
+    }
+
+    pub fn get_forecasts(&self) -> Result<Vec<DiskForecast>> {
+        let config = self.config.read().unwrap().clone();
+
+        self.disk_service.get_forecasts(&config)
+    }
+
+    /// Emits `on-disk-full-forecast` for every partition projected to run full within `horizon` while enabled,
+    /// the choice is remembered for the next start
+    pub fn set_notifications(&self, enabled: bool, horizon: Option<Duration>) {
+        if let Some(horizon) = horizon {
+            self.config.write().unwrap().horizon = horizon;
+            // re-evaluated against the new horizon on the next check
+            self.notified.write().unwrap().clear();
+        }
+
+        self.notifications.store(enabled, Ordering::Relaxed);
+
+        let settings = NotificationSettings {
+            enabled,
+            horizon: self.config.read().unwrap().horizon,
+        };
+
+        if let Err(err) = self.save_settings(&settings) {
+            warn!("could not save {}: {}", self.settings_path.display(), err);
+        }
+    }
+
+    fn save_settings(&self, settings: &NotificationSettings) -> Result<()> {
+        fs::write(&self.settings_path, serde_json::to_vec(settings)?)?;
+        Ok(())
+    }
+
+    fn run_checks(&self) {
+        loop {
+            if let Err(err) = self.check() {
+                warn!("disk forecast failed: {}", err);
+            }
+
+            thread::sleep(CHECK_INTERVAL);
+        }
+    }
+
+    fn check(&self) -> Result<()> {
+        let flagged: Vec<DiskForecast> = self
+            .get_forecasts()?
+            .into_iter()
+            .filter(|forecast| forecast.within_horizon)
+            .collect();
+
+        let mut notified = self.notified.write().unwrap();
+        // forget partitions that left the horizon so a relapse is reported again
+        notified.retain(|name| flagged.iter().any(|forecast| forecast.name == *name));
+
+        if !self.notifications.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        for forecast in flagged {
+            if notified.insert(forecast.name.clone()) {
+                self.app_handle.emit("on-disk-full-forecast", forecast)?;
+            }
+        }
+
+        Ok(())
+    }
+}
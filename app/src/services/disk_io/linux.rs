@@ -0,0 +1,176 @@
+use std::{fs, path::Path};
+
+use anyhow::Result;
+
+use super::{DiskCounters, ProcessIoCounters};
+
+/// `/proc/diskstats` counts in 512 byte sectors whatever the device block size
+const SECTOR_SIZE: u64 = 512;
+
+/// Whole disks from `/proc/diskstats`; partitions, loop and ram devices are left out
+pub fn disk_counters() -> Result<Vec<DiskCounters>> {
+    let content = fs::read_to_string("/proc/diskstats")?;
+
+    let disks = parse_diskstats(&content)
+        .into_iter()
+        .filter(|counters| is_physical_disk(&counters.name))
+        .collect();
+
+    Ok(disks)
+}
+
+fn is_physical_disk(name: &str) -> bool {
+    if name.starts_with("loop") || name.starts_with("ram") || name.starts_with("zram") {
+        return false;
+    }
+
+    // partitions appear in diskstats too but only disks have an entry in /sys/block
+    Path::new("/sys/block").join(name).exists()
+}
+
+/// Parses `/proc/diskstats`
+///
+/// ```text
+///    8       0 sda 4096 12 262144 1000 2048 30 131072 2000 0 1500 3000 ...
+/// ```
+pub fn parse_diskstats(content: &str) -> Vec<DiskCounters> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let field = |index: usize| fields.get(index).and_then(|value| value.parse::<u64>().ok());
+
+            Some(DiskCounters {
+                name: fields.get(2)?.to_string(),
+                reads: field(3)?,
+                read_bytes: field(5)? * SECTOR_SIZE,
+                writes: field(7)?,
+                write_bytes: field(9)? * SECTOR_SIZE,
+                busy_ms: field(12)?,
+                weighted_ms: field(13)?,
+            })
+        })
+        .collect()
+}
+
+/// `/proc/<pid>/io` of every process we are allowed to read, others need ptrace rights
+pub fn process_counters() -> Result<Vec<ProcessIoCounters>> {
+    let mut items = Vec::new();
+
+    for entry in fs::read_dir("/proc")?.flatten() {
+        let Some(process_id) = entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+            continue;
+        };
+
+        let Ok(io) = fs::read_to_string(entry.path().join("io")) else {
+            continue;
+        };
+
+        let process_name = fs::read_to_string(entry.path().join("comm"))
+            .map(|name| name.trim().to_string())
+            .unwrap_or_default();
+
+        if let Some(counters) = parse_process_io(process_id, process_name, &io) {
+            items.push(counters);
+        }
+    }
+
+    Ok(items)
+}
+
+/// Parses `/proc/<pid>/io`. `read_bytes` and `write_bytes` are what reached the
+/// block layer, `rchar` and `wchar` would include pipes, sockets and the page cache.
+/// `syscr` and `syscw` count read and write syscalls of any kind, not disk requests.
+///
+/// ```text
+/// rchar: 323934931
+/// wchar: 323929600
+/// syscr: 632687
+/// syscw: 632675
+/// read_bytes: 0
+/// write_bytes: 323932160
+/// cancelled_write_bytes: 0
+/// ```
+pub fn parse_process_io(process_id: u32, process_name: String, content: &str) -> Option<ProcessIoCounters> {
+    let mut counters = ProcessIoCounters {
+        process_id,
+        process_name,
+        ..Default::default()
+    };
+    let mut found = false;
+
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let Ok(value) = value.trim().parse::<u64>() else {
+            continue;
+        };
+
+        match key {
+            "read_bytes" => counters.read_bytes = value,
+            "write_bytes" => counters.write_bytes = value,
+            "syscr" => counters.read_ops = value,
+            "syscw" => counters.write_ops = value,
+            _ => continue,
+        }
+
+        found = true;
+    }
+
+    found.then_some(counters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diskstats_counts_sectors_and_io_time() {
+        let content = "   8       0 sda 4096 12 262144 1000 2048 30 131072 2000 0 1500 3000 0 0 0 0 120 40\n\
+            \x20  8       1 sda1 100 0 800 10 0 0 0 0 0 20 30\n\
+            \x20259       0 nvme0n1 10 0 20 1 5 0 40 2 1 7 9\n";
+
+        let disks = parse_diskstats(content);
+
+        assert_eq!(disks.len(), 3);
+        assert_eq!(disks[0], DiskCounters {
+            name: "sda".into(),
+            reads: 4096,
+            writes: 2048,
+            read_bytes: 262144 * 512,
+            write_bytes: 131072 * 512,
+            busy_ms: 1500,
+            weighted_ms: 3000,
+        });
+        assert_eq!(disks[1].name, "sda1");
+        assert_eq!((disks[2].busy_ms, disks[2].weighted_ms), (7, 9));
+    }
+
+    #[test]
+    fn short_diskstats_lines_are_skipped() {
+        assert!(parse_diskstats("   8       0 sda 4096 12 262144 1000\n\n").is_empty());
+    }
+
+    #[test]
+    fn process_io_uses_block_layer_bytes_and_syscall_counts() {
+        let content = "rchar: 323934931\nwchar: 323929600\nsyscr: 632687\nsyscw: 632675\nread_bytes: 4096\nwrite_bytes: 323932160\ncancelled_write_bytes: 0\n";
+
+        let counters = parse_process_io(42, "dd".into(), content).unwrap();
+
+        assert_eq!(counters, ProcessIoCounters {
+            process_id: 42,
+            process_name: "dd".into(),
+            read_bytes: 4096,
+            write_bytes: 323932160,
+            read_ops: 632687,
+            write_ops: 632675,
+        });
+    }
+
+    #[test]
+    fn process_io_without_counters_is_skipped() {
+        assert_eq!(parse_process_io(1, "init".into(), ""), None);
+        assert_eq!(parse_process_io(1, "init".into(), "rchar: 10\nwchar: 20\n"), None);
+    }
+}
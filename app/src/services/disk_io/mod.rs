@@ -0,0 +1,297 @@
+use std::{
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use log::*;
+
+use crate::{models::{DiskIoStats, ProcessIoStats}, utils::format_bytes};
+
+#[cfg(windows)]
+mod windows;
+#[cfg(windows)]
+use self::windows as platform;
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "linux")]
+use self::linux as platform;
+
+/// Cumulative counters of a physical disk since boot
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiskCounters {
+    pub name: String,
+    pub reads: u64,
+    pub writes: u64,
+    pub read_bytes: u64,
+    pub write_bytes: u64,
+    /// time with at least one request in flight
+    pub busy_ms: u64,
+    /// time spent by all requests, its rate is the average queue length
+    pub weighted_ms: u64,
+}
+
+/// Cumulative I/O counters of a process since it started
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProcessIoCounters {
+    pub process_id: u32,
+    pub process_name: String,
+    pub read_bytes: u64,
+    pub write_bytes: u64,
+    /// read and write calls, whatever they were made on
+    pub read_ops: u64,
+    pub write_ops: u64,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    refreshed_on: Instant,
+    disk_counters: Vec<DiskCounters>,
+    process_counters: Vec<ProcessIoCounters>,
+    disks: Vec<DiskIoStats>,
+    processes: Vec<ProcessIoStats>,
+}
+
+/// Turns cumulative disk and process I/O counters into rates between refreshes
+pub struct DiskIoService {
+    query_interval: Duration,
+    cache: Arc<RwLock<CacheEntry>>,
+}
+
+impl DiskIoService {
+    pub fn new() -> Self {
+        let query_interval = Duration::from_secs(1);
+
+        // baseline so the first query already has something to compare against
+        let disk_counters = platform::disk_counters().unwrap_or_else(|err| {
+            warn!("disk counters unavailable: {}", err);
+            vec![]
+        });
+        let process_counters = platform::process_counters().unwrap_or_default();
+
+        Self {
+            query_interval,
+            cache: Arc::new(RwLock::new(CacheEntry {
+                refreshed_on: Instant::now(),
+                disk_counters,
+                process_counters,
+                disks: vec![],
+                processes: vec![],
+            })),
+        }
+    }
+
+    pub fn get_disk_io(&self) -> Result<Vec<DiskIoStats>> {
+        self.refresh_if_stale()?;
+
+        Ok(self.cache.read().unwrap().disks.clone())
+    }
+
+    /// Processes ordered by combined read and write rate
+    pub fn get_process_io(&self, limit: usize) -> Result<Vec<ProcessIoStats>> {
+        self.refresh_if_stale()?;
+
+        let guard = self.cache.read().unwrap();
+        Ok(guard.processes.iter().take(limit).cloned().collect())
+    }
+
+    fn refresh_if_stale(&self) -> Result<()> {
+        let guard = self.cache.read().unwrap();
+        if guard.refreshed_on.elapsed() > self.query_interval {
+            drop(guard);
+            self.refresh_cache()?;
+        }
+
+        Ok(())
+    }
+
+    fn refresh_cache(&self) -> Result<()> {
+        let disk_counters = platform::disk_counters()?;
+        let process_counters = platform::process_counters()?;
+
+        let mut cache = self.cache.write().unwrap();
+        let elapsed = cache.refreshed_on.elapsed().as_secs_f64();
+
+        cache.disks = disk_counters
+            .iter()
+            .map(|current| {
+                let previous = cache.disk_counters.iter().find(|previous| previous.name == current.name);
+                disk_rate(previous, current, elapsed)
+            })
+            .collect();
+
+        let mut processes: Vec<ProcessIoStats> = process_counters
+            .iter()
+            .map(|current| process_rate(previous_reading(&cache.process_counters, current), current, elapsed))
+            .collect();
+
+        // idle processes fall back to their lifetime totals
+        processes.sort_by(|a, b| {
+            (b.read_bytes_per_sec + b.write_bytes_per_sec)
+                .cmp(&(a.read_bytes_per_sec + a.write_bytes_per_sec))
+                .then_with(|| (b.read_bytes + b.write_bytes).cmp(&(a.read_bytes + a.write_bytes)))
+        });
+
+        cache.processes = processes;
+        cache.disk_counters = disk_counters;
+        cache.process_counters = process_counters;
+        cache.refreshed_on = Instant::now();
+
+        Ok(())
+    }
+}
+
+/// Rates between two readings, a missing previous reading counts as no activity
+pub fn disk_rate(previous: Option<&DiskCounters>, current: &DiskCounters, elapsed_secs: f64) -> DiskIoStats {
+    let delta = |value: fn(&DiskCounters) -> u64| match previous {
+        // counters wrap on 32 bit kernels, treat that as no activity
+        Some(previous) if elapsed_secs > 0.0 => value(current).saturating_sub(value(previous)) as f64,
+        _ => 0.0,
+    };
+    let per_second = |value: fn(&DiskCounters) -> u64| if elapsed_secs > 0.0 { delta(value) / elapsed_secs } else { 0.0 };
+
+    let elapsed_ms = elapsed_secs * 1000.0;
+    let read_bytes_per_sec = per_second(|counters| counters.read_bytes) as u64;
+    let write_bytes_per_sec = per_second(|counters| counters.write_bytes) as u64;
+
+    DiskIoStats {
+        name: current.name.clone(),
+        read_bytes_per_sec,
+        read_formatted: format!("{}/s", format_bytes(read_bytes_per_sec)),
+        write_bytes_per_sec,
+        write_formatted: format!("{}/s", format_bytes(write_bytes_per_sec)),
+        read_iops: per_second(|counters| counters.reads),
+        write_iops: per_second(|counters| counters.writes),
+        queue_depth: if elapsed_ms > 0.0 { delta(|counters| counters.weighted_ms) / elapsed_ms } else { 0.0 },
+        utilisation: if elapsed_ms > 0.0 { (delta(|counters| counters.busy_ms) / elapsed_ms * 100.0).min(100.0) } else { 0.0 },
+    }
+}
+
+/// A reused pid shows up under another name, its counters start over
+fn previous_reading<'a>(previous: &'a [ProcessIoCounters], current: &ProcessIoCounters) -> Option<&'a ProcessIoCounters> {
+    previous
+        .iter()
+        .find(|previous| previous.process_id == current.process_id && previous.process_name == current.process_name)
+}
+
+pub fn process_rate(previous: Option<&ProcessIoCounters>, current: &ProcessIoCounters, elapsed_secs: f64) -> ProcessIoStats {
+    let per_second = |value: fn(&ProcessIoCounters) -> u64| match previous {
+        Some(previous) if elapsed_secs > 0.0 => value(current).saturating_sub(value(previous)) as f64 / elapsed_secs,
+        _ => 0.0,
+    };
+
+    let read_bytes_per_sec = per_second(|counters| counters.read_bytes) as u64;
+    let write_bytes_per_sec = per_second(|counters| counters.write_bytes) as u64;
+
+    ProcessIoStats {
+        process_id: current.process_id,
+        process_name: current.process_name.clone(),
+        read_bytes_per_sec,
+        read_formatted: format!("{}/s", format_bytes(read_bytes_per_sec)),
+        write_bytes_per_sec,
+        write_formatted: format!("{}/s", format_bytes(write_bytes_per_sec)),
+        read_ops_per_sec: per_second(|counters| counters.read_ops),
+        write_ops_per_sec: per_second(|counters| counters.write_ops),
+        read_bytes: current.read_bytes,
+        write_bytes: current.write_bytes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn disk(reads: u64, read_bytes: u64, busy_ms: u64, weighted_ms: u64) -> DiskCounters {
+        DiskCounters {
+            name: "sda".into(),
+            reads,
+            writes: 0,
+            read_bytes,
+            write_bytes: 0,
+            busy_ms,
+            weighted_ms,
+        }
+    }
+
+    fn process(process_id: u32, process_name: &str, read_bytes: u64, read_ops: u64) -> ProcessIoCounters {
+        ProcessIoCounters {
+            process_id,
+            process_name: process_name.into(),
+            read_bytes,
+            read_ops,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn disk_rates_are_per_second_over_the_interval() {
+        let stats = disk_rate(Some(&disk(100, 1_000_000, 1000, 3000)), &disk(300, 5_000_000, 1500, 5000), 2.0);
+
+        assert_eq!(stats.read_bytes_per_sec, 2_000_000);
+        assert_eq!(stats.read_iops, 100.0);
+        // 500 ms busy out of 2000, 2000 ms of requests over 2000 ms
+        assert_eq!(stats.utilisation, 25.0);
+        assert_eq!(stats.queue_depth, 1.0);
+    }
+
+    #[test]
+    fn utilisation_is_capped() {
+        let stats = disk_rate(Some(&disk(0, 0, 0, 0)), &disk(0, 0, 3000, 0), 1.0);
+
+        assert_eq!(stats.utilisation, 100.0);
+    }
+
+    #[test]
+    fn disk_counters_going_backwards_count_as_idle() {
+        let stats = disk_rate(Some(&disk(300, 5_000_000, 1500, 5000)), &disk(100, 1_000_000, 1000, 3000), 1.0);
+
+        assert_eq!(stats.read_bytes_per_sec, 0);
+        assert_eq!(stats.read_iops, 0.0);
+        assert_eq!(stats.utilisation, 0.0);
+        assert_eq!(stats.queue_depth, 0.0);
+    }
+
+    #[test]
+    fn no_elapsed_time_or_first_reading_gives_no_rates() {
+        let previous = disk(100, 1_000_000, 1000, 3000);
+        let current = disk(300, 5_000_000, 1500, 5000);
+
+        for stats in [disk_rate(Some(&previous), &current, 0.0), disk_rate(None, &current, 1.0)] {
+            assert_eq!(stats.read_bytes_per_sec, 0);
+            assert_eq!(stats.read_iops, 0.0);
+            assert_eq!(stats.utilisation, 0.0);
+            assert_eq!(stats.queue_depth, 0.0);
+        }
+
+        let stats = process_rate(Some(&process(1, "a", 0, 0)), &process(1, "a", 4096, 4), 0.0);
+        assert_eq!(stats.read_bytes_per_sec, 0);
+        assert_eq!(stats.read_ops_per_sec, 0.0);
+    }
+
+    #[test]
+    fn process_rates_keep_the_lifetime_totals() {
+        let stats = process_rate(Some(&process(1, "a", 1000, 10)), &process(1, "a", 9000, 50), 4.0);
+
+        assert_eq!(stats.read_bytes_per_sec, 2000);
+        assert_eq!(stats.read_ops_per_sec, 10.0);
+        assert_eq!(stats.read_bytes, 9000);
+
+        let stats = process_rate(Some(&process(1, "a", 9000, 50)), &process(1, "a", 1000, 10), 4.0);
+        assert_eq!(stats.read_bytes_per_sec, 0);
+        assert_eq!(stats.read_bytes, 1000);
+    }
+
+    #[test]
+    fn reused_pids_start_over() {
+        let previous = vec![process(1, "old", 1_000_000, 100), process(2, "other", 0, 0)];
+        let current = process(1, "new", 4096, 1);
+
+        assert_eq!(previous_reading(&previous, &current), None);
+        assert_eq!(previous_reading(&previous, &process(1, "old", 2_000_000, 200)), Some(&previous[0]));
+
+        let stats = process_rate(previous_reading(&previous, &current), &current, 1.0);
+        assert_eq!(stats.read_bytes_per_sec, 0);
+        assert_eq!(stats.read_bytes, 4096);
+    }
+}
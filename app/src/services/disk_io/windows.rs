@@ -0,0 +1,156 @@
+use std::{mem, ptr};
+
+use anyhow::{bail, Result};
+use widestring::U16CString;
+use winapi::{
+    shared::minwindef::{DWORD, FALSE},
+    um::{
+        fileapi::{CreateFileW, OPEN_EXISTING},
+        handleapi::{CloseHandle, INVALID_HANDLE_VALUE},
+        ioapiset::DeviceIoControl,
+        processthreadsapi::OpenProcess,
+        tlhelp32::{CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS},
+        winbase::GetProcessIoCounters,
+        winioctl::{DISK_PERFORMANCE, IOCTL_DISK_PERFORMANCE},
+        winnt::{FILE_SHARE_READ, FILE_SHARE_WRITE, IO_COUNTERS, PROCESS_QUERY_LIMITED_INFORMATION},
+    },
+};
+
+use crate::utils::widestr_to_string;
+
+use super::{DiskCounters, ProcessIoCounters};
+
+/// Physical drive numbers probed, gaps are left by removed disks
+const MAX_PHYSICAL_DRIVES: u32 = 32;
+
+/// Times in `DISK_PERFORMANCE` are in 100ns units
+const TICKS_PER_MS: i64 = 10_000;
+
+/// The counters behind the PhysicalDisk performance object, read with `IOCTL_DISK_PERFORMANCE`
+pub fn disk_counters() -> Result<Vec<DiskCounters>> {
+    let mut disks = Vec::new();
+
+    for index in 0..MAX_PHYSICAL_DRIVES {
+        let name = format!("PhysicalDrive{}", index);
+
+        if let Some(counters) = unsafe { query_disk_performance(&name)? } {
+            disks.push(counters);
+        }
+    }
+
+    Ok(disks)
+}
+
+unsafe fn query_disk_performance(name: &str) -> Result<Option<DiskCounters>> {
+    let path = U16CString::from_str(format!(r"\\.\{}", name))?;
+
+    // no access rights are needed to query performance
+    let handle = CreateFileW(
+        path.as_ptr(),
+        0,
+        FILE_SHARE_READ | FILE_SHARE_WRITE,
+        ptr::null_mut(),
+        OPEN_EXISTING,
+        0,
+        ptr::null_mut(),
+    );
+
+    if handle == INVALID_HANDLE_VALUE {
+        return Ok(None);
+    }
+
+    let mut performance: DISK_PERFORMANCE = mem::zeroed();
+    let mut returned: DWORD = 0;
+
+    let result = DeviceIoControl(
+        handle,
+        IOCTL_DISK_PERFORMANCE,
+        ptr::null_mut(),
+        0,
+        &mut performance as *mut _ as *mut _,
+        mem::size_of::<DISK_PERFORMANCE>() as u32,
+        &mut returned,
+        ptr::null_mut(),
+    );
+
+    CloseHandle(handle);
+
+    if result == FALSE {
+        return Ok(None);
+    }
+
+    let ticks_to_ms = |ticks: i64| (ticks / TICKS_PER_MS).max(0) as u64;
+    let busy = *performance.QueryTime.QuadPart() - *performance.IdleTime.QuadPart();
+    // ReadTime and WriteTime add up the time of every request, like "Avg. Disk Queue Length"
+    let weighted = *performance.ReadTime.QuadPart() + *performance.WriteTime.QuadPart();
+
+    Ok(Some(DiskCounters {
+        name: name.to_string(),
+        reads: performance.ReadCount as u64,
+        writes: performance.WriteCount as u64,
+        read_bytes: *performance.BytesRead.QuadPart() as u64,
+        write_bytes: *performance.BytesWritten.QuadPart() as u64,
+        busy_ms: ticks_to_ms(busy),
+        weighted_ms: ticks_to_ms(weighted),
+    }))
+}
+
+/// `GetProcessIoCounters` of every process we can open. Windows counts all I/O
+/// issued by the process, network and device I/O included.
+pub fn process_counters() -> Result<Vec<ProcessIoCounters>> {
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+        if snapshot == INVALID_HANDLE_VALUE {
+            bail!("snapshot fail")
+        }
+
+        let mut entry: PROCESSENTRY32W = mem::zeroed();
+        entry.dwSize = mem::size_of::<PROCESSENTRY32W>() as u32;
+
+        if Process32FirstW(snapshot, &mut entry) == FALSE {
+            CloseHandle(snapshot);
+            bail!("Process32FirstW fail");
+        }
+
+        let mut items = Vec::new();
+
+        loop {
+            if let Some(counters) = read_process_io(entry.th32ProcessID, widestr_to_string(&entry.szExeFile)) {
+                items.push(counters);
+            }
+
+            if Process32NextW(snapshot, &mut entry) == FALSE {
+                break;
+            }
+        }
+
+        CloseHandle(snapshot);
+        Ok(items)
+    }
+}
+
+unsafe fn read_process_io(process_id: DWORD, process_name: String) -> Option<ProcessIoCounters> {
+    let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, FALSE, process_id);
+
+    if handle.is_null() {
+        return None;
+    }
+
+    let mut counters: IO_COUNTERS = mem::zeroed();
+    let result = GetProcessIoCounters(handle, &mut counters);
+
+    CloseHandle(handle);
+
+    if result == FALSE {
+        return None;
+    }
+
+    Some(ProcessIoCounters {
+        process_id,
+        process_name,
+        read_bytes: counters.ReadTransferCount,
+        write_bytes: counters.WriteTransferCount,
+        read_ops: counters.ReadOperationCount,
+        write_ops: counters.WriteOperationCount,
+    })
+}
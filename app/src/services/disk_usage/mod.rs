@@ -0,0 +1,333 @@
+use std::{
+    cmp::Reverse,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
+    thread,
+    time::Duration,
+};
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use hashbrown::HashMap;
+use log::*;
+use moka::sync::Cache;
+use tauri::{AppHandle, Emitter};
+use uuid::Uuid;
+
+use crate::{
+    models::{DiskUsageDiff, DiskUsageReport, ExtensionUsage, SavedScanInfo, ScanProgress, ScanState, UsageEntry},
+    utils::format_bytes,
+};
+
+mod scanner;
+mod store;
+
+pub use self::scanner::{scan, DirTotals, ScanCounters, ScanTree};
+pub use self::store::{diff_scans, SavedScan, ScanStore};
+
+/// Entries kept for the largest directories, files and extensions
+const TOP_ITEMS: usize = 100;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Parallel directory size scans reporting progress as `on-disk-usage-progress` events
+#[derive(Clone)]
+pub struct DiskUsageService {
+    app_handle: AppHandle,
+    store: Arc<ScanStore>,
+    /// cancel flags of the scans still running
+    running: Arc<RwLock<HashMap<Uuid, Arc<AtomicBool>>>>,
+    /// recent scans, saves rereading large json files while drilling down
+    finished: Cache<Uuid, Arc<SavedScan>>,
+}
+
+impl DiskUsageService {
+    pub fn new(app_handle: AppHandle, storage_dir: &Path) -> Self {
+        Self {
+            app_handle,
+            store: Arc::new(ScanStore::new(storage_dir.join("disk_usage"))),
+            running: Arc::new(RwLock::new(HashMap::new())),
+            finished: Cache::builder().max_capacity(4).build(),
+        }
+    }
+
+    pub fn start_scan(&self, root: &str) -> Result<Uuid> {
+        let root = PathBuf::from(root);
+
+        if !root.is_dir() {
+            bail!("{} is not a directory", root.display());
+        }
+
+        let scan_id = Uuid::now_v7();
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.running.write().unwrap().insert(scan_id, cancel.clone());
+
+        let service = self.clone();
+        thread::Builder::new()
+            .name("disk-usage-scan".into())
+            .spawn(move || service.run_scan(scan_id, root, cancel))?;
+
+        Ok(scan_id)
+    }
+
+    pub fn cancel_scan(&self, scan_id: Uuid) -> Result<()> {
+        match self.running.read().unwrap().get(&scan_id) {
+            Some(cancel) => cancel.store(true, Ordering::Relaxed),
+            None => bail!("scan {} is not running", scan_id),
+        }
+
+        Ok(())
+    }
+
+    pub fn get_report(&self, scan_id: Uuid) -> Result<DiskUsageReport> {
+        Ok(self.get_scan(scan_id)?.report.clone())
+    }
+
+    /// Subdirectories of `path` within a finished scan, for drilling down the size tree
+    pub fn get_children(&self, scan_id: Uuid, path: &str) -> Result<Vec<UsageEntry>> {
+        Ok(self.get_scan(scan_id)?.children(path))
+    }
+
+    pub fn get_saved_scans(&self) -> Result<Vec<SavedScanInfo>> {
+        self.store.list()
+    }
+
+    /// What grew between two saved scans
+    pub fn diff(&self, previous: Uuid, current: Uuid, limit: usize) -> Result<DiskUsageDiff> {
+        let previous = self.get_scan(previous)?;
+        let current = self.get_scan(current)?;
+
+        if previous.report.root != current.report.root {
+            bail!("scans of {} and {} cannot be compared", previous.report.root, current.report.root);
+        }
+
+        Ok(diff_scans(&previous, &current, limit))
+    }
+
+    pub fn delete_scan(&self, scan_id: Uuid) -> Result<()> {
+        self.finished.invalidate(&scan_id);
+        self.store.delete(scan_id)
+    }
+
+    fn get_scan(&self, scan_id: Uuid) -> Result<Arc<SavedScan>> {
+        if self.running.read().unwrap().contains_key(&scan_id) {
+            bail!("scan {} is still running", scan_id);
+        }
+
+        if let Some(scan) = self.finished.get(&scan_id) {
+            return Ok(scan);
+        }
+
+        let scan = Arc::new(self.store.load(scan_id)?);
+        self.finished.insert(scan_id, scan.clone());
+
+        Ok(scan)
+    }
+
+    fn run_scan(&self, scan_id: Uuid, root: PathBuf, cancel: Arc<AtomicBool>) {
+        let started_on = Utc::now();
+        let counters = ScanCounters::default();
+        let done = AtomicBool::new(false);
+
+        let tree = thread::scope(|scope| {
+            scope.spawn(|| {
+                while !done.load(Ordering::Relaxed) {
+                    self.emit_progress(scan_id, &root, ScanState::Running, &counters, None);
+                    thread::sleep(PROGRESS_INTERVAL);
+                }
+            });
+
+            let tree = scan(&root, TOP_ITEMS, &counters, &cancel);
+            done.store(true, Ordering::Relaxed);
+            tree
+        });
+
+        let mut state = if cancel.load(Ordering::Relaxed) { ScanState::Cancelled } else { ScanState::Completed };
+        let scan = Arc::new(build_scan(scan_id, &root, state, started_on, &counters, tree));
+        let mut error = None;
+
+        // partial trees would show up as shrinkage in later diffs
+        let saved = match state {
+            ScanState::Completed => self.store.save(&scan),
+            _ => Ok(()),
+        };
+
+        if let Err(err) = saved {
+            warn!("could not save scan {}: {}", scan_id, err);
+            state = ScanState::Failed;
+            error = Some(err.to_string());
+        }
+
+        self.finished.insert(scan_id, scan);
+        self.running.write().unwrap().remove(&scan_id);
+        self.emit_progress(scan_id, &root, state, &counters, error);
+    }
+
+    fn emit_progress(&self, scan_id: Uuid, root: &Path, state: ScanState, counters: &ScanCounters, error: Option<String>) {
+        let bytes = counters.bytes.load(Ordering::Relaxed);
+
+        let progress = ScanProgress {
+            scan_id,
+            root: root.to_string_lossy().into_owned(),
+            state,
+            files: counters.files.load(Ordering::Relaxed),
+            directories: counters.directories.load(Ordering::Relaxed),
+            bytes,
+            bytes_formatted: format_bytes(bytes),
+            current_path: counters.current_path.lock().unwrap().as_ref().map(|path| path.to_string_lossy().into_owned()),
+            error,
+        };
+
+        if let Err(err) = self.app_handle.emit("on-disk-usage-progress", progress) {
+            debug!("could not emit scan progress: {}", err);
+        }
+    }
+}
+
+fn build_scan(scan_id: Uuid, root: &Path, state: ScanState, started_on: DateTime<Utc>, counters: &ScanCounters, tree: ScanTree) -> SavedScan {
+    let entry = |path: &Path, totals: &DirTotals| UsageEntry {
+        path: path.to_string_lossy().into_owned(),
+        bytes: totals.bytes,
+        bytes_formatted: format_bytes(totals.bytes),
+        files: totals.files,
+    };
+
+    let total = tree.directories.get(root).copied().unwrap_or_default();
+
+    let mut directories: Vec<UsageEntry> = tree.directories.iter().map(|(path, totals)| entry(path, totals)).collect();
+    directories.sort_by_key(|item| Reverse(item.bytes));
+
+    let top_directories = directories
+        .iter()
+        .filter(|directory| Path::new(&directory.path) != root)
+        .take(TOP_ITEMS)
+        .cloned()
+        .collect();
+
+    let top_files = tree
+        .largest_files
+        .iter()
+        .map(|(path, size)| entry(path, &DirTotals { bytes: *size, files: 1 }))
+        .collect();
+
+    let mut extensions: Vec<ExtensionUsage> = tree
+        .extensions
+        .into_iter()
+        .map(|(extension, totals)| ExtensionUsage {
+            extension,
+            bytes: totals.bytes,
+            bytes_formatted: format_bytes(totals.bytes),
+            files: totals.files,
+        })
+        .collect();
+
+    extensions.sort_by_key(|item| Reverse(item.bytes));
+    extensions.truncate(TOP_ITEMS);
+
+    SavedScan {
+        report: DiskUsageReport {
+            scan_id,
+            root: root.to_string_lossy().into_owned(),
+            state,
+            started_on,
+            finished_on: Utc::now(),
+            total_bytes: total.bytes,
+            total_bytes_formatted: format_bytes(total.bytes),
+            total_files: total.files,
+            total_directories: counters.directories.load(Ordering::Relaxed),
+            errors: counters.errors.load(Ordering::Relaxed),
+            top_directories,
+            top_files,
+            extensions,
+        },
+        directories,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    fn scan_of(root: &Path) -> SavedScan {
+        let counters = ScanCounters::default();
+        let tree = scan(root, TOP_ITEMS, &counters, &AtomicBool::new(false));
+
+        build_scan(Uuid::now_v7(), root, ScanState::Completed, Utc::now(), &counters, tree)
+    }
+
+    fn change(diff: &DiskUsageDiff, path: &Path) -> Option<(Option<u64>, Option<u64>, i64)> {
+        diff.changes
+            .iter()
+            .find(|change| Path::new(&change.path) == path)
+            .map(|change| (change.before, change.after, change.delta))
+    }
+
+    #[test]
+    fn diff_reports_grown_added_and_removed_directories() {
+        let root = std::env::temp_dir().join(format!("disk-usage-diff-{}", Uuid::now_v7()));
+        fs::create_dir_all(root.join("grows")).unwrap();
+        fs::create_dir_all(root.join("removed")).unwrap();
+        fs::create_dir_all(root.join("same")).unwrap();
+        fs::write(root.join("grows/a"), [0; 100]).unwrap();
+        fs::write(root.join("removed/b"), [0; 40]).unwrap();
+        fs::write(root.join("same/c"), [0; 10]).unwrap();
+
+        let previous = scan_of(&root);
+
+        fs::write(root.join("grows/a2"), [0; 500]).unwrap();
+        fs::remove_dir_all(root.join("removed")).unwrap();
+        fs::create_dir_all(root.join("added")).unwrap();
+        fs::write(root.join("added/d"), [0; 7]).unwrap();
+
+        let current = scan_of(&root);
+        let diff = diff_scans(&previous, &current, 10);
+
+        assert_eq!(diff.total_delta, 500 - 40 + 7);
+        assert_eq!(change(&diff, &root), Some((Some(150), Some(617), 467)));
+        assert_eq!(change(&diff, &root.join("grows")), Some((Some(100), Some(600), 500)));
+        assert_eq!(change(&diff, &root.join("added")), Some((None, Some(7), 7)));
+        assert_eq!(change(&diff, &root.join("removed")), Some((Some(40), None, -40)));
+        assert_eq!(change(&diff, &root.join("same")), None);
+
+        // growth first, shrinkage last
+        assert_eq!(Path::new(&diff.changes[0].path), root.join("grows"));
+        assert_eq!(Path::new(&diff.changes.last().unwrap().path), root.join("removed"));
+
+        // the limit keeps the biggest changes either way
+        let limited = diff_scans(&previous, &current, 2);
+        let paths: Vec<&str> = limited.changes.iter().map(|change| change.path.as_str()).collect();
+        assert_eq!(paths, vec![root.join("grows").to_str().unwrap(), root.to_str().unwrap()]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn store_lists_saved_scans_from_their_headers() {
+        let root = std::env::temp_dir().join(format!("disk-usage-store-{}", Uuid::now_v7()));
+        fs::create_dir_all(root.join("data")).unwrap();
+        fs::write(root.join("data/a"), [0; 64]).unwrap();
+
+        let store = ScanStore::new(root.join("scans"));
+        let scan = scan_of(&root.join("data"));
+        let scan_id = scan.report.scan_id;
+        store.save(&scan).unwrap();
+
+        // listing must not need the full scan
+        fs::write(root.join("scans").join(format!("{}.json", scan_id)), "not json").unwrap();
+
+        let scans = store.list().unwrap();
+        assert_eq!(scans.len(), 1);
+        assert_eq!(scans[0].scan_id, scan_id);
+        assert_eq!(scans[0].total_bytes, 64);
+
+        store.delete(scan_id).unwrap();
+        assert!(store.list().unwrap().is_empty());
+        assert_eq!(fs::read_dir(root.join("scans")).unwrap().count(), 0);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
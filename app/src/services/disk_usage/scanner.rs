@@ -0,0 +1,309 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Condvar, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use hashbrown::HashMap;
+
+/// Running totals shared with the progress reporter
+#[derive(Debug, Default)]
+pub struct ScanCounters {
+    pub files: AtomicU64,
+    pub directories: AtomicU64,
+    pub bytes: AtomicU64,
+    pub errors: AtomicU64,
+    pub current_path: Mutex<Option<PathBuf>>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DirTotals {
+    /// apparent size of every file below the directory, hard links counted once per link
+    pub bytes: u64,
+    pub files: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct ScanTree {
+    /// cumulative totals per directory, the root included
+    pub directories: HashMap<PathBuf, DirTotals>,
+    /// largest files, biggest first
+    pub largest_files: Vec<(PathBuf, u64)>,
+    /// extension to bytes and file count
+    pub extensions: HashMap<String, DirTotals>,
+}
+
+/// Directories waiting to be read plus the number being read, workers stop once both are zero
+struct WorkQueue {
+    state: Mutex<(VecDeque<PathBuf>, usize)>,
+    ready: Condvar,
+}
+
+impl WorkQueue {
+    fn new(root: PathBuf) -> Self {
+        Self {
+            state: Mutex::new((VecDeque::from([root]), 0)),
+            ready: Condvar::new(),
+        }
+    }
+
+    fn pop(&self, cancel: &AtomicBool) -> Option<PathBuf> {
+        let mut state = self.state.lock().unwrap();
+
+        loop {
+            if cancel.load(Ordering::Relaxed) {
+                return None;
+            }
+
+            if let Some(path) = state.0.pop_front() {
+                state.1 += 1;
+                return Some(path);
+            }
+
+            if state.1 == 0 {
+                return None;
+            }
+
+            // wake up now and then so a cancel is noticed while others are still reading
+            state = self.ready.wait_timeout(state, Duration::from_millis(100)).unwrap().0;
+        }
+    }
+
+    fn finish(&self, found: Vec<PathBuf>) {
+        let mut state = self.state.lock().unwrap();
+        state.0.extend(found);
+        state.1 -= 1;
+        self.ready.notify_all();
+    }
+}
+
+/// What a single worker saw, merged once every worker is done
+#[derive(Default)]
+struct WorkerResult {
+    /// each directory with only the files directly inside it
+    directories: Vec<(PathBuf, DirTotals)>,
+    largest_files: BinaryHeap<Reverse<(u64, PathBuf)>>,
+    extensions: HashMap<String, DirTotals>,
+}
+
+/// Walks `root` on all cores without following symlinks or, on unix, crossing into other filesystems.
+/// A cancelled scan returns whatever was read so far.
+pub fn scan(root: &Path, top: usize, counters: &ScanCounters, cancel: &AtomicBool) -> ScanTree {
+    let queue = WorkQueue::new(root.to_path_buf());
+    let root_device = device_of(root);
+    let workers = thread::available_parallelism().map(|count| count.get()).unwrap_or(4).min(16);
+
+    let results: Vec<WorkerResult> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| scope.spawn(|| run_worker(&queue, root_device, top, counters, cancel)))
+            .collect();
+
+        handles.into_iter().filter_map(|handle| handle.join().ok()).collect()
+    });
+
+    merge(root, results, top)
+}
+
+fn run_worker(queue: &WorkQueue, root_device: Option<u64>, top: usize, counters: &ScanCounters, cancel: &AtomicBool) -> WorkerResult {
+    let mut result = WorkerResult::default();
+
+    while let Some(directory) = queue.pop(cancel) {
+        *counters.current_path.lock().unwrap() = Some(directory.clone());
+
+        let mut own = DirTotals::default();
+        let mut found = Vec::new();
+
+        match fs::read_dir(&directory) {
+            Ok(entries) => {
+                for entry in entries {
+                    let Ok(entry) = entry else {
+                        counters.errors.fetch_add(1, Ordering::Relaxed);
+                        continue;
+                    };
+
+                    // DirEntry::metadata does not follow symlinks
+                    let Ok(metadata) = entry.metadata() else {
+                        counters.errors.fetch_add(1, Ordering::Relaxed);
+                        continue;
+                    };
+
+                    if metadata.is_dir() {
+                        if root_device.is_none() || device_of(&entry.path()) == root_device {
+                            found.push(entry.path());
+                        }
+                        continue;
+                    }
+
+                    if !metadata.is_file() {
+                        continue;
+                    }
+
+                    let size = metadata.len();
+                    let path = entry.path();
+
+                    own.bytes += size;
+                    own.files += 1;
+
+                    let extension = path
+                        .extension()
+                        .map(|extension| extension.to_string_lossy().to_lowercase())
+                        .unwrap_or_default();
+                    let totals = result.extensions.entry(extension).or_default();
+                    totals.bytes += size;
+                    totals.files += 1;
+
+                    push_largest(&mut result.largest_files, top, size, path);
+                }
+            }
+            Err(_) => {
+                counters.errors.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        counters.files.fetch_add(own.files, Ordering::Relaxed);
+        counters.bytes.fetch_add(own.bytes, Ordering::Relaxed);
+        counters.directories.fetch_add(1, Ordering::Relaxed);
+
+        result.directories.push((directory, own));
+        queue.finish(found);
+    }
+
+    result
+}
+
+fn push_largest(heap: &mut BinaryHeap<Reverse<(u64, PathBuf)>>, top: usize, size: u64, path: PathBuf) {
+    if heap.len() < top {
+        heap.push(Reverse((size, path)));
+    } else if heap.peek().is_some_and(|Reverse((smallest, _))| size > *smallest) {
+        heap.pop();
+        heap.push(Reverse((size, path)));
+    }
+}
+
+/// Adds every directory's own totals to all of its ancestors up to `root`
+fn merge(root: &Path, results: Vec<WorkerResult>, top: usize) -> ScanTree {
+    let mut tree = ScanTree::default();
+    let mut largest = BinaryHeap::new();
+
+    for result in results {
+        for (directory, own) in result.directories {
+            let mut path = directory.as_path();
+
+            loop {
+                let totals = tree.directories.entry(path.to_path_buf()).or_default();
+                totals.bytes += own.bytes;
+                totals.files += own.files;
+
+                if path == root {
+                    break;
+                }
+
+                match path.parent() {
+                    Some(parent) => path = parent,
+                    None => break,
+                }
+            }
+        }
+
+        for (extension, totals) in result.extensions {
+            let merged = tree.extensions.entry(extension).or_default();
+            merged.bytes += totals.bytes;
+            merged.files += totals.files;
+        }
+
+        for Reverse((size, path)) in result.largest_files {
+            push_largest(&mut largest, top, size, path);
+        }
+    }
+
+    tree.largest_files = largest.into_sorted_vec().into_iter().map(|Reverse((size, path))| (path, size)).collect();
+    tree
+}
+
+#[cfg(unix)]
+fn device_of(path: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+
+    fs::symlink_metadata(path).ok().map(|metadata| metadata.dev())
+}
+
+/// Junctions and mount points are reparse points that `DirEntry::metadata` reports as symlinks
+#[cfg(not(unix))]
+fn device_of(_path: &Path) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_tree(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("{}-{}", name, uuid::Uuid::now_v7()));
+
+        fs::create_dir_all(root.join("sub/deep")).unwrap();
+        fs::create_dir_all(root.join("empty")).unwrap();
+        fs::write(root.join("a.txt"), [0; 10]).unwrap();
+        fs::write(root.join("sub/b.log"), [0; 20]).unwrap();
+        fs::write(root.join("sub/deep/c.LOG"), [0; 30]).unwrap();
+
+        root
+    }
+
+    #[test]
+    fn totals_are_cumulative_per_directory() {
+        let root = temp_tree("scan-totals");
+        let counters = ScanCounters::default();
+
+        let tree = scan(&root, 2, &counters, &AtomicBool::new(false));
+        let totals = |path: &Path| tree.directories.get(path).copied().map(|totals| (totals.bytes, totals.files));
+
+        assert_eq!(totals(&root), Some((60, 3)));
+        assert_eq!(totals(&root.join("sub")), Some((50, 2)));
+        assert_eq!(totals(&root.join("sub/deep")), Some((30, 1)));
+        assert_eq!(totals(&root.join("empty")), Some((0, 0)));
+        assert_eq!(tree.directories.len(), 4);
+
+        assert_eq!(tree.largest_files, vec![(root.join("sub/deep/c.LOG"), 30), (root.join("sub/b.log"), 20)]);
+        assert_eq!(tree.extensions.get("log").map(|totals| (totals.bytes, totals.files)), Some((50, 2)));
+        assert_eq!(tree.extensions.get("txt").map(|totals| (totals.bytes, totals.files)), Some((10, 1)));
+
+        assert_eq!(counters.files.load(Ordering::Relaxed), 3);
+        assert_eq!(counters.directories.load(Ordering::Relaxed), 4);
+        assert_eq!(counters.bytes.load(Ordering::Relaxed), 60);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_are_not_followed() {
+        let root = temp_tree("scan-symlinks");
+        std::os::unix::fs::symlink(root.join("sub"), root.join("empty/link")).unwrap();
+        std::os::unix::fs::symlink(root.join("a.txt"), root.join("empty/a.txt")).unwrap();
+
+        let tree = scan(&root, 10, &ScanCounters::default(), &AtomicBool::new(false));
+
+        assert_eq!(tree.directories.get(&root).map(|totals| totals.bytes), Some(60));
+        assert_eq!(tree.directories.get(&root.join("empty")).map(|totals| totals.bytes), Some(0));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn cancelled_scan_stops_early() {
+        let root = temp_tree("scan-cancel");
+
+        let tree = scan(&root, 10, &ScanCounters::default(), &AtomicBool::new(true));
+
+        assert!(tree.directories.is_empty());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,173 @@
+use std::{
+    cmp::Reverse,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    models::{DiskUsageDiff, DiskUsageReport, SavedScanInfo, UsageChange, UsageEntry},
+    utils::format_bytes,
+};
+
+/// A finished scan as written to disk, the report plus every directory total for drilling down and diffing
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedScan {
+    pub report: DiskUsageReport,
+    pub directories: Vec<UsageEntry>,
+}
+
+impl SavedScan {
+    /// Direct subdirectories of `path`, largest first
+    pub fn children(&self, path: &str) -> Vec<UsageEntry> {
+        let path = Path::new(path);
+
+        let mut children: Vec<UsageEntry> = self
+            .directories
+            .iter()
+            .filter(|entry| Path::new(&entry.path).parent() == Some(path))
+            .cloned()
+            .collect();
+
+        children.sort_by_key(|item| Reverse(item.bytes));
+        children
+    }
+}
+
+/// One JSON file per scan under `<exec dir>/disk_usage`, plus a small `<id>.info.json`
+/// header so listing does not parse every directory total
+pub struct ScanStore {
+    directory: PathBuf,
+}
+
+impl ScanStore {
+    pub fn new(directory: PathBuf) -> Self {
+        Self { directory }
+    }
+
+    pub fn save(&self, scan: &SavedScan) -> Result<()> {
+        fs::create_dir_all(&self.directory)?;
+        fs::write(self.path_of(scan.report.scan_id), serde_json::to_vec(scan)?)?;
+        self.save_info(&info_of(&scan.report))?;
+
+        Ok(())
+    }
+
+    pub fn load(&self, scan_id: Uuid) -> Result<SavedScan> {
+        let content = fs::read(self.path_of(scan_id)).map_err(|err| anyhow!("scan {} not found: {}", scan_id, err))?;
+
+        Ok(serde_json::from_slice(&content)?)
+    }
+
+    /// Saved scans, newest first
+    pub fn list(&self) -> Result<Vec<SavedScanInfo>> {
+        let Ok(entries) = fs::read_dir(&self.directory) else {
+            return Ok(vec![]);
+        };
+
+        let mut scans: Vec<SavedScanInfo> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let scan_id = entry.path().file_stem()?.to_str()?.parse::<Uuid>().ok()?;
+
+                self.load_info(scan_id).or_else(|| {
+                    // scans saved before headers were written
+                    let info = info_of(&self.load(scan_id).ok()?.report);
+                    let _ = self.save_info(&info);
+                    Some(info)
+                })
+            })
+            .collect();
+
+        scans.sort_by_key(|item| Reverse(item.finished_on));
+        Ok(scans)
+    }
+
+    pub fn delete(&self, scan_id: Uuid) -> Result<()> {
+        fs::remove_file(self.path_of(scan_id))?;
+        let _ = fs::remove_file(self.info_path_of(scan_id));
+
+        Ok(())
+    }
+
+    fn load_info(&self, scan_id: Uuid) -> Option<SavedScanInfo> {
+        let content = fs::read(self.info_path_of(scan_id)).ok()?;
+
+        serde_json::from_slice(&content).ok()
+    }
+
+    fn save_info(&self, info: &SavedScanInfo) -> Result<()> {
+        fs::write(self.info_path_of(info.scan_id), serde_json::to_vec(info)?)?;
+
+        Ok(())
+    }
+
+    fn path_of(&self, scan_id: Uuid) -> PathBuf {
+        self.directory.join(format!("{}.json", scan_id))
+    }
+
+    fn info_path_of(&self, scan_id: Uuid) -> PathBuf {
+        self.directory.join(format!("{}.info.json", scan_id))
+    }
+}
+
+fn info_of(report: &DiskUsageReport) -> SavedScanInfo {
+    SavedScanInfo {
+        scan_id: report.scan_id,
+        root: report.root.clone(),
+        state: report.state,
+        finished_on: report.finished_on,
+        total_bytes: report.total_bytes,
+        total_bytes_formatted: report.total_bytes_formatted.clone(),
+    }
+}
+
+/// Directory totals that changed between two scans of the same root, biggest growth first
+pub fn diff_scans(previous: &SavedScan, current: &SavedScan, limit: usize) -> DiskUsageDiff {
+    let before: HashMap<&str, u64> = previous.directories.iter().map(|entry| (entry.path.as_str(), entry.bytes)).collect();
+    let after: HashMap<&str, u64> = current.directories.iter().map(|entry| (entry.path.as_str(), entry.bytes)).collect();
+
+    let mut changes: Vec<UsageChange> = after
+        .keys()
+        .chain(before.keys().filter(|path| !after.contains_key(*path)))
+        .filter_map(|path| {
+            let before = before.get(path).copied();
+            let after = after.get(path).copied();
+            let delta = after.unwrap_or_default() as i64 - before.unwrap_or_default() as i64;
+
+            (delta != 0).then(|| UsageChange {
+                path: path.to_string(),
+                before,
+                after,
+                delta,
+                delta_formatted: format_delta(delta),
+            })
+        })
+        .collect();
+
+    // keep the biggest changes either way, then list growth first
+    changes.sort_by_key(|item| Reverse(item.delta.unsigned_abs()));
+    changes.truncate(limit);
+    changes.sort_by_key(|item| Reverse(item.delta));
+
+    let total_delta = current.report.total_bytes as i64 - previous.report.total_bytes as i64;
+
+    DiskUsageDiff {
+        previous_scan_id: previous.report.scan_id,
+        current_scan_id: current.report.scan_id,
+        total_delta,
+        total_delta_formatted: format_delta(total_delta),
+        changes,
+    }
+}
+
+fn format_delta(delta: i64) -> String {
+    let sign = if delta < 0 { "-" } else { "+" };
+
+    format!("{}{}", sign, format_bytes(delta.unsigned_abs()))
+}
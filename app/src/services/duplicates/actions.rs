@@ -0,0 +1,169 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::models::{ActionOutcome, DuplicateAction, DuplicateActionResult, DuplicateResolution};
+
+use super::finder::file_id;
+
+/// Deletes or hard links the files of one group after checking that each still matches the kept file.
+/// The kept file is hashed once, every other file is hashed again right before it is touched.
+pub fn resolve_group(group: &DuplicateResolution, action: DuplicateAction, dry_run: bool) -> Vec<DuplicateActionResult> {
+    let keep = Path::new(&group.keep);
+
+    let kept = fs::metadata(keep)
+        .map_err(|err| anyhow!("kept file unreadable: {}", err))
+        .and_then(|metadata| {
+            if !metadata.is_file() {
+                bail!("kept file is not a regular file");
+            }
+
+            let id = file_id(keep, &metadata).map_err(|err| anyhow!("kept file unreadable: {}", err))?;
+            let hash = sha256::try_digest(keep).map_err(|err| anyhow!("kept file unreadable: {}", err))?;
+            Ok((metadata, id, hash))
+        });
+
+    group
+        .remove
+        .iter()
+        .map(|path| {
+            let result = kept
+                .as_ref()
+                .map_err(|err| anyhow!("{}", err))
+                .and_then(|(metadata, id, hash)| check(metadata, *id, hash, Path::new(path), action));
+
+            let (outcome, reason, bytes_freed) = match result {
+                Err(err) => (ActionOutcome::Skipped, Some(err.to_string()), 0),
+                Ok(size) if dry_run => (ActionOutcome::WouldDo, None, size),
+                Ok(size) => match apply(keep, Path::new(path), action) {
+                    Ok(()) => (ActionOutcome::Done, None, size),
+                    Err(err) => (ActionOutcome::Failed, Some(err.to_string()), 0),
+                },
+            };
+
+            DuplicateActionResult {
+                path: path.clone(),
+                keep: group.keep.clone(),
+                outcome,
+                reason,
+                bytes_freed,
+            }
+        })
+        .collect()
+}
+
+/// Size that acting on `path` frees, or why it must be left alone
+fn check(kept: &fs::Metadata, kept_id: (u64, u64), hash: &str, path: &Path, action: DuplicateAction) -> Result<u64> {
+    let metadata = fs::symlink_metadata(path)?;
+
+    if !metadata.is_file() {
+        bail!("not a regular file");
+    }
+
+    if metadata.len() != kept.len() {
+        bail!("size differs from the kept file");
+    }
+
+    // the same path spelled differently or an existing hard link
+    let id = file_id(path, &metadata)?;
+
+    if id == kept_id {
+        bail!("this is the kept file or already a hard link to it");
+    }
+
+    if action == DuplicateAction::HardLink && id.0 != kept_id.0 {
+        bail!("hard links cannot cross volumes");
+    }
+
+    if sha256::try_digest(path)? != hash {
+        bail!("content differs from the kept file");
+    }
+
+    Ok(metadata.len())
+}
+
+fn apply(keep: &Path, path: &Path, action: DuplicateAction) -> Result<()> {
+    match action {
+        DuplicateAction::Delete => fs::remove_file(path)?,
+        DuplicateAction::HardLink => {
+            // link next to the duplicate first and swap it in, the duplicate survives a failed link
+            let temporary = temporary_path(path)?;
+            fs::hard_link(keep, &temporary)?;
+
+            if let Err(err) = fs::rename(&temporary, path) {
+                let _ = fs::remove_file(&temporary);
+                return Err(err.into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn temporary_path(path: &Path) -> Result<PathBuf> {
+    let name = path.file_name().ok_or_else(|| anyhow!("no file name"))?;
+
+    Ok(path.with_file_name(format!(".{}.duplicate-link", name.to_string_lossy())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcomes(keep: &Path, remove: &[PathBuf], action: DuplicateAction) -> Vec<(ActionOutcome, Option<String>)> {
+        let group = DuplicateResolution {
+            keep: keep.to_string_lossy().into_owned(),
+            remove: remove.iter().map(|path| path.to_string_lossy().into_owned()).collect(),
+        };
+
+        resolve_group(&group, action, false)
+            .into_iter()
+            .map(|result| (result.outcome, result.reason))
+            .collect()
+    }
+
+    #[test]
+    fn kept_file_and_its_hard_links_are_left_alone() {
+        let directory = std::env::temp_dir().join(format!("duplicates-{}", uuid::Uuid::now_v7()));
+        fs::create_dir_all(&directory).unwrap();
+        let keep = directory.join("keep");
+        fs::write(&keep, "same content").unwrap();
+        fs::hard_link(&keep, directory.join("link")).unwrap();
+        fs::write(directory.join("copy"), "same content").unwrap();
+
+        let results = outcomes(
+            &keep,
+            &[directory.join(".").join("keep"), directory.join("link"), directory.join("copy")],
+            DuplicateAction::Delete,
+        );
+
+        assert_eq!(results[0].0, ActionOutcome::Skipped);
+        assert_eq!(results[1].0, ActionOutcome::Skipped);
+        assert_eq!(results[2], (ActionOutcome::Done, None));
+        assert!(keep.exists());
+        assert!(directory.join("link").exists());
+        assert!(!directory.join("copy").exists());
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn hard_linked_duplicate_shares_the_kept_file() {
+        let directory = std::env::temp_dir().join(format!("duplicates-{}", uuid::Uuid::now_v7()));
+        fs::create_dir_all(&directory).unwrap();
+        let keep = directory.join("keep");
+        let copy = directory.join("copy");
+        fs::write(&keep, "same content").unwrap();
+        fs::write(&copy, "same content").unwrap();
+
+        assert_eq!(outcomes(&keep, std::slice::from_ref(&copy), DuplicateAction::HardLink), vec![(ActionOutcome::Done, None)]);
+
+        let keep_metadata = fs::metadata(&keep).unwrap();
+        assert_eq!(file_id(&keep, &keep_metadata).unwrap(), file_id(&copy, &fs::metadata(&copy).unwrap()).unwrap());
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
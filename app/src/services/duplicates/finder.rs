@@ -0,0 +1,371 @@
+use std::{
+    fs::{self, File},
+    io::{self, Read},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+    thread,
+};
+
+use hashbrown::{HashMap, HashSet};
+
+/// Bytes read from the start of each file for the partial hash
+pub const PARTIAL_SIZE: u64 = 16 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// Files that share a size and full SHA-256
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Duplicates {
+    pub hash: String,
+    pub size: u64,
+    pub paths: Vec<PathBuf>,
+}
+
+#[derive(Debug, Default)]
+pub struct FinderStats {
+    pub files_scanned: AtomicU64,
+    pub files_hashed: AtomicU64,
+    pub errors: AtomicU64,
+}
+
+/// Narrows candidates by size, then by a hash of the first bytes and finally by a full hash,
+/// so only files that could still be equal are read completely
+pub fn find_duplicates(roots: &[PathBuf], min_size: u64, stats: &FinderStats) -> Vec<Duplicates> {
+    let files = collect_files(roots, min_size.max(1), stats);
+
+    let mut by_size: HashMap<u64, Vec<Candidate>> = HashMap::new();
+    for file in files {
+        by_size.entry(file.size).or_default().push(file);
+    }
+
+    let same_size: Vec<Candidate> = by_size.into_values().filter(|group| group.len() > 1).flatten().collect();
+
+    let same_start = regroup(same_size, stats, |candidate| partial_hash(&candidate.path));
+
+    let mut duplicates = Vec::new();
+    let mut needs_full_hash = Vec::new();
+
+    for (hash, group) in same_start {
+        // files no larger than the partial read were hashed completely already
+        if group[0].size <= PARTIAL_SIZE {
+            duplicates.push(to_duplicates(hash, group));
+        } else {
+            needs_full_hash.extend(group);
+        }
+    }
+
+    let same_content = regroup(needs_full_hash, stats, |candidate| sha256::try_digest(candidate.path.as_path()));
+    duplicates.extend(same_content.into_iter().map(|(hash, group)| to_duplicates(hash, group)));
+
+    duplicates
+}
+
+fn to_duplicates(hash: String, mut group: Vec<Candidate>) -> Duplicates {
+    group.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Duplicates {
+        size: group[0].size,
+        hash,
+        paths: group.into_iter().map(|candidate| candidate.path).collect(),
+    }
+}
+
+/// Hashes every candidate in parallel and keeps the groups of two or more with equal size and hash
+fn regroup(
+    candidates: Vec<Candidate>,
+    stats: &FinderStats,
+    hash: impl Fn(&Candidate) -> io::Result<String> + Sync,
+) -> Vec<(String, Vec<Candidate>)> {
+    if candidates.is_empty() {
+        return vec![];
+    }
+
+    let workers = thread::available_parallelism().map(|count| count.get()).unwrap_or(4).min(8);
+    let chunk_size = candidates.len().div_ceil(workers);
+
+    let hashed: Vec<(String, Candidate)> = thread::scope(|scope| {
+        let handles: Vec<_> = candidates
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let hash = &hash;
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .filter_map(|candidate| match hash(candidate) {
+                            Ok(digest) => {
+                                stats.files_hashed.fetch_add(1, Ordering::Relaxed);
+                                Some((digest, candidate.clone()))
+                            }
+                            Err(_) => {
+                                stats.errors.fetch_add(1, Ordering::Relaxed);
+                                None
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        handles.into_iter().filter_map(|handle| handle.join().ok()).flatten().collect()
+    });
+
+    let mut groups: HashMap<(u64, String), Vec<Candidate>> = HashMap::new();
+    for (digest, candidate) in hashed {
+        groups.entry((candidate.size, digest)).or_default().push(candidate);
+    }
+
+    groups
+        .into_iter()
+        .filter(|(_, group)| group.len() > 1)
+        .map(|((_, digest), group)| (digest, group))
+        .collect()
+}
+
+pub fn partial_hash(path: &Path) -> io::Result<String> {
+    let mut buffer = Vec::with_capacity(PARTIAL_SIZE as usize);
+    File::open(path)?.take(PARTIAL_SIZE).read_to_end(&mut buffer)?;
+
+    Ok(sha256::digest(buffer.as_slice()))
+}
+
+/// Regular files below the roots without following symlinks. Overlapping roots
+/// and, on unix, hard links to one inode are only listed once.
+fn collect_files(roots: &[PathBuf], min_size: u64, stats: &FinderStats) -> Vec<Candidate> {
+    let mut files = Vec::new();
+    // directories already queued, overlapping roots would otherwise be walked twice
+    let mut seen_directories: HashSet<PathBuf> = roots.iter().cloned().collect();
+    let mut seen_inodes = HashSet::new();
+    let mut pending: Vec<PathBuf> = seen_directories.iter().cloned().collect();
+
+    while let Some(directory) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&directory) else {
+            stats.errors.fetch_add(1, Ordering::Relaxed);
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                stats.errors.fetch_add(1, Ordering::Relaxed);
+                continue;
+            };
+
+            let path = entry.path();
+
+            if metadata.is_dir() {
+                if seen_directories.insert(path.clone()) {
+                    pending.push(path);
+                }
+                continue;
+            }
+
+            if !metadata.is_file() {
+                continue;
+            }
+
+            stats.files_scanned.fetch_add(1, Ordering::Relaxed);
+
+            if metadata.len() < min_size {
+                continue;
+            }
+
+            if file_id(&path, &metadata).is_ok_and(|id| !seen_inodes.insert(id)) {
+                continue;
+            }
+
+            files.push(Candidate { path, size: metadata.len() });
+        }
+    }
+
+    files
+}
+
+/// Volume and file number, shared by every hard link to the same file
+#[cfg(unix)]
+pub fn file_id(_path: &Path, metadata: &fs::Metadata) -> io::Result<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+
+    Ok((metadata.dev(), metadata.ino()))
+}
+
+/// Volume serial number and file index, Windows only hands them out through an open handle
+#[cfg(not(unix))]
+pub fn file_id(path: &Path, _metadata: &fs::Metadata) -> io::Result<(u64, u64)> {
+    use std::{fs::OpenOptions, mem, os::windows::{fs::OpenOptionsExt, io::AsRawHandle}};
+    use winapi::um::{
+        fileapi::{GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION},
+        winbase::FILE_FLAG_OPEN_REPARSE_POINT,
+    };
+
+    // no access rights needed for the attributes, files opened elsewhere without sharing still qualify
+    let file = OpenOptions::new().access_mode(0).custom_flags(FILE_FLAG_OPEN_REPARSE_POINT).open(path)?;
+
+    unsafe {
+        let mut info: BY_HANDLE_FILE_INFORMATION = mem::zeroed();
+
+        if GetFileInformationByHandle(file.as_raw_handle() as _, &mut info) == 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok((info.dwVolumeSerialNumber as u64, ((info.nFileIndexHigh as u64) << 32) | info.nFileIndexLow as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_directory() -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("duplicates-finder-{}", uuid::Uuid::now_v7()));
+        fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    /// `size` bytes of `fill`, with `tail` written over the last bytes
+    fn write(path: &Path, size: usize, fill: u8, tail: &[u8]) {
+        let mut content = vec![fill; size];
+        content[size - tail.len()..].copy_from_slice(tail);
+        fs::write(path, content).unwrap();
+    }
+
+    fn sorted_paths(duplicates: &[Duplicates]) -> Vec<Vec<PathBuf>> {
+        let mut groups: Vec<Vec<PathBuf>> = duplicates.iter().map(|group| group.paths.clone()).collect();
+        groups.sort();
+        groups
+    }
+
+    #[test]
+    fn same_size_with_different_content_is_not_a_duplicate() {
+        let directory = temp_directory();
+        write(&directory.join("a"), 1000, b'a', b"");
+        write(&directory.join("b"), 1000, b'b', b"");
+        write(&directory.join("c"), 1000, b'a', b"");
+
+        let stats = FinderStats::default();
+        let duplicates = find_duplicates(std::slice::from_ref(&directory), 1, &stats);
+
+        assert_eq!(sorted_paths(&duplicates), vec![vec![directory.join("a"), directory.join("c")]]);
+        assert_eq!(duplicates[0].size, 1000);
+        assert_eq!(duplicates[0].hash, sha256::digest(vec![b'a'; 1000].as_slice()));
+        assert_eq!(stats.files_scanned.load(Ordering::Relaxed), 3);
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn same_start_with_a_different_tail_is_not_a_duplicate() {
+        let directory = temp_directory();
+        let size = PARTIAL_SIZE as usize * 3;
+        write(&directory.join("a"), size, b'x', b"one");
+        write(&directory.join("b"), size, b'x', b"two");
+
+        let stats = FinderStats::default();
+
+        assert_eq!(partial_hash(&directory.join("a")).unwrap(), partial_hash(&directory.join("b")).unwrap());
+        assert!(find_duplicates(std::slice::from_ref(&directory), 1, &stats).is_empty());
+        // both partial and full hashes were taken
+        assert_eq!(stats.files_hashed.load(Ordering::Relaxed), 4);
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn large_duplicates_are_found_by_their_full_hash() {
+        let directory = temp_directory();
+        let size = PARTIAL_SIZE as usize + 1;
+        write(&directory.join("a"), size, b'x', b"end");
+        write(&directory.join("b"), size, b'x', b"end");
+        write(&directory.join("c"), size, b'x', b"END");
+
+        let duplicates = find_duplicates(std::slice::from_ref(&directory), 1, &FinderStats::default());
+
+        assert_eq!(sorted_paths(&duplicates), vec![vec![directory.join("a"), directory.join("b")]]);
+        assert_eq!(duplicates[0].size, size as u64);
+        assert_eq!(duplicates[0].hash, sha256::try_digest(directory.join("a").as_path()).unwrap());
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn hard_links_are_reported_once() {
+        let directory = temp_directory();
+        write(&directory.join("a"), 100, b'a', b"");
+        fs::hard_link(directory.join("a"), directory.join("link")).unwrap();
+
+        let stats = FinderStats::default();
+
+        assert!(find_duplicates(std::slice::from_ref(&directory), 1, &stats).is_empty());
+        assert_eq!(collect_files(std::slice::from_ref(&directory), 1, &stats).len(), 1);
+
+        write(&directory.join("copy"), 100, b'a', b"");
+        let duplicates = find_duplicates(std::slice::from_ref(&directory), 1, &stats);
+
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].paths.len(), 2);
+        assert!(duplicates[0].paths.contains(&directory.join("copy")));
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn overlapping_roots_are_walked_once() {
+        let directory = temp_directory();
+        let nested = directory.join("nested");
+        fs::create_dir_all(&nested).unwrap();
+        write(&directory.join("a"), 100, b'a', b"");
+        write(&nested.join("b"), 100, b'a', b"");
+
+        let roots = [nested.clone(), directory.clone(), nested.clone()];
+        let files = collect_files(&roots, 1, &FinderStats::default());
+        let duplicates = find_duplicates(&roots, 1, &FinderStats::default());
+
+        assert_eq!(files.len(), 2);
+        assert_eq!(sorted_paths(&duplicates), vec![vec![directory.join("a"), nested.join("b")]]);
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn files_below_the_minimum_size_are_ignored() {
+        let directory = temp_directory();
+        write(&directory.join("a"), 10, b'a', b"");
+        write(&directory.join("b"), 10, b'a', b"");
+        fs::write(directory.join("empty"), "").unwrap();
+        fs::write(directory.join("also-empty"), "").unwrap();
+
+        assert!(find_duplicates(std::slice::from_ref(&directory), 11, &FinderStats::default()).is_empty());
+        // empty files are never duplicates of each other
+        assert_eq!(find_duplicates(std::slice::from_ref(&directory), 0, &FinderStats::default()).len(), 1);
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn groups_are_split_by_size_and_hash() {
+        let candidate = |path: &str, size: u64| Candidate { path: PathBuf::from(path), size };
+        let candidates = vec![candidate("a", 1), candidate("b", 1), candidate("c", 2), candidate("d", 1)];
+
+        // a and c share a hash but not a size
+        let groups = regroup(candidates, &FinderStats::default(), |candidate| {
+            Ok(if candidate.path == Path::new("a") || candidate.path == Path::new("c") { "first" } else { "second" }.to_string())
+        });
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].0, "second");
+        assert_eq!(groups[0].1.len(), 2);
+    }
+
+    #[test]
+    fn unreadable_candidates_count_as_errors() {
+        let stats = FinderStats::default();
+        let candidates = vec![Candidate { path: "a".into(), size: 1 }, Candidate { path: "b".into(), size: 1 }];
+
+        let groups = regroup(candidates, &stats, |_| Err(io::ErrorKind::NotFound.into()));
+
+        assert!(groups.is_empty());
+        assert_eq!(stats.errors.load(Ordering::Relaxed), 2);
+    }
+}
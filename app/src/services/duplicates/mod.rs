@@ -0,0 +1,86 @@
+use std::{cmp::Reverse, path::PathBuf, sync::atomic::Ordering};
+
+use anyhow::{bail, Result};
+
+use crate::{
+    models::{DuplicateGroup, DuplicateReport, FindDuplicatesArgs, ResolveDuplicatesArgs, ResolveDuplicatesResult},
+    utils::format_bytes,
+};
+
+mod actions;
+mod finder;
+
+pub use self::actions::resolve_group;
+pub use self::finder::{find_duplicates, FinderStats};
+
+/// Finds files with identical content and reclaims the space they take
+#[derive(Clone)]
+pub struct DuplicateService;
+
+impl DuplicateService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn find(&self, args: &FindDuplicatesArgs) -> Result<DuplicateReport> {
+        let roots: Vec<PathBuf> = args.roots.iter().map(PathBuf::from).collect();
+
+        if roots.is_empty() {
+            bail!("no folder to search");
+        }
+
+        if let Some(root) = roots.iter().find(|root| !root.is_dir()) {
+            bail!("{} is not a directory", root.display());
+        }
+
+        let stats = FinderStats::default();
+
+        let mut groups: Vec<DuplicateGroup> = find_duplicates(&roots, args.min_size, &stats)
+            .into_iter()
+            .map(|duplicates| {
+                let wasted = duplicates.size * (duplicates.paths.len() as u64 - 1);
+
+                DuplicateGroup {
+                    hash: duplicates.hash,
+                    size: duplicates.size,
+                    size_formatted: format_bytes(duplicates.size),
+                    files: duplicates.paths.iter().map(|path| path.to_string_lossy().into_owned()).collect(),
+                    wasted,
+                    wasted_formatted: format_bytes(wasted),
+                }
+            })
+            .collect();
+
+        groups.sort_by_key(|item| Reverse(item.wasted));
+
+        let total_wasted = groups.iter().map(|group| group.wasted).sum();
+
+        Ok(DuplicateReport {
+            groups,
+            files_scanned: stats.files_scanned.load(Ordering::Relaxed),
+            files_hashed: stats.files_hashed.load(Ordering::Relaxed),
+            total_wasted,
+            total_wasted_formatted: format_bytes(total_wasted),
+            errors: stats.errors.load(Ordering::Relaxed),
+        })
+    }
+
+    /// Deletes or hard links the chosen duplicates, `dry_run` only reports what would happen
+    pub fn resolve(&self, args: &ResolveDuplicatesArgs) -> ResolveDuplicatesResult {
+        let results: Vec<_> = args
+            .groups
+            .iter()
+            .flat_map(|group| resolve_group(group, args.action, args.dry_run))
+            .collect();
+
+        let bytes_freed = results.iter().map(|result| result.bytes_freed).sum();
+
+        ResolveDuplicatesResult {
+            action: args.action,
+            dry_run: args.dry_run,
+            results,
+            bytes_freed,
+            bytes_freed_formatted: format_bytes(bytes_freed),
+        }
+    }
+}
@@ -0,0 +1,393 @@
+use std::{
+    collections::HashMap,
+    fs,
+    net::IpAddr,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::{Duration, Instant, SystemTime},
+};
+
+use anyhow::Result;
+use log::*;
+use maxminddb::{geoip2, MaxMindDBError, Reader};
+
+use crate::models::{GeoInfo, GeoIpDatabase};
+
+struct LoadedDatabase {
+    path: PathBuf,
+    reader: Reader<Vec<u8>>,
+}
+
+struct GeoIpState {
+    paths: Vec<PathBuf>,
+    /// modification time of every path at its last load attempt, failed ones included,
+    /// so a broken file is only retried once it changes
+    seen: HashMap<PathBuf, Option<SystemTime>>,
+    databases: Vec<LoadedDatabase>,
+    checked_on: Instant,
+}
+
+/// Offline country/city/ASN lookups from MaxMind-format `.mmdb` files.
+/// Nothing leaves the machine, the databases are provided by the user.
+#[derive(Clone)]
+pub struct GeoIpService {
+    check_interval: Duration,
+    /// the paths chosen by the user, kept next to the database directory
+    settings_path: PathBuf,
+    state: Arc<RwLock<GeoIpState>>,
+}
+
+impl GeoIpService {
+    /// Loads the databases chosen earlier, or every `.mmdb` file from the given directory
+    /// when nothing was chosen yet. A missing directory is not an error.
+    pub fn new(database_dir: &Path) -> Self {
+        let settings_path = database_dir.with_extension("json");
+
+        let paths = fs::read(&settings_path)
+            .ok()
+            .and_then(|content| serde_json::from_slice(&content).ok())
+            .unwrap_or_else(|| {
+                fs::read_dir(database_dir)
+                    .map(|entries| {
+                        entries
+                            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                            .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("mmdb")))
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            });
+
+        let check_interval = Duration::from_secs(10);
+        let service = Self {
+            check_interval,
+            settings_path,
+            state: Arc::new(RwLock::new(GeoIpState {
+                paths: vec![],
+                seen: HashMap::new(),
+                databases: vec![],
+                checked_on: Instant::now(),
+            })),
+        };
+
+        service.apply_paths(paths);
+        service
+    }
+
+    /// Loads the given databases and remembers them for the next start
+    pub fn set_paths(&self, paths: Vec<PathBuf>) -> Vec<GeoIpDatabase> {
+        if let Err(err) = self.save_paths(&paths) {
+            warn!("could not save {}: {}", self.settings_path.display(), err);
+        }
+
+        self.apply_paths(paths)
+    }
+
+    fn save_paths(&self, paths: &[PathBuf]) -> Result<()> {
+        fs::write(&self.settings_path, serde_json::to_vec(paths)?)?;
+        Ok(())
+    }
+
+    fn apply_paths(&self, paths: Vec<PathBuf>) -> Vec<GeoIpDatabase> {
+        let seen = paths.iter().map(|path| (path.clone(), Self::modified(path))).collect();
+        let databases = paths.iter().filter_map(|path| Self::load(path)).collect();
+
+        let mut state = self.state.write().unwrap();
+        state.paths = paths;
+        state.seen = seen;
+        state.databases = databases;
+        state.checked_on = Instant::now();
+
+        Self::describe(&state.databases)
+    }
+
+    pub fn get_databases(&self) -> Vec<GeoIpDatabase> {
+        self.reload_if_changed();
+        let state = self.state.read().unwrap();
+        Self::describe(&state.databases)
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        !self.state.read().unwrap().databases.is_empty()
+    }
+
+    /// Merges what every loaded database knows about the address
+    pub fn lookup(&self, ip: IpAddr) -> Option<GeoInfo> {
+        self.reload_if_changed();
+
+        let state = self.state.read().unwrap();
+        let mut info = GeoInfo::default();
+
+        for database in &state.databases {
+            if database.reader.metadata.database_type.contains("ASN") {
+                match database.reader.lookup::<geoip2::Asn>(ip) {
+                    Ok(asn) => {
+                        info.asn = info.asn.or(asn.autonomous_system_number);
+                        info.organization = info.organization.or(asn.autonomous_system_organization.map(str::to_string));
+                    }
+                    Err(MaxMindDBError::AddressNotFoundError(_)) => {}
+                    Err(err) => debug!("{} lookup failed: {}", database.path.display(), err),
+                }
+
+                continue;
+            }
+
+            // City is a superset of Country, so it decodes both database kinds
+            match database.reader.lookup::<geoip2::City>(ip) {
+                Ok(city) => {
+                    if let Some(country) = city.country {
+                        info.country_code = info.country_code.or(country.iso_code.map(str::to_string));
+                        info.country = info.country.or(country.names.and_then(|names| names.get("en").map(|name| name.to_string())));
+                    }
+
+                    if let Some(city) = city.city {
+                        info.city = info.city.or(city.names.and_then(|names| names.get("en").map(|name| name.to_string())));
+                    }
+                }
+                Err(MaxMindDBError::AddressNotFoundError(_)) => {}
+                Err(err) => debug!("{} lookup failed: {}", database.path.display(), err),
+            }
+        }
+
+        (info != GeoInfo::default()).then_some(info)
+    }
+
+    /// Reloads the databases whose file changed on disk, checked at most every `check_interval`.
+    /// Files are read without holding the lock and a database that fails to reload keeps its old contents.
+    pub fn reload_if_changed(&self) {
+        {
+            let state = self.state.read().unwrap();
+            if state.checked_on.elapsed() < self.check_interval {
+                return;
+            }
+        }
+
+        let seen = {
+            let mut state = self.state.write().unwrap();
+
+            // another caller got here first
+            if state.checked_on.elapsed() < self.check_interval {
+                return;
+            }
+
+            state.checked_on = Instant::now();
+            state.seen.clone()
+        };
+
+        let changed = Self::changed_paths(&seen);
+
+        if changed.is_empty() {
+            return;
+        }
+
+        info!("{} geoip database(s) changed, reloading", changed.len());
+        let reloaded: Vec<_> = changed.into_iter().map(|(path, modified)| {
+            let database = Self::load(&path);
+            (path, modified, database)
+        }).collect();
+
+        let mut state = self.state.write().unwrap();
+        let GeoIpState { paths, seen, databases, .. } = &mut *state;
+
+        for (path, modified, database) in reloaded {
+            // the paths were replaced in the meantime
+            let Some(last_seen) = seen.get_mut(&path) else {
+                continue;
+            };
+
+            *last_seen = modified;
+
+            let Some(database) = database else {
+                continue;
+            };
+
+            match databases.iter().position(|loaded| loaded.path == path) {
+                Some(index) => databases[index] = database,
+                None => {
+                    databases.push(database);
+                    // earlier paths win when databases disagree
+                    databases.sort_by_key(|loaded| paths.iter().position(|path| *path == loaded.path));
+                }
+            }
+        }
+    }
+
+    /// Paths whose modification time differs from the one of the last load attempt
+    fn changed_paths(seen: &HashMap<PathBuf, Option<SystemTime>>) -> Vec<(PathBuf, Option<SystemTime>)> {
+        seen.iter()
+            .filter_map(|(path, last_seen)| {
+                let modified = Self::modified(path);
+                (modified != *last_seen).then(|| (path.clone(), modified))
+            })
+            .collect()
+    }
+
+    fn load(path: &Path) -> Option<LoadedDatabase> {
+        match Reader::open_readfile(path) {
+            Ok(reader) => Some(LoadedDatabase {
+                path: path.to_owned(),
+                reader,
+            }),
+            Err(err) => {
+                warn!("could not load geoip database {}: {}", path.display(), err);
+                None
+            }
+        }
+    }
+
+    fn modified(path: &Path) -> Option<SystemTime> {
+        fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+    }
+
+    fn describe(databases: &[LoadedDatabase]) -> Vec<GeoIpDatabase> {
+        databases
+            .iter()
+            .map(|database| GeoIpDatabase {
+                path: database.path.clone(),
+                database_type: database.reader.metadata.database_type.clone(),
+                build_epoch: database.reader.metadata.build_epoch,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use uuid::Uuid;
+
+    use super::*;
+
+    /// An IPv4 database with a single empty node, enough for the reader to open it
+    fn mmdb(database_type: &str) -> Vec<u8> {
+        let string = |value: &str| [&[0x40 | value.len() as u8], value.as_bytes()].concat();
+        let metadata: [(&str, Vec<u8>); 9] = [
+            ("binary_format_major_version", vec![0xA1, 2]),
+            ("binary_format_minor_version", vec![0xA0]),
+            ("build_epoch", vec![0x04, 0x02, 0x65, 0x00, 0x00, 0x00]),
+            ("database_type", string(database_type)),
+            ("description", vec![0xE0]),
+            ("ip_version", vec![0xA1, 4]),
+            ("languages", vec![0x00, 0x04]),
+            ("node_count", vec![0xC1, 1]),
+            ("record_size", vec![0xA1, 24]),
+        ];
+
+        // both 24 bit records equal the node count: no data for any address
+        let mut bytes = vec![0, 0, 1, 0, 0, 1];
+        bytes.extend([0u8; 16]);
+        bytes.extend(b"\xAB\xCD\xEFMaxMind.com");
+        bytes.push(0xE0 | metadata.len() as u8);
+
+        for (key, value) in metadata {
+            bytes.extend(string(key));
+            bytes.extend(value);
+        }
+
+        bytes
+    }
+
+    fn write(path: &Path, content: &[u8], modified: SystemTime) {
+        fs::write(path, content).unwrap();
+        File::options().write(true).open(path).unwrap().set_modified(modified).unwrap();
+    }
+
+    fn service(directory: &Path, paths: Vec<PathBuf>) -> GeoIpService {
+        let mut service = GeoIpService::new(&directory.join("geoip"));
+        service.check_interval = Duration::ZERO;
+        service.set_paths(paths);
+        service
+    }
+
+    fn types(service: &GeoIpService) -> Vec<String> {
+        service.get_databases().into_iter().map(|database| database.database_type).collect()
+    }
+
+    #[test]
+    fn reloads_a_changed_database_only() {
+        let directory = std::env::temp_dir().join(format!("geoip-{}", Uuid::now_v7()));
+        fs::create_dir_all(&directory).unwrap();
+
+        let city = directory.join("city.mmdb");
+        let asn = directory.join("asn.mmdb");
+        let then = SystemTime::now() - Duration::from_secs(3600);
+        write(&city, &mmdb("Test-City"), then);
+        write(&asn, &mmdb("Test-ASN"), then);
+
+        let service = service(&directory, vec![city.clone(), asn.clone()]);
+        let unchanged = GeoIpService::changed_paths(&service.state.read().unwrap().seen);
+        let before = types(&service);
+
+        write(&asn, &mmdb("Test-ASN-v2"), SystemTime::now());
+        let changed = GeoIpService::changed_paths(&service.state.read().unwrap().seen);
+        let after = types(&service);
+
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert!(unchanged.is_empty());
+        assert_eq!(before, vec!["Test-City", "Test-ASN"]);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].0, asn);
+        assert_eq!(after, vec!["Test-City", "Test-ASN-v2"]);
+    }
+
+    #[test]
+    fn a_failed_load_is_retried_once_the_file_changes() {
+        let directory = std::env::temp_dir().join(format!("geoip-{}", Uuid::now_v7()));
+        fs::create_dir_all(&directory).unwrap();
+
+        let path = directory.join("broken.mmdb");
+        write(&path, b"not a database", SystemTime::now() - Duration::from_secs(3600));
+
+        let service = service(&directory, vec![path.clone()]);
+        let loaded = service.is_loaded();
+        // the failed attempt counts as seen, nothing is reloaded on later checks
+        let unchanged = GeoIpService::changed_paths(&service.state.read().unwrap().seen);
+
+        write(&path, &mmdb("Test-City"), SystemTime::now());
+        let after = types(&service);
+
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert!(!loaded);
+        assert!(unchanged.is_empty());
+        assert_eq!(after, vec!["Test-City"]);
+    }
+
+    #[test]
+    fn a_database_that_fails_to_reload_keeps_its_contents() {
+        let directory = std::env::temp_dir().join(format!("geoip-{}", Uuid::now_v7()));
+        fs::create_dir_all(&directory).unwrap();
+
+        let path = directory.join("city.mmdb");
+        write(&path, &mmdb("Test-City"), SystemTime::now() - Duration::from_secs(3600));
+
+        let service = service(&directory, vec![path.clone()]);
+
+        write(&path, b"half copied", SystemTime::now());
+        let after = types(&service);
+        let retried = GeoIpService::changed_paths(&service.state.read().unwrap().seen);
+
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(after, vec!["Test-City"]);
+        assert!(retried.is_empty());
+    }
+
+    #[test]
+    fn remembers_the_chosen_paths() {
+        let directory = std::env::temp_dir().join(format!("geoip-{}", Uuid::now_v7()));
+        fs::create_dir_all(&directory).unwrap();
+
+        let path = directory.join("city.mmdb");
+        write(&path, &mmdb("Test-City"), SystemTime::now());
+
+        service(&directory, vec![path]);
+        let reopened = GeoIpService::new(&directory.join("geoip"));
+        let loaded = types(&reopened);
+
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(loaded, vec!["Test-City"]);
+    }
+}
@@ -0,0 +1,294 @@
+use std::{
+    collections::HashMap,
+    ffi::CString,
+    fs::{self, File},
+    io::ErrorKind,
+    mem,
+    os::fd::AsRawFd,
+    path::Path,
+};
+
+use anyhow::Result;
+
+use crate::models::{HandleAccessDenied, HandleInfo};
+
+use super::{HandleSnapshot, HandleSource};
+
+const O_ACCMODE: u32 = libc::O_ACCMODE as u32;
+const O_WRONLY: u32 = libc::O_WRONLY as u32;
+const O_RDWR: u32 = libc::O_RDWR as u32;
+
+const S_IFMT: u32 = 0o170000;
+const S_IFSOCK: u32 = 0o140000;
+const S_IFLNK: u32 = 0o120000;
+const S_IFREG: u32 = 0o100000;
+const S_IFBLK: u32 = 0o060000;
+const S_IFDIR: u32 = 0o040000;
+const S_IFCHR: u32 = 0o020000;
+const S_IFIFO: u32 = 0o010000;
+
+/// Open flags beyond the access mode, as printed in `fdinfo`. The values differ between
+/// architectures, 64-bit glibc targets define `O_LARGEFILE` as 0 so the bit the kernel sets is not named there.
+const OPEN_FLAGS: &[(u32, &str)] = &[
+    (libc::O_CREAT as u32, "O_CREAT"),
+    (libc::O_EXCL as u32, "O_EXCL"),
+    (libc::O_NOCTTY as u32, "O_NOCTTY"),
+    (libc::O_TRUNC as u32, "O_TRUNC"),
+    (libc::O_APPEND as u32, "O_APPEND"),
+    (libc::O_NONBLOCK as u32, "O_NONBLOCK"),
+    (libc::O_DSYNC as u32, "O_DSYNC"),
+    (libc::O_ASYNC as u32, "O_ASYNC"),
+    (libc::O_DIRECT as u32, "O_DIRECT"),
+    (libc::O_LARGEFILE as u32, "O_LARGEFILE"),
+    (libc::O_DIRECTORY as u32, "O_DIRECTORY"),
+    (libc::O_NOFOLLOW as u32, "O_NOFOLLOW"),
+    (libc::O_NOATIME as u32, "O_NOATIME"),
+    (libc::O_CLOEXEC as u32, "O_CLOEXEC"),
+    // O_SYNC and O_TMPFILE include the O_DSYNC and O_DIRECTORY bits
+    (libc::O_SYNC as u32, "O_SYNC"),
+    (libc::O_PATH as u32, "O_PATH"),
+    (libc::O_TMPFILE as u32, "O_TMPFILE"),
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FdInfo {
+    pub flags: u32,
+    /// target of a pidfd
+    pub pid: Option<u32>,
+}
+
+/// Descriptors from `/proc/<pid>/fd` with flags from `/proc/<pid>/fdinfo`
+pub struct ProcFdSource;
+
+impl ProcFdSource {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl HandleSource for ProcFdSource {
+    fn enumerate(&self, _names: &HashMap<u32, String>) -> Result<HandleSnapshot> {
+        let mut snapshot = HandleSnapshot::default();
+
+        for entry in fs::read_dir("/proc")?.flatten() {
+            let Some(process_id) = entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+                continue;
+            };
+
+            let process_name = fs::read_to_string(entry.path().join("comm"))
+                .map(|name| name.trim().to_string())
+                .unwrap_or_default();
+
+            let fds = match fs::read_dir(entry.path().join("fd")) {
+                Ok(fds) => fds,
+                // the process exited while walking /proc
+                Err(err) if err.kind() == ErrorKind::NotFound => continue,
+                Err(err) => {
+                    snapshot.denied.push(HandleAccessDenied {
+                        process_id,
+                        process_name,
+                        reason: err.to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            let Ok(fd_dir) = File::open(entry.path().join("fd")) else {
+                continue;
+            };
+
+            for fd in fds.flatten() {
+                let Some(descriptor) = fd.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+                    continue;
+                };
+
+                let Ok(target) = fs::read_link(fd.path()) else {
+                    continue;
+                };
+
+                let target = target.to_string_lossy().into_owned();
+                let file_mode = object_mode(&fd_dir, descriptor, &target);
+                let fdinfo = read_fdinfo(&entry.path(), descriptor).unwrap_or_default();
+
+                snapshot.handles.push(HandleInfo {
+                    process_id,
+                    process_name: process_name.clone(),
+                    handle: descriptor,
+                    object_type: 0,
+                    type_name: Some(fd_type_name(&target, file_mode)),
+                    object_name: Some(target),
+                    granted_access: fdinfo.flags,
+                    access_rights: decode_open_flags(fdinfo.flags),
+                    target_process_id: fdinfo.pid,
+                    file_mode,
+                });
+            }
+        }
+
+        Ok(snapshot)
+    }
+}
+
+fn read_fdinfo(process: &Path, descriptor: u32) -> Option<FdInfo> {
+    fs::read_to_string(process.join("fdinfo").join(descriptor.to_string()))
+        .ok()
+        .map(|content| parse_fdinfo(&content))
+}
+
+/// File type and permission bits of the object behind a descriptor. Sockets and pipes are told
+/// apart by their link target, only path targets are stat'ed, relative to the open fd directory.
+fn object_mode(fd_dir: &File, descriptor: u32, target: &str) -> Option<u32> {
+    if target.starts_with("socket:[") {
+        return Some(S_IFSOCK);
+    }
+
+    if target.starts_with("pipe:[") {
+        return Some(S_IFIFO);
+    }
+
+    if !target.starts_with('/') {
+        return None;
+    }
+
+    let name = CString::new(descriptor.to_string()).ok()?;
+
+    unsafe {
+        let mut stat: libc::stat = mem::zeroed();
+        // following the magic link reaches the open object, unlinked files included
+        (libc::fstatat(fd_dir.as_raw_fd(), name.as_ptr(), &mut stat, 0) == 0).then_some(stat.st_mode)
+    }
+}
+
+/// Parses `/proc/<pid>/fdinfo/<fd>`
+///
+/// ```text
+/// pos:    0
+/// flags:  02004002
+/// mnt_id: 15
+/// Pid:    1234
+/// ```
+pub fn parse_fdinfo(content: &str) -> FdInfo {
+    let mut info = FdInfo::default();
+
+    for line in content.lines() {
+        if let Some(flags) = line.strip_prefix("flags:") {
+            info.flags = u32::from_str_radix(flags.trim(), 8).unwrap_or_default();
+        } else if let Some(pid) = line.strip_prefix("Pid:") {
+            // -1 once the process has been reaped
+            info.pid = pid.trim().parse::<u32>().ok();
+        }
+    }
+
+    info
+}
+
+/// `socket:[123]` -> socket, `anon_inode:[eventfd]` -> anon_inode:eventfd,
+/// paths are classified by the file type bits of their mode
+pub fn fd_type_name(target: &str, mode: Option<u32>) -> String {
+    if let Some(kind) = target.strip_prefix("anon_inode:") {
+        return format!("anon_inode:{}", kind.trim_start_matches('[').trim_end_matches(']'));
+    }
+
+    if let Some((kind, _)) = target.split_once(":[") {
+        return kind.to_string();
+    }
+
+    let kind = match mode.map(|mode| mode & S_IFMT) {
+        Some(S_IFREG) => "file",
+        Some(S_IFDIR) => "directory",
+        Some(S_IFCHR) => "char device",
+        Some(S_IFBLK) => "block device",
+        Some(S_IFIFO) => "fifo",
+        Some(S_IFSOCK) => "socket",
+        Some(S_IFLNK) => "symlink",
+        // unlinked files can no longer be stat'ed through some filesystems
+        _ => "unknown",
+    };
+
+    kind.to_string()
+}
+
+pub fn decode_open_flags(flags: u32) -> Vec<String> {
+    let access = match flags & O_ACCMODE {
+        O_WRONLY => "O_WRONLY",
+        O_RDWR => "O_RDWR",
+        _ => "O_RDONLY",
+    };
+
+    std::iter::once(access)
+        .chain(OPEN_FLAGS.iter().filter(|(flag, _)| *flag != 0 && flags & flag == *flag).map(|(_, name)| *name))
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn object_mode_of_pseudo_files_comes_from_the_target() {
+        let fd_dir = File::open("/proc/self/fd").unwrap();
+
+        assert_eq!(object_mode(&fd_dir, 0, "socket:[1234]"), Some(S_IFSOCK));
+        assert_eq!(object_mode(&fd_dir, 0, "pipe:[1234]"), Some(S_IFIFO));
+        assert_eq!(object_mode(&fd_dir, 0, "anon_inode:[eventfd]"), None);
+    }
+
+    #[test]
+    fn object_mode_of_paths_follows_the_descriptor() {
+        let fd_dir = File::open("/proc/self/fd").unwrap();
+        let file = File::open("Cargo.toml").unwrap();
+        let directory = File::open("src").unwrap();
+
+        let file_mode = object_mode(&fd_dir, file.as_raw_fd() as u32, "/app/Cargo.toml");
+        let directory_mode = object_mode(&fd_dir, directory.as_raw_fd() as u32, "/app/src");
+
+        assert_eq!(fd_type_name("/app/Cargo.toml", file_mode), "file");
+        assert_eq!(fd_type_name("/app/src", directory_mode), "directory");
+    }
+
+    #[test]
+    fn fdinfo_gives_flags_and_pidfd_target() {
+        let info = parse_fdinfo("pos:\t0\nflags:\t02004002\nmnt_id:\t15\nino:\t1057\nPid:\t1234\nNSpid:\t1234\n");
+
+        assert_eq!(info, FdInfo { flags: 0o2004002, pid: Some(1234) });
+    }
+
+    #[test]
+    fn fdinfo_of_a_reaped_pidfd_has_no_target() {
+        let info = parse_fdinfo("pos:\t0\nflags:\t02000002\nPid:\t-1\n");
+
+        assert_eq!(info.pid, None);
+        assert_eq!(parse_fdinfo("pos:\t0\n"), FdInfo::default());
+    }
+
+    #[test]
+    fn open_flags_are_named_after_the_access_mode() {
+        let flags = (libc::O_WRONLY | libc::O_CREAT | libc::O_APPEND | libc::O_CLOEXEC) as u32;
+
+        assert_eq!(decode_open_flags(flags), vec!["O_WRONLY", "O_CREAT", "O_APPEND", "O_CLOEXEC"]);
+        assert_eq!(decode_open_flags(0), vec!["O_RDONLY"]);
+        assert_eq!(decode_open_flags(O_RDWR), vec!["O_RDWR"]);
+    }
+
+    #[test]
+    fn combined_open_flags_need_all_their_bits() {
+        assert_eq!(decode_open_flags(libc::O_DSYNC as u32), vec!["O_RDONLY", "O_DSYNC"]);
+        assert_eq!(decode_open_flags(libc::O_SYNC as u32), vec!["O_RDONLY", "O_DSYNC", "O_SYNC"]);
+        assert_eq!(decode_open_flags(libc::O_DIRECTORY as u32), vec!["O_RDONLY", "O_DIRECTORY"]);
+    }
+
+    #[test]
+    fn flags_of_an_open_directory_match_this_architecture() {
+        use std::os::unix::fs::OpenOptionsExt;
+
+        let directory = File::options().read(true).custom_flags(libc::O_DIRECTORY).open("src").unwrap();
+        let info = parse_fdinfo(&fs::read_to_string(format!("/proc/self/fdinfo/{}", directory.as_raw_fd())).unwrap());
+
+        let names = decode_open_flags(info.flags);
+
+        assert!(names.contains(&"O_DIRECTORY".to_string()), "{:?}", names);
+        assert!(names.contains(&"O_CLOEXEC".to_string()), "{:?}", names);
+        assert!(!names.contains(&"O_DIRECT".to_string()), "{:?}", names);
+    }
+}
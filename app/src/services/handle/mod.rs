@@ -0,0 +1,283 @@
+use std::{
+    collections::HashMap, sync::{Arc, RwLock}, thread, time::{Duration, Instant}
+};
+
+use anyhow::Result;
+use log::*;
+
+use crate::{models::{DangerousHandle, GetSystemHandlesArgs, HandleAccessDenied, HandleFilter, HandleGrowth, HandleInfo, PageArgs, Paged, ProcessHandleCounts}, services::{dangerous_rights, HandleHistory, ProcessManager}};
+
+#[cfg(windows)]
+mod resolver;
+#[cfg(windows)]
+mod windows;
+#[cfg(windows)]
+pub use self::windows::NtHandleSource;
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "linux")]
+pub use self::linux::ProcFdSource;
+
+/// Where handles come from, the NT handle table on Windows and `/proc/<pid>/fd` on Linux
+pub trait HandleSource: Send + Sync {
+    /// Handles of every process, `names` maps process ids to names where the source has none of its own
+    fn enumerate(&self, names: &HashMap<u32, String>) -> Result<HandleSnapshot>;
+}
+
+#[derive(Debug, Default)]
+pub struct HandleSnapshot {
+    pub handles: Vec<HandleInfo>,
+    /// processes whose handles could not be read, or only partially
+    pub denied: Vec<HandleAccessDenied>,
+}
+
+#[cfg(windows)]
+fn default_source() -> Arc<dyn HandleSource> {
+    Arc::new(NtHandleSource::new())
+}
+
+#[cfg(target_os = "linux")]
+fn default_source() -> Arc<dyn HandleSource> {
+    Arc::new(ProcFdSource::new())
+}
+
+#[derive(Debug)]
+pub struct CacheEntry {
+    refreshed_on: Instant,
+    items: Vec<HandleInfo>,
+    denied: Vec<HandleAccessDenied>,
+}
+
+#[derive(Clone)]
+pub struct HandleManager {
+    query_interval: Duration,
+    cache: Arc<RwLock<CacheEntry>>,
+    process_manager: ProcessManager,
+    source: Arc<dyn HandleSource>,
+    history: Arc<RwLock<HandleHistory>>,
+}
+
+impl HandleManager {
+    pub fn new(process_manager: ProcessManager) -> Self {
+        let query_interval = Duration::from_secs(1);
+        let manager = Self {
+            query_interval,
+            cache: Arc::new(RwLock::new(CacheEntry {
+                refreshed_on: Instant::now() - query_interval,
+                items: vec![],
+                denied: vec![],
+            })),
+            process_manager,
+            source: default_source(),
+            // two hours of samples
+            history: Arc::new(RwLock::new(HandleHistory::new(Duration::from_secs(30), 240))),
+        };
+
+        {
+            let manager = manager.clone();
+            thread::Builder::new()
+                .name("handle-sampler".into())
+                .spawn(move || manager.run_sampler())
+                .expect("could not spawn handle sampler");
+        }
+
+        manager
+    }
+
+    /// Runs `f` over the cached handles without cloning the whole table
+    pub fn with_handles<R>(&self, f: impl FnOnce(&[HandleInfo]) -> R) -> Result<R> {
+        {
+            let guard = self.cache.read().unwrap();
+            if guard.refreshed_on.elapsed() > self.query_interval {
+                drop(guard);
+                self.refresh_cache()?;
+            }
+        }
+
+        let guard = self.cache.read().unwrap();
+        Ok(f(&guard.items))
+    }
+
+    pub fn get_handles(&self, args: &GetSystemHandlesArgs) -> Result<Paged<HandleInfo>> {
+        self.with_handles(|handles| paginate(handles.iter().filter(|handle| args.filter.matches(handle)), &args.page))
+    }
+
+    pub fn get_access_denied(&self) -> Result<Vec<HandleAccessDenied>> {
+        self.refresh_if_stale()?;
+
+        let mut denied = self.cache.read().unwrap().denied.clone();
+        denied.sort_by_key(|a| a.process_id);
+
+        Ok(denied)
+    }
+
+    /// Handle counts per process and object type from the latest sample
+    pub fn get_counts(&self) -> Result<Vec<ProcessHandleCounts>> {
+        self.refresh_if_stale()?;
+
+        Ok(self.history.read().unwrap().latest())
+    }
+
+    /// Processes whose handle count grew the most over `window`, steady growth is flagged as a suspected leak
+    pub fn get_top_growers(&self, window: Duration, limit: usize) -> Result<Vec<HandleGrowth>> {
+        self.refresh_if_stale()?;
+
+        Ok(self.history.read().unwrap().top_growers(window, limit))
+    }
+
+    fn refresh_if_stale(&self) -> Result<()> {
+        self.with_handles(|_| ())
+    }
+
+    fn run_sampler(&self) {
+        let interval = self.history.read().unwrap().sample_interval();
+
+        loop {
+            if let Err(err) = self.refresh_cache() {
+                warn!("handle sampling failed: {}", err);
+            }
+
+            thread::sleep(interval);
+        }
+    }
+
+    /// Handles held on other processes or their threads with rights that allow
+    /// writing memory, injecting threads or rewriting security, e.g. PROCESS_VM_WRITE
+    pub fn get_dangerous_handles(&self) -> Result<Vec<DangerousHandle>> {
+        let map = self.process_manager.get_id_name_map()?;
+
+        self.with_handles(|handles| {
+            handles
+                .iter()
+                .filter_map(|handle| {
+                    let rights = dangerous_access(handle);
+
+                    if rights.is_empty() {
+                        return None;
+                    }
+
+                    Some(DangerousHandle {
+                        target_process_name: handle.target_process_id.and_then(|target| map.get(&target).cloned()),
+                        handle: handle.clone(),
+                        rights,
+                    })
+                })
+                .collect()
+        })
+    }
+
+    fn refresh_cache(&self) -> Result<()> {
+        let map = self.process_manager.get_id_name_map()?;
+        let HandleSnapshot { handles, denied } = self.source.enumerate(&map)?;
+
+        self.history.write().unwrap().record_if_due(&handles);
+
+        let mut cache = self.cache.write().unwrap();
+        cache.items = handles;
+        cache.denied = denied;
+        cache.refreshed_on = Instant::now();
+        Ok(())
+    }
+}
+
+/// Dangerous rights a handle holds on another process or one of its threads.
+/// The target of a handle without query rights cannot be resolved,
+/// such handles are reported unless they are known to point back at their holder.
+/// Pages past the addressable range come back empty instead of overflowing
+fn paginate<'a>(handles: impl Iterator<Item = &'a HandleInfo>, args: &PageArgs) -> Paged<HandleInfo> {
+    let PageArgs { page, page_size } = *args;
+    let start = page.saturating_mul(page_size);
+    let end = start.saturating_add(page_size);
+    let mut items = Vec::new();
+    let mut total = 0u32;
+
+    for handle in handles {
+        if total >= start && total < end {
+            items.push(handle.clone());
+        }
+
+        total += 1;
+    }
+
+    Paged {
+        items,
+        page,
+        page_size,
+        total,
+    }
+}
+
+fn dangerous_access(handle: &HandleInfo) -> Vec<String> {
+    match handle.target_process_id {
+        Some(target) if target == handle.process_id => vec![],
+        _ => dangerous_rights(handle.type_name.as_deref(), handle.granted_access),
+    }
+}
+
+impl HandleFilter {
+    pub fn matches(&self, handle: &HandleInfo) -> bool {
+        let contains = |value: Option<&String>, needle: &String| {
+            value.is_some_and(|value| value.to_lowercase().contains(&needle.to_lowercase()))
+        };
+
+        self.process_ids.as_ref().is_none_or(|ids| ids.contains(&handle.process_id))
+            && self.process_name.as_ref().is_none_or(|name| contains(Some(&handle.process_name), name))
+            && self.type_names.as_ref().is_none_or(|types| handle.type_name.as_ref().is_some_and(|type_name| types.contains(type_name)))
+            && self.object_name.as_ref().is_none_or(|name| contains(handle.object_name.as_ref(), name))
+            && self.access_rights.as_ref().is_none_or(|rights| handle.access_rights.iter().any(|right| rights.contains(right)))
+            && self.access_mask.is_none_or(|mask| handle.granted_access & mask == mask)
+            && (!self.dangerous_only || !dangerous_access(handle).is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process_handle(process_id: u32, target_process_id: Option<u32>) -> HandleInfo {
+        HandleInfo {
+            process_id,
+            process_name: "holder.exe".into(),
+            handle: 0x40,
+            object_type: 7,
+            type_name: Some("Process".into()),
+            object_name: None,
+            granted_access: 0x0020 | 0x0008,
+            access_rights: vec![],
+            target_process_id,
+            file_mode: None,
+        }
+    }
+
+    #[test]
+    fn handles_on_other_or_unknown_targets_are_dangerous() {
+        assert!(!dangerous_access(&process_handle(10, Some(20))).is_empty());
+        assert!(!dangerous_access(&process_handle(10, None)).is_empty());
+    }
+
+    #[test]
+    fn handles_on_the_holder_itself_are_not_dangerous() {
+        assert!(dangerous_access(&process_handle(10, Some(10))).is_empty());
+    }
+
+    #[test]
+    fn pages_slice_the_matching_handles() {
+        let handles: Vec<HandleInfo> = (1..=5).map(|process_id| process_handle(process_id, None)).collect();
+
+        let paged = paginate(handles.iter(), &PageArgs { page: 1, page_size: 2 });
+
+        assert_eq!(paged.items.iter().map(|handle| handle.process_id).collect::<Vec<_>>(), vec![3, 4]);
+        assert_eq!(paged.total, 5);
+    }
+
+    #[test]
+    fn pages_beyond_u32_are_empty() {
+        let handles: Vec<HandleInfo> = (1..=5).map(|process_id| process_handle(process_id, None)).collect();
+
+        let paged = paginate(handles.iter(), &PageArgs { page: u32::MAX, page_size: u32::MAX });
+
+        assert!(paged.items.is_empty());
+        assert_eq!(paged.total, 5);
+    }
+}
@@ -0,0 +1,329 @@
+use std::{
+    mem,
+    ptr::null_mut,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{channel, sync_channel, Receiver, SendError, Sender, SyncSender},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use hashbrown::HashMap;
+use log::*;
+use moka::sync::Cache;
+use ntapi::ntobapi::{NtQueryObject, ObjectNameInformation, OBJECT_INFORMATION_CLASS, ObjectTypeInformation, OBJECT_NAME_INFORMATION, OBJECT_TYPE_INFORMATION};
+use widestring::{U16CStr, U16CString};
+use winapi::{
+    shared::{
+        ntdef::{HANDLE, UNICODE_STRING},
+        ntstatus::{STATUS_BUFFER_OVERFLOW, STATUS_BUFFER_TOO_SMALL, STATUS_INFO_LENGTH_MISMATCH},
+    },
+    um::{
+        fileapi::QueryDosDeviceW,
+        handleapi::{CloseHandle, DuplicateHandle},
+        processthreadsapi::{GetCurrentProcess, GetProcessId, GetProcessIdOfThread, OpenProcess},
+        winnt::{DUPLICATE_SAME_ACCESS, PROCESS_DUP_HANDLE, PROCESS_QUERY_LIMITED_INFORMATION, THREAD_QUERY_LIMITED_INFORMATION},
+    },
+};
+
+/// Access masks of synchronous file handles on which `NtQueryObject` is known to block
+/// (named pipes opened for synchronous I/O)
+const BLOCKING_FILE_ACCESS: [u32; 4] = [0x0012_019f, 0x001a_019f, 0x0012_0189, 0x0010_0000];
+/// Workers left blocked in `NtQueryObject` before name queries are given up,
+/// each one holds a thread until its call returns
+const MAX_ABANDONED_WORKERS: usize = 8;
+
+#[derive(Debug, Clone, Default)]
+pub struct ResolvedHandle {
+    /// kernel object address, a reused handle value points at a different object
+    object: usize,
+    pub type_name: Option<String>,
+    pub object_name: Option<String>,
+    /// process a Process or Thread handle refers to
+    pub target_process_id: Option<u32>,
+    /// the owning process could not be opened for duplication
+    pub access_denied: bool,
+}
+
+/// Raw `SYSTEM_HANDLE_TABLE_ENTRY_INFO_EX` fields the resolver needs
+#[derive(Debug, Clone, Copy)]
+pub struct HandleEntry {
+    pub process_id: u32,
+    pub handle: usize,
+    pub object: usize,
+    pub object_type: u16,
+    pub granted_access: u32,
+}
+
+struct NameRequest {
+    handle: usize,
+    reply: SyncSender<Option<String>>,
+}
+
+/// Resolves handle type and object names of foreign processes.
+///
+/// Handles are duplicated into this process first. Type names never block and are
+/// queried inline, name queries go through a worker thread and are abandoned
+/// after `timeout`, a worker stuck on a pipe is replaced by a fresh one.
+/// Once `MAX_ABANDONED_WORKERS` are stuck, names are skipped until some return.
+pub struct HandleNameResolver {
+    timeout: Duration,
+    resolved: Cache<(u32, usize), ResolvedHandle>,
+    type_names: Mutex<HashMap<u16, String>>,
+    worker: Mutex<Sender<NameRequest>>,
+    /// workers replaced after a timeout that have not exited yet
+    abandoned: Arc<AtomicUsize>,
+}
+
+impl HandleNameResolver {
+    pub fn new() -> Self {
+        let abandoned = Arc::new(AtomicUsize::new(0));
+
+        Self {
+            timeout: Duration::from_millis(100),
+            resolved: Cache::builder()
+                .max_capacity(500_000)
+                .time_to_idle(Duration::from_mins(5))
+                .build(),
+            type_names: Mutex::new(HashMap::new()),
+            worker: Mutex::new(Self::spawn_worker(abandoned.clone())),
+            abandoned,
+        }
+    }
+
+    /// Resolves a batch of handles, serving unchanged ones from the cache
+    pub fn resolve(&self, entries: &[HandleEntry]) -> Vec<ResolvedHandle> {
+        let device_map = unsafe { dos_device_map() };
+        let mut processes: HashMap<u32, Option<usize>> = HashMap::new();
+
+        let resolved = entries
+            .iter()
+            .map(|entry| {
+                let key = (entry.process_id, entry.handle);
+
+                if let Some(cached) = self.resolved.get(&key).filter(|cached| cached.object == entry.object) {
+                    return cached;
+                }
+
+                let process = *processes
+                    .entry(entry.process_id)
+                    .or_insert_with(|| unsafe { open_for_duplication(entry.process_id) });
+
+                let resolved = unsafe { self.resolve_entry(process, entry, &device_map) };
+                self.resolved.insert(key, resolved.clone());
+                resolved
+            })
+            .collect();
+
+        for process in processes.into_values().flatten() {
+            unsafe { CloseHandle(process as HANDLE) };
+        }
+
+        resolved
+    }
+
+    unsafe fn resolve_entry(&self, process: Option<usize>, entry: &HandleEntry, device_map: &[(String, String)]) -> ResolvedHandle {
+        let mut resolved = ResolvedHandle {
+            object: entry.object,
+            type_name: self.type_names.lock().unwrap().get(&entry.object_type).cloned(),
+            object_name: None,
+            target_process_id: None,
+            access_denied: process.is_none(),
+        };
+
+        let Some(process) = process else {
+            return resolved;
+        };
+
+        let mut duplicate: HANDLE = null_mut();
+
+        if DuplicateHandle(process as HANDLE, entry.handle as HANDLE, GetCurrentProcess(), &mut duplicate, 0, 0, DUPLICATE_SAME_ACCESS) == 0 {
+            return resolved;
+        }
+
+        if resolved.type_name.is_none() {
+            resolved.type_name = query_type_name(duplicate);
+
+            if let Some(type_name) = &resolved.type_name {
+                self.type_names.lock().unwrap().insert(entry.object_type, type_name.clone());
+            }
+        }
+
+        resolved.target_process_id = match resolved.type_name.as_deref() {
+            Some("Process") => query_target_id(process as HANDLE, entry.handle, PROCESS_QUERY_LIMITED_INFORMATION, |handle| GetProcessId(handle)),
+            Some("Thread") => query_target_id(process as HANDLE, entry.handle, THREAD_QUERY_LIMITED_INFORMATION, |handle| GetProcessIdOfThread(handle)),
+            _ => None,
+        };
+
+        let is_file = resolved.type_name.as_deref() == Some("File");
+
+        if is_file && BLOCKING_FILE_ACCESS.contains(&entry.granted_access) {
+            CloseHandle(duplicate);
+            return resolved;
+        }
+
+        // the worker owns and closes the duplicate from here on
+        resolved.object_name = self
+            .query_name_with_timeout(duplicate as usize)
+            .map(|name| if is_file { to_dos_path(&name, device_map) } else { name });
+
+        resolved
+    }
+
+    fn query_name_with_timeout(&self, handle: usize) -> Option<String> {
+        if self.abandoned.load(Ordering::SeqCst) >= MAX_ABANDONED_WORKERS {
+            unsafe { CloseHandle(handle as HANDLE) };
+            return None;
+        }
+
+        let (reply, response) = sync_channel(1);
+        let mut worker = self.worker.lock().unwrap();
+
+        if let Err(SendError(request)) = worker.send(NameRequest { handle, reply }) {
+            // the worker is gone, the duplicate is still ours
+            unsafe { CloseHandle(request.handle as HANDLE) };
+            *worker = Self::spawn_worker(self.abandoned.clone());
+            return None;
+        }
+
+        match response.recv_timeout(self.timeout) {
+            Ok(name) => name,
+            Err(_) => {
+                debug!("object name query for handle 0x{:x} timed out", handle);
+                // dropping the sender ends the stuck worker once its call returns
+                self.abandoned.fetch_add(1, Ordering::SeqCst);
+                *worker = Self::spawn_worker(self.abandoned.clone());
+                None
+            }
+        }
+    }
+
+    fn spawn_worker(abandoned: Arc<AtomicUsize>) -> Sender<NameRequest> {
+        let (sender, receiver) = channel();
+
+        thread::Builder::new()
+            .name("handle-name".into())
+            .spawn(move || Self::run_worker(receiver, abandoned))
+            .expect("could not spawn handle name worker");
+
+        sender
+    }
+
+    fn run_worker(receiver: Receiver<NameRequest>, abandoned: Arc<AtomicUsize>) {
+        for request in receiver {
+            let name = unsafe {
+                let name = query_object_name(request.handle as HANDLE);
+                CloseHandle(request.handle as HANDLE);
+                name
+            };
+
+            // the caller timed out and replaced this worker
+            if request.reply.send(name).is_err() {
+                break;
+            }
+        }
+
+        // while the resolver lives a worker only ends after being replaced on a timeout
+        let _ = abandoned.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| count.checked_sub(1));
+    }
+}
+
+unsafe fn open_for_duplication(process_id: u32) -> Option<usize> {
+    let process = OpenProcess(PROCESS_DUP_HANDLE, 0, process_id);
+    (!process.is_null()).then_some(process as usize)
+}
+
+/// Process id behind a Process or Thread handle. The original handle may lack query rights
+/// (e.g. only PROCESS_VM_WRITE), so a second duplicate asks for them; `None` when refused.
+unsafe fn query_target_id(process: HANDLE, handle: usize, access: u32, query: impl Fn(HANDLE) -> u32) -> Option<u32> {
+    let mut duplicate: HANDLE = null_mut();
+
+    if DuplicateHandle(process, handle as HANDLE, GetCurrentProcess(), &mut duplicate, access, 0, 0) == 0 {
+        return None;
+    }
+
+    let id = query(duplicate);
+    CloseHandle(duplicate);
+    Some(id).filter(|id| *id != 0)
+}
+
+unsafe fn query_type_name(handle: HANDLE) -> Option<String> {
+    let buffer = query_object(handle, ObjectTypeInformation, mem::size_of::<OBJECT_TYPE_INFORMATION>() + 256)?;
+    let information = &*(buffer.as_ptr() as *const OBJECT_TYPE_INFORMATION);
+    unicode_string_to_string(&information.TypeName)
+}
+
+unsafe fn query_object_name(handle: HANDLE) -> Option<String> {
+    let buffer = query_object(handle, ObjectNameInformation, mem::size_of::<OBJECT_NAME_INFORMATION>() + 512)?;
+    let information = &*(buffer.as_ptr() as *const OBJECT_NAME_INFORMATION);
+    unicode_string_to_string(&information.Name)
+}
+
+/// Calls `NtQueryObject` growing the buffer until the information fits.
+/// The buffer is u64 backed to keep the returned structures aligned.
+unsafe fn query_object(handle: HANDLE, class: OBJECT_INFORMATION_CLASS, initial_size: usize) -> Option<Vec<u64>> {
+    let mut size = initial_size;
+
+    loop {
+        let mut buffer = vec![0u64; size.div_ceil(8)];
+        let mut return_length = 0u32;
+
+        let status = NtQueryObject(handle, class, buffer.as_mut_ptr() as *mut _, (buffer.len() * 8) as u32, &mut return_length);
+
+        match status {
+            0 => return Some(buffer),
+            STATUS_INFO_LENGTH_MISMATCH | STATUS_BUFFER_OVERFLOW | STATUS_BUFFER_TOO_SMALL
+                if (return_length as usize) > buffer.len() * 8 =>
+            {
+                size = return_length as usize;
+            }
+            _ => return None,
+        }
+    }
+}
+
+unsafe fn unicode_string_to_string(value: &UNICODE_STRING) -> Option<String> {
+    if value.Buffer.is_null() || value.Length == 0 {
+        return None;
+    }
+
+    let characters = std::slice::from_raw_parts(value.Buffer, value.Length as usize / 2);
+    Some(String::from_utf16_lossy(characters))
+}
+
+/// `\Device\HarddiskVolume3` -> `C:` for every mapped drive letter
+unsafe fn dos_device_map() -> Vec<(String, String)> {
+    let mut map = Vec::new();
+
+    for letter in b'A'..=b'Z' {
+        let drive = format!("{}:", letter as char);
+        let Ok(drive_name) = U16CString::from_str(&drive) else {
+            continue;
+        };
+
+        let mut buffer = [0u16; 512];
+
+        if QueryDosDeviceW(drive_name.as_ptr(), buffer.as_mut_ptr(), buffer.len() as u32) == 0 {
+            continue;
+        }
+
+        if let Ok(device) = U16CStr::from_slice_truncate(&buffer) {
+            map.push((device.to_string_lossy(), drive));
+        }
+    }
+
+    map
+}
+
+fn to_dos_path(path: &str, device_map: &[(String, String)]) -> String {
+    device_map
+        .iter()
+        .find_map(|(device, drive)| {
+            path.strip_prefix(device.as_str())
+                .filter(|rest| rest.is_empty() || rest.starts_with('\\'))
+                .map(|rest| format!("{}{}", drive, rest))
+        })
+        .unwrap_or_else(|| path.to_string())
+}
@@ -0,0 +1,116 @@
+use std::{collections::HashMap, ptr};
+
+use anyhow::{bail, Result};
+use hashbrown::HashSet;
+use ntapi::ntexapi::{NtQuerySystemInformation, SystemExtendedHandleInformation, SYSTEM_HANDLE_TABLE_ENTRY_INFO_EX};
+use winapi::shared::ntstatus::STATUS_INFO_LENGTH_MISMATCH;
+
+use crate::{models::{HandleAccessDenied, HandleInfo}, services::decode_access_mask};
+
+use super::{resolver::{HandleEntry, HandleNameResolver}, HandleSnapshot, HandleSource};
+
+/// System wide handle table from `NtQuerySystemInformation`,
+/// names are resolved by duplicating the handles into this process
+pub struct NtHandleSource {
+    resolver: HandleNameResolver,
+}
+
+impl NtHandleSource {
+    pub fn new() -> Self {
+        Self {
+            resolver: HandleNameResolver::new(),
+        }
+    }
+
+    unsafe fn enumerate_handles() -> Result<Vec<HandleEntry>> {
+
+        let mut buffer_size = 0x10000;
+        let mut buffer = vec![0u8; buffer_size];
+        let mut return_length: u32 = 0;
+
+        let mut status = NtQuerySystemInformation(
+            SystemExtendedHandleInformation,
+            buffer.as_mut_ptr() as *mut _,
+            buffer_size as u32,
+            &mut return_length,
+        );
+
+        while status == STATUS_INFO_LENGTH_MISMATCH {
+            buffer_size = (return_length as usize).saturating_add(512);
+            buffer.resize(buffer_size, 0);
+
+            status = NtQuerySystemInformation(
+                SystemExtendedHandleInformation,
+                buffer.as_mut_ptr() as *mut _,
+                buffer_size as u32,
+                &mut return_length,
+            );
+        }
+
+        if status != 0 {
+            bail!("NtQuerySystemInformation failed: 0x{:X}", status);
+        }
+
+        let ptr = buffer.as_ptr() as *const usize;
+        let handle_count = *ptr;
+        let entries_ptr = ptr.add(2) as *const SYSTEM_HANDLE_TABLE_ENTRY_INFO_EX;
+
+        let mut handles = Vec::with_capacity(handle_count);
+        for i in 0..handle_count {
+            let entry = ptr::read_unaligned(entries_ptr.add(i));
+
+            handles.push(HandleEntry {
+                process_id: entry.UniqueProcessId as u32,
+                handle: entry.HandleValue,
+                object: entry.Object as usize,
+                object_type: entry.ObjectTypeIndex as u16,
+                granted_access: entry.GrantedAccess,
+            });
+        }
+
+        Ok(handles)
+    }
+}
+
+impl HandleSource for NtHandleSource {
+    fn enumerate(&self, names: &HashMap<u32, String>) -> Result<HandleSnapshot> {
+        let entries = unsafe { Self::enumerate_handles()? };
+        let resolved = self.resolver.resolve(&entries);
+        let mut denied = HashSet::new();
+
+        let handles = entries
+            .iter()
+            .zip(resolved)
+            .map(|(entry, resolved)| {
+                if resolved.access_denied {
+                    denied.insert(entry.process_id);
+                }
+
+                HandleInfo {
+                    process_id: entry.process_id,
+                    process_name: names.get(&entry.process_id).cloned().unwrap_or_default(),
+                    handle: entry.handle as u32,
+                    object_type: entry.object_type,
+                    object_name: resolved.object_name,
+                    granted_access: entry.granted_access,
+                    access_rights: decode_access_mask(resolved.type_name.as_deref(), entry.granted_access),
+                    target_process_id: resolved.target_process_id,
+                    type_name: resolved.type_name,
+                    file_mode: None,
+                }
+            })
+            .collect();
+
+        // handles are still listed from the system table, only their names are missing
+        let denied = denied
+            .into_iter()
+            .map(|process_id| HandleAccessDenied {
+                process_id,
+                process_name: names.get(&process_id).cloned().unwrap_or_default(),
+                reason: "process could not be opened for handle duplication".to_string(),
+            })
+            .collect();
+
+        Ok(HandleSnapshot { handles, denied })
+    }
+}
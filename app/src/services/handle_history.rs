@@ -0,0 +1,299 @@
+use std::{cmp::Reverse, collections::VecDeque, time::Duration};
+
+use chrono::{DateTime, Utc};
+use hashbrown::HashMap;
+
+use crate::{
+    models::{HandleCountPoint, HandleGrowth, HandleInfo, HandleTypeGrowth, ProcessHandleCounts},
+    services::{fit_trend, steady_ratio},
+};
+
+/// Thresholds deciding when growth counts as a leak rather than normal churn
+#[derive(Debug, Clone)]
+pub struct LeakDetectorConfig {
+    /// fewer samples than this within the window never flag a process
+    pub min_samples: usize,
+    /// absolute growth over the window
+    pub min_growth: i64,
+    /// share of sample to sample deltas that must not be negative
+    pub min_steady_ratio: f64,
+}
+
+impl Default for LeakDetectorConfig {
+    fn default() -> Self {
+        Self {
+            min_samples: 5,
+            min_growth: 100,
+            min_steady_ratio: 0.8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ProcessCounts {
+    /// tells a reused pid apart from the process that held it before
+    pub process_name: String,
+    pub total: u32,
+    pub by_type: HashMap<String, u32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct HandleSample {
+    pub taken_on: DateTime<Utc>,
+    pub processes: HashMap<u32, ProcessCounts>,
+}
+
+/// Ring buffer of per-process, per-type handle counts
+#[derive(Debug)]
+pub struct HandleHistory {
+    sample_interval: Duration,
+    capacity: usize,
+    samples: VecDeque<HandleSample>,
+    config: LeakDetectorConfig,
+}
+
+impl HandleHistory {
+    pub fn new(sample_interval: Duration, capacity: usize) -> Self {
+        Self {
+            sample_interval,
+            capacity,
+            samples: VecDeque::with_capacity(capacity),
+            config: LeakDetectorConfig::default(),
+        }
+    }
+
+    pub fn sample_interval(&self) -> Duration {
+        self.sample_interval
+    }
+
+    /// Records a sample unless the previous one is younger than the sample interval
+    pub fn record_if_due(&mut self, handles: &[HandleInfo]) {
+        let now = Utc::now();
+        let is_due = self.samples.back().is_none_or(|last| {
+            (now - last.taken_on).to_std().unwrap_or_default() >= self.sample_interval
+        });
+
+        if !is_due {
+            return;
+        }
+
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+
+        self.samples.push_back(HandleSample {
+            taken_on: now,
+            processes: count_handles(handles),
+        });
+    }
+
+    pub fn latest(&self) -> Vec<ProcessHandleCounts> {
+        let Some(sample) = self.samples.back() else {
+            return vec![];
+        };
+
+        let mut counts: Vec<ProcessHandleCounts> = sample
+            .processes
+            .iter()
+            .map(|(process_id, counts)| ProcessHandleCounts {
+                process_id: *process_id,
+                process_name: counts.process_name.clone(),
+                total: counts.total,
+                by_type: counts.by_type.clone(),
+            })
+            .collect();
+
+        counts.sort_by_key(|item| Reverse(item.total));
+        counts
+    }
+
+    /// Processes ordered by handle growth over the last `window`
+    pub fn top_growers(&self, window: Duration, limit: usize) -> Vec<HandleGrowth> {
+        let Some(last) = self.samples.back() else {
+            return vec![];
+        };
+
+        let since = last.taken_on - chrono::Duration::from_std(window).unwrap_or(chrono::Duration::zero());
+        let samples: Vec<&HandleSample> = self.samples.iter().filter(|sample| sample.taken_on >= since).collect();
+
+        let mut growers: Vec<HandleGrowth> = last
+            .processes
+            .keys()
+            .filter_map(|process_id| self.process_growth(*process_id, &samples))
+            .filter(|growth| growth.growth > 0)
+            .collect();
+
+        growers.sort_by(|a, b| {
+            b.suspected_leak
+                .cmp(&a.suspected_leak)
+                .then_with(|| b.growth.cmp(&a.growth))
+        });
+        growers.truncate(limit);
+        growers
+    }
+
+    fn process_growth(&self, process_id: u32, samples: &[&HandleSample]) -> Option<HandleGrowth> {
+        let name = &samples.last()?.processes.get(&process_id)?.process_name;
+
+        // only the latest unbroken run of samples, a gap or another name under the pid
+        // means the process exited and the pid was reused
+        let mut series: Vec<(&HandleSample, &ProcessCounts)> = samples
+            .iter()
+            .rev()
+            .map_while(|sample| {
+                sample
+                    .processes
+                    .get(&process_id)
+                    .filter(|counts| &counts.process_name == name)
+                    .map(|counts| (*sample, counts))
+            })
+            .collect();
+        series.reverse();
+
+        let (first_sample, first) = series.first()?;
+        let (_, last) = series.last()?;
+
+        let points: Vec<HandleCountPoint> = series
+            .iter()
+            .map(|(sample, counts)| HandleCountPoint {
+                timestamp: sample.taken_on,
+                count: counts.total,
+            })
+            .collect();
+
+        let growth = last.total as i64 - first.total as i64;
+
+        let xy: Vec<(f64, f64)> = points
+            .iter()
+            .map(|point| ((point.timestamp - first_sample.taken_on).num_milliseconds() as f64 / 60_000.0, point.count as f64))
+            .collect();
+        let slope_per_minute = fit_trend(&xy).map_or(0.0, |trend| trend.slope);
+        let steady_ratio = steady_ratio(&points.iter().map(|point| point.count).collect::<Vec<_>>());
+
+        let suspected_leak = points.len() >= self.config.min_samples
+            && growth >= self.config.min_growth
+            && steady_ratio >= self.config.min_steady_ratio;
+
+        let mut growing_types: Vec<HandleTypeGrowth> = last
+            .by_type
+            .iter()
+            .map(|(type_name, count)| {
+                let previous = first.by_type.get(type_name).copied().unwrap_or_default();
+
+                HandleTypeGrowth {
+                    type_name: type_name.clone(),
+                    first_count: previous,
+                    last_count: *count,
+                    growth: *count as i64 - previous as i64,
+                }
+            })
+            .filter(|growth| growth.growth > 0)
+            .collect();
+
+        growing_types.sort_by_key(|item| Reverse(item.growth));
+
+        Some(HandleGrowth {
+            process_id,
+            process_name: name.clone(),
+            first_count: first.total,
+            last_count: last.total,
+            growth,
+            slope_per_minute,
+            suspected_leak,
+            growing_types,
+            samples: points,
+        })
+    }
+}
+
+fn count_handles(handles: &[HandleInfo]) -> HashMap<u32, ProcessCounts> {
+    let mut processes: HashMap<u32, ProcessCounts> = HashMap::new();
+
+    for handle in handles {
+        let counts = processes.entry(handle.process_id).or_insert_with(|| ProcessCounts {
+            process_name: handle.process_name.clone(),
+            ..Default::default()
+        });
+        let type_name = handle
+            .type_name
+            .clone()
+            .unwrap_or_else(|| format!("#{}", handle.object_type));
+
+        counts.total += 1;
+        *counts.by_type.entry(type_name).or_default() += 1;
+    }
+
+    processes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(minute: i64, processes: &[(u32, &str, u32)]) -> HandleSample {
+        HandleSample {
+            taken_on: DateTime::from_timestamp(minute * 60, 0).unwrap(),
+            processes: processes
+                .iter()
+                .map(|(process_id, process_name, total)| {
+                    let counts = ProcessCounts {
+                        process_name: process_name.to_string(),
+                        total: *total,
+                        by_type: HashMap::from([("File".to_string(), *total)]),
+                    };
+                    (*process_id, counts)
+                })
+                .collect(),
+        }
+    }
+
+    fn history(samples: Vec<HandleSample>) -> HandleHistory {
+        let mut history = HandleHistory::new(Duration::from_secs(60), 10);
+        history.samples.extend(samples);
+        history
+    }
+
+    #[test]
+    fn steady_growth_is_a_suspected_leak() {
+        let history = history((0..6).map(|minute| sample(minute, &[(4, "leaky.exe", 100 + minute as u32 * 50)])).collect());
+
+        let growers = history.top_growers(Duration::from_secs(3600), 10);
+
+        assert_eq!(growers.len(), 1);
+        assert_eq!(growers[0].process_name, "leaky.exe");
+        assert_eq!(growers[0].growth, 250);
+        assert!((growers[0].slope_per_minute - 50.0).abs() < 1e-9);
+        assert!(growers[0].suspected_leak);
+    }
+
+    #[test]
+    fn reused_pid_restarts_the_series() {
+        let history = history(vec![
+            sample(0, &[(4, "old.exe", 10)]),
+            sample(1, &[(4, "old.exe", 20)]),
+            sample(2, &[(4, "new.exe", 500)]),
+            sample(3, &[(4, "new.exe", 600)]),
+        ]);
+
+        let growers = history.top_growers(Duration::from_secs(3600), 10);
+
+        assert_eq!(growers[0].process_name, "new.exe");
+        assert_eq!(growers[0].first_count, 500);
+        assert_eq!(growers[0].samples.len(), 2);
+    }
+
+    #[test]
+    fn gap_in_samples_restarts_the_series() {
+        let history = history(vec![
+            sample(0, &[(4, "app.exe", 10)]),
+            sample(1, &[]),
+            sample(2, &[(4, "app.exe", 30)]),
+            sample(3, &[(4, "app.exe", 40)]),
+        ]);
+
+        let growers = history.top_growers(Duration::from_secs(3600), 10);
+
+        assert_eq!(growers[0].first_count, 30);
+        assert_eq!(growers[0].growth, 10);
+    }
+}
@@ -0,0 +1,289 @@
+use std::{
+    fs::{self, Metadata},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{IntegrityChange, IntegrityChangeKind, IntegrityEntry};
+
+/// The approved state of the watched files, changes are reported against it until a new one is taken
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Baseline {
+    pub roots: Vec<PathBuf>,
+    pub created_on: DateTime<Utc>,
+    /// keyed by path
+    pub files: HashMap<String, IntegrityEntry>,
+}
+
+impl Baseline {
+    pub fn take(roots: Vec<PathBuf>) -> Self {
+        let files = roots.iter().flat_map(|root| collect_entries(root)).map(|entry| (entry.path.clone(), entry)).collect();
+
+        Self {
+            roots,
+            created_on: Utc::now(),
+            files,
+        }
+    }
+
+    /// Hashes every file under the roots again and lists what differs from the baseline
+    pub fn verify(&self) -> (u64, Vec<IntegrityChange>) {
+        let current: HashMap<String, IntegrityEntry> = self
+            .roots
+            .iter()
+            .flat_map(|root| collect_entries(root))
+            .map(|entry| (entry.path.clone(), entry))
+            .collect();
+
+        let detected_on = Utc::now();
+
+        let mut paths: Vec<&String> = self.files.keys().chain(current.keys().filter(|path| !self.files.contains_key(*path))).collect();
+        paths.sort();
+
+        let changes = paths
+            .into_iter()
+            .filter_map(|path| to_change(self.files.get(path), current.get(path), detected_on))
+            .collect();
+
+        (current.len() as u64, changes)
+    }
+}
+
+/// The regular files at or beneath `path`, symbolic links are not followed
+pub fn collect_entries(path: &Path) -> Vec<IntegrityEntry> {
+    let mut entries = Vec::new();
+    let mut pending = vec![path.to_owned()];
+
+    while let Some(path) = pending.pop() {
+        match fs::symlink_metadata(&path) {
+            Ok(metadata) if metadata.is_file() => entries.push(to_entry(&path, &metadata)),
+            Ok(metadata) if metadata.is_dir() => {
+                pending.extend(fs::read_dir(&path).into_iter().flatten().flatten().map(|child| child.path()));
+            }
+            _ => {}
+        }
+    }
+
+    entries
+}
+
+pub fn to_entry(path: &Path, metadata: &Metadata) -> IntegrityEntry {
+    IntegrityEntry {
+        path: path.to_string_lossy().into_owned(),
+        size: metadata.len(),
+        modified_on: metadata.modified().ok().map(DateTime::<Utc>::from),
+        hash: sha256::try_digest(path).ok(),
+        permissions: permissions_of(metadata),
+        security_hash: security_hash_of(path, metadata),
+    }
+}
+
+/// A touched modification time alone is not a change, the content decides
+pub fn classify(before: Option<&IntegrityEntry>, after: Option<&IntegrityEntry>) -> Option<IntegrityChangeKind> {
+    match (before, after) {
+        (None, None) => None,
+        (None, Some(_)) => Some(IntegrityChangeKind::Added),
+        (Some(_), None) => Some(IntegrityChangeKind::Deleted),
+        (Some(before), Some(after)) if before.size != after.size || before.hash != after.hash => Some(IntegrityChangeKind::Modified),
+        (Some(before), Some(after)) if before.permissions != after.permissions => Some(IntegrityChangeKind::PermissionsChanged),
+        // entries recorded before security descriptors were hashed have none to compare
+        (Some(before), Some(after))
+            if before.security_hash.is_some() && after.security_hash.is_some() && before.security_hash != after.security_hash =>
+        {
+            Some(IntegrityChangeKind::PermissionsChanged)
+        }
+        _ => None,
+    }
+}
+
+pub fn to_change(before: Option<&IntegrityEntry>, after: Option<&IntegrityEntry>, detected_on: DateTime<Utc>) -> Option<IntegrityChange> {
+    let kind = classify(before, after)?;
+    let path = before.or(after)?.path.clone();
+
+    Some(IntegrityChange {
+        path,
+        kind,
+        detected_on,
+        before_hash: before.and_then(|entry| entry.hash.clone()),
+        after_hash: after.and_then(|entry| entry.hash.clone()),
+        before: before.cloned(),
+        after: after.cloned(),
+    })
+}
+
+#[cfg(unix)]
+fn permissions_of(metadata: &Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+
+    metadata.permissions().mode() & 0o7777
+}
+
+#[cfg(windows)]
+fn permissions_of(metadata: &Metadata) -> u32 {
+    use std::os::windows::fs::MetadataExt;
+    use winapi::um::winnt::{FILE_ATTRIBUTE_HIDDEN, FILE_ATTRIBUTE_READONLY, FILE_ATTRIBUTE_SYSTEM};
+
+    metadata.file_attributes() & (FILE_ATTRIBUTE_READONLY | FILE_ATTRIBUTE_HIDDEN | FILE_ATTRIBUTE_SYSTEM)
+}
+
+/// Hashes the owning user and group, the mode bits alone miss a chown
+#[cfg(unix)]
+fn security_hash_of(_path: &Path, metadata: &Metadata) -> Option<String> {
+    use std::os::unix::fs::MetadataExt;
+
+    Some(sha256::digest(format!("{}:{}", metadata.uid(), metadata.gid())))
+}
+
+/// Hashes the self-relative security descriptor holding the owner and DACL
+#[cfg(windows)]
+fn security_hash_of(path: &Path, _metadata: &Metadata) -> Option<String> {
+    use std::{os::windows::ffi::OsStrExt, ptr, slice};
+    use winapi::{
+        shared::winerror::ERROR_SUCCESS,
+        um::{
+            accctrl::SE_FILE_OBJECT,
+            aclapi::GetNamedSecurityInfoW,
+            securitybaseapi::GetSecurityDescriptorLength,
+            winbase::LocalFree,
+            winnt::{DACL_SECURITY_INFORMATION, OWNER_SECURITY_INFORMATION, PSECURITY_DESCRIPTOR},
+        },
+    };
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+
+    unsafe {
+        let mut descriptor: PSECURITY_DESCRIPTOR = ptr::null_mut();
+
+        let result = GetNamedSecurityInfoW(
+            wide.as_ptr(),
+            SE_FILE_OBJECT,
+            OWNER_SECURITY_INFORMATION | DACL_SECURITY_INFORMATION,
+            ptr::null_mut(),
+            ptr::null_mut(),
+            ptr::null_mut(),
+            ptr::null_mut(),
+            &mut descriptor,
+        );
+
+        if result != ERROR_SUCCESS || descriptor.is_null() {
+            return None;
+        }
+
+        let bytes = slice::from_raw_parts(descriptor as *const u8, GetSecurityDescriptorLength(descriptor) as usize);
+        let hash = sha256::digest(bytes);
+        LocalFree(descriptor);
+
+        Some(hash)
+    }
+}
+
+/// Hosts, startup and autostart locations plus the usual system configuration,
+/// only the ones present on this machine
+pub fn default_paths() -> Vec<PathBuf> {
+    candidate_paths().into_iter().filter(|path| path.exists()).collect()
+}
+
+#[cfg(windows)]
+fn candidate_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+
+    if let Some(windows) = std::env::var_os("SystemRoot").map(PathBuf::from) {
+        paths.push(windows.join(r"System32\drivers\etc"));
+        paths.push(windows.join(r"System32\Tasks"));
+    }
+
+    if let Some(program_data) = std::env::var_os("ProgramData").map(PathBuf::from) {
+        paths.push(program_data.join(r"Microsoft\Windows\Start Menu\Programs\StartUp"));
+    }
+
+    if let Some(app_data) = dirs::data_dir() {
+        paths.push(app_data.join(r"Microsoft\Windows\Start Menu\Programs\Startup"));
+    }
+
+    if let Some(home) = dirs::home_dir() {
+        paths.push(home.join(r".ssh\authorized_keys"));
+    }
+
+    paths
+}
+
+#[cfg(unix)]
+fn candidate_paths() -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = [
+        "/etc/hosts",
+        "/etc/passwd",
+        "/etc/group",
+        "/etc/sudoers",
+        "/etc/sudoers.d",
+        "/etc/ssh",
+        "/etc/crontab",
+        "/etc/cron.d",
+        "/etc/systemd/system",
+        "/etc/xdg/autostart",
+        "/etc/ld.so.preload",
+        "/Library/LaunchAgents",
+        "/Library/LaunchDaemons",
+    ]
+    .into_iter()
+    .map(PathBuf::from)
+    .collect();
+
+    if let Some(home) = dirs::home_dir() {
+        paths.push(home.join(".ssh/authorized_keys"));
+        paths.push(home.join(".config/autostart"));
+        paths.push(home.join(".bashrc"));
+        paths.push(home.join(".profile"));
+        paths.push(home.join("Library/LaunchAgents"));
+    }
+
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(hash: &str, permissions: u32, security_hash: Option<&str>) -> IntegrityEntry {
+        IntegrityEntry {
+            path: "/etc/hosts".into(),
+            size: 10,
+            modified_on: None,
+            hash: Some(hash.into()),
+            permissions,
+            security_hash: security_hash.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn content_changes_win_over_permission_changes() {
+        assert_eq!(classify(Some(&entry("a", 0o644, None)), Some(&entry("b", 0o600, None))), Some(IntegrityChangeKind::Modified));
+        assert_eq!(classify(Some(&entry("a", 0o644, None)), Some(&entry("a", 0o600, None))), Some(IntegrityChangeKind::PermissionsChanged));
+        assert_eq!(classify(Some(&entry("a", 0o644, None)), Some(&entry("a", 0o644, None))), None);
+    }
+
+    #[test]
+    fn changed_security_descriptor_is_a_permission_change() {
+        assert_eq!(
+            classify(Some(&entry("a", 0, Some("owner-1"))), Some(&entry("a", 0, Some("owner-2")))),
+            Some(IntegrityChangeKind::PermissionsChanged)
+        );
+        // baselines from before descriptors were hashed
+        assert_eq!(classify(Some(&entry("a", 0, None)), Some(&entry("a", 0, Some("owner-1")))), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn owner_and_group_are_part_of_the_security_hash() {
+        use std::os::unix::fs::MetadataExt;
+
+        let metadata = fs::metadata("Cargo.toml").unwrap();
+        let entry = to_entry(Path::new("Cargo.toml"), &metadata);
+
+        assert_eq!(entry.security_hash, Some(sha256::digest(format!("{}:{}", metadata.uid(), metadata.gid()))));
+        assert_ne!(entry.security_hash, Some(sha256::digest(format!("{}:{}", metadata.uid() + 1, metadata.gid()))));
+    }
+}
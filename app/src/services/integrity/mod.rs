@@ -0,0 +1,443 @@
+use std::{
+    path::{self, Path, PathBuf},
+    sync::{
+        mpsc::{self, Receiver, RecvTimeoutError},
+        Arc, Mutex, RwLock,
+    },
+    thread,
+    time::Duration,
+};
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use hashbrown::{HashMap, HashSet};
+use log::*;
+use notify::{Event, RecommendedWatcher};
+use tauri::{AppHandle, Emitter};
+
+use crate::models::{IntegrityChange, IntegrityEntry, IntegrityStatus, IntegrityVerification};
+
+mod baseline;
+mod store;
+mod watcher;
+
+pub use self::baseline::{collect_entries, default_paths, to_change, Baseline};
+pub use self::store::IntegrityStore;
+pub use self::watcher::{changed_paths, watch};
+
+/// Editors save in several steps, a burst of events is handled once it has been quiet this long
+const SETTLE_INTERVAL: Duration = Duration::from_millis(500);
+/// Oldest changes are dropped beyond this
+const MAX_CHANGES: usize = 1000;
+
+struct IntegrityState {
+    baseline: Option<Baseline>,
+    /// last seen state of every watched file, starts out as the baseline
+    current: HashMap<String, IntegrityEntry>,
+    /// oldest first
+    changes: Vec<IntegrityChange>,
+}
+
+/// Records a hash baseline of sensitive files and watches them for changes,
+/// reporting each as an `on-integrity-change` event
+#[derive(Clone)]
+pub struct IntegrityService {
+    app_handle: AppHandle,
+    store: Arc<IntegrityStore>,
+    state: Arc<RwLock<IntegrityState>>,
+    /// dropping the watcher ends the thread handling its events
+    watcher: Arc<Mutex<Option<RecommendedWatcher>>>,
+}
+
+impl IntegrityService {
+    pub fn new(app_handle: AppHandle, storage_dir: &Path) -> Self {
+        let store = IntegrityStore::new(storage_dir.join("integrity"));
+        let baseline = store.load_baseline();
+
+        let service = Self {
+            app_handle,
+            state: Arc::new(RwLock::new(IntegrityState {
+                current: baseline.as_ref().map(|baseline| baseline.files.clone()).unwrap_or_default(),
+                baseline,
+                changes: store.load_changes(),
+            })),
+            store: Arc::new(store),
+            watcher: Arc::new(Mutex::new(None)),
+        };
+
+        if let Err(err) = service.start_watching() {
+            warn!("could not watch the integrity baseline: {}", err);
+        }
+
+        service
+    }
+
+    pub fn get_status(&self) -> IntegrityStatus {
+        let watching = self.watcher.lock().unwrap().is_some();
+        let state = self.state.read().unwrap();
+
+        IntegrityStatus {
+            roots: state
+                .baseline
+                .iter()
+                .flat_map(|baseline| &baseline.roots)
+                .map(|root| root.to_string_lossy().into_owned())
+                .collect(),
+            files: state.baseline.as_ref().map(|baseline| baseline.files.len() as u64).unwrap_or_default(),
+            created_on: state.baseline.as_ref().map(|baseline| baseline.created_on),
+            watching,
+            changes: state.changes.len() as u64,
+        }
+    }
+
+    pub fn get_default_paths(&self) -> Vec<String> {
+        default_paths().iter().map(|path| path.to_string_lossy().into_owned()).collect()
+    }
+
+    /// Hashes the files under `paths`, or the default locations when empty, and watches them from now on.
+    /// Replaces the previous baseline and forgets the changes recorded against it.
+    pub fn create_baseline(&self, paths: &[String]) -> Result<IntegrityStatus> {
+        let roots = if paths.is_empty() {
+            default_paths()
+        } else {
+            paths.iter().map(|path| path::absolute(path.trim())).collect::<Result<Vec<_>, _>>()?
+        };
+
+        if roots.is_empty() {
+            bail!("nothing to watch");
+        }
+
+        if let Some(root) = roots.iter().find(|root| !root.exists()) {
+            bail!("{} does not exist", root.display());
+        }
+
+        let baseline = Baseline::take(roots);
+
+        self.store.save_baseline(&baseline)?;
+        self.store.save_changes(&[])?;
+
+        {
+            let mut state = self.state.write().unwrap();
+            state.current = baseline.files.clone();
+            state.baseline = Some(baseline);
+            state.changes.clear();
+        }
+
+        self.start_watching()?;
+
+        Ok(self.get_status())
+    }
+
+    /// Stops watching and removes the baseline along with the recorded changes
+    pub fn delete_baseline(&self) -> Result<()> {
+        self.watcher.lock().unwrap().take();
+
+        {
+            let mut state = self.state.write().unwrap();
+            state.baseline = None;
+            state.current.clear();
+            state.changes.clear();
+        }
+
+        self.store.delete()
+    }
+
+    /// Compares every file against the baseline, catching changes made while nothing was watching
+    pub fn verify(&self) -> Result<IntegrityVerification> {
+        let Some(baseline) = self.state.read().unwrap().baseline.clone() else {
+            bail!("no integrity baseline recorded");
+        };
+
+        let (files_checked, changes) = baseline.verify();
+
+        Ok(IntegrityVerification {
+            verified_on: Utc::now(),
+            files_checked,
+            changes,
+        })
+    }
+
+    /// Changes seen by the watcher, newest first
+    pub fn get_changes(&self, limit: usize) -> Vec<IntegrityChange> {
+        self.state.read().unwrap().changes.iter().rev().take(limit).cloned().collect()
+    }
+
+    pub fn clear_changes(&self) -> Result<()> {
+        self.state.write().unwrap().changes.clear();
+        self.store.save_changes(&[])
+    }
+
+    fn start_watching(&self) -> Result<()> {
+        let roots = self.state.read().unwrap().baseline.as_ref().map(|baseline| baseline.roots.clone());
+
+        let mut watcher = self.watcher.lock().unwrap();
+        // the previous thread ends with its watcher
+        watcher.take();
+
+        let Some(roots) = roots else {
+            return Ok(());
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        *watcher = Some(watch(&roots, sender)?);
+
+        let service = self.clone();
+        thread::Builder::new()
+            .name("integrity-watcher".into())
+            .spawn(move || service.run_watcher(receiver))?;
+
+        Ok(())
+    }
+
+    fn run_watcher(&self, events: Receiver<notify::Result<Event>>) {
+        while let Ok(event) = events.recv() {
+            let mut paths = HashSet::new();
+            let mut closed = false;
+
+            collect_paths(event, &mut paths);
+
+            loop {
+                match events.recv_timeout(SETTLE_INTERVAL) {
+                    Ok(event) => collect_paths(event, &mut paths),
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => {
+                        closed = true;
+                        break;
+                    }
+                }
+            }
+
+            if let Err(err) = self.check_paths(paths) {
+                warn!("integrity check failed: {}", err);
+            }
+
+            if closed {
+                break;
+            }
+        }
+    }
+
+    /// Compares what is on disk at each path, and beneath it for folders, with the last seen state.
+    /// Files are hashed before taking the lock, status queries are not held up by large files.
+    fn check_paths(&self, paths: HashSet<PathBuf>) -> Result<()> {
+        let Some(check) = PendingCheck::collect(&self.state, &paths) else {
+            return Ok(());
+        };
+
+        let mut state = self.state.write().unwrap();
+        let recorded = check.apply(&mut state, Utc::now());
+
+        if recorded.is_empty() {
+            return Ok(());
+        }
+
+        self.store.save_changes(&state.changes)?;
+        drop(state);
+
+        for change in recorded {
+            info!("integrity change {:?} {}", change.kind, change.path);
+            self.app_handle.emit("on-integrity-change", change)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// What is on disk beneath the changed paths, collected against the baseline of `created_on`
+struct PendingCheck {
+    created_on: DateTime<Utc>,
+    collected: Vec<(PathBuf, HashMap<String, IntegrityEntry>)>,
+}
+
+impl PendingCheck {
+    /// Hashes the paths inside the watched roots without holding the lock, `None` without a baseline
+    fn collect(state: &RwLock<IntegrityState>, paths: &HashSet<PathBuf>) -> Option<Self> {
+        let (roots, created_on) = state
+            .read()
+            .unwrap()
+            .baseline
+            .as_ref()
+            .map(|baseline| (baseline.roots.clone(), baseline.created_on))?;
+
+        let collected = paths
+            .iter()
+            .filter(|path| roots.iter().any(|root| path.starts_with(root)))
+            .map(|path| (path.clone(), collect_entries(path).into_iter().map(|entry| (entry.path.clone(), entry)).collect()))
+            .collect();
+
+        Some(Self { created_on, collected })
+    }
+
+    /// Records the differences to the last seen state and returns them
+    fn apply(self, state: &mut IntegrityState, detected_on: DateTime<Utc>) -> Vec<IntegrityChange> {
+        // a baseline taken meanwhile already holds the current state
+        if state.baseline.as_ref().map(|baseline| baseline.created_on) != Some(self.created_on) {
+            return vec![];
+        }
+
+        let mut recorded = Vec::new();
+
+        for (path, found) in self.collected {
+            let known: Vec<String> = state
+                .current
+                .keys()
+                .filter(|known| Path::new(known).starts_with(&path) && !found.contains_key(*known))
+                .cloned()
+                .collect();
+
+            for key in known.into_iter().chain(found.keys().cloned()) {
+                let after = found.get(&key);
+
+                if let Some(change) = to_change(state.current.get(&key), after, detected_on) {
+                    recorded.push(change);
+                }
+
+                match after {
+                    Some(after) => state.current.insert(key, after.clone()),
+                    None => state.current.remove(&key),
+                };
+            }
+        }
+
+        state.changes.extend(recorded.iter().cloned());
+
+        let overflow = state.changes.len().saturating_sub(MAX_CHANGES);
+        state.changes.drain(..overflow);
+
+        recorded
+    }
+}
+
+fn collect_paths(event: notify::Result<Event>, paths: &mut HashSet<PathBuf>) {
+    match event {
+        Ok(event) => paths.extend(changed_paths(event)),
+        Err(err) => warn!("integrity watcher error: {}", err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::models::IntegrityChangeKind;
+
+    use super::*;
+
+    fn watched_folder() -> (PathBuf, RwLock<IntegrityState>) {
+        let root = std::env::temp_dir().join(format!("integrity-{}", uuid::Uuid::now_v7()));
+        fs::create_dir_all(root.join("folder")).unwrap();
+        fs::write(root.join("folder").join("a.conf"), "a").unwrap();
+        fs::write(root.join("folder").join("b.conf"), "b").unwrap();
+        fs::write(root.join("hosts"), "127.0.0.1 localhost").unwrap();
+
+        let baseline = Baseline::take(vec![root.clone()]);
+        let state = IntegrityState {
+            current: baseline.files.clone(),
+            baseline: Some(baseline),
+            changes: vec![],
+        };
+
+        (root, RwLock::new(state))
+    }
+
+    fn check(state: &RwLock<IntegrityState>, paths: &[PathBuf]) -> Vec<(IntegrityChangeKind, String)> {
+        let check = PendingCheck::collect(state, &paths.iter().cloned().collect()).unwrap();
+        let mut changes: Vec<_> = check
+            .apply(&mut state.write().unwrap(), Utc::now())
+            .into_iter()
+            .map(|change| (change.kind, change.path))
+            .collect();
+        changes.sort_by(|a, b| a.1.cmp(&b.1));
+        changes
+    }
+
+    fn key(path: PathBuf) -> String {
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn removed_folders_report_every_file_beneath_them() {
+        let (root, state) = watched_folder();
+        let folder = root.join("folder");
+        fs::remove_dir_all(&folder).unwrap();
+
+        let changes = check(&state, std::slice::from_ref(&folder));
+
+        assert_eq!(changes, vec![
+            (IntegrityChangeKind::Deleted, key(folder.join("a.conf"))),
+            (IntegrityChangeKind::Deleted, key(folder.join("b.conf"))),
+        ]);
+
+        let state = state.read().unwrap();
+        assert_eq!(state.current.keys().collect::<Vec<_>>(), vec![&key(root.join("hosts"))]);
+        assert_eq!(state.changes.len(), 2);
+        drop(state);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn changes_in_a_folder_are_recorded_once() {
+        let (root, state) = watched_folder();
+        let folder = root.join("folder");
+        fs::write(folder.join("a.conf"), "changed").unwrap();
+        fs::write(folder.join("c.conf"), "c").unwrap();
+
+        let changes = check(&state, &[folder.clone(), folder.join("a.conf")]);
+
+        assert_eq!(changes, vec![
+            (IntegrityChangeKind::Modified, key(folder.join("a.conf"))),
+            (IntegrityChangeKind::Added, key(folder.join("c.conf"))),
+        ]);
+        // the last seen state moved on, the same files are not reported again
+        assert!(check(&state, std::slice::from_ref(&folder)).is_empty());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn paths_outside_the_roots_are_ignored() {
+        let (root, state) = watched_folder();
+        let outside = std::env::temp_dir().join(format!("integrity-outside-{}", uuid::Uuid::now_v7()));
+        fs::write(&outside, "x").unwrap();
+
+        assert!(check(&state, std::slice::from_ref(&outside)).is_empty());
+        assert_eq!(state.read().unwrap().current.len(), 3);
+
+        fs::remove_file(outside).unwrap();
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn checks_against_a_replaced_baseline_are_dropped() {
+        let (root, state) = watched_folder();
+        fs::write(root.join("hosts"), "0.0.0.0 example.com").unwrap();
+
+        let pending = PendingCheck::collect(&state, &HashSet::from([root.join("hosts")])).unwrap();
+
+        // a new baseline is taken while the files are being hashed
+        let mut replaced = Baseline::take(vec![root.clone()]);
+        replaced.created_on += chrono::Duration::seconds(1);
+        {
+            let mut state = state.write().unwrap();
+            state.current = replaced.files.clone();
+            state.baseline = Some(replaced);
+        }
+
+        let mut state = state.write().unwrap();
+
+        assert!(pending.apply(&mut state, Utc::now()).is_empty());
+        assert!(state.changes.is_empty());
+        assert_eq!(state.current[&key(root.join("hosts"))].hash, Some(sha256::digest("0.0.0.0 example.com")));
+        drop(state);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn nothing_is_checked_without_a_baseline() {
+        let state = RwLock::new(IntegrityState { baseline: None, current: HashMap::new(), changes: vec![] });
+
+        assert!(PendingCheck::collect(&state, &HashSet::from([PathBuf::from("/etc/hosts")])).is_none());
+    }
+}
@@ -0,0 +1,54 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::Result;
+
+use crate::models::IntegrityChange;
+
+use super::baseline::Baseline;
+
+/// The baseline and the recorded changes under `<exec dir>/integrity`
+pub struct IntegrityStore {
+    directory: PathBuf,
+}
+
+impl IntegrityStore {
+    pub fn new(directory: PathBuf) -> Self {
+        Self { directory }
+    }
+
+    pub fn load_baseline(&self) -> Option<Baseline> {
+        let content = fs::read(self.directory.join("baseline.json")).ok()?;
+
+        serde_json::from_slice(&content).ok()
+    }
+
+    pub fn save_baseline(&self, baseline: &Baseline) -> Result<()> {
+        fs::create_dir_all(&self.directory)?;
+        fs::write(self.directory.join("baseline.json"), serde_json::to_vec(baseline)?)?;
+
+        Ok(())
+    }
+
+    /// Oldest first
+    pub fn load_changes(&self) -> Vec<IntegrityChange> {
+        fs::read(self.directory.join("changes.json"))
+            .ok()
+            .and_then(|content| serde_json::from_slice(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save_changes(&self, changes: &[IntegrityChange]) -> Result<()> {
+        fs::create_dir_all(&self.directory)?;
+        fs::write(self.directory.join("changes.json"), serde_json::to_vec(changes)?)?;
+
+        Ok(())
+    }
+
+    pub fn delete(&self) -> Result<()> {
+        if self.directory.exists() {
+            fs::remove_dir_all(&self.directory)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+use std::{path::PathBuf, sync::mpsc::Sender};
+
+use log::*;
+use notify::{
+    event::{AccessKind, AccessMode},
+    recommended_watcher, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+};
+
+/// Watches folders recursively and single files through their parent, editors often replace
+/// a file with a renamed copy and a watch on the file itself would end with the old one
+pub fn watch(roots: &[PathBuf], sender: Sender<notify::Result<Event>>) -> notify::Result<RecommendedWatcher> {
+    let mut watcher = recommended_watcher(sender)?;
+
+    for root in roots {
+        let result = match root.parent() {
+            Some(parent) if !root.is_dir() => watcher.watch(parent, RecursiveMode::NonRecursive),
+            _ => watcher.watch(root, RecursiveMode::Recursive),
+        };
+
+        if let Err(err) = result {
+            warn!("could not watch {}: {}", root.display(), err);
+        }
+    }
+
+    Ok(watcher)
+}
+
+/// Paths an event may have changed, reads are skipped as hashing a file would report itself
+pub fn changed_paths(event: Event) -> Vec<PathBuf> {
+    match event.kind {
+        EventKind::Access(AccessKind::Close(AccessMode::Write)) => event.paths,
+        EventKind::Access(_) => vec![],
+        _ => event.paths,
+    }
+}
@@ -0,0 +1,246 @@
+use std::{collections::HashMap, fs};
+
+use anyhow::Result;
+use log::*;
+
+use crate::{models::{IpcEndpoint, IpcKind, IpcPeer, IpcState}, services::{netlink, procfs}};
+
+const SO_ACCEPTCON: u32 = 0x0001_0000;
+
+const SOCK_DIAG_BY_FAMILY: u16 = 20;
+const UDIAG_SHOW_PEER: u32 = 0x04;
+const UNIX_DIAG_PEER: u16 = 2;
+const UNIX_DIAG_MSG_LEN: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnixSocketRow {
+    pub inode: u64,
+    pub socket_type: u16,
+    pub state: u8,
+    pub listening: bool,
+    pub path: Option<String>,
+}
+
+pub fn enumerate_endpoints() -> Result<Vec<IpcEndpoint>> {
+    let rows = parse_proc_net_unix(&fs::read_to_string("/proc/net/unix")?);
+    let owners = socket_owners();
+
+    // peer information is only available through sock_diag, missing it is not fatal
+    let peers = query_unix_peers().unwrap_or_else(|err| {
+        debug!("sock_diag unavailable: {}", err);
+        HashMap::new()
+    });
+
+    let endpoints = rows
+        .into_iter()
+        .map(|row| {
+            let owner = owners.get(&row.inode);
+            let peers = peers
+                .get(&row.inode)
+                .filter(|peer| **peer != 0)
+                .map(|peer| {
+                    let owner = owners.get(peer);
+                    vec![IpcPeer {
+                        inode: Some(*peer),
+                        process_id: owner.map(|(pid, _)| *pid),
+                        process_name: owner.map(|(_, name)| name.clone()),
+                    }]
+                })
+                .unwrap_or_default();
+
+            IpcEndpoint {
+                kind: IpcKind::UnixSocket,
+                path: row.path,
+                socket_type: Some(socket_type_name(row.socket_type).to_string()),
+                state: if row.listening { IpcState::Listening } else { to_state(row.state) },
+                inode: Some(row.inode),
+                process_id: owner.map(|(pid, _)| *pid),
+                process_name: owner.map(|(_, name)| name.clone()),
+                peers,
+                instances: None,
+                max_instances: None,
+            }
+        })
+        .collect();
+
+    Ok(endpoints)
+}
+
+/// Parses `/proc/net/unix`
+///
+/// ```text
+/// Num       RefCount Protocol Flags    Type St Inode Path
+/// 0000000000000000: 00000002 00000000 00010000 0001 01 23456 /run/dbus/system_bus_socket
+/// ```
+pub fn parse_proc_net_unix(content: &str) -> Vec<UnixSocketRow> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+
+            if columns.len() < 7 {
+                return None;
+            }
+
+            let flags = u32::from_str_radix(columns[3], 16).ok()?;
+
+            Some(UnixSocketRow {
+                inode: columns[6].parse().ok()?,
+                socket_type: u16::from_str_radix(columns[4], 16).ok()?,
+                state: u8::from_str_radix(columns[5], 16).ok()?,
+                listening: flags & SO_ACCEPTCON != 0,
+                // paths may contain spaces
+                path: (columns.len() > 7).then(|| columns[7..].join(" ")),
+            })
+        })
+        .collect()
+}
+
+fn socket_type_name(socket_type: u16) -> &'static str {
+    match socket_type {
+        1 => "stream",
+        2 => "dgram",
+        5 => "seqpacket",
+        _ => "unknown",
+    }
+}
+
+/// `socket_state` values from include/uapi/linux/net.h
+fn to_state(state: u8) -> IpcState {
+    match state {
+        1 => IpcState::Unconnected,
+        2 => IpcState::Connecting,
+        3 => IpcState::Connected,
+        4 => IpcState::Disconnecting,
+        _ => IpcState::Unknown,
+    }
+}
+
+/// Socket inodes to the process holding them and its name
+fn socket_owners() -> HashMap<u64, (u32, String)> {
+    let mut names = HashMap::new();
+
+    procfs::socket_owners()
+        .into_iter()
+        .map(|(inode, pid)| {
+            let name = names.entry(pid).or_insert_with(|| procfs::process_name(pid)).clone();
+            (inode, (pid, name))
+        })
+        .collect()
+}
+
+/// Dumps all unix sockets over NETLINK_SOCK_DIAG asking for the peer inode
+fn query_unix_peers() -> Result<HashMap<u64, u64>> {
+    let mut peers = HashMap::new();
+
+    netlink::dump(libc::NETLINK_SOCK_DIAG, SOCK_DIAG_BY_FAMILY, &build_unix_diag_request(), |datagram| {
+        parse_unix_diag_peers(datagram, &mut peers)
+    })?;
+
+    Ok(peers)
+}
+
+/// `unix_diag_req` for every state
+fn build_unix_diag_request() -> Vec<u8> {
+    let mut request = vec![libc::AF_UNIX as u8, 0];
+    request.extend_from_slice(&0u16.to_ne_bytes());
+    request.extend_from_slice(&u32::MAX.to_ne_bytes());
+    request.extend_from_slice(&0u32.to_ne_bytes());
+    request.extend_from_slice(&UDIAG_SHOW_PEER.to_ne_bytes());
+    request.extend_from_slice(&[0u8; 8]);
+
+    request
+}
+
+/// Parses one datagram of `unix_diag_msg` replies into `inode -> peer inode`.
+/// Returns true once the dump is complete.
+pub fn parse_unix_diag_peers(buffer: &[u8], peers: &mut HashMap<u64, u64>) -> Result<bool> {
+    netlink::parse_messages(buffer, |_, message| {
+        if message.len() < UNIX_DIAG_MSG_LEN {
+            return Ok(());
+        }
+
+        let inode = netlink::read_u32(message, 4) as u64;
+
+        for (attribute_type, value) in netlink::attributes(&message[UNIX_DIAG_MSG_LEN..]) {
+            if attribute_type == UNIX_DIAG_PEER && value.len() >= 4 {
+                peers.insert(inode, netlink::read_u32(value, 0) as u64);
+            }
+        }
+
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::services::netlink::{build_request, NLMSG_DONE};
+
+    use super::*;
+
+    #[test]
+    fn proc_net_unix_rows_keep_paths_abstract_names_and_unnamed_sockets() {
+        let content = "Num       RefCount Protocol Flags    Type St Inode Path
+0000000000000000: 00000002 00000000 00010000 0001 01 23456 /run/dbus/system_bus_socket
+0000000000000000: 00000002 00000000 00010000 0001 01 23457 @/tmp/.X11-unix/X0
+0000000000000000: 00000003 00000000 00000000 0001 03 23458
+0000000000000000: 00000002 00000000 00000000 0002 01 23459 /run/user/1000/my socket
+0000000000000000: 00000002 00000000 00000000 0005 03 23460
+";
+
+        let rows = parse_proc_net_unix(content);
+
+        assert_eq!(rows, vec![
+            UnixSocketRow { inode: 23456, socket_type: 1, state: 1, listening: true, path: Some("/run/dbus/system_bus_socket".into()) },
+            UnixSocketRow { inode: 23457, socket_type: 1, state: 1, listening: true, path: Some("@/tmp/.X11-unix/X0".into()) },
+            UnixSocketRow { inode: 23458, socket_type: 1, state: 3, listening: false, path: None },
+            UnixSocketRow { inode: 23459, socket_type: 2, state: 1, listening: false, path: Some("/run/user/1000/my socket".into()) },
+            UnixSocketRow { inode: 23460, socket_type: 5, state: 3, listening: false, path: None },
+        ]);
+        assert_eq!(socket_type_name(rows[4].socket_type), "seqpacket");
+        assert_eq!(to_state(rows[2].state), IpcState::Connected);
+    }
+
+    #[test]
+    fn short_proc_net_unix_lines_are_skipped() {
+        assert!(parse_proc_net_unix("Num RefCount\n0000000000000000: 00000002 00000000\n").is_empty());
+    }
+
+    fn unix_diag_message(inode: u32, peer: Option<u32>) -> Vec<u8> {
+        // unix_diag_msg: family, type, state, pad, inode, cookie
+        let mut payload = vec![libc::AF_UNIX as u8, 1, 1, 0];
+        payload.extend_from_slice(&inode.to_ne_bytes());
+        payload.extend_from_slice(&[0u8; 8]);
+
+        // a name attribute before the peer, padded to four bytes
+        payload.extend_from_slice(&6u16.to_ne_bytes());
+        payload.extend_from_slice(&0u16.to_ne_bytes());
+        payload.extend_from_slice(&[b'@', b'x', 0, 0]);
+
+        if let Some(peer) = peer {
+            payload.extend_from_slice(&8u16.to_ne_bytes());
+            payload.extend_from_slice(&UNIX_DIAG_PEER.to_ne_bytes());
+            payload.extend_from_slice(&peer.to_ne_bytes());
+        }
+
+        build_request(SOCK_DIAG_BY_FAMILY, &payload)
+    }
+
+    #[test]
+    fn unix_diag_replies_give_peer_inodes() {
+        let mut buffer = unix_diag_message(100, Some(101));
+        buffer.extend(unix_diag_message(101, Some(100)));
+        buffer.extend(unix_diag_message(102, None));
+
+        let mut peers = HashMap::new();
+
+        assert!(!parse_unix_diag_peers(&buffer, &mut peers).unwrap());
+
+        let mut done = build_request(NLMSG_DONE, &0u32.to_ne_bytes());
+        done.extend(unix_diag_message(103, Some(104)));
+
+        assert!(parse_unix_diag_peers(&done, &mut peers).unwrap());
+        assert_eq!(peers, HashMap::from([(100, 101), (101, 100)]));
+    }
+}
@@ -0,0 +1,95 @@
+use std::{
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+
+use crate::models::{IpcEndpoint, IpcFilter};
+
+#[cfg(windows)]
+mod windows;
+#[cfg(windows)]
+use self::windows as platform;
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "linux")]
+use self::linux as platform;
+
+#[derive(Debug)]
+struct CacheEntry {
+    refreshed_on: Instant,
+    endpoints: Vec<IpcEndpoint>,
+}
+
+/// Unix domain sockets on Linux, named pipes on Windows
+pub struct IpcService {
+    query_interval: Duration,
+    cache: Arc<RwLock<CacheEntry>>,
+}
+
+impl IpcService {
+    pub fn new() -> Self {
+        let query_interval = Duration::from_secs(5);
+
+        Self {
+            query_interval,
+            cache: Arc::new(RwLock::new(CacheEntry {
+                refreshed_on: Instant::now() - query_interval,
+                endpoints: vec![],
+            })),
+        }
+    }
+
+    pub fn query(&self, filter: &IpcFilter) -> Result<Vec<IpcEndpoint>> {
+        {
+            let guard = self.cache.read().unwrap();
+            if guard.refreshed_on.elapsed() > self.query_interval {
+                drop(guard);
+                self.refresh_cache()?;
+            }
+        }
+
+        let guard = self.cache.read().unwrap();
+
+        Ok(guard.endpoints
+            .iter()
+            .filter(|endpoint| filter.matches(endpoint))
+            .cloned()
+            .collect())
+    }
+
+    fn refresh_cache(&self) -> Result<()> {
+        let mut endpoints = platform::enumerate_endpoints()?;
+
+        endpoints.sort_by(|a, b| a.path.cmp(&b.path).then_with(|| a.inode.cmp(&b.inode)));
+
+        let mut cache = self.cache.write().unwrap();
+        cache.endpoints = endpoints;
+        cache.refreshed_on = Instant::now();
+
+        Ok(())
+    }
+}
+
+impl IpcFilter {
+    pub fn matches(&self, endpoint: &IpcEndpoint) -> bool {
+        let contains = |value: Option<&String>, needle: &String| {
+            value.is_some_and(|value| value.to_lowercase().contains(&needle.to_lowercase()))
+        };
+
+        self.kinds.as_ref().is_none_or(|kinds| kinds.contains(&endpoint.kind))
+            && self.path.as_ref().is_none_or(|path| contains(endpoint.path.as_ref(), path))
+            && self.process_name.as_ref().is_none_or(|name| {
+                contains(endpoint.process_name.as_ref(), name)
+                    || endpoint.peers.iter().any(|peer| contains(peer.process_name.as_ref(), name))
+            })
+            && self.process_ids.as_ref().is_none_or(|ids| {
+                endpoint.process_id.is_some_and(|id| ids.contains(&id))
+                    || endpoint.peers.iter().any(|peer| peer.process_id.is_some_and(|id| ids.contains(&id)))
+            })
+            && self.states.as_ref().is_none_or(|states| states.contains(&endpoint.state))
+            && (!self.connected_only || endpoint.peers.iter().any(|peer| peer.process_id.is_some()))
+    }
+}
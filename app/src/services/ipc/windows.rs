@@ -0,0 +1,66 @@
+use std::{io, mem};
+
+use anyhow::Result;
+use widestring::{U16CStr, U16CString};
+use winapi::{
+    shared::winerror::ERROR_NO_MORE_FILES,
+    um::{
+        fileapi::{FindClose, FindFirstFileW, FindNextFileW},
+        handleapi::INVALID_HANDLE_VALUE,
+        minwinbase::WIN32_FIND_DATAW,
+    },
+};
+
+use crate::models::{IpcEndpoint, IpcKind, IpcState};
+
+const PIPE_PREFIX: &str = r"\\.\pipe\";
+
+/// Lists the named pipe namespace. NPFS reports the current instance count
+/// in `nFileSizeLow` and the instance limit in `nFileSizeHigh`, the owning
+/// process is not exposed without connecting to the pipe.
+pub fn enumerate_endpoints() -> Result<Vec<IpcEndpoint>> {
+    unsafe {
+        let pattern = U16CString::from_str(format!("{}*", PIPE_PREFIX))?;
+        let mut data: WIN32_FIND_DATAW = mem::zeroed();
+        let handle = FindFirstFileW(pattern.as_ptr(), &mut data);
+
+        if handle == INVALID_HANDLE_VALUE {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        let mut endpoints = Vec::new();
+
+        loop {
+            let name = U16CStr::from_slice_truncate(&data.cFileName)
+                .map(|name| name.to_string_lossy())
+                .unwrap_or_default();
+
+            endpoints.push(IpcEndpoint {
+                kind: IpcKind::NamedPipe,
+                path: Some(format!("{}{}", PIPE_PREFIX, name)),
+                socket_type: None,
+                state: IpcState::Unknown,
+                inode: None,
+                process_id: None,
+                process_name: None,
+                peers: vec![],
+                instances: Some(data.nFileSizeLow),
+                // -1 stands for PIPE_UNLIMITED_INSTANCES
+                max_instances: Some(data.nFileSizeHigh).filter(|max| *max != u32::MAX),
+            });
+
+            if FindNextFileW(handle, &mut data) == 0 {
+                let error = io::Error::last_os_error();
+                FindClose(handle);
+
+                if error.raw_os_error() != Some(ERROR_NO_MORE_FILES as i32) {
+                    return Err(error.into());
+                }
+
+                break;
+            }
+        }
+
+        Ok(endpoints)
+    }
+}
@@ -0,0 +1,164 @@
+use std::{collections::HashMap, fs};
+
+use anyhow::{bail, Result};
+
+use super::MemoryCounters;
+
+pub fn read_memory_counters() -> Result<MemoryCounters> {
+    counters_from_meminfo(&parse_meminfo(&fs::read_to_string("/proc/meminfo")?))
+}
+
+/// Parses `/proc/meminfo` into bytes, unit-less fields such as `HugePages_Total` stay counts
+///
+/// ```text
+/// MemTotal:       16318284 kB
+/// HugePages_Total:       0
+/// ```
+pub fn parse_meminfo(content: &str) -> HashMap<String, u64> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let mut parts = value.split_whitespace();
+            let value: u64 = parts.next()?.parse().ok()?;
+            let value = match parts.next() {
+                Some("kB") => value * 1024,
+                _ => value,
+            };
+
+            Some((key.trim().to_string(), value))
+        })
+        .collect()
+}
+
+/// Maps meminfo fields onto the Windows shaped counters.
+/// The page file is swap, virtual memory is RAM plus swap as in `free -t`.
+pub fn counters_from_meminfo(meminfo: &HashMap<String, u64>) -> Result<MemoryCounters> {
+    let get = |key: &str| meminfo.get(key).copied();
+
+    let Some(total_phys) = get("MemTotal") else {
+        bail!("MemTotal missing from /proc/meminfo");
+    };
+
+    let free = get("MemFree").unwrap_or_default();
+    let buffers = get("Buffers");
+    let cached = get("Cached").map(|cached| cached + get("SReclaimable").unwrap_or_default());
+    // MemAvailable appeared in 3.14
+    let avail_phys = get("MemAvailable")
+        .unwrap_or_else(|| free + buffers.unwrap_or_default() + cached.unwrap_or_default())
+        .min(total_phys);
+
+    let swap_total = get("SwapTotal").unwrap_or_default();
+    let swap_free = get("SwapFree").unwrap_or_default().min(swap_total);
+
+    let huge_page_size = get("Hugepagesize");
+    let huge_pages = |key: &str| get(key).zip(huge_page_size).map(|(count, size)| count * size);
+
+    let memory_load = ((total_phys - avail_phys) * 100).checked_div(total_phys).unwrap_or(0) as u32;
+
+    Ok(MemoryCounters {
+        total_phys,
+        avail_phys,
+        total_pagefile: swap_total,
+        avail_pagefile: swap_free,
+        total_virtual: total_phys + swap_total,
+        avail_virtual: avail_phys + swap_free,
+        memory_load,
+        cached,
+        buffers,
+        committed: get("Committed_AS"),
+        commit_limit: get("CommitLimit"),
+        swap_used: Some(swap_total - swap_free),
+        huge_pages_total: huge_pages("HugePages_Total"),
+        huge_pages_free: huge_pages("HugePages_Free"),
+        // the kernel has no pageable pool, it never swaps out its own allocations
+        paged_pool: None,
+        non_paged_pool: get("SUnreclaim").map(|slab| slab + get("KernelStack").unwrap_or_default() + get("PageTables").unwrap_or_default()),
+        compressed: get("Zswap"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MEMINFO: &str = "MemTotal:       16318284 kB
+MemFree:         1048576 kB
+MemAvailable:    8388608 kB
+Buffers:          262144 kB
+Cached:          4194304 kB
+SwapCached:            0 kB
+SwapTotal:       2097152 kB
+SwapFree:        1572864 kB
+Zswap:             65536 kB
+SReclaimable:     524288 kB
+SUnreclaim:       131072 kB
+KernelStack:       16384 kB
+PageTables:        32768 kB
+CommitLimit:    10256292 kB
+Committed_AS:   12582912 kB
+HugePages_Total:       4
+HugePages_Free:        1
+Hugepagesize:       2048 kB
+";
+
+    const KIB: u64 = 1024;
+
+    #[test]
+    fn meminfo_sizes_are_bytes_and_counts_stay_counts() {
+        let meminfo = parse_meminfo(MEMINFO);
+
+        assert_eq!(meminfo["MemTotal"], 16318284 * KIB);
+        assert_eq!(meminfo["HugePages_Total"], 4);
+        assert_eq!(meminfo["Hugepagesize"], 2048 * KIB);
+        assert!(!meminfo.contains_key(""));
+    }
+
+    #[test]
+    fn meminfo_maps_onto_the_counters() {
+        let counters = counters_from_meminfo(&parse_meminfo(MEMINFO)).unwrap();
+
+        assert_eq!(counters.total_phys, 16318284 * KIB);
+        assert_eq!(counters.avail_phys, 8388608 * KIB);
+        assert_eq!(counters.total_pagefile, 2097152 * KIB);
+        assert_eq!(counters.avail_pagefile, 1572864 * KIB);
+        assert_eq!(counters.swap_used, Some(524288 * KIB));
+        assert_eq!(counters.total_virtual, (16318284 + 2097152) * KIB);
+        assert_eq!(counters.avail_virtual, (8388608 + 1572864) * KIB);
+        assert_eq!(counters.memory_load, 48);
+        assert_eq!(counters.cached, Some((4194304 + 524288) * KIB));
+        assert_eq!(counters.buffers, Some(262144 * KIB));
+        assert_eq!(counters.committed, Some(12582912 * KIB));
+        assert_eq!(counters.commit_limit, Some(10256292 * KIB));
+        assert_eq!(counters.huge_pages_total, Some(4 * 2048 * KIB));
+        assert_eq!(counters.huge_pages_free, Some(2048 * KIB));
+        assert_eq!(counters.non_paged_pool, Some((131072 + 16384 + 32768) * KIB));
+        assert_eq!(counters.compressed, Some(65536 * KIB));
+        assert_eq!(counters.paged_pool, None);
+    }
+
+    /// kernels before 3.14 have no MemAvailable
+    #[test]
+    fn missing_mem_available_is_estimated_from_free_and_cache() {
+        let content = MEMINFO.lines().filter(|line| !line.starts_with("MemAvailable")).collect::<Vec<_>>().join("\n");
+
+        let counters = counters_from_meminfo(&parse_meminfo(&content)).unwrap();
+
+        assert_eq!(counters.avail_phys, (1048576 + 262144 + 4194304 + 524288) * KIB);
+    }
+
+    #[test]
+    fn estimates_never_exceed_the_total() {
+        let counters = counters_from_meminfo(&parse_meminfo("MemTotal: 1000 kB\nMemFree: 800 kB\nCached: 400 kB\nSwapTotal: 0 kB\nSwapFree: 10 kB\n")).unwrap();
+
+        assert_eq!(counters.avail_phys, 1000 * KIB);
+        assert_eq!(counters.memory_load, 0);
+        assert_eq!(counters.swap_used, Some(0));
+        assert_eq!(counters.huge_pages_total, None);
+    }
+
+    #[test]
+    fn missing_mem_total_is_an_error() {
+        assert!(counters_from_meminfo(&parse_meminfo("MemFree: 800 kB\n")).is_err());
+    }
+}
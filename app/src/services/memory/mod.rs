@@ -0,0 +1,134 @@
+use std::{
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+
+use crate::{models::MemoryStats, utils::format_bytes};
+
+#[cfg(windows)]
+mod windows;
+#[cfg(windows)]
+use self::windows as platform;
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "linux")]
+use self::linux as platform;
+
+/// Raw byte counts reported by the platform, values the platform lacks stay `None`
+#[derive(Debug, Default, Clone)]
+pub struct MemoryCounters {
+    pub total_phys: u64,
+    pub avail_phys: u64,
+    pub total_pagefile: u64,
+    pub avail_pagefile: u64,
+    pub total_virtual: u64,
+    pub avail_virtual: u64,
+    pub memory_load: u32,
+    pub cached: Option<u64>,
+    pub buffers: Option<u64>,
+    pub committed: Option<u64>,
+    pub commit_limit: Option<u64>,
+    /// swap in use on Linux, not reported on Windows
+    pub swap_used: Option<u64>,
+    pub huge_pages_total: Option<u64>,
+    pub huge_pages_free: Option<u64>,
+    pub paged_pool: Option<u64>,
+    pub non_paged_pool: Option<u64>,
+    pub compressed: Option<u64>,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    refreshed_on: Instant,
+    stats: MemoryStats,
+}
+
+#[derive(Clone)]
+pub struct MemoryService {
+    query_interval: Duration,
+    cache: Arc<RwLock<CacheEntry>>,
+}
+
+impl MemoryService {
+    pub fn new() -> Self {
+        let initial_stats = Self::fetch_memory_stats().unwrap_or_default();
+
+        Self {
+            query_interval: Duration::from_secs(1),
+            cache: Arc::new(RwLock::new(CacheEntry {
+                refreshed_on: Instant::now(),
+                stats: initial_stats,
+            })),
+        }
+    }
+
+    pub fn get_stats(&self) -> Result<MemoryStats> {
+        {
+            let guard = self.cache.read().unwrap();
+            if guard.refreshed_on.elapsed() > self.query_interval {
+                drop(guard); // unlock before refreshing
+                self.refresh_cache()?;
+            }
+        }
+
+        let guard = self.cache.read().unwrap();
+        Ok(guard.stats.clone())
+    }
+
+    fn refresh_cache(&self) -> Result<()> {
+        let stats = Self::fetch_memory_stats()?;
+        let mut guard = self.cache.write().unwrap();
+        guard.stats = stats;
+        guard.refreshed_on = Instant::now();
+        Ok(())
+    }
+
+    fn fetch_memory_stats() -> Result<MemoryStats> {
+        Ok(platform::read_memory_counters()?.into())
+    }
+}
+
+impl From<MemoryCounters> for MemoryStats {
+    fn from(counters: MemoryCounters) -> Self {
+        let formatted = |value: Option<u64>| value.map(format_bytes);
+
+        MemoryStats {
+            total_phys: counters.total_phys,
+            total_phys_formatted: format_bytes(counters.total_phys),
+            avail_phys: counters.avail_phys,
+            avail_phys_formatted: format_bytes(counters.avail_phys),
+            total_pagefile: counters.total_pagefile,
+            total_pagefile_formatted: format_bytes(counters.total_pagefile),
+            avail_pagefile: counters.avail_pagefile,
+            avail_pagefile_formatted: format_bytes(counters.avail_pagefile),
+            total_virtual: counters.total_virtual,
+            total_virtual_formatted: format_bytes(counters.total_virtual),
+            avail_virtual: counters.avail_virtual,
+            avail_virtual_formatted: format_bytes(counters.avail_virtual),
+            memory_load: counters.memory_load,
+            cached: counters.cached,
+            cached_formatted: formatted(counters.cached),
+            buffers: counters.buffers,
+            buffers_formatted: formatted(counters.buffers),
+            committed: counters.committed,
+            committed_formatted: formatted(counters.committed),
+            commit_limit: counters.commit_limit,
+            commit_limit_formatted: formatted(counters.commit_limit),
+            swap_used: counters.swap_used,
+            swap_used_formatted: formatted(counters.swap_used),
+            huge_pages_total: counters.huge_pages_total,
+            huge_pages_total_formatted: formatted(counters.huge_pages_total),
+            huge_pages_free: counters.huge_pages_free,
+            huge_pages_free_formatted: formatted(counters.huge_pages_free),
+            paged_pool: counters.paged_pool,
+            paged_pool_formatted: formatted(counters.paged_pool),
+            non_paged_pool: counters.non_paged_pool,
+            non_paged_pool_formatted: formatted(counters.non_paged_pool),
+            compressed: counters.compressed,
+            compressed_formatted: formatted(counters.compressed),
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use std::mem;
+
+use anyhow::{bail, Result};
+use winapi::um::{
+    psapi::{GetPerformanceInfo, PERFORMANCE_INFORMATION},
+    sysinfoapi::{GlobalMemoryStatusEx, MEMORYSTATUSEX},
+};
+
+use super::MemoryCounters;
+
+pub fn read_memory_counters() -> Result<MemoryCounters> {
+    unsafe {
+        let mut mem_info: MEMORYSTATUSEX = mem::zeroed();
+        mem_info.dwLength = mem::size_of::<MEMORYSTATUSEX>() as u32;
+
+        if GlobalMemoryStatusEx(&mut mem_info) == 0 {
+            bail!("Failed to get memory status");
+        }
+
+        let mut counters = MemoryCounters {
+            total_phys: mem_info.ullTotalPhys,
+            avail_phys: mem_info.ullAvailPhys,
+            total_pagefile: mem_info.ullTotalPageFile,
+            avail_pagefile: mem_info.ullAvailPageFile,
+            total_virtual: mem_info.ullTotalVirtual,
+            avail_virtual: mem_info.ullAvailVirtual,
+            memory_load: mem_info.dwMemoryLoad,
+            // swap_used stays empty, page file usage is only exposed through the paging file
+            // performance counters and committed memory takes page file space only once paged out
+            ..Default::default()
+        };
+
+        let mut performance: PERFORMANCE_INFORMATION = mem::zeroed();
+        let size = mem::size_of::<PERFORMANCE_INFORMATION>() as u32;
+        performance.cb = size;
+
+        // the extended counters are best effort, the basic ones above are enough for the dashboard
+        if GetPerformanceInfo(&mut performance, size) != 0 {
+            let pages = |count: usize| Some(count as u64 * performance.PageSize as u64);
+
+            counters.cached = pages(performance.SystemCache);
+            counters.committed = pages(performance.CommitTotal);
+            counters.commit_limit = pages(performance.CommitLimit);
+            counters.paged_pool = pages(performance.KernelPaged);
+            counters.non_paged_pool = pages(performance.KernelNonpaged);
+        }
+
+        Ok(counters)
+    }
+}
@@ -0,0 +1,278 @@
+use std::{collections::VecDeque, time::Duration};
+
+use chrono::{DateTime, Utc};
+use hashbrown::HashMap;
+
+use crate::{
+    models::{MemoryGrowth, MemoryPoint, ProcessMemory},
+    services::{fit_trend, steady_ratio, time_to_threshold, Trend},
+    utils::format_bytes,
+};
+
+/// Thresholds deciding when memory growth counts as a leak rather than a warming cache
+#[derive(Debug, Clone)]
+pub struct MemoryLeakConfig {
+    /// fewer samples than this within the window never flag a process
+    pub min_samples: usize,
+    /// absolute growth over the window in bytes
+    pub min_growth: u64,
+    /// how closely the samples must follow the fitted line
+    pub min_r_squared: f64,
+    /// share of sample to sample deltas that must not be negative
+    pub min_steady_ratio: f64,
+    /// fixed per-process limit, otherwise the point where the process alone would use up the available physical memory
+    pub threshold: Option<u64>,
+}
+
+impl Default for MemoryLeakConfig {
+    fn default() -> Self {
+        Self {
+            min_samples: 6,
+            min_growth: 32 * 1024 * 1024,
+            min_r_squared: 0.8,
+            min_steady_ratio: 0.7,
+            threshold: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ProcessSeries {
+    process_name: String,
+    points: VecDeque<MemoryPoint>,
+}
+
+/// Private memory of every process over a sliding window
+#[derive(Debug)]
+pub struct MemoryHistory {
+    sample_interval: Duration,
+    capacity: usize,
+    last_sampled: Option<DateTime<Utc>>,
+    series: HashMap<u32, ProcessSeries>,
+    config: MemoryLeakConfig,
+}
+
+impl MemoryHistory {
+    pub fn new(sample_interval: Duration, capacity: usize, config: MemoryLeakConfig) -> Self {
+        Self {
+            sample_interval,
+            capacity,
+            last_sampled: None,
+            series: HashMap::new(),
+            config,
+        }
+    }
+
+    pub fn sample_interval(&self) -> Duration {
+        self.sample_interval
+    }
+
+    /// Records a sample unless the previous one is younger than the sample interval
+    pub fn record_if_due(&mut self, items: &[ProcessMemory]) {
+        let now = Utc::now();
+        let is_due = self.last_sampled.is_none_or(|last| {
+            (now - last).to_std().unwrap_or_default() >= self.sample_interval
+        });
+
+        if is_due {
+            self.record(now, items);
+        }
+    }
+
+    pub fn record(&mut self, taken_on: DateTime<Utc>, items: &[ProcessMemory]) {
+        self.last_sampled = Some(taken_on);
+
+        // exited processes
+        self.series.retain(|process_id, _| items.iter().any(|item| item.process_id == *process_id));
+
+        for item in items {
+            // commit charge where the private pages are not readable
+            let Some(bytes) = item.private_bytes.or(item.commit_charge) else {
+                continue;
+            };
+
+            let series = self.series.entry(item.process_id).or_insert_with(|| ProcessSeries {
+                process_name: item.process_name.clone(),
+                points: VecDeque::with_capacity(self.capacity),
+            });
+
+            // a reused pid restarts the series
+            if series.process_name != item.process_name {
+                series.process_name = item.process_name.clone();
+                series.points.clear();
+            }
+
+            if series.points.len() == self.capacity {
+                series.points.pop_front();
+            }
+
+            series.points.push_back(MemoryPoint { timestamp: taken_on, bytes });
+        }
+    }
+
+    /// Processes whose private memory grew over the last `window`, suspected leaks first
+    pub fn growth(&self, window: Duration, available: u64) -> Vec<MemoryGrowth> {
+        let Some(last) = self.last_sampled else {
+            return vec![];
+        };
+
+        let since = last - chrono::Duration::from_std(window).unwrap_or(chrono::Duration::zero());
+
+        let mut growers: Vec<MemoryGrowth> = self
+            .series
+            .iter()
+            .filter_map(|(process_id, series)| self.process_growth(*process_id, series, since, available))
+            .filter(|growth| growth.growth > 0)
+            .collect();
+
+        growers.sort_by(|a, b| {
+            b.suspected_leak
+                .cmp(&a.suspected_leak)
+                .then_with(|| b.bytes_per_minute.total_cmp(&a.bytes_per_minute))
+        });
+        growers
+    }
+
+    fn process_growth(&self, process_id: u32, series: &ProcessSeries, since: DateTime<Utc>, available: u64) -> Option<MemoryGrowth> {
+        let points: Vec<MemoryPoint> = series.points.iter().filter(|point| point.timestamp >= since).cloned().collect();
+
+        let first = points.first()?;
+        let last = points.last()?;
+
+        let xy: Vec<(f64, f64)> = points
+            .iter()
+            .map(|point| ((point.timestamp - first.timestamp).num_milliseconds() as f64 / 60_000.0, point.bytes as f64))
+            .collect();
+
+        let trend = fit_trend(&xy).unwrap_or(Trend { slope: 0.0, intercept: last.bytes as f64, r_squared: 0.0 });
+        let growth = last.bytes as i64 - first.bytes as i64;
+        let threshold = self.config.threshold.unwrap_or(last.bytes.saturating_add(available));
+
+        let suspected_leak = points.len() >= self.config.min_samples
+            && growth >= self.config.min_growth as i64
+            && trend.slope > 0.0
+            && trend.r_squared >= self.config.min_r_squared
+            && steady_ratio(&points.iter().map(|point| point.bytes).collect::<Vec<_>>()) >= self.config.min_steady_ratio;
+
+        Some(MemoryGrowth {
+            process_id,
+            process_name: series.process_name.clone(),
+            current: last.bytes,
+            current_formatted: format_bytes(last.bytes),
+            growth,
+            bytes_per_minute: trend.slope,
+            bytes_per_minute_formatted: format_bytes(trend.slope.max(0.0) as u64),
+            r_squared: trend.r_squared,
+            suspected_leak,
+            time_to_threshold: time_to_threshold(last.bytes, threshold, trend.slope).map(|duration| duration.as_secs()),
+            threshold,
+            threshold_formatted: format_bytes(threshold),
+            samples: points,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIB: u64 = 1024 * 1024;
+
+    fn process(process_id: u32, process_name: &str, private_bytes: u64) -> ProcessMemory {
+        ProcessMemory {
+            process_id,
+            process_name: process_name.into(),
+            working_set: None,
+            private_bytes: Some(private_bytes),
+            shared_bytes: None,
+            proportional_bytes: None,
+            commit_charge: None,
+            peak_working_set: None,
+            page_faults: None,
+        }
+    }
+
+    fn minute(minute: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(minute * 60, 0).unwrap()
+    }
+
+    fn history() -> MemoryHistory {
+        MemoryHistory::new(Duration::from_secs(60), 100, MemoryLeakConfig::default())
+    }
+
+    #[test]
+    fn steady_growth_is_a_suspected_leak() {
+        let mut history = history();
+
+        for index in 0..10 {
+            history.record(minute(index), &[process(1, "leaky", 100 * MIB + index as u64 * 10 * MIB)]);
+        }
+
+        let growth = history.growth(Duration::from_secs(3600), 1000 * MIB);
+
+        assert_eq!(growth.len(), 1);
+        assert_eq!(growth[0].growth, 90 * MIB as i64);
+        assert!((growth[0].bytes_per_minute - (10 * MIB) as f64).abs() < 1.0);
+        assert!(growth[0].suspected_leak);
+        // 1000 MiB left at 10 MiB a minute
+        assert!(growth[0].time_to_threshold.unwrap().abs_diff(100 * 60) <= 1);
+    }
+
+    #[test]
+    fn noisy_or_small_growth_is_not_a_leak() {
+        let mut history = history();
+
+        for index in 0..10 {
+            let noisy = if index % 2 == 0 { 100 * MIB } else { 300 * MIB } + index as u64 * MIB;
+            history.record(minute(index), &[process(1, "noisy", noisy), process(2, "small", 100 * MIB + index as u64 * 1024)]);
+        }
+
+        let growth = history.growth(Duration::from_secs(3600), 1000 * MIB);
+
+        assert!(growth.iter().all(|growth| !growth.suspected_leak));
+    }
+
+    #[test]
+    fn shrinking_processes_are_left_out() {
+        let mut history = history();
+
+        for index in 0..10 {
+            history.record(minute(index), &[process(1, "shrinking", 500 * MIB - index as u64 * MIB)]);
+        }
+
+        assert!(history.growth(Duration::from_secs(3600), 1000 * MIB).is_empty());
+    }
+
+    #[test]
+    fn window_limits_the_samples() {
+        let mut history = history();
+
+        for index in 0..10 {
+            let bytes = if index < 5 { 100 * MIB + index as u64 * 50 * MIB } else { 400 * MIB + index as u64 * MIB };
+            history.record(minute(index), &[process(1, "app", bytes)]);
+        }
+
+        let growth = history.growth(Duration::from_secs(3 * 60), 1000 * MIB);
+
+        assert_eq!(growth[0].samples.len(), 4);
+        assert_eq!(growth[0].growth, 3 * MIB as i64);
+    }
+
+    #[test]
+    fn reused_pid_restarts_the_series() {
+        let mut history = history();
+
+        for index in 0..5 {
+            history.record(minute(index), &[process(1, "old", 100 * MIB + index as u64 * 50 * MIB)]);
+        }
+        for index in 5..7 {
+            history.record(minute(index), &[process(1, "new", 10 * MIB + index as u64 * MIB)]);
+        }
+
+        let growth = history.growth(Duration::from_secs(3600), 1000 * MIB);
+
+        assert_eq!(growth[0].process_name, "new");
+        assert_eq!(growth[0].samples.len(), 2);
+        assert!(!growth[0].suspected_leak);
+    }
+}
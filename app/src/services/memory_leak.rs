@@ -0,0 +1,105 @@
+use std::{
+    sync::{atomic::{AtomicBool, Ordering}, Arc, RwLock},
+    thread,
+    time::Duration,
+};
+
+use anyhow::Result;
+use hashbrown::HashSet;
+use log::*;
+use tauri::{AppHandle, Emitter};
+
+use crate::{models::MemoryGrowth, services::{MemoryHistory, MemoryLeakConfig, MemoryService, ProcessMemoryService}};
+
+/// Window the background notification looks at
+const NOTIFY_WINDOW: Duration = Duration::from_mins(30);
+
+/// Samples private memory in the background and flags processes that grow steadily
+#[derive(Clone)]
+pub struct MemoryLeakService {
+    app_handle: AppHandle,
+    memory_service: MemoryService,
+    process_memory_service: ProcessMemoryService,
+    history: Arc<RwLock<MemoryHistory>>,
+    notifications: Arc<AtomicBool>,
+    /// processes already reported, so a leak is announced once rather than every sample
+    notified: Arc<RwLock<HashSet<u32>>>,
+}
+
+impl MemoryLeakService {
+    pub fn new(app_handle: AppHandle, memory_service: MemoryService, process_memory_service: ProcessMemoryService) -> Self {
+        let service = Self {
+            app_handle,
+            memory_service,
+            process_memory_service,
+            // two hours of samples
+            history: Arc::new(RwLock::new(MemoryHistory::new(Duration::from_secs(30), 240, MemoryLeakConfig::default()))),
+            notifications: Arc::new(AtomicBool::new(false)),
+            notified: Arc::new(RwLock::new(HashSet::new())),
+        };
+
+        {
+            let service = service.clone();
+            thread::Builder::new()
+                .name("memory-sampler".into())
+                .spawn(move || service.run_sampler())
+                .expect("could not spawn memory sampler");
+        }
+
+        service
+    }
+
+    /// Processes whose private memory grew over `window`, suspected leaks first
+    pub fn get_growth(&self, window: Duration, limit: usize) -> Result<Vec<MemoryGrowth>> {
+        let stats = self.memory_service.get_stats()?;
+
+        let mut growth = self.history.read().unwrap().growth(window, stats.avail_phys);
+        growth.truncate(limit);
+
+        Ok(growth)
+    }
+
+    /// Emits `on-memory-leak` for every newly suspected process while enabled
+    pub fn set_notifications(&self, enabled: bool) {
+        self.notifications.store(enabled, Ordering::Relaxed);
+    }
+
+    fn run_sampler(&self) {
+        let interval = self.history.read().unwrap().sample_interval();
+
+        loop {
+            if let Err(err) = self.sample() {
+                warn!("memory sampling failed: {}", err);
+            }
+
+            thread::sleep(interval);
+        }
+    }
+
+    fn sample(&self) -> Result<()> {
+        let items = self.process_memory_service.query(None, None)?;
+        self.history.write().unwrap().record_if_due(&items);
+
+        let suspects: Vec<MemoryGrowth> = self
+            .get_growth(NOTIFY_WINDOW, usize::MAX)?
+            .into_iter()
+            .filter(|growth| growth.suspected_leak)
+            .collect();
+
+        let mut notified = self.notified.write().unwrap();
+        // forget processes that stopped growing so a relapse is reported again
+        notified.retain(|process_id| suspects.iter().any(|growth| growth.process_id == *process_id));
+
+        if !self.notifications.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        for growth in suspects {
+            if notified.insert(growth.process_id) {
+                self.app_handle.emit("on-memory-leak", growth)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,23 +1,85 @@
 mod process;
+#[cfg(windows)]
 mod programs;
 mod memory;
+mod process_memory;
+mod memory_history;
+mod memory_leak;
 mod disk;
+mod disk_forecast;
+mod disk_io;
+mod disk_usage;
+mod benchmark;
+mod duplicates;
+mod partition_table;
+mod cleanup;
+mod integrity;
+#[cfg(windows)]
 mod wmii;
+#[cfg(any(windows, test))]
+mod wmi_path;
+#[cfg(windows)]
 mod loaded_driver;
+#[cfg(windows)]
 mod installed_driver;
 mod save_screenshot;
 mod handle;
+mod access_rights;
+mod handle_history;
+mod open_files;
 mod net_table;
+mod net_interface;
+mod net_filter;
+mod net_aggregate;
+mod reverse_dns;
+mod geoip;
+mod route;
+mod ipc;
+#[cfg(target_os = "linux")]
+mod netlink;
+#[cfg(target_os = "linux")]
+mod procfs;
+mod stats;
+#[cfg(windows)]
 mod device_class;
+#[cfg(windows)]
 mod utils;
 
 pub use process::*;
+#[cfg(windows)]
 pub use programs::*;
 pub use memory::*;
+pub use process_memory::*;
+pub use memory_history::*;
+pub use memory_leak::*;
 pub use disk::*;
+pub use disk_forecast::*;
+pub use disk_io::*;
+pub use disk_usage::*;
+pub use benchmark::*;
+pub use duplicates::*;
+pub use partition_table::*;
+pub use cleanup::*;
+pub use integrity::*;
+#[cfg(windows)]
 pub use wmii::*;
+#[cfg(windows)]
+pub use wmi_path::*;
+#[cfg(windows)]
 pub use loaded_driver::*;
+#[cfg(windows)]
 pub use installed_driver::*;
 pub use save_screenshot::*;
 pub use handle::*;
-pub use net_table::*;
\ No newline at end of file
+pub use access_rights::*;
+pub use handle_history::*;
+pub use open_files::*;
+pub use net_table::*;
+pub use net_interface::*;
+pub use net_filter::*;
+pub use net_aggregate::*;
+pub use reverse_dns::*;
+pub use geoip::*;
+pub use route::*;
+pub use ipc::*;
+pub use stats::*;
\ No newline at end of file
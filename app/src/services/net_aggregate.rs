@@ -0,0 +1,228 @@
+use std::net::SocketAddr;
+
+use hashbrown::{HashMap, HashSet};
+
+use crate::{models::{ConnectionEdge, ConnectionGraph, ConnectionGroup, ConnectionNode, NetTableAggregate, NetTableEntry, NetTableGroupBy, NetworkThroughput, TcpState}, services::{remote_geo_of, remote_of, throughput_of}};
+
+#[derive(Default)]
+struct GroupAccumulator {
+    label: String,
+    count: u32,
+    process_ids: HashSet<u32>,
+    states: HashMap<TcpState, u32>,
+    throughput: Option<NetworkThroughput>,
+}
+
+pub fn aggregate_entries(entries: &[NetTableEntry], group_by: NetTableGroupBy) -> NetTableAggregate {
+    NetTableAggregate {
+        groups: group_entries(entries, group_by),
+        graph: build_graph(entries),
+    }
+}
+
+pub fn group_entries(entries: &[NetTableEntry], group_by: NetTableGroupBy) -> Vec<ConnectionGroup> {
+    let mut groups: HashMap<String, GroupAccumulator> = HashMap::new();
+
+    for entry in entries {
+        let (remote_ip_address, remote_port, state) = remote_of(entry);
+
+        let (key, label) = match group_by {
+            NetTableGroupBy::Process => (entry.process_id.to_string(), format!("{} ({})", entry.process_name, entry.process_id)),
+            NetTableGroupBy::RemoteAddress => match remote_ip_address {
+                Some(ip) => (ip.to_string(), ip.to_string()),
+                None => (String::new(), "none".to_string()),
+            },
+            NetTableGroupBy::RemotePort => match remote_port {
+                Some(port) => (port.to_string(), port.to_string()),
+                None => (String::new(), "none".to_string()),
+            },
+            NetTableGroupBy::State => match state {
+                Some(state) => (format!("{:?}", state), format!("{:?}", state)),
+                None => (String::new(), "stateless".to_string()),
+            },
+            NetTableGroupBy::Country => match remote_geo_of(entry).and_then(|geo| geo.country_code.clone()) {
+                Some(code) => {
+                    let label = remote_geo_of(entry).and_then(|geo| geo.country.clone()).unwrap_or_else(|| code.clone());
+                    (code, label)
+                }
+                None => (String::new(), "unknown".to_string()),
+            },
+            NetTableGroupBy::Asn => match remote_geo_of(entry).and_then(|geo| geo.asn) {
+                Some(asn) => {
+                    let label = match remote_geo_of(entry).and_then(|geo| geo.organization.as_ref()) {
+                        Some(organization) => format!("AS{} {}", asn, organization),
+                        None => format!("AS{}", asn),
+                    };
+                    (asn.to_string(), label)
+                }
+                None => (String::new(), "unknown".to_string()),
+            },
+        };
+
+        let group = groups.entry(key).or_default();
+        group.label = label;
+        group.count += 1;
+        group.process_ids.insert(entry.process_id);
+
+        if let Some(state) = state {
+            *group.states.entry(state).or_default() += 1;
+        }
+
+        add_throughput(&mut group.throughput, entry);
+    }
+
+    let mut groups: Vec<ConnectionGroup> = groups
+        .into_iter()
+        .map(|(key, group)| ConnectionGroup {
+            key,
+            label: group.label,
+            count: group.count,
+            process_count: group.process_ids.len() as u32,
+            states: group.states,
+            throughput: group.throughput,
+        })
+        .collect();
+
+    groups.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.label.cmp(&b.label)));
+
+    groups
+}
+
+/// Process -> remote endpoint graph, edges are weighted by the number of connections
+pub fn build_graph(entries: &[NetTableEntry]) -> ConnectionGraph {
+    let mut nodes: HashMap<String, ConnectionNode> = HashMap::new();
+    let mut edges: HashMap<(String, String), ConnectionEdge> = HashMap::new();
+
+    for entry in entries {
+        let (Some(remote_ip_address), remote_port, state) = remote_of(entry) else {
+            continue;
+        };
+
+        let process_node_id = format!("process:{}", entry.process_id);
+        let endpoint_node_id = match remote_port {
+            Some(port) => format!("endpoint:{}", SocketAddr::new(remote_ip_address, port)),
+            None => format!("endpoint:{}", remote_ip_address),
+        };
+
+        nodes.entry(process_node_id.clone()).or_insert_with(|| ConnectionNode::Process {
+            id: process_node_id.clone(),
+            process_id: entry.process_id,
+            process_name: entry.process_name.clone(),
+        });
+
+        nodes.entry(endpoint_node_id.clone()).or_insert_with(|| ConnectionNode::Endpoint {
+            id: endpoint_node_id.clone(),
+            ip_address: remote_ip_address,
+            port: remote_port,
+            geo: remote_geo_of(entry).cloned(),
+        });
+
+        let edge = edges
+            .entry((process_node_id.clone(), endpoint_node_id.clone()))
+            .or_insert_with(|| ConnectionEdge {
+                source: process_node_id,
+                target: endpoint_node_id,
+                weight: 0,
+                states: HashMap::new(),
+                throughput: None,
+            });
+
+        edge.weight += 1;
+
+        if let Some(state) = state {
+            *edge.states.entry(state).or_default() += 1;
+        }
+
+        add_throughput(&mut edge.throughput, entry);
+    }
+
+    // map iteration order changes between calls, a stable order keeps the graph layout steady
+    let mut nodes: Vec<(String, ConnectionNode)> = nodes.into_iter().collect();
+    nodes.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut edges: Vec<ConnectionEdge> = edges.into_values().collect();
+    edges.sort_by(|a, b| b.weight.cmp(&a.weight).then_with(|| a.source.cmp(&b.source)).then_with(|| a.target.cmp(&b.target)));
+
+    ConnectionGraph {
+        nodes: nodes.into_iter().map(|(_, node)| node).collect(),
+        edges,
+    }
+}
+
+fn add_throughput(total: &mut Option<NetworkThroughput>, entry: &NetTableEntry) {
+    let Some(throughput) = throughput_of(entry) else {
+        return;
+    };
+
+    let (rx, tx) = total.as_ref().map_or((0, 0), |total| (total.rx_bytes_per_sec, total.tx_bytes_per_sec));
+    *total = Some(NetworkThroughput::new(rx + throughput.rx_bytes_per_sec, tx + throughput.tx_bytes_per_sec));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+
+    use crate::models::ProtocolInfo;
+
+    use super::*;
+
+    fn tcp(process_id: u32, remote: [u8; 4], remote_port: u16, throughput: Option<(u64, u64)>) -> NetTableEntry {
+        NetTableEntry {
+            process_id,
+            process_name: format!("p{}", process_id),
+            local_port: 50000,
+            local_ip_address: IpAddr::from([10, 0, 0, 2]),
+            protocol: ProtocolInfo::Tcp {
+                remote_port: Some(remote_port),
+                remote_ip_address: Some(IpAddr::from(remote)),
+                remote_host_name: None,
+                remote_geo: None,
+                state: TcpState::Established,
+                throughput: throughput.map(|(rx, tx)| NetworkThroughput::new(rx, tx)),
+            },
+        }
+    }
+
+    fn node_id(node: &ConnectionNode) -> &str {
+        match node {
+            ConnectionNode::Process { id, .. } | ConnectionNode::Endpoint { id, .. } => id,
+        }
+    }
+
+    #[test]
+    fn groups_sum_the_available_throughput() {
+        let entries = [
+            tcp(1, [1, 1, 1, 1], 443, Some((100, 10))),
+            tcp(1, [8, 8, 8, 8], 443, Some((50, 5))),
+            tcp(1, [9, 9, 9, 9], 443, None),
+            tcp(2, [1, 1, 1, 1], 443, None),
+        ];
+
+        let groups = group_entries(&entries, NetTableGroupBy::Process);
+        let first = groups.iter().find(|group| group.key == "1").unwrap();
+        let second = groups.iter().find(|group| group.key == "2").unwrap();
+
+        let throughput = first.throughput.as_ref().unwrap();
+        assert_eq!((throughput.rx_bytes_per_sec, throughput.tx_bytes_per_sec), (150, 15));
+        assert!(second.throughput.is_none());
+    }
+
+    #[test]
+    fn graph_edges_sum_throughput_and_nodes_keep_their_order() {
+        let entries = [
+            tcp(2, [8, 8, 8, 8], 53, Some((1, 2))),
+            tcp(1, [1, 1, 1, 1], 443, None),
+            tcp(2, [8, 8, 8, 8], 53, Some((3, 4))),
+        ];
+
+        let graph = build_graph(&entries);
+        let ids: Vec<&str> = graph.nodes.iter().map(node_id).collect();
+
+        assert_eq!(ids, vec!["endpoint:1.1.1.1:443", "endpoint:8.8.8.8:53", "process:1", "process:2"]);
+        assert_eq!(graph.edges[0].weight, 2);
+
+        let throughput = graph.edges[0].throughput.as_ref().unwrap();
+        assert_eq!((throughput.rx_bytes_per_sec, throughput.tx_bytes_per_sec), (4, 6));
+        assert!(graph.edges[1].throughput.is_none());
+    }
+}
@@ -0,0 +1,278 @@
+use std::{cmp::Ordering, net::IpAddr};
+
+use hashbrown::HashSet;
+
+use crate::models::{GeoInfo, NetTableColumn, NetTableEntry, NetTableFilter, NetTableSort, NetworkThroughput, PortFilter, ProtocolInfo, TcpState, TransportProtocol};
+
+impl PortFilter {
+    pub fn contains(&self, port: u16) -> bool {
+        match *self {
+            PortFilter::Single(value) => value == port,
+            PortFilter::Range { start, end } => start <= port && port <= end,
+        }
+    }
+}
+
+impl NetTableFilter {
+    pub fn is_empty(&self) -> bool {
+        self.process_name.is_none()
+            && self.process_ids.is_none()
+            && self.local_port.is_none()
+            && self.remote_port.is_none()
+            && self.local_ip_addr.is_none()
+            && self.remote_ip_addr.is_none()
+            && self.states.is_none()
+            && self.loopback.is_none()
+            && self.countries.is_none()
+            && self.asns.is_none()
+    }
+
+    /// True when the entry satisfies every criterion that is set
+    pub fn matches(&self, entry: &NetTableEntry) -> bool {
+        let (remote_ip_address, remote_port, state) = remote_of(entry);
+        let geo = remote_geo_of(entry);
+
+        let is_loopback = entry.local_ip_address.is_loopback()
+            || remote_ip_address.is_some_and(|ip| ip.is_loopback());
+
+        self.process_name.as_ref().is_none_or(|name| entry.process_name.to_lowercase().contains(&name.to_lowercase()))
+            && self.process_ids.as_ref().is_none_or(|ids| ids.contains(&entry.process_id))
+            && self.local_port.is_none_or(|filter| filter.contains(entry.local_port))
+            && self.local_ip_addr.is_none_or(|network| network.contains(entry.local_ip_address))
+            && self.remote_port.is_none_or(|filter| remote_port.is_some_and(|port| filter.contains(port)))
+            && self.remote_ip_addr.is_none_or(|network| remote_ip_address.is_some_and(|ip| network.contains(ip)))
+            && self.states.as_ref().is_none_or(|states| state.is_some_and(|state| states.contains(&state)))
+            && self.loopback.is_none_or(|loopback| loopback == is_loopback)
+            && self.countries.as_ref().is_none_or(|countries| {
+                geo.and_then(|geo| geo.country_code.as_ref()).is_some_and(|code| countries.iter().any(|country| country.eq_ignore_ascii_case(code)))
+            })
+            && self.asns.as_ref().is_none_or(|asns| geo.and_then(|geo| geo.asn).is_some_and(|asn| asns.contains(&asn)))
+    }
+}
+
+pub fn protocol_of(entry: &NetTableEntry) -> TransportProtocol {
+    match &entry.protocol {
+        ProtocolInfo::Tcp { .. } => TransportProtocol::Tcp,
+        ProtocolInfo::Udp => TransportProtocol::Udp,
+    }
+}
+
+pub fn filter_entries(
+    entries: Vec<NetTableEntry>,
+    protocols: &HashSet<TransportProtocol>,
+    filter: &NetTableFilter,
+    exclude: Option<&NetTableFilter>) -> Vec<NetTableEntry> {
+    let exclude = exclude.filter(|exclude| !exclude.is_empty());
+
+    entries
+        .into_iter()
+        .filter(|entry| protocols.contains(&protocol_of(entry)))
+        .filter(|entry| filter.matches(entry))
+        .filter(|entry| exclude.is_none_or(|exclude| !exclude.matches(entry)))
+        .collect()
+}
+
+pub fn sort_entries(entries: &mut [NetTableEntry], sort: &NetTableSort) {
+    entries.sort_by(|a, b| {
+        let ordering = compare_by(a, b, sort.column);
+
+        if sort.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+}
+
+fn compare_by(a: &NetTableEntry, b: &NetTableEntry, column: NetTableColumn) -> Ordering {
+    match column {
+        NetTableColumn::ProcessId => a.process_id.cmp(&b.process_id),
+        NetTableColumn::ProcessName => a.process_name.to_lowercase().cmp(&b.process_name.to_lowercase()),
+        NetTableColumn::Protocol => protocol_rank(a).cmp(&protocol_rank(b)),
+        NetTableColumn::LocalIpAddress => a.local_ip_address.cmp(&b.local_ip_address),
+        NetTableColumn::LocalPort => a.local_port.cmp(&b.local_port),
+        NetTableColumn::RemoteIpAddress => remote_of(a).0.cmp(&remote_of(b).0),
+        NetTableColumn::RemotePort => remote_of(a).1.cmp(&remote_of(b).1),
+        NetTableColumn::State => remote_of(a).2.map(|state| state as u32).cmp(&remote_of(b).2.map(|state| state as u32)),
+    }
+}
+
+fn protocol_rank(entry: &NetTableEntry) -> u8 {
+    match protocol_of(entry) {
+        TransportProtocol::Tcp => 0,
+        TransportProtocol::Udp => 1,
+    }
+}
+
+pub fn remote_of(entry: &NetTableEntry) -> (Option<IpAddr>, Option<u16>, Option<TcpState>) {
+    match &entry.protocol {
+        ProtocolInfo::Tcp { remote_ip_address, remote_port, state, .. } => (*remote_ip_address, *remote_port, Some(*state)),
+        ProtocolInfo::Udp => (None, None, None),
+    }
+}
+
+pub fn remote_geo_of(entry: &NetTableEntry) -> Option<&GeoInfo> {
+    match &entry.protocol {
+        ProtocolInfo::Tcp { remote_geo, .. } => remote_geo.as_ref(),
+        ProtocolInfo::Udp => None,
+    }
+}
+
+pub fn throughput_of(entry: &NetTableEntry) -> Option<&NetworkThroughput> {
+    match &entry.protocol {
+        ProtocolInfo::Tcp { throughput, .. } => throughput.as_ref(),
+        ProtocolInfo::Udp => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv6Addr;
+
+    use super::*;
+
+    fn tcp(process_name: &str, local: impl Into<IpAddr>, local_port: u16, remote: Option<([u8; 4], u16)>, state: TcpState) -> NetTableEntry {
+        NetTableEntry {
+            process_id: 100,
+            process_name: process_name.to_string(),
+            local_port,
+            local_ip_address: local.into(),
+            protocol: ProtocolInfo::Tcp {
+                remote_port: remote.map(|(_, port)| port),
+                remote_ip_address: remote.map(|(ip, _)| IpAddr::from(ip)),
+                remote_host_name: None,
+                remote_geo: None,
+                state,
+                throughput: None,
+            },
+        }
+    }
+
+    fn udp(local: [u8; 4], local_port: u16) -> NetTableEntry {
+        NetTableEntry {
+            process_id: 200,
+            process_name: "dns.exe".to_string(),
+            local_port,
+            local_ip_address: IpAddr::from(local),
+            protocol: ProtocolInfo::Udp,
+        }
+    }
+
+    fn filter(json: &str) -> NetTableFilter {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn empty_filter_matches_everything() {
+        let filter = NetTableFilter::default();
+
+        assert!(filter.is_empty());
+        assert!(filter.matches(&udp([0, 0, 0, 0], 53)));
+        assert!(filter.matches(&tcp("a", [127, 0, 0, 1], 80, None, TcpState::Listening)));
+    }
+
+    #[test]
+    fn matches_process_name_ignoring_case() {
+        let filter = filter(r#"{ "processName": "CHROME" }"#);
+
+        assert!(filter.matches(&tcp("chrome.exe", [10, 0, 0, 2], 50000, None, TcpState::Established)));
+        assert!(!filter.matches(&tcp("firefox.exe", [10, 0, 0, 2], 50000, None, TcpState::Established)));
+    }
+
+    #[test]
+    fn matches_single_port_and_range() {
+        let single = filter(r#"{ "localPort": 443 }"#);
+        let range = filter(r#"{ "remotePort": { "start": 8000, "end": 8999 } }"#);
+
+        assert!(single.matches(&tcp("a", [10, 0, 0, 2], 443, None, TcpState::Listening)));
+        assert!(!single.matches(&tcp("a", [10, 0, 0, 2], 444, None, TcpState::Listening)));
+
+        assert!(range.matches(&tcp("a", [10, 0, 0, 2], 50000, Some(([1, 1, 1, 1], 8080)), TcpState::Established)));
+        assert!(!range.matches(&tcp("a", [10, 0, 0, 2], 50000, Some(([1, 1, 1, 1], 9000)), TcpState::Established)));
+        // udp rows have no remote end
+        assert!(!range.matches(&udp([0, 0, 0, 0], 8080)));
+    }
+
+    #[test]
+    fn matches_cidr() {
+        let filter = filter(r#"{ "localIpAddr": "192.168.0.0/16", "remoteIpAddr": "8.8.8.0/24" }"#);
+
+        assert!(filter.matches(&tcp("a", [192, 168, 1, 20], 50000, Some(([8, 8, 8, 8], 53)), TcpState::Established)));
+        assert!(!filter.matches(&tcp("a", [10, 0, 0, 2], 50000, Some(([8, 8, 8, 8], 53)), TcpState::Established)));
+        assert!(!filter.matches(&tcp("a", [192, 168, 1, 20], 50000, Some(([8, 8, 4, 4], 53)), TcpState::Established)));
+        assert!(!filter.matches(&tcp("a", [192, 168, 1, 20], 50000, None, TcpState::Listening)));
+    }
+
+    #[test]
+    fn matches_ipv6_cidr_and_skips_ipv4_rows() {
+        let filter = filter(r#"{ "localIpAddr": "fe80::/10" }"#);
+        let link_local: Ipv6Addr = "fe80::1c2:3ff:fe04:506".parse().unwrap();
+        let global: Ipv6Addr = "2001:db8::1".parse().unwrap();
+
+        assert!(filter.matches(&tcp("a", link_local, 50000, None, TcpState::Listening)));
+        assert!(!filter.matches(&tcp("a", global, 50000, None, TcpState::Listening)));
+        assert!(!filter.matches(&tcp("a", [10, 0, 0, 2], 50000, None, TcpState::Listening)));
+        assert!(!filter.matches(&udp([0, 0, 0, 0], 53)));
+    }
+
+    #[test]
+    fn matches_states_and_loopback() {
+        let filter = filter(r#"{ "states": ["listening"], "loopback": false }"#);
+
+        assert!(filter.matches(&tcp("a", [0, 0, 0, 0], 80, None, TcpState::Listening)));
+        assert!(!filter.matches(&tcp("a", [127, 0, 0, 1], 80, None, TcpState::Listening)));
+        assert!(!filter.matches(&tcp("a", [0, 0, 0, 0], 80, Some(([1, 1, 1, 1], 443)), TcpState::Established)));
+        assert!(!filter.matches(&udp([0, 0, 0, 0], 53)));
+    }
+
+    #[test]
+    fn matches_countries_and_asns() {
+        let mut entry = tcp("a", [10, 0, 0, 2], 50000, Some(([1, 1, 1, 1], 443)), TcpState::Established);
+
+        if let ProtocolInfo::Tcp { remote_geo, .. } = &mut entry.protocol {
+            *remote_geo = Some(GeoInfo {
+                country_code: Some("AU".to_string()),
+                asn: Some(13335),
+                ..Default::default()
+            });
+        }
+
+        assert!(filter(r#"{ "countries": ["au"] }"#).matches(&entry));
+        assert!(!filter(r#"{ "countries": ["US"] }"#).matches(&entry));
+        assert!(filter(r#"{ "asns": [13335] }"#).matches(&entry));
+        assert!(!filter(r#"{ "asns": [15169] }"#).matches(&entry));
+        // without geo data the criterion cannot hold
+        assert!(!filter(r#"{ "countries": ["AU"] }"#).matches(&udp([0, 0, 0, 0], 53)));
+    }
+
+    #[test]
+    fn filters_by_protocol_and_exclude() {
+        let entries = vec![
+            tcp("a", [127, 0, 0, 1], 80, Some(([127, 0, 0, 1], 50000)), TcpState::Established),
+            tcp("b", [10, 0, 0, 2], 50001, Some(([1, 1, 1, 1], 443)), TcpState::Established),
+            udp([0, 0, 0, 0], 53),
+        ];
+        let protocols = HashSet::from([TransportProtocol::Tcp]);
+        let exclude = filter(r#"{ "loopback": true }"#);
+
+        let filtered = filter_entries(entries, &protocols, &NetTableFilter::default(), Some(&exclude));
+
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].process_name, "b");
+    }
+
+    #[test]
+    fn sorts_descending() {
+        let mut entries = vec![
+            tcp("a", [10, 0, 0, 1], 80, None, TcpState::Listening),
+            tcp("b", [10, 0, 0, 1], 443, None, TcpState::Listening),
+            tcp("c", [10, 0, 0, 1], 22, None, TcpState::Listening),
+        ];
+        let sort: NetTableSort = serde_json::from_str(r#"{ "column": "localPort", "descending": true }"#).unwrap();
+
+        sort_entries(&mut entries, &sort);
+
+        let ports: Vec<u16> = entries.iter().map(|entry| entry.local_port).collect();
+        assert_eq!(ports, vec![443, 80, 22]);
+    }
+}
@@ -0,0 +1,230 @@
+use std::{
+    collections::HashMap,
+    ffi::CStr,
+    fs,
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    path::Path,
+    ptr,
+};
+
+use anyhow::Result;
+use ipnetwork::IpNetwork;
+
+use crate::{models::{InterfaceCounters, InterfaceTraffic, LinkState, NetworkInterface}, services::{parse_ipv4_routes, parse_ipv6_routes}};
+
+const SYS_CLASS_NET: &str = "/sys/class/net";
+const ARPHRD_LOOPBACK: u32 = 772;
+
+pub fn enumerate_interfaces() -> Result<Vec<NetworkInterface>> {
+    let counters = parse_proc_net_dev(&fs::read_to_string("/proc/net/dev")?);
+    let mut addresses = unsafe { interface_addresses()? };
+    let mut gateways = parse_gateways(
+        &fs::read_to_string("/proc/net/route").unwrap_or_default(),
+        &fs::read_to_string("/proc/net/ipv6_route").unwrap_or_default(),
+    );
+    let dns_servers = parse_resolv_conf(&fs::read_to_string("/etc/resolv.conf").unwrap_or_default());
+
+    let mut interfaces = Vec::new();
+
+    for entry in fs::read_dir(SYS_CLASS_NET)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let path = entry.path();
+
+        let mac_address = read_attribute(&path, "address")
+            .filter(|mac| !mac.is_empty() && mac != "00:00:00:00:00:00");
+        let mtu = read_attribute(&path, "mtu").and_then(|value| value.parse().ok());
+        let link_state = read_attribute(&path, "operstate")
+            .map(|value| parse_operstate(&value))
+            .unwrap_or(LinkState::Unknown);
+        // reading speed fails with EINVAL while the link is down
+        let speed_mbps = read_attribute(&path, "speed")
+            .and_then(|value| value.parse::<i64>().ok())
+            .filter(|speed| *speed > 0)
+            .map(|speed| speed as u64);
+        let is_loopback = read_attribute(&path, "type")
+            .and_then(|value| value.parse::<u32>().ok()) == Some(ARPHRD_LOOPBACK);
+
+        interfaces.push(NetworkInterface {
+            description: None,
+            mac_address,
+            mtu,
+            link_state,
+            speed_mbps,
+            is_loopback,
+            addresses: addresses.remove(&name).unwrap_or_default(),
+            gateways: gateways.remove(&name).unwrap_or_default(),
+            dns_servers: if is_loopback { vec![] } else { dns_servers.clone() },
+            traffic: InterfaceTraffic {
+                total: counters.get(&name).copied().unwrap_or_default(),
+                per_second: InterfaceCounters::default(),
+            },
+            name,
+        });
+    }
+
+    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(interfaces)
+}
+
+fn read_attribute(path: &Path, attribute: &str) -> Option<String> {
+    fs::read_to_string(path.join(attribute))
+        .ok()
+        .map(|value| value.trim().to_string())
+}
+
+fn parse_operstate(value: &str) -> LinkState {
+    match value {
+        "up" => LinkState::Up,
+        "down" => LinkState::Down,
+        "testing" => LinkState::Testing,
+        "dormant" => LinkState::Dormant,
+        "notpresent" => LinkState::NotPresent,
+        "lowerlayerdown" => LinkState::LowerLayerDown,
+        _ => LinkState::Unknown,
+    }
+}
+
+/// Parses `/proc/net/dev`
+///
+/// ```text
+/// Inter-|   Receive                                                |  Transmit
+///  face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+///     lo: 1234       10    0    0    0     0          0         0     1234      10    0    0    0     0       0          0
+/// ```
+pub fn parse_proc_net_dev(content: &str) -> HashMap<String, InterfaceCounters> {
+    content
+        .lines()
+        .skip(2)
+        .filter_map(|line| {
+            let (name, values) = line.split_once(':')?;
+            let values: Vec<u64> = values
+                .split_whitespace()
+                .map(|value| value.parse().unwrap_or_default())
+                .collect();
+
+            if values.len() < 12 {
+                return None;
+            }
+
+            let counters = InterfaceCounters {
+                rx_bytes: values[0],
+                rx_errors: values[2],
+                rx_dropped: values[3],
+                tx_bytes: values[8],
+                tx_errors: values[10],
+                tx_dropped: values[11],
+            };
+
+            Some((name.trim().to_string(), counters))
+        })
+        .collect()
+}
+
+/// Default gateways per interface from `/proc/net/route` and `/proc/net/ipv6_route`
+pub fn parse_gateways(route_v4: &str, route_v6: &str) -> HashMap<String, Vec<IpAddr>> {
+    let mut gateways: HashMap<String, Vec<IpAddr>> = HashMap::new();
+
+    let routes = parse_ipv4_routes(route_v4).into_iter().chain(parse_ipv6_routes(route_v6));
+
+    for route in routes {
+        if let (0, Some(gateway)) = (route.destination.prefix(), route.gateway) {
+            gateways.entry(route.interface).or_default().push(gateway);
+        }
+    }
+
+    gateways
+}
+
+pub fn parse_resolv_conf(content: &str) -> Vec<IpAddr> {
+    content
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("nameserver"))
+        .filter_map(|value| value.trim().parse().ok())
+        .collect()
+}
+
+unsafe fn interface_addresses() -> Result<HashMap<String, Vec<IpNetwork>>> {
+    let mut ifap: *mut libc::ifaddrs = ptr::null_mut();
+
+    if libc::getifaddrs(&mut ifap) != 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+
+    let mut addresses: HashMap<String, Vec<IpNetwork>> = HashMap::new();
+    let mut cursor = ifap;
+
+    while !cursor.is_null() {
+        let entry = &*cursor;
+        cursor = entry.ifa_next;
+
+        let (Some(ip), Some(mask)) = (sockaddr_to_ip(entry.ifa_addr), sockaddr_to_ip(entry.ifa_netmask)) else {
+            continue;
+        };
+
+        let name = CStr::from_ptr(entry.ifa_name).to_string_lossy().into_owned();
+
+        if let Ok(network) = IpNetwork::with_netmask(ip, mask) {
+            addresses.entry(name).or_default().push(network);
+        }
+    }
+
+    libc::freeifaddrs(ifap);
+
+    Ok(addresses)
+}
+
+unsafe fn sockaddr_to_ip(addr: *const libc::sockaddr) -> Option<IpAddr> {
+    if addr.is_null() {
+        return None;
+    }
+
+    match (*addr).sa_family as i32 {
+        libc::AF_INET => {
+            let addr = &*(addr as *const libc::sockaddr_in);
+            Some(IpAddr::V4(Ipv4Addr::from(u32::from_be(addr.sin_addr.s_addr))))
+        }
+        libc::AF_INET6 => {
+            let addr = &*(addr as *const libc::sockaddr_in6);
+            Some(IpAddr::V6(Ipv6Addr::from(addr.sin6_addr.s6_addr)))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// the IPv4 fixture was captured on a little-endian machine
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn gateways_come_from_default_routes_only() {
+        let route_v4 = "Iface\tDestination\tGateway\tFlags\tRefCnt\tUse\tMetric\tMask\tMTU\tWindow\tIRTT\n\
+            eth0\t00000000\t0101A8C0\t0003\t0\t0\t100\t00000000\t0\t0\t0\n\
+            eth0\t0001A8C0\t00000000\t0001\t0\t0\t100\t00FFFFFF\t0\t0\t0\n\
+            wlan0\t0000000A\tFE00000A\t0003\t0\t0\t600\t000000FF\t0\t0\t0\n";
+        let route_v6 = "00000000000000000000000000000000 00 00000000000000000000000000000000 00 fe800000000000000000000000000001 00000400 00000001 00000000 00000003 eth0\n\
+            fe800000000000000000000000000000 40 00000000000000000000000000000000 00 00000000000000000000000000000000 00000100 00000001 00000000 00000001 eth0\n";
+
+        let gateways = parse_gateways(route_v4, route_v6);
+
+        assert_eq!(gateways.len(), 1);
+        assert_eq!(gateways["eth0"], vec![
+            IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)),
+            IpAddr::V6("fe80::1".parse::<Ipv6Addr>().unwrap()),
+        ]);
+    }
+
+    #[test]
+    fn resolv_conf_nameservers_are_parsed() {
+        let content = "# generated\nsearch lan\nnameserver 1.1.1.1\n  nameserver ::1\nnameserver bogus\n";
+
+        assert_eq!(parse_resolv_conf(content), vec![
+            IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)),
+            IpAddr::V6(Ipv6Addr::LOCALHOST),
+        ]);
+    }
+}
@@ -0,0 +1,120 @@
+use std::{
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+
+use crate::{models::{InterfaceCounters, NetworkInterface, NetworkThroughput}, utils::format_bytes};
+
+#[cfg(windows)]
+mod windows;
+#[cfg(windows)]
+use self::windows as platform;
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "linux")]
+use self::linux as platform;
+
+impl NetworkThroughput {
+    pub fn new(rx_bytes_per_sec: u64, tx_bytes_per_sec: u64) -> Self {
+        Self {
+            rx_bytes_per_sec,
+            rx_formatted: format!("{}/s", format_bytes(rx_bytes_per_sec)),
+            tx_bytes_per_sec,
+            tx_formatted: format!("{}/s", format_bytes(tx_bytes_per_sec)),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    refreshed_on: Instant,
+    interfaces: Vec<NetworkInterface>,
+}
+
+pub struct NetInterfaceService {
+    query_interval: Duration,
+    cache: Arc<RwLock<CacheEntry>>,
+}
+
+impl NetInterfaceService {
+    pub fn new() -> Self {
+        let query_interval = Duration::from_secs(1);
+
+        Self {
+            query_interval,
+            cache: Arc::new(RwLock::new(CacheEntry {
+                refreshed_on: Instant::now() - query_interval,
+                interfaces: vec![],
+            })),
+        }
+    }
+
+    pub fn get_interfaces(&self) -> Result<Vec<NetworkInterface>> {
+        {
+            let guard = self.cache.read().unwrap();
+            if guard.refreshed_on.elapsed() > self.query_interval {
+                drop(guard);
+                self.refresh_cache()?;
+            }
+        }
+
+        let guard = self.cache.read().unwrap();
+        Ok(guard.interfaces.clone())
+    }
+
+    /// Sum of per-second traffic over all non-loopback interfaces
+    pub fn get_throughput(&self) -> Result<NetworkThroughput> {
+        let interfaces = self.get_interfaces()?;
+
+        let (rx_bytes_per_sec, tx_bytes_per_sec) = interfaces
+            .iter()
+            .filter(|interface| !interface.is_loopback)
+            .fold((0, 0), |(rx, tx), interface| {
+                let per_second = &interface.traffic.per_second;
+                (rx + per_second.rx_bytes, tx + per_second.tx_bytes)
+            });
+
+        Ok(NetworkThroughput::new(rx_bytes_per_sec, tx_bytes_per_sec))
+    }
+
+    fn refresh_cache(&self) -> Result<()> {
+        let mut interfaces = platform::enumerate_interfaces()?;
+
+        let mut cache = self.cache.write().unwrap();
+        let elapsed = cache.refreshed_on.elapsed().as_secs_f64();
+
+        for interface in &mut interfaces {
+            let previous = cache.interfaces.iter().find(|pr| pr.name == interface.name);
+
+            if let Some(previous) = previous {
+                interface.traffic.per_second = rate(&previous.traffic.total, &interface.traffic.total, elapsed);
+            }
+        }
+
+        cache.interfaces = interfaces;
+        cache.refreshed_on = Instant::now();
+
+        Ok(())
+    }
+}
+
+fn rate(previous: &InterfaceCounters, current: &InterfaceCounters, elapsed_secs: f64) -> InterfaceCounters {
+    if elapsed_secs <= 0.0 {
+        return InterfaceCounters::default();
+    }
+
+    // counters can reset when an adapter is re-enabled, treat that as no traffic
+    let per_second = |before: u64, after: u64| (after.saturating_sub(before) as f64 / elapsed_secs) as u64;
+
+    InterfaceCounters {
+        rx_bytes: per_second(previous.rx_bytes, current.rx_bytes),
+        tx_bytes: per_second(previous.tx_bytes, current.tx_bytes),
+        rx_errors: per_second(previous.rx_errors, current.rx_errors),
+        tx_errors: per_second(previous.tx_errors, current.tx_errors),
+        rx_dropped: per_second(previous.rx_dropped, current.rx_dropped),
+        tx_dropped: per_second(previous.tx_dropped, current.tx_dropped),
+    }
+}
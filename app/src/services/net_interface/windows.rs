@@ -0,0 +1,167 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    ptr,
+};
+
+use anyhow::{bail, Result};
+use ipnetwork::IpNetwork;
+use widestring::U16CStr;
+use winapi::{
+    shared::{
+        ifdef::{IfOperStatusDormant, IfOperStatusDown, IfOperStatusLowerLayerDown, IfOperStatusNotPresent, IfOperStatusTesting, IfOperStatusUp, IF_OPER_STATUS},
+        ipifcons::IF_TYPE_SOFTWARE_LOOPBACK,
+        netioapi::{GetIfEntry2, MIB_IF_ROW2},
+        winerror::{ERROR_BUFFER_OVERFLOW, NO_ERROR},
+        ws2def::{AF_INET, AF_INET6, AF_UNSPEC, SOCKADDR_IN, SOCKET_ADDRESS},
+        ws2ipdef::SOCKADDR_IN6,
+    },
+    um::{
+        iphlpapi::GetAdaptersAddresses,
+        iptypes::{GAA_FLAG_INCLUDE_GATEWAYS, GAA_FLAG_SKIP_ANYCAST, GAA_FLAG_SKIP_MULTICAST, IP_ADAPTER_ADDRESSES},
+    },
+};
+
+use crate::{models::{InterfaceCounters, InterfaceTraffic, LinkState, NetworkInterface}, utils::format_mac};
+
+pub fn enumerate_interfaces() -> Result<Vec<NetworkInterface>> {
+    unsafe {
+        let flags = GAA_FLAG_INCLUDE_GATEWAYS | GAA_FLAG_SKIP_ANYCAST | GAA_FLAG_SKIP_MULTICAST;
+        let mut size = 16 * 1024u32;
+        let mut buffer = vec![0u8; size as usize];
+
+        let mut result = GetAdaptersAddresses(
+            AF_UNSPEC as u32,
+            flags,
+            ptr::null_mut(),
+            buffer.as_mut_ptr() as *mut IP_ADAPTER_ADDRESSES,
+            &mut size,
+        );
+
+        if result == ERROR_BUFFER_OVERFLOW {
+            buffer.resize(size as usize, 0);
+            result = GetAdaptersAddresses(
+                AF_UNSPEC as u32,
+                flags,
+                ptr::null_mut(),
+                buffer.as_mut_ptr() as *mut IP_ADAPTER_ADDRESSES,
+                &mut size,
+            );
+        }
+
+        if result != NO_ERROR {
+            bail!("GetAdaptersAddresses failed: {}", result);
+        }
+
+        let mut interfaces = Vec::new();
+        let mut adapter_ptr = buffer.as_ptr() as *const IP_ADAPTER_ADDRESSES;
+
+        while !adapter_ptr.is_null() {
+            let adapter = &*adapter_ptr;
+            adapter_ptr = adapter.Next;
+
+            let name = U16CStr::from_ptr_str(adapter.FriendlyName).to_string_lossy();
+            let description = Some(U16CStr::from_ptr_str(adapter.Description).to_string_lossy())
+                .filter(|value| !value.is_empty());
+            let mac_length = (adapter.PhysicalAddressLength as usize).min(adapter.PhysicalAddress.len());
+            let mac_address = Some(format_mac(&adapter.PhysicalAddress[..mac_length]))
+                .filter(|value| !value.is_empty());
+            let speed_mbps = Some(adapter.TransmitLinkSpeed)
+                .filter(|speed| *speed != 0 && *speed != u64::MAX)
+                .map(|speed| speed / 1_000_000);
+
+            let mut addresses = Vec::new();
+            let mut unicast_ptr = adapter.FirstUnicastAddress;
+            while !unicast_ptr.is_null() {
+                let unicast = &*unicast_ptr;
+                unicast_ptr = unicast.Next;
+
+                if let Some(network) = socket_address_to_ip(&unicast.Address)
+                    .and_then(|ip| IpNetwork::new(ip, unicast.OnLinkPrefixLength).ok()) {
+                    addresses.push(network);
+                }
+            }
+
+            let mut gateways = Vec::new();
+            let mut gateway_ptr = adapter.FirstGatewayAddress;
+            while !gateway_ptr.is_null() {
+                let gateway = &*gateway_ptr;
+                gateway_ptr = gateway.Next;
+                gateways.extend(socket_address_to_ip(&gateway.Address));
+            }
+
+            let mut dns_servers = Vec::new();
+            let mut dns_ptr = adapter.FirstDnsServerAddress;
+            while !dns_ptr.is_null() {
+                let dns = &*dns_ptr;
+                dns_ptr = dns.Next;
+                dns_servers.extend(socket_address_to_ip(&dns.Address));
+            }
+
+            let mut row: MIB_IF_ROW2 = std::mem::zeroed();
+            row.InterfaceLuid = adapter.Luid;
+            let total = if GetIfEntry2(&mut row) == NO_ERROR as i32 {
+                InterfaceCounters {
+                    rx_bytes: row.InOctets,
+                    tx_bytes: row.OutOctets,
+                    rx_errors: row.InErrors,
+                    tx_errors: row.OutErrors,
+                    rx_dropped: row.InDiscards,
+                    tx_dropped: row.OutDiscards,
+                }
+            } else {
+                InterfaceCounters::default()
+            };
+
+            interfaces.push(NetworkInterface {
+                name,
+                description,
+                mac_address,
+                mtu: Some(adapter.Mtu).filter(|mtu| *mtu != u32::MAX),
+                link_state: to_link_state(adapter.OperStatus),
+                speed_mbps,
+                is_loopback: adapter.IfType == IF_TYPE_SOFTWARE_LOOPBACK,
+                addresses,
+                gateways,
+                dns_servers,
+                traffic: InterfaceTraffic {
+                    total,
+                    per_second: InterfaceCounters::default(),
+                },
+            });
+        }
+
+        Ok(interfaces)
+    }
+}
+
+fn to_link_state(status: IF_OPER_STATUS) -> LinkState {
+    match status {
+        IfOperStatusUp => LinkState::Up,
+        IfOperStatusDown => LinkState::Down,
+        IfOperStatusTesting => LinkState::Testing,
+        IfOperStatusDormant => LinkState::Dormant,
+        IfOperStatusNotPresent => LinkState::NotPresent,
+        IfOperStatusLowerLayerDown => LinkState::LowerLayerDown,
+        _ => LinkState::Unknown,
+    }
+}
+
+pub unsafe fn socket_address_to_ip(address: &SOCKET_ADDRESS) -> Option<IpAddr> {
+    let sockaddr = address.lpSockaddr;
+
+    if sockaddr.is_null() {
+        return None;
+    }
+
+    match (*sockaddr).sa_family as i32 {
+        AF_INET => {
+            let sockaddr = &*(sockaddr as *const SOCKADDR_IN);
+            Some(IpAddr::V4(Ipv4Addr::from(u32::from_be(*sockaddr.sin_addr.S_un.S_addr()))))
+        }
+        AF_INET6 => {
+            let sockaddr = &*(sockaddr as *const SOCKADDR_IN6);
+            Some(IpAddr::V6(Ipv6Addr::from(*sockaddr.sin6_addr.u.Byte())))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,313 @@
+use std::{collections::HashMap, fs, net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr}};
+
+use anyhow::Result;
+
+use crate::{models::{NetTableEntry, ProtocolInfo, TcpState}, services::{netlink, procfs}};
+
+use super::{ConnectionKey, TcpCounters};
+
+const SOCK_DIAG_BY_FAMILY: u16 = 20;
+const INET_DIAG_MSG_LEN: usize = 72;
+const INET_DIAG_INFO: u16 = 2;
+/// `tcpi_bytes_acked` and `tcpi_bytes_received` of `struct tcp_info`, present since Linux 4.1
+const TCP_INFO_BYTES_ACKED: usize = 120;
+const TCP_INFO_BYTES_RECEIVED: usize = 128;
+
+/// A row of `/proc/net/tcp`, `/proc/net/udp` or their IPv6 counterparts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SocketRow {
+    pub local_ip_address: IpAddr,
+    pub local_port: u16,
+    pub remote_ip_address: IpAddr,
+    pub remote_port: u16,
+    /// the kernel's `TCP_*` state number
+    pub state: u8,
+    pub inode: u64,
+}
+
+pub fn enumerate_udp_table(map: &HashMap<u32, String>) -> Result<Vec<NetTableEntry>> {
+    let rows = read_socket_tables("/proc/net/udp")?;
+    let owners = procfs::socket_owners();
+
+    let items = rows.into_iter().map(|row| {
+        let process_id = owners.get(&row.inode).copied().unwrap_or_default();
+
+        NetTableEntry {
+            process_id,
+            process_name: map.get(&process_id).cloned().unwrap_or_default(),
+            local_port: row.local_port,
+            local_ip_address: row.local_ip_address,
+            protocol: ProtocolInfo::Udp
+        }
+    }).collect();
+
+    Ok(items)
+}
+
+pub fn enumerate_tcp_table(process_name_map: &HashMap<u32, String>) -> Result<Vec<NetTableEntry>> {
+    let rows = read_socket_tables("/proc/net/tcp")?;
+    let owners = procfs::socket_owners();
+
+    let items = rows.into_iter().map(|row| {
+        let process_id = owners.get(&row.inode).copied().unwrap_or_default();
+        let remote_port = if row.remote_port != 0 { Some(row.remote_port) } else { None };
+        let remote_ip_address = if !row.remote_ip_address.is_unspecified() { Some(row.remote_ip_address) } else { None };
+
+        NetTableEntry {
+            process_id,
+            process_name: process_name_map.get(&process_id).cloned().unwrap_or_default(),
+            local_port: row.local_port,
+            local_ip_address: row.local_ip_address,
+            protocol: ProtocolInfo::Tcp {
+                remote_port,
+                remote_ip_address,
+                remote_host_name: None,
+                remote_geo: None,
+                state: tcp_state(row.state),
+                throughput: None,
+            }
+        }
+    }).collect();
+
+    Ok(items)
+}
+
+/// Rows of the IPv4 table followed by the `6` suffixed IPv6 one,
+/// which is missing when IPv6 is disabled
+fn read_socket_tables(path: &str) -> Result<Vec<SocketRow>> {
+    let mut rows = parse_socket_table(&fs::read_to_string(path)?);
+
+    if let Ok(content) = fs::read_to_string(format!("{}6", path)) {
+        rows.extend(parse_socket_table(&content));
+    }
+
+    Ok(rows)
+}
+
+/// Byte counters of every TCP socket from the `tcp_info` sock_diag reports,
+/// the dump is cheaper than looking up the requested connections one by one
+pub fn query_tcp_counters(_connections: &[ConnectionKey]) -> Result<HashMap<ConnectionKey, TcpCounters>> {
+    let mut counters = HashMap::new();
+
+    for family in [libc::AF_INET, libc::AF_INET6] {
+        let result = netlink::dump(libc::NETLINK_SOCK_DIAG, SOCK_DIAG_BY_FAMILY, &build_inet_diag_request(family as u8), |datagram| {
+            parse_inet_diag_counters(datagram, &mut counters)
+        });
+
+        // without IPv6 support the second dump fails, the IPv4 counters still stand
+        if family == libc::AF_INET {
+            result?;
+        }
+    }
+
+    Ok(counters)
+}
+
+/// `inet_diag_req_v2` for every state and an empty socket id
+fn build_inet_diag_request(family: u8) -> Vec<u8> {
+    let mut request = vec![family, libc::IPPROTO_TCP as u8, 1 << (INET_DIAG_INFO - 1), 0];
+    request.extend_from_slice(&u32::MAX.to_ne_bytes());
+    request.extend_from_slice(&[0u8; 48]);
+
+    request
+}
+
+/// Parses one datagram of `inet_diag_msg` replies into the counters of each connection.
+/// Returns true once the dump is complete.
+pub fn parse_inet_diag_counters(buffer: &[u8], counters: &mut HashMap<ConnectionKey, TcpCounters>) -> Result<bool> {
+    netlink::parse_messages(buffer, |_, message| {
+        if message.len() < INET_DIAG_MSG_LEN {
+            return Ok(());
+        }
+
+        // addresses are 16 bytes wide, an IPv4 one fills the first four
+        let read_endpoint = |family: u8, port: usize, address: usize| {
+            let ip = if family == libc::AF_INET6 as u8 {
+                IpAddr::from(<[u8; 16]>::try_from(&message[address..address + 16]).unwrap()).to_canonical()
+            } else {
+                IpAddr::from(<[u8; 4]>::try_from(&message[address..address + 4]).unwrap())
+            };
+
+            SocketAddr::new(ip, u16::from_be_bytes([message[port], message[port + 1]]))
+        };
+        let read_u64 = |info: &[u8], offset: usize| u64::from_ne_bytes(info[offset..offset + 8].try_into().unwrap());
+
+        // idiag_family first, inet_diag_sockid starts at 4: sport, dport, src, dst
+        let family = message[0];
+        let key = (read_endpoint(family, 4, 8), read_endpoint(family, 6, 24));
+
+        for (attribute_type, info) in netlink::attributes(&message[INET_DIAG_MSG_LEN..]) {
+            if attribute_type == INET_DIAG_INFO && info.len() >= TCP_INFO_BYTES_RECEIVED + 8 {
+                counters.insert(key, TcpCounters {
+                    rx_bytes: read_u64(info, TCP_INFO_BYTES_RECEIVED),
+                    tx_bytes: read_u64(info, TCP_INFO_BYTES_ACKED),
+                });
+            }
+        }
+
+        Ok(())
+    })
+}
+
+/// Parses `/proc/net/tcp`, `/proc/net/udp` and the `tcp6`/`udp6` tables
+///
+/// ```text
+///   sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+///    0: 3500007F:0035 00000000:0000 0A 00000000:00000000 00:00000000 00000000   101        0 20936
+/// ```
+pub fn parse_socket_table(content: &str) -> Vec<SocketRow> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (local_ip_address, local_port) = parse_endpoint(fields.get(1)?)?;
+            let (remote_ip_address, remote_port) = parse_endpoint(fields.get(2)?)?;
+
+            Some(SocketRow {
+                local_ip_address,
+                local_port,
+                remote_ip_address,
+                remote_port,
+                state: u8::from_str_radix(fields.get(3)?, 16).ok()?,
+                inode: fields.get(9)?.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// The address is the in-memory `__be32` printed as a native integer, an IPv6 one is
+/// four such words, the port is in host order. IPv4-mapped addresses of dual stack
+/// sockets come back as IPv4.
+fn parse_endpoint(field: &str) -> Option<(IpAddr, u16)> {
+    let (address, port) = field.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let word = |index: usize| address.get(index * 8..index * 8 + 8).and_then(|word| u32::from_str_radix(word, 16).ok());
+
+    let address = match address.len() {
+        8 => IpAddr::V4(Ipv4Addr::from(word(0)?.to_ne_bytes())),
+        32 => {
+            let mut octets = [0u8; 16];
+
+            for index in 0..4 {
+                octets[index * 4..index * 4 + 4].copy_from_slice(&word(index)?.to_ne_bytes());
+            }
+
+            IpAddr::V6(Ipv6Addr::from(octets)).to_canonical()
+        }
+        _ => return None,
+    };
+
+    Some((address, port))
+}
+
+/// The kernel numbers states from `include/net/tcp_states.h`
+fn tcp_state(state: u8) -> TcpState {
+    match state {
+        1 => TcpState::Established,
+        2 => TcpState::SynSent,
+        3 => TcpState::SynReceived,
+        4 => TcpState::FinWait1,
+        5 => TcpState::FinWait2,
+        6 => TcpState::TimeWait,
+        8 => TcpState::CloseWait,
+        9 => TcpState::LastAck,
+        10 => TcpState::Listening,
+        11 => TcpState::Closing,
+        _ => TcpState::Closed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::services::netlink::{NLMSG_DONE, NLMSG_HEADER_LEN};
+
+    use super::*;
+
+    fn octets(address: &SocketAddr) -> Vec<u8> {
+        match address.ip() {
+            IpAddr::V4(ip) => [ip.octets().as_slice(), &[0u8; 12]].concat(),
+            IpAddr::V6(ip) => ip.octets().to_vec(),
+        }
+    }
+
+    fn inet_diag_message(local: SocketAddr, remote: SocketAddr, bytes_acked: u64, bytes_received: u64) -> Vec<u8> {
+        let family = if local.is_ipv4() { libc::AF_INET } else { libc::AF_INET6 };
+        let mut payload = vec![family as u8, 1, 0, 0];
+        payload.extend_from_slice(&local.port().to_be_bytes());
+        payload.extend_from_slice(&remote.port().to_be_bytes());
+        payload.extend(octets(&local));
+        payload.extend(octets(&remote));
+        payload.resize(INET_DIAG_MSG_LEN, 0);
+
+        let mut info = vec![0u8; TCP_INFO_BYTES_RECEIVED + 8];
+        info[TCP_INFO_BYTES_ACKED..TCP_INFO_BYTES_ACKED + 8].copy_from_slice(&bytes_acked.to_ne_bytes());
+        info[TCP_INFO_BYTES_RECEIVED..].copy_from_slice(&bytes_received.to_ne_bytes());
+
+        payload.extend_from_slice(&((4 + info.len()) as u16).to_ne_bytes());
+        payload.extend_from_slice(&INET_DIAG_INFO.to_ne_bytes());
+        payload.extend(info);
+
+        let mut message = ((NLMSG_HEADER_LEN + payload.len()) as u32).to_ne_bytes().to_vec();
+        message.extend_from_slice(&SOCK_DIAG_BY_FAMILY.to_ne_bytes());
+        message.extend_from_slice(&[0u8; 10]);
+        message.extend(payload);
+        message
+    }
+
+    #[test]
+    fn inet_diag_replies_give_counters_per_connection() {
+        let local = SocketAddr::from(([10, 0, 0, 2], 50000));
+        let remote = SocketAddr::from(([1, 1, 1, 1], 443));
+        let local6: SocketAddr = "[2001:db8::2]:50001".parse().unwrap();
+        let remote6: SocketAddr = "[2001:db8::1]:443".parse().unwrap();
+        let mut buffer = inet_diag_message(local, remote, 1000, 5000);
+        buffer.extend(inet_diag_message(local6, remote6, 2000, 7000));
+
+        let mut done = (NLMSG_HEADER_LEN as u32).to_ne_bytes().to_vec();
+        done.extend_from_slice(&NLMSG_DONE.to_ne_bytes());
+        done.extend_from_slice(&[0u8; 10]);
+        buffer.extend(done);
+
+        let mut counters = HashMap::new();
+
+        assert!(parse_inet_diag_counters(&buffer, &mut counters).unwrap());
+        assert_eq!(counters[&(local, remote)], TcpCounters { rx_bytes: 5000, tx_bytes: 1000 });
+        assert_eq!(counters[&(local6, remote6)], TcpCounters { rx_bytes: 7000, tx_bytes: 2000 });
+    }
+
+    #[test]
+    fn socket_table_addresses_are_native_order() {
+        let content = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n\
+            0: 0100007F:0035 00000000:0000 0A 00000000:00000000 00:00000000 00000000   101        0 20936\n";
+
+        let rows = parse_socket_table(content);
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].local_port, 53);
+        assert_eq!(rows[0].state, 10);
+        assert_eq!(rows[0].inode, 20936);
+        if cfg!(target_endian = "little") {
+            assert_eq!(rows[0].local_ip_address, Ipv4Addr::LOCALHOST);
+        }
+    }
+
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn socket_table_reads_ipv6_words() {
+        let content = "  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n\
+            0: 000080FE00000000FF030201060504FE:01BB 00000000000000000000000001000000:C350 01 00000000:00000000 00:00000000 00000000  1000        0 31337\n\
+            1: 0000000000000000FFFF00000200000A:0016 0000000000000000FFFF00000101A8C0:D431 01 00000000:00000000 00:00000000 00000000     0        0 31338\n";
+
+        let rows = parse_socket_table(content);
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].local_ip_address, "fe80::102:3ff:fe04:506".parse::<IpAddr>().unwrap());
+        assert_eq!(rows[0].local_port, 443);
+        assert_eq!(rows[0].remote_ip_address, Ipv6Addr::LOCALHOST);
+        assert_eq!(rows[0].remote_port, 50000);
+        // dual stack sockets carry IPv4-mapped addresses
+        assert_eq!(rows[1].local_ip_address, Ipv4Addr::new(10, 0, 0, 2));
+        assert_eq!(rows[1].remote_ip_address, Ipv4Addr::new(192, 168, 1, 1));
+    }
+}
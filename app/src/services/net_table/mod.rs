@@ -0,0 +1,216 @@
+use anyhow::Result;
+use std::{sync::{Arc, RwLock}, time::{Duration, Instant}};
+use std::{collections::HashMap, net::SocketAddr};
+
+use hashbrown::HashSet;
+use log::*;
+
+use crate::{models::{GetNetTableAggregateArgs, GetNetTableArgs, NetTableAggregate, NetTableEntry, NetworkThroughput, PageArgs, ProtocolInfo, TcpState, TransportProtocol}, services::{aggregate_entries, filter_entries, sort_entries, GeoIpService, ProcessManager, ReverseDnsResolver}};
+
+#[cfg(windows)]
+mod windows;
+#[cfg(windows)]
+use self::windows as platform;
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "linux")]
+use self::linux as platform;
+
+pub struct NetTableService {
+    query_interval: Duration,
+    cache: Arc<RwLock<CacheEntry>>,
+    process_manager: ProcessManager,
+    resolver: ReverseDnsResolver,
+    geoip: GeoIpService
+}
+
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    refreshed_on: Instant,
+    tcp_entries: Vec<NetTableEntry>,
+    udp_entries: Vec<NetTableEntry>,
+    /// the UDP refresh moves `refreshed_on` as well, rates need the time of the last counters
+    counters_taken_on: Instant,
+    counters: HashMap<ConnectionKey, TcpCounters>,
+}
+
+/// Bytes moved over one connection since it was opened, on Windows since collection was enabled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TcpCounters {
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+}
+
+/// Local and remote endpoint of a TCP connection
+pub type ConnectionKey = (SocketAddr, SocketAddr);
+
+impl NetTableService {
+    pub fn new(process_manager: ProcessManager, resolver: ReverseDnsResolver, geoip: GeoIpService) -> Self {
+        Self {
+            query_interval: Duration::from_secs(5),
+            cache: Arc::new(RwLock::new(CacheEntry {
+                refreshed_on: Instant::now(),
+                tcp_entries: vec![],
+                udp_entries: vec![],
+                counters_taken_on: Instant::now(),
+                counters: HashMap::new(),
+            })),
+            process_manager,
+            resolver,
+            geoip
+        }
+    }
+
+    pub fn query(&self, args: &GetNetTableArgs) -> Result<Vec<NetTableEntry>> {
+        let entries = self.collect(&args.protocols)?;
+        let mut entries = filter_entries(entries, &args.protocols, &args.filter, args.exclude.as_ref());
+
+        if let Some(sort) = &args.sort {
+            sort_entries(&mut entries, sort);
+        }
+
+        if args.resolve_host_names {
+            // only the requested page is shown, resolving every row would queue a query per connection
+            let PageArgs { page, page_size } = args.page;
+            let start = ((page * page_size) as usize).min(entries.len());
+            let end = (start + page_size as usize).min(entries.len());
+
+            self.fill_host_names(&mut entries[start..end]);
+        }
+
+        Ok(entries)
+    }
+
+    pub fn aggregate(&self, args: &GetNetTableAggregateArgs) -> Result<NetTableAggregate> {
+        let entries = self.collect(&args.protocols)?;
+        let entries = filter_entries(entries, &args.protocols, &args.filter, args.exclude.as_ref());
+
+        Ok(aggregate_entries(&entries, args.group_by))
+    }
+
+    /// Names come from the resolver cache only, misses are resolved in the background
+    /// and show up on a later call
+    fn fill_host_names(&self, entries: &mut [NetTableEntry]) {
+        for entry in entries {
+            if let ProtocolInfo::Tcp { remote_ip_address: Some(ip), remote_host_name, .. } = &mut entry.protocol {
+                *remote_host_name = self.resolver.lookup(*ip);
+            }
+        }
+    }
+
+    fn fill_geo(&self, entries: &mut [NetTableEntry]) {
+        // a database added to an empty list only loads on a reload check
+        self.geoip.reload_if_changed();
+
+        if !self.geoip.is_loaded() {
+            return;
+        }
+
+        for entry in entries {
+            if let ProtocolInfo::Tcp { remote_ip_address: Some(ip), remote_geo, .. } = &mut entry.protocol {
+                *remote_geo = self.geoip.lookup(*ip);
+            }
+        }
+    }
+
+    fn collect(&self, protocols: &HashSet<TransportProtocol>) -> Result<Vec<NetTableEntry>> {
+        let mut entries = Vec::new();
+
+        if protocols.contains(&TransportProtocol::Tcp) {
+            entries.extend(self.get_tcp_table()?);
+        }
+
+        if protocols.contains(&TransportProtocol::Udp) {
+            entries.extend(self.get_udp_table()?);
+        }
+
+        // geo data has to be present before filtering by country or ASN
+        self.fill_geo(&mut entries);
+
+        Ok(entries)
+    }
+
+    pub fn get_udp_table(&self) -> Result<Vec<NetTableEntry>> {
+        let map = self.process_manager.get_id_name_map()?;
+        
+        let mut cache = self.cache.write().unwrap();
+        if cache.refreshed_on.elapsed() >= self.query_interval || cache.udp_entries.is_empty() {
+            cache.udp_entries = platform::enumerate_udp_table(&map)?;
+            cache.refreshed_on = Instant::now();
+        }
+
+        Ok(cache.udp_entries.clone())
+    }
+
+    pub fn get_tcp_table(&self) -> Result<Vec<NetTableEntry>> {
+        {
+            let cache = self.cache.read().unwrap();
+            if cache.refreshed_on.elapsed() < self.query_interval {
+                return Ok(cache.tcp_entries.clone());
+            }
+        }
+
+        let map = self.process_manager.get_id_name_map()?;
+        let mut refreshed = platform::enumerate_tcp_table(&map)?;
+        let connections: Vec<ConnectionKey> = refreshed.iter().filter_map(connection_of).collect();
+        let counters = platform::query_tcp_counters(&connections).unwrap_or_else(|err| {
+            debug!("per connection counters are unavailable: {}", err);
+            HashMap::new()
+        });
+
+        {
+            let mut cache = self.cache.write().unwrap();
+            let elapsed = cache.counters_taken_on.elapsed().as_secs_f64();
+
+            fill_throughput(&mut refreshed, &cache.counters, &counters, elapsed);
+
+            cache.counters = counters;
+            cache.counters_taken_on = Instant::now();
+            cache.tcp_entries = refreshed.clone();
+            cache.refreshed_on = Instant::now();
+        }
+
+        Ok(refreshed)
+    }
+}
+
+/// Only established connections move data
+fn connection_of(entry: &NetTableEntry) -> Option<ConnectionKey> {
+    match entry.protocol {
+        ProtocolInfo::Tcp { remote_ip_address: Some(remote_ip_address), remote_port: Some(remote_port), state: TcpState::Established, .. } => Some((
+            SocketAddr::new(entry.local_ip_address, entry.local_port),
+            SocketAddr::new(remote_ip_address, remote_port),
+        )),
+        _ => None,
+    }
+}
+
+/// Rates of the connections present in both samples, new ones get theirs on the next refresh
+fn fill_throughput(
+    entries: &mut [NetTableEntry],
+    previous: &HashMap<ConnectionKey, TcpCounters>,
+    current: &HashMap<ConnectionKey, TcpCounters>,
+    elapsed: f64,
+) {
+    if elapsed <= 0.0 {
+        return;
+    }
+
+    for entry in entries {
+        let Some(key) = connection_of(entry) else {
+            continue;
+        };
+
+        let (Some(before), Some(after)) = (previous.get(&key), current.get(&key)) else {
+            continue;
+        };
+
+        if let ProtocolInfo::Tcp { throughput, .. } = &mut entry.protocol {
+            *throughput = Some(NetworkThroughput::new(
+                (after.rx_bytes.saturating_sub(before.rx_bytes) as f64 / elapsed) as u64,
+                (after.tx_bytes.saturating_sub(before.tx_bytes) as f64 / elapsed) as u64,
+            ));
+        }
+    }
+}
@@ -0,0 +1,347 @@
+use std::{collections::HashMap, mem, net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr}, ptr, slice};
+
+use anyhow::Result;
+use winapi::{shared::{in6addr::IN6_ADDR, iprtrmib::{TCP_TABLE_OWNER_PID_ALL, UDP_TABLE_OWNER_PID}, minwindef::{FALSE, PUCHAR}, tcpestats::{TcpConnectionEstatsData, TCP_ESTATS_DATA_ROD_v0, TCP_ESTATS_DATA_RW_v0}, tcpmib::{MIB_TCP6ROW, MIB_TCP6ROW_OWNER_PID, MIB_TCP6TABLE_OWNER_PID, MIB_TCPROW, MIB_TCPROW_OWNER_PID, MIB_TCPTABLE_OWNER_PID, MIB_TCP_STATE_ESTAB}, udpmib::{MIB_UDP6TABLE_OWNER_PID, MIB_UDPTABLE_OWNER_PID}, winerror::NO_ERROR, ws2def::{AF_INET, AF_INET6}}, um::iphlpapi::{GetExtendedTcpTable, GetExtendedUdpTable, GetPerTcp6ConnectionEStats, GetPerTcpConnectionEStats, SetPerTcp6ConnectionEStats, SetPerTcpConnectionEStats}};
+
+use crate::models::{NetTableEntry, ProtocolInfo};
+
+use super::{ConnectionKey, TcpCounters};
+
+pub fn enumerate_udp_table(map: &HashMap<u32, String>) -> Result<Vec<NetTableEntry>> {
+    let mut items = enumerate_udp4_table(map)?;
+    items.extend(enumerate_udp6_table(map)?);
+
+    Ok(items)
+}
+
+pub fn enumerate_tcp_table(process_name_map: &HashMap<u32, String>) -> Result<Vec<NetTableEntry>> {
+    let mut items = enumerate_tcp4_table(process_name_map)?;
+    items.extend(enumerate_tcp6_table(process_name_map)?);
+
+    Ok(items)
+}
+
+fn enumerate_udp4_table(map: &HashMap<u32, String>) -> Result<Vec<NetTableEntry>> {
+    unsafe {
+        let mut size = 0u32;
+
+        GetExtendedUdpTable(ptr::null_mut(), &mut size, FALSE, AF_INET as u32, UDP_TABLE_OWNER_PID, 0);
+
+        let mut buffer = vec![0u8; size as usize];
+        let table_ptr = buffer.as_mut_ptr() as *mut MIB_UDPTABLE_OWNER_PID;
+
+        if GetExtendedUdpTable(
+            table_ptr as *mut _,
+            &mut size,
+            FALSE,
+            AF_INET as u32,
+            UDP_TABLE_OWNER_PID, 0) != 0 {
+            return Ok(vec![]);
+        }
+
+        let table = &*table_ptr;
+        let rows_ptr = table.table.as_ptr();
+        let items = slice::from_raw_parts(rows_ptr, table.dwNumEntries as usize);
+
+        let items = items.into_iter().map(|item| {
+            NetTableEntry {
+                process_id: item.dwOwningPid,
+                process_name: map.get(&item.dwOwningPid).cloned().unwrap_or_default(),
+                local_port: u16::from_be(item.dwLocalPort as u16),
+                local_ip_address: IpAddr::V4(Ipv4Addr::from(item.dwLocalAddr)),
+                protocol: ProtocolInfo::Udp
+            }
+        }).collect();
+
+        Ok(items)
+    }
+}
+
+fn enumerate_udp6_table(map: &HashMap<u32, String>) -> Result<Vec<NetTableEntry>> {
+    unsafe {
+        let mut size = 0u32;
+
+        GetExtendedUdpTable(ptr::null_mut(), &mut size, FALSE, AF_INET6 as u32, UDP_TABLE_OWNER_PID, 0);
+
+        let mut buffer = vec![0u8; size as usize];
+        let table_ptr = buffer.as_mut_ptr() as *mut MIB_UDP6TABLE_OWNER_PID;
+
+        if GetExtendedUdpTable(
+            table_ptr as *mut _,
+            &mut size,
+            FALSE,
+            AF_INET6 as u32,
+            UDP_TABLE_OWNER_PID, 0) != 0 {
+            return Ok(vec![]);
+        }
+
+        let table = &*table_ptr;
+        let rows_ptr = table.table.as_ptr();
+        let items = slice::from_raw_parts(rows_ptr, table.dwNumEntries as usize);
+
+        let items = items.iter().map(|item| {
+            NetTableEntry {
+                process_id: item.dwOwningPid,
+                process_name: map.get(&item.dwOwningPid).cloned().unwrap_or_default(),
+                local_port: u16::from_be(item.dwLocalPort as u16),
+                local_ip_address: IpAddr::V6(Ipv6Addr::from(item.ucLocalAddr)),
+                protocol: ProtocolInfo::Udp
+            }
+        }).collect();
+
+        Ok(items)
+    }
+}
+
+fn enumerate_tcp4_table(process_name_map: &HashMap<u32, String>) -> Result<Vec<NetTableEntry>> {
+    unsafe {
+        let mut size = 0u32;
+
+        GetExtendedTcpTable(
+            std::ptr::null_mut(),
+            &mut size,
+            false.into(),
+            AF_INET as u32,
+            TCP_TABLE_OWNER_PID_ALL,
+            0,
+        );
+
+        let mut buffer = vec![0u8; size as usize];
+        let table_ptr = buffer.as_mut_ptr() as *mut MIB_TCPTABLE_OWNER_PID;
+
+        let result = GetExtendedTcpTable(
+            table_ptr as *mut _,
+            &mut size,
+            false.into(),
+            AF_INET as u32,
+            TCP_TABLE_OWNER_PID_ALL,
+            0,
+        );
+
+        if result != 0 {
+            return Ok(vec![]);
+        }
+
+        let table = &*table_ptr;
+        let rows_ptr = table.table.as_ptr();
+        let items = slice::from_raw_parts(rows_ptr, table.dwNumEntries as usize);
+
+        let items: Vec<_> = items.into_iter().map(|item: &MIB_TCPROW_OWNER_PID| {
+
+            let &MIB_TCPROW_OWNER_PID {
+                dwState,
+                dwLocalAddr,
+                dwLocalPort,
+                dwRemoteAddr,
+                dwRemotePort,
+                dwOwningPid: process_id,
+            } = item;
+
+            let local_port = u16::from_be(dwLocalPort as u16);
+            let remote_port = if dwRemotePort != 0 { Some(u16::from_be(dwRemotePort as u16)) } else { None };
+            let local_ip_address = IpAddr::V4(Ipv4Addr::from(dwLocalAddr));
+            let remote_ip_address = if dwRemoteAddr != 0 { Some(IpAddr::V4(Ipv4Addr::from(dwRemoteAddr))) } else { None };
+            let state = dwState.into();
+            let process_name = process_name_map.get(&process_id).cloned().unwrap_or_default();
+
+            NetTableEntry {
+                process_id,
+                process_name,
+                local_port,
+                local_ip_address,
+                protocol: ProtocolInfo::Tcp {
+                    remote_port,
+                    remote_ip_address,
+                    remote_host_name: None,
+                    remote_geo: None,
+                    state,
+                    throughput: None,
+                }
+            }
+        }).collect();
+
+        Ok(items)
+    }
+}
+
+fn enumerate_tcp6_table(process_name_map: &HashMap<u32, String>) -> Result<Vec<NetTableEntry>> {
+    unsafe {
+        let mut size = 0u32;
+
+        GetExtendedTcpTable(
+            std::ptr::null_mut(),
+            &mut size,
+            false.into(),
+            AF_INET6 as u32,
+            TCP_TABLE_OWNER_PID_ALL,
+            0,
+        );
+
+        let mut buffer = vec![0u8; size as usize];
+        let table_ptr = buffer.as_mut_ptr() as *mut MIB_TCP6TABLE_OWNER_PID;
+
+        let result = GetExtendedTcpTable(
+            table_ptr as *mut _,
+            &mut size,
+            false.into(),
+            AF_INET6 as u32,
+            TCP_TABLE_OWNER_PID_ALL,
+            0,
+        );
+
+        if result != 0 {
+            return Ok(vec![]);
+        }
+
+        let table = &*table_ptr;
+        let rows_ptr = table.table.as_ptr();
+        let items = slice::from_raw_parts(rows_ptr, table.dwNumEntries as usize);
+
+        let items: Vec<_> = items.iter().map(|item: &MIB_TCP6ROW_OWNER_PID| {
+            let remote_ip_address = Ipv6Addr::from(item.ucRemoteAddr);
+            let process_id = item.dwOwningPid;
+
+            NetTableEntry {
+                process_id,
+                process_name: process_name_map.get(&process_id).cloned().unwrap_or_default(),
+                local_port: u16::from_be(item.dwLocalPort as u16),
+                local_ip_address: IpAddr::V6(Ipv6Addr::from(item.ucLocalAddr)),
+                protocol: ProtocolInfo::Tcp {
+                    remote_port: if item.dwRemotePort != 0 { Some(u16::from_be(item.dwRemotePort as u16)) } else { None },
+                    remote_ip_address: if !remote_ip_address.is_unspecified() { Some(IpAddr::V6(remote_ip_address)) } else { None },
+                    remote_host_name: None,
+                    remote_geo: None,
+                    state: item.dwState.into(),
+                    throughput: None,
+                }
+            }
+        }).collect();
+
+        Ok(items)
+    }
+}
+
+/// Extended statistics are collected only once enabled per connection, which needs elevation.
+/// Connections without them are left out.
+pub fn query_tcp_counters(connections: &[ConnectionKey]) -> Result<HashMap<ConnectionKey, TcpCounters>> {
+    let mut counters = HashMap::new();
+
+    for &(local, remote) in connections {
+        let result = match (local, remote) {
+            (SocketAddr::V4(local), SocketAddr::V4(remote)) => {
+                // the table addresses were built with `Ipv4Addr::from(u32)`, converting back restores the raw values
+                let row = MIB_TCPROW {
+                    State: MIB_TCP_STATE_ESTAB,
+                    dwLocalAddr: u32::from(*local.ip()),
+                    dwLocalPort: local.port().to_be() as u32,
+                    dwRemoteAddr: u32::from(*remote.ip()),
+                    dwRemotePort: remote.port().to_be() as u32,
+                };
+
+                query_tcp4_counters(row)
+            }
+            (SocketAddr::V6(local), SocketAddr::V6(remote)) => {
+                // the table rows' scope ids are not kept, link-local connections are not found
+                let mut row: MIB_TCP6ROW = unsafe { mem::zeroed() };
+                row.State = MIB_TCP_STATE_ESTAB;
+                row.LocalAddr = in6_addr(local.ip());
+                row.dwLocalPort = local.port().to_be() as u32;
+                row.RemoteAddr = in6_addr(remote.ip());
+                row.dwRemotePort = remote.port().to_be() as u32;
+
+                query_tcp6_counters(row)
+            }
+            _ => None,
+        };
+
+        if let Some(counter) = result {
+            counters.insert((local, remote), counter);
+        }
+    }
+
+    Ok(counters)
+}
+
+fn in6_addr(ip: &Ipv6Addr) -> IN6_ADDR {
+    let mut address: IN6_ADDR = unsafe { mem::zeroed() };
+
+    unsafe {
+        *address.u.Byte_mut() = ip.octets();
+    }
+
+    address
+}
+
+fn query_tcp4_counters(mut row: MIB_TCPROW) -> Option<TcpCounters> {
+    unsafe {
+        let mut settings = TCP_ESTATS_DATA_RW_v0 { EnableCollection: 1 };
+
+        SetPerTcpConnectionEStats(
+            &mut row,
+            TcpConnectionEstatsData,
+            &mut settings as *mut _ as PUCHAR,
+            0,
+            mem::size_of::<TCP_ESTATS_DATA_RW_v0>() as u32,
+            0,
+        );
+
+        let mut data: TCP_ESTATS_DATA_ROD_v0 = mem::zeroed();
+
+        let result = GetPerTcpConnectionEStats(
+            &mut row,
+            TcpConnectionEstatsData,
+            ptr::null_mut(),
+            0,
+            0,
+            ptr::null_mut(),
+            0,
+            0,
+            &mut data as *mut _ as PUCHAR,
+            0,
+            mem::size_of::<TCP_ESTATS_DATA_ROD_v0>() as u32,
+        );
+
+        if result == NO_ERROR {
+            Some(TcpCounters { rx_bytes: data.DataBytesIn, tx_bytes: data.DataBytesOut })
+        } else {
+            None
+        }
+    }
+}
+
+fn query_tcp6_counters(mut row: MIB_TCP6ROW) -> Option<TcpCounters> {
+    unsafe {
+        let mut settings = TCP_ESTATS_DATA_RW_v0 { EnableCollection: 1 };
+
+        SetPerTcp6ConnectionEStats(
+            &mut row,
+            TcpConnectionEstatsData,
+            &mut settings as *mut _ as PUCHAR,
+            0,
+            mem::size_of::<TCP_ESTATS_DATA_RW_v0>() as u32,
+            0,
+        );
+
+        let mut data: TCP_ESTATS_DATA_ROD_v0 = mem::zeroed();
+
+        let result = GetPerTcp6ConnectionEStats(
+            &mut row,
+            TcpConnectionEstatsData,
+            ptr::null_mut(),
+            0,
+            0,
+            ptr::null_mut(),
+            0,
+            0,
+            &mut data as *mut _ as PUCHAR,
+            0,
+            mem::size_of::<TCP_ESTATS_DATA_ROD_v0>() as u32,
+        );
+
+        if result == NO_ERROR {
+            Some(TcpCounters { rx_bytes: data.DataBytesIn, tx_bytes: data.DataBytesOut })
+        } else {
+            None
+        }
+    }
+}
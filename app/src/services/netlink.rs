@@ -0,0 +1,203 @@
+use std::{io, mem};
+
+use anyhow::{bail, Result};
+
+pub const NLMSG_ERROR: u16 = 2;
+pub const NLMSG_DONE: u16 = 3;
+pub const NLM_F_REQUEST: u16 = 0x01;
+pub const NLM_F_DUMP: u16 = 0x300;
+pub const NLMSG_HEADER_LEN: usize = 16;
+
+/// Sends a dump request of `message_type` carrying `payload` over a new `protocol` socket
+/// and passes each datagram of the reply to `parse` until it reports the dump complete
+pub fn dump(protocol: libc::c_int, message_type: u16, payload: &[u8], mut parse: impl FnMut(&[u8]) -> Result<bool>) -> Result<()> {
+    unsafe {
+        let fd = libc::socket(libc::AF_NETLINK, libc::SOCK_RAW | libc::SOCK_CLOEXEC, protocol);
+
+        if fd < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        let result = send_and_receive(fd, &build_request(message_type, payload), &mut parse);
+
+        libc::close(fd);
+
+        result
+    }
+}
+
+unsafe fn send_and_receive(fd: libc::c_int, request: &[u8], parse: &mut impl FnMut(&[u8]) -> Result<bool>) -> Result<()> {
+    let mut address: libc::sockaddr_nl = mem::zeroed();
+    address.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+
+    let sent = libc::sendto(
+        fd,
+        request.as_ptr() as *const libc::c_void,
+        request.len(),
+        0,
+        &address as *const _ as *const libc::sockaddr,
+        mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+    );
+
+    if sent < 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+
+    let mut buffer = vec![0u8; 32 * 1024];
+
+    loop {
+        let received = libc::recv(fd, buffer.as_mut_ptr() as *mut libc::c_void, buffer.len(), 0);
+
+        if received < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        let done = parse(&buffer[..received as usize])?;
+
+        if done || received == 0 {
+            return Ok(());
+        }
+    }
+}
+
+pub fn build_request(message_type: u16, payload: &[u8]) -> Vec<u8> {
+    let length = (NLMSG_HEADER_LEN + payload.len()) as u32;
+    let mut request = Vec::with_capacity(length as usize);
+
+    // nlmsghdr
+    request.extend_from_slice(&length.to_ne_bytes());
+    request.extend_from_slice(&message_type.to_ne_bytes());
+    request.extend_from_slice(&(NLM_F_REQUEST | NLM_F_DUMP).to_ne_bytes());
+    request.extend_from_slice(&1u32.to_ne_bytes());
+    request.extend_from_slice(&0u32.to_ne_bytes());
+    request.extend_from_slice(payload);
+
+    request
+}
+
+/// Splits one datagram into messages and passes the type and payload of each to `visit`.
+/// Returns true once the dump is complete.
+pub fn parse_messages(buffer: &[u8], mut visit: impl FnMut(u16, &[u8]) -> Result<()>) -> Result<bool> {
+    let mut offset = 0;
+
+    while offset + NLMSG_HEADER_LEN <= buffer.len() {
+        let length = read_u32(buffer, offset) as usize;
+        let message_type = read_u16(buffer, offset + 4);
+
+        if length < NLMSG_HEADER_LEN || offset + length > buffer.len() {
+            bail!("malformed netlink message");
+        }
+
+        let payload = &buffer[offset + NLMSG_HEADER_LEN..offset + length];
+
+        match message_type {
+            NLMSG_DONE => return Ok(true),
+            // nlmsgerr starts with the negated errno
+            NLMSG_ERROR if payload.len() >= 4 => {
+                bail!("netlink request failed: {}", io::Error::from_raw_os_error(-(read_u32(payload, 0) as i32)))
+            }
+            NLMSG_ERROR => bail!("netlink request failed"),
+            _ => visit(message_type, payload)?,
+        }
+
+        offset += align(length);
+    }
+
+    Ok(false)
+}
+
+/// Type and value of each `rtattr`/`nlattr` packed in `buffer`, stopping at a malformed one
+pub fn attributes(buffer: &[u8]) -> impl Iterator<Item = (u16, &[u8])> {
+    let mut offset = 0;
+
+    std::iter::from_fn(move || {
+        if offset + 4 > buffer.len() {
+            return None;
+        }
+
+        let length = read_u16(buffer, offset) as usize;
+        let attribute_type = read_u16(buffer, offset + 2);
+
+        if length < 4 || offset + length > buffer.len() {
+            return None;
+        }
+
+        let value = &buffer[offset + 4..offset + length];
+        offset += align(length);
+
+        Some((attribute_type, value))
+    })
+}
+
+pub fn read_u16(buffer: &[u8], offset: usize) -> u16 {
+    u16::from_ne_bytes([buffer[offset], buffer[offset + 1]])
+}
+
+pub fn read_u32(buffer: &[u8], offset: usize) -> u32 {
+    u32::from_ne_bytes([buffer[offset], buffer[offset + 1], buffer[offset + 2], buffer[offset + 3]])
+}
+
+fn align(length: usize) -> usize {
+    (length + 3) & !3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(message_type: u16, payload: &[u8]) -> Vec<u8> {
+        let mut message = build_request(message_type, payload);
+        message.resize(align(message.len()), 0);
+        message
+    }
+
+    #[test]
+    fn messages_are_visited_until_done() {
+        let mut buffer = message(20, &[1, 2, 3]);
+        buffer.extend(message(20, &[4; 8]));
+        buffer.extend(message(NLMSG_DONE, &[0; 4]));
+        buffer.extend(message(20, &[5]));
+
+        let mut payloads = Vec::new();
+        let done = parse_messages(&buffer, |message_type, payload| {
+            payloads.push((message_type, payload.to_vec()));
+            Ok(())
+        });
+
+        assert!(done.unwrap());
+        assert_eq!(payloads, vec![(20, vec![1, 2, 3]), (20, vec![4; 8])]);
+    }
+
+    #[test]
+    fn errors_carry_the_errno() {
+        let buffer = message(NLMSG_ERROR, &(-libc::EPERM).to_ne_bytes());
+
+        let err = parse_messages(&buffer, |_, _| Ok(())).unwrap_err();
+
+        assert!(err.to_string().contains(&io::Error::from_raw_os_error(libc::EPERM).to_string()));
+    }
+
+    #[test]
+    fn truncated_messages_are_rejected() {
+        let mut buffer = message(20, &[0; 8]);
+        buffer.truncate(20);
+
+        assert!(parse_messages(&buffer, |_, _| Ok(())).is_err());
+    }
+
+    #[test]
+    fn attributes_stop_at_a_malformed_one() {
+        let mut buffer = Vec::new();
+        buffer.extend_from_slice(&5u16.to_ne_bytes());
+        buffer.extend_from_slice(&1u16.to_ne_bytes());
+        buffer.extend_from_slice(&[9, 0, 0, 0]);
+        buffer.extend_from_slice(&4u16.to_ne_bytes());
+        buffer.extend_from_slice(&2u16.to_ne_bytes());
+        buffer.extend_from_slice(&64u16.to_ne_bytes());
+        buffer.extend_from_slice(&3u16.to_ne_bytes());
+
+        let found: Vec<(u16, Vec<u8>)> = attributes(&buffer).map(|(kind, value)| (kind, value.to_vec())).collect();
+
+        assert_eq!(found, vec![(1, vec![9]), (2, vec![])]);
+    }
+}
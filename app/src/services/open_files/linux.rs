@@ -0,0 +1,58 @@
+use std::fs;
+
+use crate::models::{FileAccess, HandleInfo};
+
+use super::OpenFile;
+
+const O_ACCMODE: u32 = 0o3;
+const O_WRONLY: u32 = 0o1;
+const O_RDWR: u32 = 0o2;
+const O_APPEND: u32 = 0o2000;
+
+/// Descriptors pointing at files or directories
+pub fn enumerate_open_files(handles: &[HandleInfo]) -> Vec<OpenFile> {
+    handles
+        .iter()
+        .filter_map(|handle| {
+            // socket:[..], pipe:[..] and anon_inode: targets are not paths
+            let path = handle.object_name.as_ref().filter(|path| path.starts_with('/'))?;
+
+            Some(OpenFile {
+                process_id: handle.process_id,
+                process_name: handle.process_name.clone(),
+                handle: Some(handle.handle as u64),
+                path: path.clone(),
+                access: to_file_access(handle.granted_access),
+            })
+        })
+        .collect()
+}
+
+/// The working directory of every process whose descriptors we could read
+pub fn working_directories(processes: Vec<(u32, String)>) -> Vec<OpenFile> {
+    processes
+        .into_iter()
+        .filter_map(|(process_id, process_name)| {
+            let cwd = fs::read_link(format!("/proc/{}/cwd", process_id)).ok()?;
+
+            Some(OpenFile {
+                process_id,
+                process_name,
+                handle: None,
+                path: cwd.to_string_lossy().into_owned(),
+                access: FileAccess { read: true, ..Default::default() },
+            })
+        })
+        .collect()
+}
+
+fn to_file_access(flags: u32) -> FileAccess {
+    let mode = flags & O_ACCMODE;
+
+    FileAccess {
+        read: mode != O_WRONLY,
+        write: mode == O_WRONLY || mode == O_RDWR,
+        append: flags & O_APPEND != 0,
+        delete: false,
+    }
+}
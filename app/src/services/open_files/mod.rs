@@ -0,0 +1,177 @@
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use hashbrown::{HashMap, HashSet};
+
+use crate::{models::{FileAccess, HandleInfo, OpenFileHandle, OpenFileProcess}, services::HandleManager};
+
+#[cfg(windows)]
+mod windows;
+#[cfg(windows)]
+use self::windows as platform;
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "linux")]
+use self::linux as platform;
+
+/// A file or directory held open by some process
+#[derive(Debug, Clone)]
+pub struct OpenFile {
+    pub process_id: u32,
+    pub process_name: String,
+    pub handle: Option<u64>,
+    pub path: String,
+    pub access: FileAccess,
+}
+
+impl HandleManager {
+    /// Processes holding a file or directory whose path contains `query`,
+    /// a directory query also matches everything opened beneath it
+    pub fn find_open_handles(&self, query: &str) -> Result<Vec<OpenFileProcess>> {
+        let query = query.trim();
+
+        if query.is_empty() {
+            bail!("Search path must not be empty");
+        }
+
+        let files = self.open_files()?;
+
+        Ok(group_by_process(files.into_iter().filter(|file| path_matches(&file.path, query))))
+    }
+
+    /// Paths of every file some process holds open, compared with `normalize_path`
+    pub fn get_open_file_paths(&self) -> Result<HashSet<String>> {
+        let files = self.open_files()?;
+
+        Ok(files.into_iter().map(|file| normalize_path(&file.path)).collect())
+    }
+
+    /// Handles are read under the cache lock, working directories only once it is released
+    fn open_files(&self) -> Result<Vec<OpenFile>> {
+        let (mut files, processes) = self.with_handles(|handles| (platform::enumerate_open_files(handles), processes_of(handles)))?;
+
+        files.extend(platform::working_directories(processes));
+
+        Ok(files)
+    }
+}
+
+/// Every process holding a handle, once
+fn processes_of(handles: &[HandleInfo]) -> Vec<(u32, String)> {
+    let mut processes: Vec<(u32, &String)> = handles.iter().map(|handle| (handle.process_id, &handle.process_name)).collect();
+
+    processes.sort_by_key(|(process_id, _)| *process_id);
+    processes.dedup_by_key(|(process_id, _)| *process_id);
+
+    processes.into_iter().map(|(process_id, process_name)| (process_id, process_name.clone())).collect()
+}
+
+#[cfg(windows)]
+pub fn normalize_path(path: &str) -> String {
+    path.replace('/', "\\").to_lowercase()
+}
+
+#[cfg(not(windows))]
+pub fn normalize_path(path: &str) -> String {
+    path.to_string()
+}
+
+#[cfg(windows)]
+fn path_matches(path: &str, query: &str) -> bool {
+    // NTFS lookups are case insensitive and both separators are accepted
+    path.to_lowercase().contains(&query.replace('/', "\\").to_lowercase())
+}
+
+#[cfg(not(windows))]
+fn path_matches(path: &str, query: &str) -> bool {
+    path.contains(query)
+}
+
+pub fn group_by_process(files: impl Iterator<Item = OpenFile>) -> Vec<OpenFileProcess> {
+    let mut processes: HashMap<u32, OpenFileProcess> = HashMap::new();
+
+    for file in files {
+        let is_directory = Path::new(&file.path).is_dir();
+
+        processes
+            .entry(file.process_id)
+            .or_insert_with(|| OpenFileProcess {
+                process_id: file.process_id,
+                process_name: file.process_name,
+                handles: vec![],
+            })
+            .handles
+            .push(OpenFileHandle {
+                handle: file.handle,
+                path: file.path,
+                access: file.access,
+                is_directory,
+            });
+    }
+
+    let mut processes: Vec<OpenFileProcess> = processes.into_values().collect();
+
+    for process in &mut processes {
+        process.handles.sort_by(|a, b| a.path.cmp(&b.path).then_with(|| a.handle.cmp(&b.handle)));
+    }
+
+    processes.sort_by(|a, b| a.process_name.to_lowercase().cmp(&b.process_name.to_lowercase()).then_with(|| a.process_id.cmp(&b.process_id)));
+
+    processes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handle(process_id: u32, process_name: &str) -> HandleInfo {
+        HandleInfo {
+            process_id,
+            process_name: process_name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn processes_are_listed_once_whatever_the_handle_order() {
+        let handles = [handle(20, "b"), handle(10, "a"), handle(20, "b"), handle(10, "a")];
+
+        assert_eq!(processes_of(&handles), vec![(10, "a".to_string()), (20, "b".to_string())]);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn finds_a_child_holding_a_temp_file() {
+        use std::{fs::{self, File}, process::{Command, Stdio}};
+
+        use uuid::Uuid;
+
+        use crate::services::ProcessManager;
+
+        let path = std::env::temp_dir().join(format!("open-files-{}", Uuid::now_v7()));
+        fs::write(&path, b"held").unwrap();
+
+        let mut child = Command::new("sleep")
+            .arg("30")
+            .stdin(File::open(&path).unwrap())
+            .stdout(Stdio::null())
+            .spawn()
+            .unwrap();
+
+        let manager = HandleManager::new(ProcessManager::new());
+        let processes = manager.find_open_handles(&path.to_string_lossy());
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let process = processes.unwrap().into_iter().find(|process| process.process_id == child.id()).unwrap();
+
+        assert_eq!(process.handles.len(), 1);
+        assert_eq!(process.handles[0].path, path.to_string_lossy());
+        assert_eq!(process.handles[0].handle, Some(0));
+        assert!(process.handles[0].access.read);
+        assert!(!process.handles[0].access.write);
+    }
+}
@@ -0,0 +1,46 @@
+use crate::models::{FileAccess, HandleInfo};
+
+use super::OpenFile;
+
+const FILE_READ_DATA: u32 = 0x0001;
+const FILE_WRITE_DATA: u32 = 0x0002;
+const FILE_APPEND_DATA: u32 = 0x0004;
+const DELETE: u32 = 0x0001_0000;
+const GENERIC_WRITE: u32 = 0x4000_0000;
+const GENERIC_READ: u32 = 0x8000_0000;
+const GENERIC_ALL: u32 = 0x1000_0000;
+
+/// File handles with a resolved DOS path, devices and named pipes are left out
+pub fn enumerate_open_files(handles: &[HandleInfo]) -> Vec<OpenFile> {
+    handles
+        .iter()
+        .filter(|handle| handle.type_name.as_deref() == Some("File"))
+        .filter_map(|handle| {
+            let path = handle.object_name.as_ref().filter(|path| !path.starts_with('\\'))?;
+
+            Some(OpenFile {
+                process_id: handle.process_id,
+                process_name: handle.process_name.clone(),
+                handle: Some(handle.handle as u64),
+                path: path.clone(),
+                access: to_file_access(handle.granted_access),
+            })
+        })
+        .collect()
+}
+
+/// A working directory is a handle of its own on Windows and already listed
+pub fn working_directories(_processes: Vec<(u32, String)>) -> Vec<OpenFile> {
+    vec![]
+}
+
+fn to_file_access(mask: u32) -> FileAccess {
+    let all = mask & GENERIC_ALL != 0;
+
+    FileAccess {
+        read: all || mask & (FILE_READ_DATA | GENERIC_READ) != 0,
+        write: all || mask & (FILE_WRITE_DATA | GENERIC_WRITE) != 0,
+        append: all || mask & FILE_APPEND_DATA != 0,
+        delete: all || mask & DELETE != 0,
+    }
+}
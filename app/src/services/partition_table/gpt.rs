@@ -0,0 +1,174 @@
+use anyhow::{bail, Result};
+use flate2::Crc;
+use uuid::Uuid;
+
+pub const GPT_SIGNATURE: &[u8; 8] = b"EFI PART";
+
+/// Size of the header fields defined by the UEFI spec, `header_size` may be larger
+const HEADER_SIZE: usize = 92;
+const MIN_ENTRY_SIZE: u32 = 128;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GptHeader {
+    pub revision: u32,
+    pub header_size: u32,
+    pub header_crc: u32,
+    /// `header_crc` matches the header content
+    pub crc_valid: bool,
+    /// LBA of this header
+    pub my_lba: u64,
+    /// LBA of the other copy, the backup for the primary and the other way round
+    pub alternate_lba: u64,
+    pub first_usable_lba: u64,
+    pub last_usable_lba: u64,
+    pub disk_guid: Uuid,
+    pub entries_lba: u64,
+    pub entry_count: u32,
+    pub entry_size: u32,
+    pub entries_crc: u32,
+}
+
+impl GptHeader {
+    pub fn entries_len(&self) -> usize {
+        self.entry_count as usize * self.entry_size as usize
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GptEntry {
+    /// zero based slot in the entry array
+    pub index: u32,
+    pub type_guid: Uuid,
+    pub unique_guid: Uuid,
+    pub first_lba: u64,
+    pub last_lba: u64,
+    pub attributes: u64,
+    pub name: String,
+}
+
+/// Parses the header in `sector`, a wrong checksum is reported through `crc_valid` rather than failing
+pub fn parse_gpt_header(sector: &[u8]) -> Result<GptHeader> {
+    if sector.len() < HEADER_SIZE || &sector[..8] != GPT_SIGNATURE {
+        bail!("no GPT signature");
+    }
+
+    let header_size = u32_at(sector, 12);
+
+    if (header_size as usize) < HEADER_SIZE || header_size as usize > sector.len() {
+        bail!("invalid GPT header size {}", header_size);
+    }
+
+    let header_crc = u32_at(sector, 16);
+
+    // the checksum covers the header with its own field zeroed
+    let mut header = sector[..header_size as usize].to_vec();
+    header[16..20].fill(0);
+
+    let entry_size = u32_at(sector, 84);
+
+    if entry_size < MIN_ENTRY_SIZE || !entry_size.is_multiple_of(8) {
+        bail!("invalid GPT entry size {}", entry_size);
+    }
+
+    Ok(GptHeader {
+        revision: u32_at(sector, 8),
+        header_size,
+        header_crc,
+        crc_valid: crc32(&header) == header_crc,
+        my_lba: u64_at(sector, 24),
+        alternate_lba: u64_at(sector, 32),
+        first_usable_lba: u64_at(sector, 40),
+        last_usable_lba: u64_at(sector, 48),
+        disk_guid: guid_at(sector, 56),
+        entries_lba: u64_at(sector, 72),
+        entry_count: u32_at(sector, 80),
+        entry_size,
+        entries_crc: u32_at(sector, 88),
+    })
+}
+
+/// Used slots of the entry array, slots with a zero type GUID are empty
+pub fn parse_gpt_entries(bytes: &[u8], entry_size: u32) -> Vec<GptEntry> {
+    bytes
+        .chunks_exact(entry_size as usize)
+        .enumerate()
+        .filter_map(|(index, entry)| {
+            let type_guid = guid_at(entry, 0);
+
+            if type_guid.is_nil() {
+                return None;
+            }
+
+            let name: Vec<u16> = entry[56..128]
+                .chunks_exact(2)
+                .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+                .take_while(|unit| *unit != 0)
+                .collect();
+
+            Some(GptEntry {
+                index: index as u32,
+                type_guid,
+                unique_guid: guid_at(entry, 16),
+                first_lba: u64_at(entry, 32),
+                last_lba: u64_at(entry, 40),
+                attributes: u64_at(entry, 48),
+                name: String::from_utf16_lossy(&name),
+            })
+        })
+        .collect()
+}
+
+/// Checksum failures and fields where the backup header disagrees with the primary
+pub fn compare_headers(primary: &GptHeader, backup: &GptHeader) -> Vec<String> {
+    let mut issues = Vec::new();
+
+    if !primary.crc_valid {
+        issues.push("primary GPT header checksum mismatch".to_string());
+    }
+
+    if !backup.crc_valid {
+        issues.push("backup GPT header checksum mismatch".to_string());
+    }
+
+    if backup.my_lba != primary.alternate_lba {
+        issues.push(format!("backup GPT header is at LBA {}, the primary points to LBA {}", backup.my_lba, primary.alternate_lba));
+    }
+
+    if backup.alternate_lba != primary.my_lba {
+        issues.push(format!("backup GPT header points to LBA {} instead of the primary at LBA {}", backup.alternate_lba, primary.my_lba));
+    }
+
+    let mut differs = |field: &str, primary: String, backup: String| {
+        if primary != backup {
+            issues.push(format!("{} differs between the primary ({}) and backup ({}) GPT headers", field, primary, backup));
+        }
+    };
+
+    differs("disk GUID", primary.disk_guid.to_string(), backup.disk_guid.to_string());
+    differs("first usable LBA", primary.first_usable_lba.to_string(), backup.first_usable_lba.to_string());
+    differs("last usable LBA", primary.last_usable_lba.to_string(), backup.last_usable_lba.to_string());
+    differs("entry count", primary.entry_count.to_string(), backup.entry_count.to_string());
+    differs("entry size", primary.entry_size.to_string(), backup.entry_size.to_string());
+    differs("entry array checksum", format!("{:08x}", primary.entries_crc), format!("{:08x}", backup.entries_crc));
+
+    issues
+}
+
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = Crc::new();
+    crc.update(bytes);
+    crc.sum()
+}
+
+/// GUIDs are stored with the first three fields little endian
+fn guid_at(bytes: &[u8], offset: usize) -> Uuid {
+    Uuid::from_bytes_le(bytes[offset..offset + 16].try_into().unwrap())
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn u64_at(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
@@ -0,0 +1,48 @@
+use anyhow::{bail, Result};
+
+/// Type byte of the single partition covering a GPT disk
+pub const PROTECTIVE_TYPE: u8 = 0xEE;
+
+const TABLE_OFFSET: usize = 446;
+const ENTRY_SIZE: usize = 16;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MbrEntry {
+    pub bootable: bool,
+    pub partition_type: u8,
+    /// relative to the start of the disk for primary entries, to the EBR or extended partition otherwise
+    pub first_lba: u32,
+    pub sectors: u32,
+}
+
+impl MbrEntry {
+    pub fn is_empty(&self) -> bool {
+        self.partition_type == 0 || self.sectors == 0
+    }
+
+    pub fn is_extended(&self) -> bool {
+        matches!(self.partition_type, 0x05 | 0x0F | 0x85)
+    }
+}
+
+/// The four entries of an MBR or EBR, empty slots included so their position is kept
+pub fn parse_mbr(sector: &[u8]) -> Result<[MbrEntry; 4]> {
+    if sector.len() < 512 || sector[510..512] != [0x55, 0xAA] {
+        bail!("no MBR boot signature");
+    }
+
+    let mut entries = [MbrEntry::default(); 4];
+
+    for (slot, entry) in entries.iter_mut().enumerate() {
+        let bytes = &sector[TABLE_OFFSET + slot * ENTRY_SIZE..][..ENTRY_SIZE];
+
+        *entry = MbrEntry {
+            bootable: bytes[0] == 0x80,
+            partition_type: bytes[4],
+            first_lba: u32::from_le_bytes(bytes[8..12].try_into().unwrap()),
+            sectors: u32::from_le_bytes(bytes[12..16].try_into().unwrap()),
+        };
+    }
+
+    Ok(entries)
+}
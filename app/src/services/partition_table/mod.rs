@@ -0,0 +1,532 @@
+use std::{
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+};
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::{
+    models::{PartitionScheme, PartitionTable, PartitionTableEntry},
+    utils::format_bytes,
+};
+
+mod gpt;
+mod mbr;
+mod types;
+
+pub use self::gpt::{compare_headers, crc32, parse_gpt_entries, parse_gpt_header, GptHeader};
+pub use self::mbr::{parse_mbr, MbrEntry, PROTECTIVE_TYPE};
+pub use self::types::{gpt_attribute_names, gpt_type_name, mbr_type_name};
+
+/// Sector sizes probed for a GPT header, MBR disks are assumed to use 512 byte sectors
+const SECTOR_SIZES: [u64; 2] = [512, 4096];
+/// Entry arrays above this are treated as corrupt, the spec minimum is 16 KiB
+const MAX_ENTRIES_LEN: usize = 1024 * 1024;
+/// Guards against EBR chains that loop back on themselves
+const MAX_LOGICAL_PARTITIONS: usize = 128;
+
+const VHD_COOKIE: &[u8; 8] = b"conectix";
+const VHDX_SIGNATURE: &[u8; 8] = b"vhdxfile";
+
+/// Reads MBR and GPT partition tables from block devices and raw disk images
+#[derive(Clone)]
+pub struct PartitionTableService;
+
+impl PartitionTableService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// `path` is a device such as `/dev/sda` or `\\.\PhysicalDrive0`, or an `.img` or fixed `.vhd` file
+    pub fn read(&self, path: &str) -> Result<PartitionTable> {
+        let mut file = File::open(path).map_err(|err| anyhow!("could not open {}: {}", path, err))?;
+        let metadata = file.metadata()?;
+
+        let disk_size = if metadata.is_file() {
+            Some(image_size(&mut file, metadata.len())?)
+        } else {
+            device_size(&mut file)
+        };
+
+        read_partition_table(&mut file, disk_size)
+    }
+}
+
+/// Size of the disk inside an image, a fixed VHD is the raw disk followed by a 512 byte footer
+fn image_size(file: &mut File, len: u64) -> Result<u64> {
+    let mut signature = [0u8; 8];
+    file.read_exact(&mut signature)?;
+
+    // dynamic and differencing VHDs start with a copy of the footer
+    if &signature == VHD_COOKIE {
+        bail!("dynamic VHDs are not supported, convert the image to a fixed VHD");
+    }
+
+    if &signature == VHDX_SIGNATURE {
+        bail!("VHDX images are not supported, convert the image to a fixed VHD");
+    }
+
+    if len >= 1024 {
+        file.seek(SeekFrom::Start(len - 512))?;
+        file.read_exact(&mut signature)?;
+
+        if &signature == VHD_COOKIE {
+            return Ok(len - 512);
+        }
+    }
+
+    Ok(len)
+}
+
+#[cfg(unix)]
+fn device_size(file: &mut File) -> Option<u64> {
+    file.seek(SeekFrom::End(0)).ok().filter(|size| *size > 0)
+}
+
+#[cfg(windows)]
+fn device_size(file: &mut File) -> Option<u64> {
+    use std::{mem, os::windows::io::AsRawHandle, ptr};
+    use winapi::{
+        shared::minwindef::{DWORD, FALSE},
+        um::{ioapiset::DeviceIoControl, winioctl::{GET_LENGTH_INFORMATION, IOCTL_DISK_GET_LENGTH_INFO}},
+    };
+
+    unsafe {
+        let mut info: GET_LENGTH_INFORMATION = mem::zeroed();
+        let mut returned: DWORD = 0;
+
+        let result = DeviceIoControl(
+            file.as_raw_handle() as *mut _,
+            IOCTL_DISK_GET_LENGTH_INFO,
+            ptr::null_mut(),
+            0,
+            &mut info as *mut _ as *mut _,
+            mem::size_of::<GET_LENGTH_INFORMATION>() as u32,
+            &mut returned,
+            ptr::null_mut(),
+        );
+
+        if result == FALSE {
+            return None;
+        }
+
+        Some(*info.Length.QuadPart() as u64)
+    }
+}
+
+/// Whole sector reads, raw devices reject unaligned ones
+struct Disk<'a, R> {
+    reader: &'a mut R,
+    sector_size: u64,
+    size: Option<u64>,
+}
+
+impl<R: Read + Seek> Disk<'_, R> {
+    fn read(&mut self, lba: u64, len: usize) -> io::Result<Vec<u8>> {
+        let sectors = (len as u64).div_ceil(self.sector_size);
+        let mut buffer = vec![0; (sectors * self.sector_size) as usize];
+
+        // corrupt tables can point anywhere
+        let offset = lba
+            .checked_mul(self.sector_size)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("LBA {} lies beyond any disk", lba)))?;
+
+        self.reader.seek(SeekFrom::Start(offset))?;
+        self.reader.read_exact(&mut buffer)?;
+        buffer.truncate(len);
+
+        Ok(buffer)
+    }
+
+    fn header_at(&mut self, lba: u64) -> Option<GptHeader> {
+        let sector = self.read(lba, self.sector_size as usize).ok()?;
+
+        parse_gpt_header(&sector).ok()
+    }
+
+    fn last_lba(&self) -> Option<u64> {
+        self.size.map(|size| size / self.sector_size).filter(|sectors| *sectors > 0).map(|sectors| sectors - 1)
+    }
+}
+
+/// Reads the GPT, falling back to the MBR when neither GPT header is found
+pub fn read_partition_table<R: Read + Seek>(reader: &mut R, disk_size: Option<u64>) -> Result<PartitionTable> {
+    for sector_size in SECTOR_SIZES {
+        let mut disk = Disk { reader: &mut *reader, sector_size, size: disk_size };
+
+        if let Some(table) = read_gpt(&mut disk)? {
+            return Ok(table);
+        }
+    }
+
+    let mut disk = Disk { reader, sector_size: 512, size: disk_size };
+    read_mbr(&mut disk)
+}
+
+fn read_gpt<R: Read + Seek>(disk: &mut Disk<R>) -> Result<Option<PartitionTable>> {
+    let primary = disk.header_at(1);
+    let last_lba = disk.last_lba();
+
+    // `None` without an MBR boot signature, GPT disks written by some tools have none
+    let protective = disk
+        .read(0, 512)
+        .ok()
+        .and_then(|sector| parse_mbr(&sector).ok())
+        .map(|entries| entries.iter().any(|entry| entry.partition_type == PROTECTIVE_TYPE));
+
+    // a disk repartitioned as MBR can keep a stale backup GPT at its end
+    if primary.is_none() && protective == Some(false) {
+        return Ok(None);
+    }
+
+    // a damaged primary still tells where the backup should be, the end of the disk otherwise
+    let backup_lba = primary.as_ref().map(|header| header.alternate_lba).or(last_lba);
+    let backup = backup_lba.and_then(|lba| disk.header_at(lba));
+
+    let mut issues = Vec::new();
+
+    match (&primary, &backup) {
+        (None, None) => return Ok(None),
+        (Some(primary), Some(backup)) => issues.extend(compare_headers(primary, backup)),
+        (None, Some(_)) => issues.push("primary GPT header is missing, using the backup".to_string()),
+        (Some(_), None) => issues.push(format!("backup GPT header is missing at LBA {}", backup_lba.unwrap_or_default())),
+    }
+
+    let expected_backup_lba = primary.as_ref().map(|header| header.alternate_lba).or(backup.as_ref().map(|header| header.my_lba));
+
+    if let Some((expected, last_lba)) = expected_backup_lba.zip(last_lba).filter(|(expected, last_lba)| expected != last_lba) {
+        issues.push(format!("backup GPT header is placed at LBA {} instead of the last LBA {}, the disk was resized or the image truncated", expected, last_lba));
+    }
+
+    let primary_entries = primary.as_ref().and_then(|header| read_entries(disk, header, "primary", &mut issues));
+    let backup_entries = backup.as_ref().and_then(|header| read_entries(disk, header, "backup", &mut issues));
+
+    if matches!((&primary_entries, &backup_entries), (Some(primary), Some(backup)) if primary != backup) {
+        issues.push("partition entries differ between the primary and backup GPT".to_string());
+    }
+
+    // the first copy whose header and entries both check out, otherwise whatever could be read
+    let candidates = [(&primary, &primary_entries), (&backup, &backup_entries)];
+    let chosen = candidates
+        .iter()
+        .find(|(header, entries)| header.as_ref().is_some_and(|header| header.crc_valid) && entries.as_ref().is_some_and(|(_, valid)| *valid))
+        .or_else(|| candidates.iter().find(|(header, entries)| header.is_some() && entries.is_some()));
+
+    let Some((Some(header), Some((entries, _)))) = chosen else {
+        bail!("GPT partition entries could not be read: {}", issues.join(", "));
+    };
+
+    if protective != Some(true) {
+        issues.push("protective MBR is missing".to_string());
+    }
+
+    let sector_size = disk.sector_size;
+
+    let partitions = parse_gpt_entries(entries, header.entry_size)
+        .into_iter()
+        .map(|entry| {
+            let size = entry.last_lba.saturating_sub(entry.first_lba).saturating_add(1).saturating_mul(sector_size);
+
+            PartitionTableEntry {
+                number: entry.index + 1,
+                type_id: entry.type_guid.to_string(),
+                type_name: gpt_type_name(&entry.type_guid).map(str::to_string),
+                unique_guid: Some(entry.unique_guid),
+                first_lba: entry.first_lba,
+                last_lba: entry.last_lba,
+                size,
+                size_formatted: format_bytes(size),
+                attributes: entry.attributes,
+                attribute_names: gpt_attribute_names(&entry.type_guid, entry.attributes),
+                name: Some(entry.name).filter(|name| !name.is_empty()),
+                bootable: entry.attributes & (1 << 2) != 0,
+                logical: false,
+            }
+        })
+        .collect();
+
+    Ok(Some(PartitionTable {
+        scheme: PartitionScheme::Gpt,
+        sector_size: sector_size as u32,
+        disk_size: disk.size,
+        disk_size_formatted: disk.size.map(format_bytes),
+        disk_guid: Some(header.disk_guid),
+        first_usable_lba: Some(header.first_usable_lba),
+        last_usable_lba: Some(header.last_usable_lba),
+        partitions,
+        issues,
+    }))
+}
+
+/// The raw entry array of `header` and whether it matches the checksum in the header
+fn read_entries<R: Read + Seek>(disk: &mut Disk<R>, header: &GptHeader, copy: &str, issues: &mut Vec<String>) -> Option<(Vec<u8>, bool)> {
+    let len = header.entries_len();
+
+    if len > MAX_ENTRIES_LEN {
+        issues.push(format!("{} GPT entry array of {} bytes is implausibly large", copy, len));
+        return None;
+    }
+
+    match disk.read(header.entries_lba, len) {
+        Ok(entries) => {
+            let valid = crc32(&entries) == header.entries_crc;
+
+            if !valid {
+                issues.push(format!("{} GPT entry array checksum mismatch", copy));
+            }
+
+            Some((entries, valid))
+        }
+        Err(err) => {
+            issues.push(format!("{} GPT entry array could not be read: {}", copy, err));
+            None
+        }
+    }
+}
+
+fn read_mbr<R: Read + Seek>(disk: &mut Disk<R>) -> Result<PartitionTable> {
+    let sector = disk.read(0, 512)?;
+    let entries = parse_mbr(&sector).map_err(|_| anyhow!("no MBR or GPT partition table found"))?;
+
+    let mut issues = Vec::new();
+    let mut partitions = Vec::new();
+
+    for (slot, entry) in entries.iter().enumerate() {
+        if entry.is_empty() {
+            continue;
+        }
+
+        partitions.push(mbr_partition(slot as u32 + 1, entry, entry.first_lba as u64, false));
+
+        if entry.partition_type == PROTECTIVE_TYPE {
+            issues.push("protective MBR without a readable GPT".to_string());
+        }
+
+        if entry.is_extended() {
+            read_logical(disk, entry.first_lba as u64, &mut partitions, &mut issues);
+        }
+    }
+
+    partitions.sort_by_key(|partition| partition.number);
+
+    Ok(PartitionTable {
+        scheme: PartitionScheme::Mbr,
+        sector_size: disk.sector_size as u32,
+        disk_size: disk.size,
+        disk_size_formatted: disk.size.map(format_bytes),
+        disk_guid: None,
+        first_usable_lba: None,
+        last_usable_lba: None,
+        partitions,
+        issues,
+    })
+}
+
+/// Follows the EBR chain of an extended partition, each EBR holds one logical partition and a link to the next
+fn read_logical<R: Read + Seek>(disk: &mut Disk<R>, extended_start: u64, partitions: &mut Vec<PartitionTableEntry>, issues: &mut Vec<String>) {
+    let mut ebr_lba = extended_start;
+
+    for number in 5..5 + MAX_LOGICAL_PARTITIONS as u32 {
+        let entries = match disk.read(ebr_lba, 512).map_err(anyhow::Error::from).and_then(|sector| parse_mbr(&sector)) {
+            Ok(entries) => entries,
+            Err(err) => {
+                issues.push(format!("EBR at LBA {} could not be read: {}", ebr_lba, err));
+                return;
+            }
+        };
+
+        let [logical, link, ..] = entries;
+
+        if !logical.is_empty() {
+            partitions.push(mbr_partition(number, &logical, ebr_lba + logical.first_lba as u64, true));
+        }
+
+        // the link is relative to the extended partition, the logical entry to its own EBR
+        if link.is_empty() || link.first_lba == 0 {
+            return;
+        }
+
+        ebr_lba = extended_start + link.first_lba as u64;
+    }
+
+    issues.push(format!("EBR chain longer than {} partitions, stopped reading", MAX_LOGICAL_PARTITIONS));
+}
+
+fn mbr_partition(number: u32, entry: &MbrEntry, first_lba: u64, logical: bool) -> PartitionTableEntry {
+    let size = entry.sectors as u64 * 512;
+
+    PartitionTableEntry {
+        number,
+        type_id: format!("0x{:02X}", entry.partition_type),
+        type_name: mbr_type_name(entry.partition_type).map(str::to_string),
+        unique_guid: None,
+        first_lba,
+        last_lba: first_lba + entry.sectors as u64 - 1,
+        size,
+        size_formatted: format_bytes(size),
+        attributes: 0,
+        attribute_names: vec![],
+        name: None,
+        bootable: entry.bootable,
+        logical,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    macro_rules! fixture {
+        ($name:literal) => {
+            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/partition_table/", $name)).to_vec()
+        };
+    }
+
+    fn read(image: Vec<u8>) -> Result<PartitionTable> {
+        let size = image.len() as u64;
+
+        read_partition_table(&mut Cursor::new(image), Some(size))
+    }
+
+    fn layout(table: &PartitionTable) -> Vec<(u32, &str, u64, u64, bool)> {
+        table
+            .partitions
+            .iter()
+            .map(|partition| (partition.number, partition.type_id.as_str(), partition.first_lba, partition.last_lba, partition.logical))
+            .collect()
+    }
+
+    #[test]
+    fn mbr_primary_partitions() {
+        let table = read(fixture!("mbr.img")).unwrap();
+
+        assert_eq!(table.scheme, PartitionScheme::Mbr);
+        assert_eq!(table.sector_size, 512);
+        assert_eq!(layout(&table), vec![(1, "0x07", 8, 47, false), (2, "0x83", 48, 87, false), (3, "0x0B", 88, 127, false)]);
+        assert!(table.partitions[0].bootable);
+        assert_eq!(table.partitions[0].size, 40 * 512);
+        assert!(table.issues.is_empty(), "{:?}", table.issues);
+    }
+
+    #[test]
+    fn ebr_chain_is_followed() {
+        let table = read(fixture!("ebr.img")).unwrap();
+
+        assert_eq!(
+            layout(&table),
+            vec![
+                (1, "0x0C", 8, 39, false),
+                (2, "0x0F", 40, 119, false),
+                (5, "0x83", 42, 49, true),
+                (6, "0x83", 52, 59, true),
+                (7, "0x83", 62, 65, true),
+            ]
+        );
+        assert!(table.issues.is_empty(), "{:?}", table.issues);
+    }
+
+    #[test]
+    fn looping_ebr_chain_is_cut_off() {
+        let mut image = fixture!("ebr.img");
+        // the last EBR links back to the second one, 10 sectors into the extended partition
+        let link = 60 * 512 + 446 + 16;
+        image[link + 4] = 0x05;
+        image[link + 8..link + 12].copy_from_slice(&10u32.to_le_bytes());
+        image[link + 12..link + 16].copy_from_slice(&10u32.to_le_bytes());
+
+        let table = read(image).unwrap();
+
+        assert_eq!(table.partitions.len(), 2 + MAX_LOGICAL_PARTITIONS);
+        assert_eq!(table.issues, vec![format!("EBR chain longer than {} partitions, stopped reading", MAX_LOGICAL_PARTITIONS)]);
+    }
+
+    #[test]
+    fn gpt_partitions() {
+        let table = read(fixture!("gpt.img")).unwrap();
+
+        assert_eq!(table.scheme, PartitionScheme::Gpt);
+        assert_eq!(table.sector_size, 512);
+        assert_eq!(table.first_usable_lba, Some(34));
+        assert_eq!(table.last_usable_lba, Some(94));
+        assert_eq!(table.disk_guid, Some("5b7e3a52-6d1c-4b1e-9c3f-1f2e3d4c5b6a".parse().unwrap()));
+        assert_eq!(
+            layout(&table),
+            vec![
+                (1, "c12a7328-f81f-11d2-ba4b-00a0c93ec93b", 34, 49, false),
+                (2, "ebd0a0a2-b9e5-4433-87c0-68b6b72699c7", 50, 89, false),
+            ]
+        );
+        assert_eq!(table.partitions[0].name.as_deref(), Some("EFI system partition"));
+        assert_eq!(table.partitions[1].attributes, 1 << 63);
+        assert!(table.issues.is_empty(), "{:?}", table.issues);
+    }
+
+    #[test]
+    fn corrupted_backup_gpt_is_reported() {
+        let table = read(fixture!("gpt-bad-backup.img")).unwrap();
+
+        // the intact primary is used
+        assert_eq!(table.partitions.len(), 2);
+        assert_eq!(table.partitions[0].first_lba, 34);
+
+        assert!(table.issues.contains(&"backup GPT header checksum mismatch".to_string()), "{:?}", table.issues);
+        assert!(table.issues.iter().any(|issue| issue.starts_with("disk GUID differs")), "{:?}", table.issues);
+        assert!(table.issues.contains(&"backup GPT entry array checksum mismatch".to_string()), "{:?}", table.issues);
+        assert!(table.issues.contains(&"partition entries differ between the primary and backup GPT".to_string()), "{:?}", table.issues);
+    }
+
+    #[test]
+    fn missing_primary_gpt_falls_back_to_the_backup() {
+        let mut image = fixture!("gpt.img");
+        image[512..1024].fill(0);
+
+        let table = read(image).unwrap();
+
+        assert_eq!(table.partitions.len(), 2);
+        assert_eq!(table.issues, vec!["primary GPT header is missing, using the backup".to_string()]);
+    }
+
+    #[test]
+    fn truncated_gpt_image_is_reported() {
+        let mut image = fixture!("gpt.img");
+        image.truncate(100 * 512);
+
+        let table = read(image).unwrap();
+
+        assert_eq!(table.partitions.len(), 2);
+        assert!(table.issues.contains(&"backup GPT header is missing at LBA 127".to_string()), "{:?}", table.issues);
+        assert!(table.issues.iter().any(|issue| issue.contains("instead of the last LBA 99")), "{:?}", table.issues);
+    }
+
+    #[test]
+    fn out_of_range_lba_is_reported() {
+        let mut image = fixture!("gpt.img");
+        // a backup LBA whose byte offset overflows, with the header checksum fixed up
+        image[512 + 32..512 + 40].copy_from_slice(&(u64::MAX / 2).to_le_bytes());
+        image[512 + 16..512 + 20].fill(0);
+        let crc = crc32(&image[512..512 + 92]);
+        image[512 + 16..512 + 20].copy_from_slice(&crc.to_le_bytes());
+
+        let table = read(image).unwrap();
+
+        assert!(table.issues.contains(&format!("backup GPT header is missing at LBA {}", u64::MAX / 2)), "{:?}", table.issues);
+    }
+
+    #[test]
+    fn gpt_with_4k_sectors() {
+        let table = read(fixture!("gpt-4k.img")).unwrap();
+
+        assert_eq!(table.scheme, PartitionScheme::Gpt);
+        assert_eq!(table.sector_size, 4096);
+        assert_eq!(layout(&table), vec![(1, "0fc63daf-8483-4772-8e79-3d69d8477de4", 6, 9, false)]);
+        assert_eq!(table.partitions[0].size, 4 * 4096);
+        assert!(table.issues.is_empty(), "{:?}", table.issues);
+    }
+
+    #[test]
+    fn blank_image_has_no_table() {
+        assert!(read(vec![0; 64 * 1024]).is_err());
+    }
+}
@@ -0,0 +1,92 @@
+use uuid::Uuid;
+
+const GPT_TYPES: &[(&str, &str)] = &[
+    ("c12a7328-f81f-11d2-ba4b-00a0c93ec93b", "EFI System"),
+    ("024dee41-33e7-11d3-9d69-0008c781f39f", "MBR partition scheme"),
+    ("21686148-6449-6e6f-744e-656564454649", "BIOS boot"),
+    ("e3c9e316-0b5c-4db8-817d-f92df00215ae", "Microsoft reserved"),
+    ("ebd0a0a2-b9e5-4433-87c0-68b6b72699c7", "Microsoft basic data"),
+    ("5808c8aa-7e8f-42e0-85d2-e1e90434cfb3", "Windows LDM metadata"),
+    ("af9b60a0-1431-4f62-bc68-3311714a69ad", "Windows LDM data"),
+    ("de94bba4-06d1-4d40-a16a-bfd50179d6ac", "Windows recovery"),
+    ("e75caf8f-f680-4cee-afa3-b001e56efc2d", "Storage Spaces"),
+    ("0fc63daf-8483-4772-8e79-3d69d8477de4", "Linux filesystem"),
+    ("0657fd6d-a4ab-43c4-84e5-0933c84b4f4f", "Linux swap"),
+    ("e6d6d379-f507-44c2-a23c-238f2a3df928", "Linux LVM"),
+    ("a19d880f-05fc-4d3b-a006-743f0f84911e", "Linux RAID"),
+    ("ca7d7ccb-63ed-4c53-861c-1742536059cc", "Linux LUKS"),
+    ("44479540-f297-41b2-9af7-d131d5f0458a", "Linux root (x86)"),
+    ("4f68bce3-e8cd-4db1-96e7-fbcaf984b709", "Linux root (x86-64)"),
+    ("b921b045-1df0-41c3-af44-4c6f280d3fae", "Linux root (ARM64)"),
+    ("933ac7e1-2eb4-4f13-b844-0e14e2aef915", "Linux home"),
+    ("3b8f8425-20e0-4f3b-907f-1a25a76f98e8", "Linux server data"),
+    ("bc13c2ff-59e6-4262-a352-b275fd6f7172", "Linux extended boot"),
+    ("8da63339-0007-60c0-c436-083ac8230908", "Linux reserved"),
+    ("7c3457ef-0000-11aa-aa11-00306543ecac", "Apple APFS"),
+    ("48465300-0000-11aa-aa11-00306543ecac", "Apple HFS+"),
+    ("426f6f74-0000-11aa-aa11-00306543ecac", "Apple boot"),
+    ("516e7cb4-6ecf-11d6-8ff8-00022d09712b", "FreeBSD data"),
+    ("83bd6b9d-7f41-11dc-be0b-001560b84f0f", "FreeBSD boot"),
+    ("516e7cb5-6ecf-11d6-8ff8-00022d09712b", "FreeBSD swap"),
+    ("516e7cb6-6ecf-11d6-8ff8-00022d09712b", "FreeBSD UFS"),
+    ("516e7cba-6ecf-11d6-8ff8-00022d09712b", "FreeBSD ZFS"),
+    ("6a898cc3-1dd2-11b2-99a6-080020736631", "ZFS"),
+    ("fe3a2a5d-4f32-41a7-b725-accc3285a309", "ChromeOS kernel"),
+    ("3cb8e202-3b7e-47dd-8a3c-7ff2a13cfcec", "ChromeOS root"),
+];
+
+const MICROSOFT_BASIC_DATA: &str = "ebd0a0a2-b9e5-4433-87c0-68b6b72699c7";
+
+pub fn gpt_type_name(type_guid: &Uuid) -> Option<&'static str> {
+    let type_guid = type_guid.to_string();
+
+    GPT_TYPES.iter().find(|(guid, _)| *guid == type_guid).map(|(_, name)| *name)
+}
+
+pub fn mbr_type_name(partition_type: u8) -> Option<&'static str> {
+    let name = match partition_type {
+        0x01 => "FAT12",
+        0x04 | 0x06 | 0x0E => "FAT16",
+        0x05 | 0x0F => "Extended",
+        0x07 => "NTFS / exFAT",
+        0x0B | 0x0C => "FAT32",
+        0x11 | 0x14 | 0x16 | 0x1E => "Hidden FAT",
+        0x17 => "Hidden NTFS",
+        0x1B | 0x1C => "Hidden FAT32",
+        0x27 => "Windows recovery",
+        0x42 => "Windows dynamic disk",
+        0x82 => "Linux swap",
+        0x83 => "Linux",
+        0x85 => "Linux extended",
+        0x8E => "Linux LVM",
+        0xA5 => "FreeBSD",
+        0xA6 => "OpenBSD",
+        0xA9 => "NetBSD",
+        0xAF => "Apple HFS+",
+        0xEE => "GPT protective",
+        0xEF => "EFI System",
+        0xFD => "Linux RAID",
+        _ => return None,
+    };
+
+    Some(name)
+}
+
+/// Names of the set attribute bits, bits 48 to 63 depend on the partition type
+pub fn gpt_attribute_names(type_guid: &Uuid, attributes: u64) -> Vec<String> {
+    let is_basic_data = type_guid.to_string() == MICROSOFT_BASIC_DATA;
+
+    (0..64)
+        .filter(|bit| attributes & (1 << bit) != 0)
+        .map(|bit| match bit {
+            0 => "required".to_string(),
+            1 => "no block IO protocol".to_string(),
+            2 => "legacy BIOS bootable".to_string(),
+            60 if is_basic_data => "read only".to_string(),
+            61 if is_basic_data => "shadow copy".to_string(),
+            62 if is_basic_data => "hidden".to_string(),
+            63 if is_basic_data => "no drive letter".to_string(),
+            bit => format!("bit {}", bit),
+        })
+        .collect()
+}
@@ -0,0 +1,108 @@
+use std::{fs, path::Path};
+
+use anyhow::Result;
+
+use crate::models::Process;
+
+/// 100-ns intervals between 1601-01-01 and the unix epoch
+const FILETIME_UNIX_EPOCH: u64 = 116_444_736_000_000_000;
+
+/// The fields of `/proc/<pid>/stat` the process list needs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcStat {
+    pub name: String,
+    pub parent_id: u32,
+    pub session_id: u32,
+    /// user + system time in clock ticks
+    pub cpu_ticks: u64,
+    /// clock ticks after boot
+    pub start_ticks: u64,
+}
+
+/// Walks `/proc`, processes exiting meanwhile are skipped
+pub fn enumerate_processes() -> Result<Vec<Process>> {
+    let ticks_per_second = unsafe { libc::sysconf(libc::_SC_CLK_TCK) }.max(1) as u64;
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) }.max(1) as u64;
+    let boot_time = fs::read_to_string("/proc/stat").ok().and_then(|stat| parse_boot_time(&stat));
+    let mut items = Vec::new();
+
+    for entry in fs::read_dir("/proc")?.flatten() {
+        let Some(id) = entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+            continue;
+        };
+
+        let path = entry.path();
+        let Some(stat) = fs::read_to_string(path.join("stat")).ok().and_then(|stat| parse_stat(&stat)) else {
+            continue;
+        };
+
+        let start_time_filetime = boot_time.map(|boot_time| {
+            let started_ms = boot_time * 1000 + stat.start_ticks * 1000 / ticks_per_second;
+            FILETIME_UNIX_EPOCH + started_ms * 10_000
+        });
+
+        items.push(Process {
+            id,
+            parent_id: stat.parent_id,
+            name: stat.name,
+            exe_path: exe_path(&path),
+            session_id: Some(stat.session_id),
+            memory_kb: resident_pages(&path).map(|pages| pages * page_size / 1024),
+            cpu_time_ms: Some(stat.cpu_ticks * 1000 / ticks_per_second),
+            start_time_filetime,
+            icon_path: None,
+        });
+    }
+
+    Ok(items)
+}
+
+pub fn kill_process(id: u32) -> Result<()> {
+    if unsafe { libc::kill(id as libc::pid_t, libc::SIGKILL) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+
+    Ok(())
+}
+
+/// Kernel threads have no executable, other users' need privileges
+fn exe_path(path: &Path) -> Option<String> {
+    fs::read_link(path.join("exe"))
+        .ok()
+        .map(|exe| exe.to_string_lossy().into_owned())
+}
+
+/// Second field of `/proc/<pid>/statm`
+fn resident_pages(path: &Path) -> Option<u64> {
+    fs::read_to_string(path.join("statm"))
+        .ok()?
+        .split_whitespace()
+        .nth(1)?
+        .parse()
+        .ok()
+}
+
+/// The command name may contain spaces and parentheses, fields are counted from the last `)`
+pub fn parse_stat(stat: &str) -> Option<ProcStat> {
+    let (head, rest) = stat.rsplit_once(')')?;
+    let (_, name) = head.split_once('(')?;
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+
+    // rest starts at field 3 (state); ppid is field 4, session 6, utime 14, stime 15, starttime 22
+    let field = |number: usize| fields.get(number - 3)?.parse::<u64>().ok();
+
+    Some(ProcStat {
+        name: name.to_string(),
+        parent_id: field(4)? as u32,
+        session_id: field(6)? as u32,
+        cpu_ticks: field(14)? + field(15)?,
+        start_ticks: field(22)?,
+    })
+}
+
+/// Seconds since the epoch from the `btime` line of `/proc/stat`
+pub fn parse_boot_time(stat: &str) -> Option<u64> {
+    stat.lines()
+        .find_map(|line| line.strip_prefix("btime "))
+        .and_then(|value| value.trim().parse().ok())
+}
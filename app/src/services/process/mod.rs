@@ -1,10 +1,17 @@
 use std::{collections::HashMap, sync::{Arc, RwLock}, time::{Duration, Instant}};
 
-use winapi::{shared::minwindef::FALSE, um::{handleapi::{CloseHandle, INVALID_HANDLE_VALUE}, processthreadsapi::{OpenProcess, TerminateProcess}, tlhelp32::{CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS}, winnt::{PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_TERMINATE, PROCESS_VM_READ}}};
+use crate::models::*;
+use anyhow::Result;
 
-use log::*;
-use crate::{models::*, services::utils::*, utils::widestr_to_string};
-use anyhow::{bail, Result};
+#[cfg(windows)]
+mod windows;
+#[cfg(windows)]
+use self::windows as platform;
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "linux")]
+use self::linux as platform;
 
 pub struct ProcessManager {
     query_interval: Duration,
@@ -103,7 +110,7 @@ impl ProcessManager {
                     out.retain(|p| p.name.to_lowercase().contains(&name.to_lowercase()));
                 }
 
-                out.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+                out.sort_by_key(|a| a.name.to_lowercase());
 
                 Ok(ProcessResult::List(out))
             }
@@ -111,23 +118,7 @@ impl ProcessManager {
     }
 
     pub fn kill_process(&self, id: u32) -> Result<()> {
-
-        unsafe {
-            let handle = OpenProcess(PROCESS_TERMINATE, 0, id);
-
-            if handle.is_null() {
-                return Err(std::io::Error::last_os_error().into());
-            }
-
-            if TerminateProcess(handle, 1) == 0 {
-                CloseHandle(handle);
-                return Err(std::io::Error::last_os_error().into());
-            }
-
-            CloseHandle(handle);
-        }
-
-        Ok(())
+        platform::kill_process(id)
     }
 
     pub fn get_by_id(&self, id: u32) -> Result<Option<Process>> {
@@ -165,7 +156,7 @@ impl ProcessManager {
     }
 
     fn refresh_cache(&self) -> Result<()> {
-        let processes = unsafe { self.enumerate_processes()? };
+        let processes = platform::enumerate_processes()?;
 
         let mut cache = self.cache.write().unwrap();
         cache.map = processes.iter().map(|pr| (pr.id, pr.name.to_owned())).collect();
@@ -175,71 +166,50 @@ impl ProcessManager {
 
         Ok(())
     }
+}
 
-    unsafe fn enumerate_processes(&self) -> Result<Vec<Process>> {
-        let mut items = Vec::new();
-
-        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
-        if snapshot == INVALID_HANDLE_VALUE {
-            bail!("snapshot fail")
-        }
-
-        let mut entry: PROCESSENTRY32W = std::mem::zeroed();
-        entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
-
-        if Process32FirstW(snapshot, &mut entry) == FALSE {
-            CloseHandle(snapshot);
-            bail!("Process32FirstW fail");
+impl Clone for ProcessManager {
+    fn clone(&self) -> Self {
+        Self {
+            query_interval: self.query_interval,
+            cache: self.cache.clone()
         }
+    }
+}
 
-        loop {
-            items.push(self.build_item(&entry));
+pub fn build_process_tree(processes: Vec<Process>) -> Vec<ProcessNode> {
+    use std::collections::HashMap;
 
-            if Process32NextW(snapshot, &mut entry) == FALSE {
-                break;
-            }
-        }
+    let mut nodes: HashMap<u32, ProcessNode> = HashMap::new();
+    let mut roots = Vec::new();
 
-        CloseHandle(snapshot);
-        Ok(items)
+    for p in processes {
+        nodes.insert(p.id, ProcessNode { process: p, children: Vec::new() });
     }
 
-    unsafe fn build_item(&self, entry: &PROCESSENTRY32W) -> Process {
-        let name = widestr_to_string(&entry.szExeFile);
-        let pid = entry.th32ProcessID;
-        let ppid = entry.th32ParentProcessID;
-
-        let proc_handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION | PROCESS_VM_READ, FALSE, pid);
-        let exe_path = proc_handle.as_ref().map(|_| get_process_image_path(proc_handle, pid)).unwrap_or(None);
-        let session_id = get_session_id(pid);
-        let memory_kb = if !proc_handle.is_null() { get_process_memory_kb(proc_handle) } else { None };
-        let (start_time_filetime, cpu_ms) = if !proc_handle.is_null() { get_process_times(proc_handle).unwrap_or((None, None)) } else { (None, None) };
+    let all_nodes: Vec<ProcessNode> = nodes.values().cloned().collect();
 
-        if !proc_handle.is_null() {
-            CloseHandle(proc_handle);
+    for node in all_nodes {
+        let ppid = node.process.parent_id;
+        if let Some(parent) = nodes.get_mut(&ppid) {
+            parent.children.push(node);
+        } else {
+            roots.push(node.process.id);
         }
+    }
+
+    let mut tree: Vec<ProcessNode> = roots.into_iter().filter_map(|pid| nodes.remove(&pid)).collect();
 
-        let icon = exe_path.as_ref().and_then(|p| extract_icon_stub(p));
-
-        Process {
-            id: pid,
-            parent_id: ppid,
-            name,
-            exe_path,
-            session_id,
-            memory_kb,
-            cpu_time_ms: cpu_ms,
-            start_time_filetime,
-            icon_path: icon,
+    fn sort_recursive(node: &mut ProcessNode) {
+        node.children.sort_by_key(|a| a.process.name.to_lowercase());
+        for child in &mut node.children {
+            sort_recursive(child);
         }
     }
-}
 
-impl Clone for ProcessManager {
-    fn clone(&self) -> Self {
-        Self {
-            query_interval: self.query_interval.clone(),
-            cache: self.cache.clone()
-        }
+    for node in &mut tree {
+        sort_recursive(node);
     }
-}
\ No newline at end of file
+
+    tree
+}
@@ -0,0 +1,84 @@
+use anyhow::{bail, Result};
+use winapi::{shared::minwindef::FALSE, um::{handleapi::{CloseHandle, INVALID_HANDLE_VALUE}, processthreadsapi::{OpenProcess, TerminateProcess}, tlhelp32::{CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS}, winnt::{PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_TERMINATE, PROCESS_VM_READ}}};
+
+use crate::{models::Process, services::utils::*, utils::widestr_to_string};
+
+pub fn enumerate_processes() -> Result<Vec<Process>> {
+    unsafe {
+        let mut items = Vec::new();
+
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+        if snapshot == INVALID_HANDLE_VALUE {
+            bail!("snapshot fail")
+        }
+
+        let mut entry: PROCESSENTRY32W = std::mem::zeroed();
+        entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
+
+        if Process32FirstW(snapshot, &mut entry) == FALSE {
+            CloseHandle(snapshot);
+            bail!("Process32FirstW fail");
+        }
+
+        loop {
+            items.push(build_item(&entry));
+
+            if Process32NextW(snapshot, &mut entry) == FALSE {
+                break;
+            }
+        }
+
+        CloseHandle(snapshot);
+        Ok(items)
+    }
+}
+
+pub fn kill_process(id: u32) -> Result<()> {
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_TERMINATE, 0, id);
+
+        if handle.is_null() {
+            return Err(std::io::Error::last_os_error().into());
+        }
+
+        if TerminateProcess(handle, 1) == 0 {
+            CloseHandle(handle);
+            return Err(std::io::Error::last_os_error().into());
+        }
+
+        CloseHandle(handle);
+    }
+
+    Ok(())
+}
+
+unsafe fn build_item(entry: &PROCESSENTRY32W) -> Process {
+    let name = widestr_to_string(&entry.szExeFile);
+    let pid = entry.th32ProcessID;
+    let ppid = entry.th32ParentProcessID;
+
+    let proc_handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION | PROCESS_VM_READ, FALSE, pid);
+    let exe_path = proc_handle.as_ref().map(|_| get_process_image_path(proc_handle, pid)).unwrap_or(None);
+    let session_id = get_session_id(pid);
+    let memory_kb = if !proc_handle.is_null() { get_process_memory_kb(proc_handle) } else { None };
+    let (start_time_filetime, cpu_ms) = if !proc_handle.is_null() { get_process_times(proc_handle).unwrap_or((None, None)) } else { (None, None) };
+
+    if !proc_handle.is_null() {
+        CloseHandle(proc_handle);
+    }
+
+    let icon = exe_path.as_ref().and_then(|p| extract_icon_stub(p));
+
+    Process {
+        id: pid,
+        parent_id: ppid,
+        name,
+        exe_path,
+        session_id,
+        memory_kb,
+        cpu_time_ms: cpu_ms,
+        start_time_filetime,
+        icon_path: icon,
+    }
+}
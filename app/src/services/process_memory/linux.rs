@@ -0,0 +1,205 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use anyhow::Result;
+
+use crate::models::ProcessMemory;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SmapsRollup {
+    pub rss: u64,
+    pub pss: u64,
+    pub shared: u64,
+    pub private: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatusMemory {
+    pub rss: Option<u64>,
+    pub peak_rss: Option<u64>,
+    /// VmData + VmStk, the private writable mappings the kernel charges against the commit limit
+    pub commit: Option<u64>,
+}
+
+/// Reads `/proc/<pid>/smaps_rollup`, `status` and `stat`. Kernel threads have
+/// no memory of their own and other users' smaps need privileges, such values stay `None`.
+pub fn enumerate_process_memory() -> Result<Vec<ProcessMemory>> {
+    let mut items = Vec::new();
+
+    for entry in fs::read_dir("/proc")?.flatten() {
+        let Some(process_id) = entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+            continue;
+        };
+
+        let path = entry.path();
+        let Ok(status) = fs::read_to_string(path.join("status")) else {
+            // exited while walking /proc
+            continue;
+        };
+
+        let process_name = fs::read_to_string(path.join("comm"))
+            .map(|name| name.trim().to_string())
+            .unwrap_or_default();
+
+        let status = parse_status(&status);
+        let rollup = read_smaps_rollup(&path);
+        let page_faults = fs::read_to_string(path.join("stat")).ok().and_then(|stat| parse_page_faults(&stat));
+
+        items.push(ProcessMemory {
+            process_id,
+            process_name,
+            working_set: rollup.as_ref().map(|rollup| rollup.rss).or(status.rss),
+            private_bytes: rollup.as_ref().map(|rollup| rollup.private),
+            shared_bytes: rollup.as_ref().map(|rollup| rollup.shared),
+            proportional_bytes: rollup.as_ref().map(|rollup| rollup.pss),
+            commit_charge: status.commit,
+            peak_working_set: status.peak_rss,
+            page_faults,
+        });
+    }
+
+    Ok(items)
+}
+
+fn read_smaps_rollup(path: &Path) -> Option<SmapsRollup> {
+    fs::read_to_string(path.join("smaps_rollup"))
+        .ok()
+        .and_then(|content| parse_smaps_rollup(&content))
+}
+
+/// `Key:   1234 kB` lines into bytes
+fn parse_kb_fields(content: &str) -> HashMap<&str, u64> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let value = value.trim().strip_suffix("kB")?.trim().parse::<u64>().ok()?;
+            Some((key.trim(), value * 1024))
+        })
+        .collect()
+}
+
+/// Parses `/proc/<pid>/smaps_rollup`, empty for kernel threads
+///
+/// ```text
+/// 55d0c0a00000-7ffd3c5f1000 ---p 00000000 00:00 0                          [rollup]
+/// Rss:               10240 kB
+/// Pss:                4096 kB
+/// Shared_Clean:       6144 kB
+/// ```
+pub fn parse_smaps_rollup(content: &str) -> Option<SmapsRollup> {
+    let fields = parse_kb_fields(content);
+    let get = |key: &str| fields.get(key).copied().unwrap_or_default();
+
+    Some(SmapsRollup {
+        rss: *fields.get("Rss")?,
+        pss: get("Pss"),
+        shared: get("Shared_Clean") + get("Shared_Dirty"),
+        private: get("Private_Clean") + get("Private_Dirty"),
+    })
+}
+
+pub fn parse_status(content: &str) -> StatusMemory {
+    let fields = parse_kb_fields(content);
+    let get = |key: &str| fields.get(key).copied();
+
+    StatusMemory {
+        rss: get("VmRSS"),
+        peak_rss: get("VmHWM"),
+        commit: get("VmData").map(|data| data + get("VmStk").unwrap_or_default()),
+    }
+}
+
+/// Minor plus major faults from `/proc/<pid>/stat`. The command name may contain
+/// spaces and parentheses, fields are counted from the last `)`.
+pub fn parse_page_faults(stat: &str) -> Option<u64> {
+    let (_, rest) = stat.rsplit_once(')')?;
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+
+    // rest starts at field 3 (state); minflt is field 10, majflt field 12
+    let minor: u64 = fields.get(7)?.parse().ok()?;
+    let major: u64 = fields.get(9)?.parse().ok()?;
+
+    Some(minor + major)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KIB: u64 = 1024;
+
+    #[test]
+    fn smaps_rollup_splits_shared_and_private() {
+        let content = "55d0c0a00000-7ffd3c5f1000 ---p 00000000 00:00 0                          [rollup]
+Rss:               10240 kB
+Pss:                4096 kB
+Pss_Anon:           2048 kB
+Shared_Clean:       6144 kB
+Shared_Dirty:        512 kB
+Private_Clean:      1024 kB
+Private_Dirty:      2560 kB
+Referenced:        10240 kB
+Swap:                  0 kB
+";
+
+        assert_eq!(parse_smaps_rollup(content), Some(SmapsRollup {
+            rss: 10240 * KIB,
+            pss: 4096 * KIB,
+            shared: (6144 + 512) * KIB,
+            private: (1024 + 2560) * KIB,
+        }));
+    }
+
+    #[test]
+    fn empty_smaps_rollup_of_kernel_threads_is_none() {
+        assert_eq!(parse_smaps_rollup(""), None);
+    }
+
+    #[test]
+    fn status_gives_resident_peak_and_commit() {
+        let content = "Name:\tfirefox
+Umask:\t0022
+State:\tS (sleeping)
+Pid:\t4242
+VmPeak:\t 3145728 kB
+VmSize:\t 2097152 kB
+VmHWM:\t  524288 kB
+VmRSS:\t  262144 kB
+VmData:\t  131072 kB
+VmStk:\t     132 kB
+Threads:\t42
+";
+
+        assert_eq!(parse_status(content), StatusMemory {
+            rss: Some(262144 * KIB),
+            peak_rss: Some(524288 * KIB),
+            commit: Some((131072 + 132) * KIB),
+        });
+    }
+
+    #[test]
+    fn status_of_kernel_threads_has_no_memory() {
+        assert_eq!(parse_status("Name:\tkworker/0:1\nState:\tI (idle)\nThreads:\t1\n"), StatusMemory::default());
+    }
+
+    #[test]
+    fn page_faults_add_minor_and_major() {
+        let stat = "4242 (bash) S 1 4242 4242 34816 4242 4194304 1500 0 25 0 10 5 0 0 20 0 1 0 12345 8192000 500 18446744073709551615";
+
+        assert_eq!(parse_page_faults(stat), Some(1525));
+    }
+
+    #[test]
+    fn page_faults_skip_names_with_spaces_and_parentheses() {
+        let stat = "4243 (Web Content (x) ) R 1 4243 4243 0 -1 4194304 700 0 3 0 10 5 0 0 20 0 1 0 12345 8192000 500";
+
+        assert_eq!(parse_page_faults(stat), Some(703));
+    }
+
+    #[test]
+    fn truncated_stat_has_no_page_faults() {
+        assert_eq!(parse_page_faults("4244 (sh) S 1 4244"), None);
+        assert_eq!(parse_page_faults("4244 sh S"), None);
+    }
+}
@@ -0,0 +1,152 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+
+use crate::{
+    models::{MemoryContributor, MemoryStats, ProcessMemory, ProcessMemoryColumn, ProcessMemorySort, ProcessMemorySummary},
+    utils::format_bytes,
+};
+
+#[cfg(windows)]
+mod windows;
+#[cfg(windows)]
+use self::windows as platform;
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "linux")]
+use self::linux as platform;
+
+#[derive(Debug)]
+struct CacheEntry {
+    refreshed_on: Instant,
+    items: Vec<ProcessMemory>,
+}
+
+/// Per-process memory breakdown, heavier to collect than the process list so it has its own cache
+#[derive(Clone)]
+pub struct ProcessMemoryService {
+    query_interval: Duration,
+    cache: Arc<RwLock<CacheEntry>>,
+}
+
+impl ProcessMemoryService {
+    pub fn new() -> Self {
+        let query_interval = Duration::from_secs(2);
+
+        Self {
+            query_interval,
+            cache: Arc::new(RwLock::new(CacheEntry {
+                refreshed_on: Instant::now() - query_interval,
+                items: vec![],
+            })),
+        }
+    }
+
+    pub fn query(&self, name: Option<&str>, sort: Option<&ProcessMemorySort>) -> Result<Vec<ProcessMemory>> {
+        {
+            let guard = self.cache.read().unwrap();
+            if guard.refreshed_on.elapsed() > self.query_interval {
+                drop(guard);
+                self.refresh_cache()?;
+            }
+        }
+
+        let guard = self.cache.read().unwrap();
+        let name = name.map(str::to_lowercase);
+
+        let mut items: Vec<ProcessMemory> = guard
+            .items
+            .iter()
+            .filter(|item| name.as_ref().is_none_or(|name| item.process_name.to_lowercase().contains(name)))
+            .cloned()
+            .collect();
+
+        if let Some(sort) = sort {
+            sort_items(&mut items, sort);
+        }
+
+        Ok(items)
+    }
+
+    /// Processes ordered by how much of the used physical memory they account for
+    pub fn summary(&self, stats: &MemoryStats, top: usize) -> Result<ProcessMemorySummary> {
+        let items = self.query(None, None)?;
+        let used_phys = stats.total_phys.saturating_sub(stats.avail_phys);
+
+        let mut contributors: Vec<(u64, &ProcessMemory)> = items
+            .iter()
+            .filter_map(|item| item.proportional_bytes.map(|bytes| (bytes, item)))
+            .collect();
+
+        contributors.sort_by_key(|item| Reverse(item.0));
+
+        let attributed: u64 = contributors.iter().map(|(bytes, _)| bytes).sum();
+        let unattributed = used_phys.saturating_sub(attributed);
+
+        let top = contributors
+            .into_iter()
+            .take(top)
+            .map(|(bytes, item)| MemoryContributor {
+                process_id: item.process_id,
+                process_name: item.process_name.clone(),
+                bytes,
+                bytes_formatted: format_bytes(bytes),
+                share: if used_phys == 0 { 0.0 } else { bytes as f64 * 100.0 / used_phys as f64 },
+            })
+            .collect();
+
+        Ok(ProcessMemorySummary {
+            used_phys,
+            used_phys_formatted: format_bytes(used_phys),
+            attributed,
+            attributed_formatted: format_bytes(attributed),
+            unattributed,
+            unattributed_formatted: format_bytes(unattributed),
+            top,
+        })
+    }
+
+    fn refresh_cache(&self) -> Result<()> {
+        let items = platform::enumerate_process_memory()?;
+
+        let mut cache = self.cache.write().unwrap();
+        cache.items = items;
+        cache.refreshed_on = Instant::now();
+
+        Ok(())
+    }
+}
+
+/// Unreadable values sort after readable ones in either direction
+fn sort_items(items: &mut [ProcessMemory], sort: &ProcessMemorySort) {
+    let value = |item: &ProcessMemory| match sort.column {
+        ProcessMemoryColumn::WorkingSet => item.working_set,
+        ProcessMemoryColumn::PrivateBytes => item.private_bytes,
+        ProcessMemoryColumn::SharedBytes => item.shared_bytes,
+        ProcessMemoryColumn::ProportionalBytes => item.proportional_bytes,
+        ProcessMemoryColumn::CommitCharge => item.commit_charge,
+        ProcessMemoryColumn::PeakWorkingSet => item.peak_working_set,
+        ProcessMemoryColumn::PageFaults => item.page_faults,
+        ProcessMemoryColumn::Id | ProcessMemoryColumn::Name => None,
+    };
+
+    items.sort_by(|a, b| {
+        let ordering = match sort.column {
+            ProcessMemoryColumn::Id => a.process_id.cmp(&b.process_id),
+            ProcessMemoryColumn::Name => a.process_name.to_lowercase().cmp(&b.process_name.to_lowercase()),
+            _ => match (value(a), value(b)) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => return Ordering::Less,
+                (None, Some(_)) => return Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+        };
+
+        if sort.descending { ordering.reverse() } else { ordering }
+    });
+}
@@ -0,0 +1,109 @@
+use std::mem;
+
+use anyhow::{bail, Result};
+use winapi::{
+    shared::{basetsd::SIZE_T, minwindef::{DWORD, FALSE}, ntdef::HANDLE},
+    um::{
+        handleapi::{CloseHandle, INVALID_HANDLE_VALUE},
+        processthreadsapi::OpenProcess,
+        psapi::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS, PROCESS_MEMORY_COUNTERS_EX},
+        tlhelp32::{CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS},
+        winnt::{PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_VM_READ},
+    },
+};
+
+use crate::{models::ProcessMemory, utils::widestr_to_string};
+
+/// `PROCESS_MEMORY_COUNTERS_EX2`, Windows 10 1809 and later; not part of winapi
+#[repr(C)]
+#[allow(non_snake_case)]
+struct ProcessMemoryCountersEx2 {
+    counters: PROCESS_MEMORY_COUNTERS_EX,
+    PrivateWorkingSetSize: SIZE_T,
+    SharedCommitUsage: u64,
+}
+
+pub fn enumerate_process_memory() -> Result<Vec<ProcessMemory>> {
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+        if snapshot == INVALID_HANDLE_VALUE {
+            bail!("snapshot fail")
+        }
+
+        let mut entry: PROCESSENTRY32W = mem::zeroed();
+        entry.dwSize = mem::size_of::<PROCESSENTRY32W>() as u32;
+
+        if Process32FirstW(snapshot, &mut entry) == FALSE {
+            CloseHandle(snapshot);
+            bail!("Process32FirstW fail");
+        }
+
+        let mut items = Vec::new();
+
+        loop {
+            items.push(read_process_memory(entry.th32ProcessID, widestr_to_string(&entry.szExeFile)));
+
+            if Process32NextW(snapshot, &mut entry) == FALSE {
+                break;
+            }
+        }
+
+        CloseHandle(snapshot);
+        Ok(items)
+    }
+}
+
+unsafe fn read_process_memory(process_id: DWORD, process_name: String) -> ProcessMemory {
+    let mut item = ProcessMemory {
+        process_id,
+        process_name,
+        working_set: None,
+        private_bytes: None,
+        shared_bytes: None,
+        proportional_bytes: None,
+        commit_charge: None,
+        peak_working_set: None,
+        page_faults: None,
+    };
+
+    let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION | PROCESS_VM_READ, FALSE, process_id);
+
+    if handle.is_null() {
+        return item;
+    }
+
+    if let Some((counters, private_working_set)) = query_counters(handle) {
+        item.working_set = Some(counters.WorkingSetSize as u64);
+        item.private_bytes = Some(counters.PrivateUsage as u64);
+        item.commit_charge = Some(counters.PagefileUsage as u64);
+        item.peak_working_set = Some(counters.PeakWorkingSetSize as u64);
+        item.page_faults = Some(counters.PageFaultCount as u64);
+        item.proportional_bytes = private_working_set;
+        item.shared_bytes = private_working_set.map(|private| (counters.WorkingSetSize as u64).saturating_sub(private));
+    }
+
+    CloseHandle(handle);
+
+    item
+}
+
+/// Asks for the EX2 layout first to get the private working set, older systems reject its size
+unsafe fn query_counters(handle: HANDLE) -> Option<(PROCESS_MEMORY_COUNTERS_EX, Option<u64>)> {
+    let mut extended: ProcessMemoryCountersEx2 = mem::zeroed();
+    let size = mem::size_of::<ProcessMemoryCountersEx2>() as u32;
+    extended.counters.cb = size;
+
+    if GetProcessMemoryInfo(handle, &mut extended as *mut _ as *mut PROCESS_MEMORY_COUNTERS, size) != FALSE {
+        return Some((extended.counters, Some(extended.PrivateWorkingSetSize as u64)));
+    }
+
+    let mut counters: PROCESS_MEMORY_COUNTERS_EX = mem::zeroed();
+    let size = mem::size_of::<PROCESS_MEMORY_COUNTERS_EX>() as u32;
+    counters.cb = size;
+
+    if GetProcessMemoryInfo(handle, &mut counters as *mut _ as *mut PROCESS_MEMORY_COUNTERS, size) == FALSE {
+        return None;
+    }
+
+    Some((counters, None))
+}
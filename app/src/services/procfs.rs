@@ -0,0 +1,70 @@
+use std::{collections::HashMap, fs};
+
+/// Maps socket inodes to the first process holding a descriptor for them. Descriptors of
+/// other users' processes are not readable without privileges, their sockets stay without an owner.
+pub fn socket_owners() -> HashMap<u64, u32> {
+    let mut owners = HashMap::new();
+
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return owners;
+    };
+
+    for entry in entries.flatten() {
+        let Some(process_id) = entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+            continue;
+        };
+
+        let Ok(descriptors) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+
+        for descriptor in descriptors.flatten() {
+            let Ok(target) = fs::read_link(descriptor.path()) else {
+                continue;
+            };
+
+            if let Some(inode) = parse_socket_link(&target.to_string_lossy()) {
+                owners.entry(inode).or_insert(process_id);
+            }
+        }
+    }
+
+    owners
+}
+
+/// `socket:[12345]`
+pub fn parse_socket_link(target: &str) -> Option<u64> {
+    target.strip_prefix("socket:[")?.strip_suffix(']')?.parse().ok()
+}
+
+/// The short name the kernel keeps for the process, empty once it exited
+pub fn process_name(process_id: u32) -> String {
+    fs::read_to_string(format!("/proc/{}/comm", process_id))
+        .map(|name| name.trim().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::UdpSocket, os::unix::fs::MetadataExt};
+
+    use super::*;
+
+    #[test]
+    fn socket_links_give_the_inode() {
+        assert_eq!(parse_socket_link("socket:[12345]"), Some(12345));
+        assert_eq!(parse_socket_link("pipe:[12345]"), None);
+        assert_eq!(parse_socket_link("/tmp/socket:[1]"), None);
+    }
+
+    #[test]
+    fn finds_the_owner_of_an_own_socket() {
+        use std::os::fd::AsRawFd;
+
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let inode = fs::metadata(format!("/proc/self/fd/{}", socket.as_raw_fd())).unwrap().ino();
+
+        assert_eq!(socket_owners().get(&inode), Some(&std::process::id()));
+        assert!(!process_name(std::process::id()).is_empty());
+    }
+}
@@ -0,0 +1,57 @@
+use std::{ffi::CStr, mem, net::IpAddr, ptr};
+
+use anyhow::{bail, Result};
+
+const NI_MAXHOST: usize = 1025;
+
+/// Reverse lookup through the system resolver (nsswitch, /etc/hosts, resolv.conf)
+pub fn lookup_host(ip: IpAddr) -> Result<Option<String>> {
+    let mut host = [0 as libc::c_char; NI_MAXHOST];
+
+    let result = unsafe {
+        match ip {
+            IpAddr::V4(ip) => {
+                let mut addr: libc::sockaddr_in = mem::zeroed();
+                addr.sin_family = libc::AF_INET as libc::sa_family_t;
+                addr.sin_addr.s_addr = u32::from_ne_bytes(ip.octets());
+
+                libc::getnameinfo(
+                    &addr as *const _ as *const libc::sockaddr,
+                    mem::size_of::<libc::sockaddr_in>() as libc::socklen_t,
+                    host.as_mut_ptr(),
+                    host.len() as libc::socklen_t,
+                    ptr::null_mut(),
+                    0,
+                    libc::NI_NAMEREQD,
+                )
+            }
+            IpAddr::V6(ip) => {
+                let mut addr: libc::sockaddr_in6 = mem::zeroed();
+                addr.sin6_family = libc::AF_INET6 as libc::sa_family_t;
+                addr.sin6_addr.s6_addr = ip.octets();
+
+                libc::getnameinfo(
+                    &addr as *const _ as *const libc::sockaddr,
+                    mem::size_of::<libc::sockaddr_in6>() as libc::socklen_t,
+                    host.as_mut_ptr(),
+                    host.len() as libc::socklen_t,
+                    ptr::null_mut(),
+                    0,
+                    libc::NI_NAMEREQD,
+                )
+            }
+        }
+    };
+
+    match result {
+        0 => {
+            let name = unsafe { CStr::from_ptr(host.as_ptr()) };
+            Ok(Some(name.to_string_lossy().into_owned()))
+        }
+        libc::EAI_NONAME => Ok(None),
+        code => {
+            let message = unsafe { CStr::from_ptr(libc::gai_strerror(code)) };
+            bail!("getnameinfo failed: {}", message.to_string_lossy())
+        }
+    }
+}
@@ -0,0 +1,316 @@
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::{mpsc::{channel, Receiver, Sender}, Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use hashbrown::HashSet;
+use log::*;
+use moka::{sync::Cache, Expiry};
+
+mod query;
+
+pub use query::*;
+
+#[cfg(windows)]
+mod windows;
+#[cfg(windows)]
+use self::windows as platform;
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "linux")]
+use self::linux as platform;
+
+#[derive(Debug, Clone)]
+pub struct ResolverConfig {
+    /// DNS server to send PTR queries to, `None` uses the system resolver
+    pub server: Option<SocketAddr>,
+    pub timeout: Duration,
+    pub positive_ttl: Duration,
+    /// how long misses are kept when the answer carries no SOA to take the negative TTL from
+    pub negative_ttl: Duration,
+    pub max_entries: u64,
+    /// upper bound on cached misses so a port scan cannot flush resolved names
+    pub max_negative_entries: u64,
+}
+
+impl Default for ResolverConfig {
+    fn default() -> Self {
+        Self {
+            server: None,
+            timeout: Duration::from_secs(2),
+            positive_ttl: Duration::from_mins(30),
+            negative_ttl: Duration::from_mins(5),
+            max_entries: 10_000,
+            max_negative_entries: 2_000,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct ResolverState {
+    config: ResolverConfig,
+    resolved: Cache<IpAddr, String>,
+    /// misses with the time each one is kept
+    unresolved: Cache<IpAddr, Duration>,
+    pending: Arc<Mutex<HashSet<IpAddr>>>,
+}
+
+/// Expires each miss after the duration stored with it
+struct NegativeExpiry;
+
+impl Expiry<IpAddr, Duration> for NegativeExpiry {
+    fn expire_after_create(&self, _ip: &IpAddr, ttl: &Duration, _created_at: Instant) -> Option<Duration> {
+        Some(*ttl)
+    }
+}
+
+/// Reverse DNS lookups with a TTL cache.
+/// Misses are queued to a background worker so callers never wait on the network.
+pub struct ReverseDnsResolver {
+    state: ResolverState,
+    sender: Mutex<Sender<IpAddr>>,
+}
+
+impl ReverseDnsResolver {
+    pub fn new(config: ResolverConfig) -> Self {
+        let state = ResolverState {
+            resolved: Cache::builder()
+                .max_capacity(config.max_entries)
+                .time_to_live(config.positive_ttl)
+                .build(),
+            unresolved: Cache::builder()
+                .max_capacity(config.max_negative_entries)
+                .expire_after(NegativeExpiry)
+                .build(),
+            pending: Arc::new(Mutex::new(HashSet::new())),
+            config,
+        };
+
+        let (sender, receiver) = channel();
+
+        {
+            let state = state.clone();
+            thread::Builder::new()
+                .name("reverse-dns".into())
+                .spawn(move || Self::run_worker(state, receiver))
+                .expect("could not spawn reverse dns worker");
+        }
+
+        Self {
+            state,
+            sender: Mutex::new(sender),
+        }
+    }
+
+    /// Returns the cached host name, queues a lookup on a miss
+    pub fn lookup(&self, ip: IpAddr) -> Option<String> {
+        if let Some(host) = self.state.resolved.get(&ip) {
+            return Some(host);
+        }
+
+        if ip.is_unspecified() || self.state.unresolved.contains_key(&ip) {
+            return None;
+        }
+
+        let is_new = self.state.pending.lock().unwrap().insert(ip);
+
+        // a dead worker would leave the address pending forever
+        if is_new && self.sender.lock().unwrap().send(ip).is_err() {
+            self.state.pending.lock().unwrap().remove(&ip);
+        }
+
+        None
+    }
+
+    fn run_worker(state: ResolverState, receiver: Receiver<IpAddr>) {
+        for ip in receiver {
+            if let Err(err) = Self::resolve_into(&state, ip) {
+                debug!("reverse lookup of {} failed: {}", ip, err);
+            }
+
+            state.pending.lock().unwrap().remove(&ip);
+        }
+    }
+
+    fn resolve_into(state: &ResolverState, ip: IpAddr) -> Result<PtrAnswer> {
+        let result = match state.config.server {
+            Some(server) => query_ptr(server, ip, state.config.timeout),
+            None => platform::lookup_host(ip).map(|host| match host {
+                Some(host) => PtrAnswer::Name(host),
+                None => PtrAnswer::NoName { negative_ttl: None },
+            }),
+        };
+
+        match &result {
+            Ok(PtrAnswer::Name(host)) => state.resolved.insert(ip, host.trim_end_matches('.').to_string()),
+            Ok(PtrAnswer::NoName { negative_ttl: Some(ttl) }) => state.unresolved.insert(ip, *ttl),
+            // failures are cached as misses as well, an unreachable server should not be hammered
+            _ => state.unresolved.insert(ip, state.config.negative_ttl),
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, UdpSocket};
+
+    use super::*;
+
+    fn encode_name(name: &str) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        for label in name.split('.') {
+            bytes.push(label.len() as u8);
+            bytes.extend_from_slice(label.as_bytes());
+        }
+
+        bytes.push(0);
+        bytes
+    }
+
+    /// A record owned by the question name through a compression pointer
+    fn record(record_type: u16, ttl: u32, data: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![0xc0, 12];
+        bytes.extend_from_slice(&record_type.to_be_bytes());
+        bytes.extend_from_slice(&1u16.to_be_bytes());
+        bytes.extend_from_slice(&ttl.to_be_bytes());
+        bytes.extend_from_slice(&(data.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    fn soa(ttl: u32, minimum: u32) -> Vec<u8> {
+        let mut data = encode_name("ns.example");
+        data.extend(encode_name("hostmaster.example"));
+
+        for value in [1u32, 7200, 900, 1_209_600, minimum] {
+            data.extend_from_slice(&value.to_be_bytes());
+        }
+
+        record(6, ttl, &data)
+    }
+
+    fn response(query: &[u8], rcode: u16, answers: &[Vec<u8>], authority: &[Vec<u8>]) -> Vec<u8> {
+        let mut message = query[..2].to_vec();
+        message.extend_from_slice(&(0x8180 | rcode).to_be_bytes());
+        message.extend_from_slice(&1u16.to_be_bytes());
+        message.extend_from_slice(&(answers.len() as u16).to_be_bytes());
+        message.extend_from_slice(&(authority.len() as u16).to_be_bytes());
+        message.extend_from_slice(&0u16.to_be_bytes());
+        message.extend_from_slice(&query[12..]);
+        answers.iter().chain(authority).for_each(|record| message.extend_from_slice(record));
+        message
+    }
+
+    /// Answers `requests` queries on a loopback port with whatever `reply` builds from the query
+    fn stub_server(requests: usize, reply: impl Fn(&[u8]) -> Vec<u8> + Send + 'static) -> SocketAddr {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let address = socket.local_addr().unwrap();
+
+        thread::spawn(move || {
+            let mut buffer = [0u8; 512];
+
+            for _ in 0..requests {
+                let Ok((length, peer)) = socket.recv_from(&mut buffer) else {
+                    return;
+                };
+
+                let _ = socket.send_to(&reply(&buffer[..length]), peer);
+            }
+        });
+
+        address
+    }
+
+    const TIMEOUT: Duration = Duration::from_secs(2);
+
+    #[test]
+    fn ptr_answer_is_returned() {
+        let server = stub_server(1, |query| response(query, 0, &[record(12, 300, &encode_name("host.example"))], &[]));
+
+        let answer = query_ptr(server, IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1)), TIMEOUT).unwrap();
+
+        assert_eq!(answer, PtrAnswer::Name("host.example".to_string()));
+    }
+
+    #[test]
+    fn nxdomain_takes_the_negative_ttl_from_the_soa() {
+        let server = stub_server(1, |query| response(query, 3, &[], &[soa(3600, 60)]));
+        let ip = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 2));
+
+        assert_eq!(query_ptr(server, ip, TIMEOUT).unwrap(), PtrAnswer::NoName { negative_ttl: Some(Duration::from_secs(60)) });
+
+        let server = stub_server(1, |query| response(query, 0, &[], &[soa(30, 600)]));
+
+        assert_eq!(query_ptr(server, ip, TIMEOUT).unwrap(), PtrAnswer::NoName { negative_ttl: Some(Duration::from_secs(30)) });
+    }
+
+    #[test]
+    fn missing_soa_leaves_the_negative_ttl_open() {
+        let server = stub_server(1, |query| response(query, 3, &[], &[]));
+
+        let answer = query_ptr(server, IpAddr::V4(Ipv4Addr::new(192, 0, 2, 3)), TIMEOUT).unwrap();
+
+        assert_eq!(answer, PtrAnswer::NoName { negative_ttl: None });
+    }
+
+    #[test]
+    fn server_failure_is_an_error() {
+        let server = stub_server(1, |query| response(query, 2, &[], &[]));
+
+        assert!(query_ptr(server, IpAddr::V4(Ipv4Addr::new(192, 0, 2, 4)), TIMEOUT).is_err());
+    }
+
+    #[test]
+    fn reserved_label_types_are_rejected() {
+        let query = build_ptr_query(7, IpAddr::V4(Ipv4Addr::new(192, 0, 2, 5)));
+
+        for label_type in [0x40u8, 0x80] {
+            let mut data = vec![label_type | 3];
+            data.extend_from_slice(b"abc\0");
+
+            let message = response(&query, 0, &[record(12, 300, &data)], &[]);
+
+            assert!(parse_ptr_response(7, &message).is_err());
+        }
+    }
+
+    #[test]
+    fn pointer_loops_are_rejected() {
+        let query = build_ptr_query(8, IpAddr::V4(Ipv4Addr::new(192, 0, 2, 6)));
+        let mut message = response(&query, 0, &[], &[]);
+        let answer_offset = message.len();
+
+        // the answer name points at itself
+        message[6..8].copy_from_slice(&1u16.to_be_bytes());
+        message.extend_from_slice(&[0xc0 | (answer_offset >> 8) as u8, answer_offset as u8]);
+        message.extend_from_slice(&[0u8; 10]);
+
+        assert!(parse_ptr_response(8, &message).is_err());
+    }
+
+    #[test]
+    fn resolver_answers_from_the_cache_after_the_background_lookup() {
+        let server = stub_server(1, |query| response(query, 0, &[record(12, 300, &encode_name("cached.example"))], &[]));
+        let resolver = ReverseDnsResolver::new(ResolverConfig { server: Some(server), ..ResolverConfig::default() });
+        let ip = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 7));
+
+        assert_eq!(resolver.lookup(ip), None);
+
+        let started = Instant::now();
+        let mut host = None;
+
+        while host.is_none() && started.elapsed() < TIMEOUT {
+            thread::sleep(Duration::from_millis(10));
+            host = resolver.lookup(ip);
+        }
+
+        assert_eq!(host.as_deref(), Some("cached.example"));
+    }
+}
@@ -0,0 +1,238 @@
+use std::{
+    net::{IpAddr, SocketAddr, UdpSocket},
+    time::Duration,
+};
+
+use anyhow::{bail, Result};
+
+const TYPE_PTR: u16 = 12;
+const TYPE_SOA: u16 = 6;
+const CLASS_IN: u16 = 1;
+const RCODE_NX_DOMAIN: u8 = 3;
+const MAX_POINTER_JUMPS: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PtrAnswer {
+    Name(String),
+    /// NXDOMAIN or no PTR record. The negative TTL is the lower of the authority SOA's TTL
+    /// and MINIMUM field (RFC 2308), missing when the server sent no SOA.
+    NoName { negative_ttl: Option<Duration> },
+}
+
+/// Sends a PTR query over UDP to the given server
+pub fn query_ptr(server: SocketAddr, ip: IpAddr, timeout: Duration) -> Result<PtrAnswer> {
+    let bind_addr: SocketAddr = match server {
+        SocketAddr::V4(_) => "0.0.0.0:0".parse()?,
+        SocketAddr::V6(_) => "[::]:0".parse()?,
+    };
+
+    let socket = UdpSocket::bind(bind_addr)?;
+    socket.set_read_timeout(Some(timeout))?;
+    socket.connect(server)?;
+
+    let id: u16 = rand::random();
+    socket.send(&build_ptr_query(id, ip))?;
+
+    let mut buffer = [0u8; 1500];
+
+    loop {
+        let length = socket.recv(&mut buffer)?;
+
+        // ignore stray datagrams from earlier queries
+        if length >= 2 && u16::from_be_bytes([buffer[0], buffer[1]]) == id {
+            return parse_ptr_response(id, &buffer[..length]);
+        }
+    }
+}
+
+/// `1.2.0.192.in-addr.arpa` / nibble reversed `ip6.arpa` name for the address
+pub fn reverse_name(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, c, d] = ip.octets();
+            format!("{}.{}.{}.{}.in-addr.arpa", d, c, b, a)
+        }
+        IpAddr::V6(ip) => {
+            let mut name = String::with_capacity(72);
+
+            for byte in ip.octets().iter().rev() {
+                name.push_str(&format!("{:x}.{:x}.", byte & 0x0f, byte >> 4));
+            }
+
+            name.push_str("ip6.arpa");
+            name
+        }
+    }
+}
+
+pub fn build_ptr_query(id: u16, ip: IpAddr) -> Vec<u8> {
+    let mut message = Vec::with_capacity(96);
+
+    message.extend_from_slice(&id.to_be_bytes());
+    // standard query, recursion desired
+    message.extend_from_slice(&0x0100u16.to_be_bytes());
+    // QDCOUNT, ANCOUNT, NSCOUNT, ARCOUNT
+    message.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 0]);
+
+    for label in reverse_name(ip).split('.') {
+        message.push(label.len() as u8);
+        message.extend_from_slice(label.as_bytes());
+    }
+
+    message.push(0);
+    message.extend_from_slice(&TYPE_PTR.to_be_bytes());
+    message.extend_from_slice(&CLASS_IN.to_be_bytes());
+
+    message
+}
+
+pub fn parse_ptr_response(id: u16, message: &[u8]) -> Result<PtrAnswer> {
+    if message.len() < 12 {
+        bail!("DNS response too short");
+    }
+
+    if u16::from_be_bytes([message[0], message[1]]) != id {
+        bail!("DNS response id mismatch");
+    }
+
+    let flags = u16::from_be_bytes([message[2], message[3]]);
+    let is_response = flags & 0x8000 != 0;
+    let rcode = (flags & 0x000f) as u8;
+
+    if !is_response {
+        bail!("DNS message is not a response");
+    }
+
+    if rcode != 0 && rcode != RCODE_NX_DOMAIN {
+        bail!("DNS server returned rcode {}", rcode);
+    }
+
+    let question_count = u16::from_be_bytes([message[4], message[5]]);
+    let answer_count = u16::from_be_bytes([message[6], message[7]]);
+    let authority_count = u16::from_be_bytes([message[8], message[9]]);
+    let mut offset = 12;
+
+    for _ in 0..question_count {
+        let (_, next) = read_name(message, offset)?;
+        // QTYPE + QCLASS
+        offset = next + 4;
+    }
+
+    for _ in 0..answer_count {
+        let record = read_record(message, offset)?;
+
+        if record.record_type == TYPE_PTR {
+            let (name, _) = read_name(message, record.data_offset)?;
+            return Ok(PtrAnswer::Name(name));
+        }
+
+        offset = record.next;
+    }
+
+    let mut negative_ttl = None;
+
+    for _ in 0..authority_count {
+        let record = read_record(message, offset)?;
+
+        if record.record_type == TYPE_SOA {
+            // MNAME and RNAME, then SERIAL, REFRESH, RETRY, EXPIRE and MINIMUM
+            let (_, next) = read_name(message, record.data_offset)?;
+            let (_, next) = read_name(message, next)?;
+
+            let Some(minimum) = message.get(next + 16..next + 20).filter(|_| next + 20 <= record.next) else {
+                bail!("truncated DNS SOA record");
+            };
+
+            let minimum = u32::from_be_bytes([minimum[0], minimum[1], minimum[2], minimum[3]]);
+            negative_ttl = Some(Duration::from_secs(record.ttl.min(minimum) as u64));
+            break;
+        }
+
+        offset = record.next;
+    }
+
+    Ok(PtrAnswer::NoName { negative_ttl })
+}
+
+struct Record {
+    record_type: u16,
+    ttl: u32,
+    data_offset: usize,
+    /// offset of the record after this one
+    next: usize,
+}
+
+fn read_record(message: &[u8], offset: usize) -> Result<Record> {
+    let (_, offset) = read_name(message, offset)?;
+
+    let Some(header) = message.get(offset..offset + 10) else {
+        bail!("truncated DNS record");
+    };
+
+    let data_length = u16::from_be_bytes([header[8], header[9]]) as usize;
+    let data_offset = offset + 10;
+
+    if data_offset + data_length > message.len() {
+        bail!("truncated DNS record data");
+    }
+
+    Ok(Record {
+        record_type: u16::from_be_bytes([header[0], header[1]]),
+        ttl: u32::from_be_bytes([header[4], header[5], header[6], header[7]]),
+        data_offset,
+        next: data_offset + data_length,
+    })
+}
+
+/// Reads a possibly compressed domain name, returns it with the offset right after it
+fn read_name(message: &[u8], start: usize) -> Result<(String, usize)> {
+    let mut labels: Vec<String> = Vec::new();
+    let mut offset = start;
+    let mut end = None;
+    let mut jumps = 0;
+
+    loop {
+        let Some(&length) = message.get(offset) else {
+            bail!("truncated DNS name");
+        };
+
+        match length {
+            0 => {
+                offset += 1;
+                break;
+            }
+            length if length & 0xc0 == 0xc0 => {
+                let Some(&low) = message.get(offset + 1) else {
+                    bail!("truncated DNS name pointer");
+                };
+
+                if end.is_none() {
+                    end = Some(offset + 2);
+                }
+
+                jumps += 1;
+
+                if jumps > MAX_POINTER_JUMPS {
+                    bail!("DNS name pointer loop");
+                }
+
+                offset = (((length & 0x3f) as usize) << 8) | low as usize;
+            }
+            // 0x40 (extended label types, RFC 6891) and 0x80 are not valid here
+            length if length & 0xc0 != 0 => bail!("reserved DNS label type {:#04x}", length & 0xc0),
+            length => {
+                let label_start = offset + 1;
+                let label_end = label_start + length as usize;
+
+                let Some(label) = message.get(label_start..label_end) else {
+                    bail!("truncated DNS label");
+                };
+
+                labels.push(String::from_utf8_lossy(label).into_owned());
+                offset = label_end;
+            }
+        }
+    }
+
+    Ok((labels.join("."), end.unwrap_or(offset)))
+}
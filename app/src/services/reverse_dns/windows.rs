@@ -0,0 +1,56 @@
+use std::{net::IpAddr, ptr};
+
+use anyhow::{bail, Result};
+use widestring::{U16CStr, WideCString};
+use winapi::{
+    shared::winerror::{DNS_ERROR_RCODE_NAME_ERROR, DNS_INFO_NO_RECORDS},
+    um::windns::{DnsFree, DnsFreeRecordList, DnsQuery_W, DNS_QUERY_STANDARD, DNS_TYPE_PTR, PDNS_RECORD},
+};
+
+use super::query::reverse_name;
+
+/// Reverse lookup through the Windows DNS client, honours the hosts file and the local cache
+pub fn lookup_host(ip: IpAddr) -> Result<Option<String>> {
+    let name = WideCString::from_str(reverse_name(ip))?;
+    let mut records: PDNS_RECORD = ptr::null_mut();
+
+    unsafe {
+        let status = DnsQuery_W(
+            name.as_ptr(),
+            DNS_TYPE_PTR,
+            DNS_QUERY_STANDARD,
+            ptr::null_mut(),
+            &mut records,
+            ptr::null_mut(),
+        );
+
+        if status == DNS_ERROR_RCODE_NAME_ERROR || status == DNS_INFO_NO_RECORDS {
+            return Ok(None);
+        }
+
+        if status != 0 {
+            bail!("DnsQuery_W failed: {}", status);
+        }
+
+        let mut host = None;
+        let mut record_ptr = records;
+
+        while !record_ptr.is_null() {
+            let record = &*record_ptr;
+            record_ptr = record.pNext;
+
+            if record.wType == DNS_TYPE_PTR {
+                let name_host = record.Data.PTR().pNameHost;
+
+                if !name_host.is_null() {
+                    host = Some(U16CStr::from_ptr_str(name_host).to_string_lossy());
+                    break;
+                }
+            }
+        }
+
+        DnsFree(records as *mut _, DnsFreeRecordList);
+
+        Ok(host)
+    }
+}
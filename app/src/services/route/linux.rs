@@ -0,0 +1,373 @@
+use std::{
+    ffi::CStr,
+    fs,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+use anyhow::Result;
+use ipnetwork::IpNetwork;
+use log::*;
+
+use crate::{models::{Neighbour, NeighbourState, Route}, services::netlink, utils::format_mac};
+
+const RTF_UP: u32 = 0x0001;
+const RTF_GATEWAY: u32 = 0x0002;
+const RTF_REJECT: u32 = 0x0200;
+const ATF_COM: u32 = 0x02;
+const ATF_PERM: u32 = 0x04;
+
+const RTM_GETNEIGH: u16 = 30;
+const RTM_NEWNEIGH: u16 = 28;
+const NDMSG_LEN: usize = 12;
+const NDA_DST: u16 = 1;
+const NDA_LLADDR: u16 = 2;
+
+const NUD_INCOMPLETE: u16 = 0x01;
+const NUD_REACHABLE: u16 = 0x02;
+const NUD_STALE: u16 = 0x04;
+const NUD_DELAY: u16 = 0x08;
+const NUD_PROBE: u16 = 0x10;
+const NUD_FAILED: u16 = 0x20;
+const NUD_NOARP: u16 = 0x40;
+const NUD_PERMANENT: u16 = 0x80;
+
+/// A `RTM_NEWNEIGH` reply of the neighbour dump
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NeighbourRow {
+    pub ip_address: IpAddr,
+    pub mac_address: Option<String>,
+    pub interface_index: u32,
+    /// the kernel's `NUD_*` state bits
+    pub state: u16,
+}
+
+pub fn enumerate_routes() -> Result<Vec<Route>> {
+    let mut routes = parse_ipv4_routes(&fs::read_to_string("/proc/net/route")?);
+    // IPv6 can be disabled on the kernel command line
+    routes.extend(parse_ipv6_routes(&fs::read_to_string("/proc/net/ipv6_route").unwrap_or_default()));
+    Ok(routes)
+}
+
+/// Only the IPv4 neighbour cache is exposed through procfs,
+/// NDP entries are dumped over netlink
+pub fn enumerate_neighbours() -> Result<Vec<Neighbour>> {
+    let mut neighbours = parse_arp(&fs::read_to_string("/proc/net/arp")?);
+
+    match query_neighbours(libc::AF_INET6 as u8) {
+        Ok(rows) => neighbours.extend(rows.into_iter().filter(|row| row.state & NUD_NOARP == 0).map(|row| Neighbour {
+            ip_address: row.ip_address,
+            mac_address: row.mac_address,
+            interface: interface_name(row.interface_index),
+            state: neighbour_state(row.state),
+        })),
+        // IPv6 can be disabled on the kernel command line
+        Err(err) => debug!("IPv6 neighbour dump failed: {}", err),
+    }
+
+    Ok(neighbours)
+}
+
+/// Dumps the neighbour cache of `family` over NETLINK_ROUTE
+fn query_neighbours(family: u8) -> Result<Vec<NeighbourRow>> {
+    let mut rows = Vec::new();
+
+    netlink::dump(libc::NETLINK_ROUTE, RTM_GETNEIGH, &build_neighbour_request(family), |datagram| {
+        parse_neighbours(datagram, &mut rows)
+    })?;
+
+    Ok(rows)
+}
+
+/// `ndmsg` of any interface and state
+fn build_neighbour_request(family: u8) -> Vec<u8> {
+    let mut request = vec![family, 0, 0, 0];
+    request.extend_from_slice(&0i32.to_ne_bytes());
+    request.extend_from_slice(&0u16.to_ne_bytes());
+    request.push(0);
+    request.push(0);
+
+    request
+}
+
+/// Parses one datagram of `RTM_NEWNEIGH` replies.
+/// Returns true once the dump is complete.
+pub fn parse_neighbours(buffer: &[u8], rows: &mut Vec<NeighbourRow>) -> Result<bool> {
+    netlink::parse_messages(buffer, |message_type, message| {
+        if message_type != RTM_NEWNEIGH || message.len() < NDMSG_LEN {
+            return Ok(());
+        }
+
+        let interface_index = netlink::read_u32(message, 4);
+        let state = netlink::read_u16(message, 8);
+        let mut ip_address = None;
+        let mut mac_address = None;
+
+        for (attribute_type, value) in netlink::attributes(&message[NDMSG_LEN..]) {
+            match (attribute_type, value.len()) {
+                (NDA_DST, 4) => ip_address = Some(IpAddr::from(<[u8; 4]>::try_from(value)?)),
+                (NDA_DST, 16) => ip_address = Some(IpAddr::from(<[u8; 16]>::try_from(value)?)),
+                (NDA_LLADDR, _) => mac_address = Some(format_mac(value)).filter(|mac| !mac.is_empty()),
+                _ => {}
+            }
+        }
+
+        if let Some(ip_address) = ip_address {
+            rows.push(NeighbourRow { ip_address, mac_address, interface_index, state });
+        }
+
+        Ok(())
+    })
+}
+
+fn interface_name(index: u32) -> String {
+    let mut name = [0 as libc::c_char; libc::IF_NAMESIZE];
+
+    unsafe {
+        if libc::if_indextoname(index, name.as_mut_ptr()).is_null() {
+            return index.to_string();
+        }
+
+        CStr::from_ptr(name.as_ptr()).to_string_lossy().into_owned()
+    }
+}
+
+/// The highest `NUD_*` bit wins, the kernel sets one at a time outside of transitions
+fn neighbour_state(state: u16) -> NeighbourState {
+    if state & NUD_PERMANENT != 0 {
+        NeighbourState::Permanent
+    } else if state & NUD_FAILED != 0 {
+        NeighbourState::Unreachable
+    } else if state & NUD_PROBE != 0 {
+        NeighbourState::Probe
+    } else if state & NUD_DELAY != 0 {
+        NeighbourState::Delay
+    } else if state & NUD_STALE != 0 {
+        NeighbourState::Stale
+    } else if state & NUD_REACHABLE != 0 {
+        NeighbourState::Reachable
+    } else if state & NUD_INCOMPLETE != 0 {
+        NeighbourState::Incomplete
+    } else {
+        NeighbourState::Unknown
+    }
+}
+
+/// Parses `/proc/net/route`
+///
+/// ```text
+/// Iface   Destination Gateway  Flags RefCnt Use Metric Mask     MTU Window IRTT
+/// eth0    00000000    0101A8C0 0003  0      0   100    00000000 0   0      0
+/// ```
+pub fn parse_ipv4_routes(content: &str) -> Vec<Route> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+
+            if columns.len() < 8 {
+                return None;
+            }
+
+            let flags = u32::from_str_radix(columns[3], 16).ok()?;
+
+            if flags & RTF_UP == 0 {
+                return None;
+            }
+
+            // the in-memory `__be32` values are printed as native integers
+            let destination = Ipv4Addr::from(u32::from_str_radix(columns[1], 16).ok()?.to_ne_bytes());
+            let gateway = Ipv4Addr::from(u32::from_str_radix(columns[2], 16).ok()?.to_ne_bytes());
+            let mask = Ipv4Addr::from(u32::from_str_radix(columns[7], 16).ok()?.to_ne_bytes());
+            let metric = columns[6].parse().ok()?;
+
+            Some(Route {
+                destination: IpNetwork::with_netmask(IpAddr::V4(destination), IpAddr::V4(mask)).ok()?,
+                gateway: (flags & RTF_GATEWAY != 0).then_some(IpAddr::V4(gateway)),
+                interface: columns[0].to_string(),
+                metric,
+                protocol: None,
+            })
+        })
+        .collect()
+}
+
+/// Parses `/proc/net/ipv6_route`
+///
+/// ```text
+/// destination                      plen source                           plen next_hop                         metric   refcnt   use      flags    iface
+/// 00000000000000000000000000000000 00   00000000000000000000000000000000 00   fe800000000000000000000000000001 00000400 00000001 00000000 00000003 eth0
+/// ```
+pub fn parse_ipv6_routes(content: &str) -> Vec<Route> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+
+            if columns.len() < 10 {
+                return None;
+            }
+
+            let flags = u32::from_str_radix(columns[8], 16).ok()?;
+
+            if flags & RTF_UP == 0 || flags & RTF_REJECT != 0 {
+                return None;
+            }
+
+            let destination = parse_ipv6_hex(columns[0])?;
+            let prefix = u8::from_str_radix(columns[1], 16).ok()?;
+            let next_hop = parse_ipv6_hex(columns[4])?;
+            let metric = u32::from_str_radix(columns[5], 16).ok()?;
+
+            Some(Route {
+                destination: IpNetwork::new(IpAddr::V6(destination), prefix).ok()?,
+                gateway: (!next_hop.is_unspecified()).then_some(IpAddr::V6(next_hop)),
+                interface: columns[9].to_string(),
+                metric,
+                protocol: None,
+            })
+        })
+        .collect()
+}
+
+pub fn parse_ipv6_hex(value: &str) -> Option<Ipv6Addr> {
+    if value.len() != 32 {
+        return None;
+    }
+
+    u128::from_str_radix(value, 16).ok().map(Ipv6Addr::from)
+}
+
+/// Parses `/proc/net/arp`
+///
+/// ```text
+/// IP address       HW type     Flags       HW address            Mask     Device
+/// 192.168.1.1      0x1         0x2         aa:bb:cc:dd:ee:ff     *        eth0
+/// ```
+pub fn parse_arp(content: &str) -> Vec<Neighbour> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+
+            if columns.len() < 6 {
+                return None;
+            }
+
+            let flags = u32::from_str_radix(columns[2].trim_start_matches("0x"), 16).ok()?;
+            let state = if flags & ATF_PERM != 0 {
+                NeighbourState::Permanent
+            } else if flags & ATF_COM != 0 {
+                NeighbourState::Reachable
+            } else {
+                NeighbourState::Incomplete
+            };
+
+            Some(Neighbour {
+                ip_address: columns[0].parse().ok()?,
+                mac_address: Some(columns[3].to_string()).filter(|mac| mac != "00:00:00:00:00:00"),
+                interface: columns[5].to_string(),
+                state,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::services::netlink::{NLMSG_DONE, NLMSG_HEADER_LEN, NLM_F_DUMP};
+
+    use super::*;
+
+    fn neighbour_message(destination: &[u8], lladdr: &[u8], interface_index: u32, state: u16) -> Vec<u8> {
+        let attribute = |kind: u16, value: &[u8]| {
+            let mut bytes = ((4 + value.len()) as u16).to_ne_bytes().to_vec();
+            bytes.extend_from_slice(&kind.to_ne_bytes());
+            bytes.extend_from_slice(value);
+            bytes.resize((bytes.len() + 3) & !3, 0);
+            bytes
+        };
+
+        let mut payload = vec![libc::AF_INET6 as u8, 0, 0, 0];
+        payload.extend_from_slice(&interface_index.to_ne_bytes());
+        payload.extend_from_slice(&state.to_ne_bytes());
+        payload.extend_from_slice(&[0, 0]);
+        payload.extend(attribute(NDA_DST, destination));
+        payload.extend(attribute(NDA_LLADDR, lladdr));
+
+        let mut message = ((NLMSG_HEADER_LEN + payload.len()) as u32).to_ne_bytes().to_vec();
+        message.extend_from_slice(&RTM_NEWNEIGH.to_ne_bytes());
+        message.extend_from_slice(&NLM_F_DUMP.to_ne_bytes());
+        message.extend_from_slice(&[0u8; 8]);
+        message.extend(payload);
+        message
+    }
+
+    fn done_message() -> Vec<u8> {
+        let mut message = (NLMSG_HEADER_LEN as u32).to_ne_bytes().to_vec();
+        message.extend_from_slice(&NLMSG_DONE.to_ne_bytes());
+        message.extend_from_slice(&[0u8; 10]);
+        message
+    }
+
+    /// the fixture was captured on a little-endian machine
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn ipv4_routes_decode_native_order_addresses() {
+        let content = "Iface\tDestination\tGateway\tFlags\tRefCnt\tUse\tMetric\tMask\tMTU\tWindow\tIRTT\n\
+            eth0\t00000000\t0101A8C0\t0003\t0\t0\t100\t00000000\t0\t0\t0\n\
+            eth0\t0001A8C0\t00000000\t0001\t0\t0\t100\t00FFFFFF\t0\t0\t0\n";
+
+        let routes = parse_ipv4_routes(content);
+
+        assert_eq!(routes.len(), 2);
+        assert_eq!(routes[0].gateway, Some(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1))));
+        assert_eq!(routes[0].destination.prefix(), 0);
+        assert_eq!(routes[1].gateway, None);
+        assert_eq!(routes[1].destination.ip(), IpAddr::V4(Ipv4Addr::new(192, 168, 1, 0)));
+        assert_eq!(routes[1].destination.prefix(), 24);
+    }
+
+    #[test]
+    fn neighbour_dump_is_parsed_until_done() {
+        let address: Ipv6Addr = "fe80::1".parse().unwrap();
+        let mut buffer = neighbour_message(&address.octets(), &[0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff], 2, NUD_STALE);
+        buffer.extend(done_message());
+
+        let mut rows = Vec::new();
+
+        assert!(parse_neighbours(&buffer, &mut rows).unwrap());
+        assert_eq!(rows, vec![NeighbourRow {
+            ip_address: IpAddr::V6(address),
+            mac_address: Some("aa:bb:cc:dd:ee:ff".to_string()),
+            interface_index: 2,
+            state: NUD_STALE,
+        }]);
+    }
+
+    #[test]
+    fn neighbour_dump_continues_without_done() {
+        let buffer = neighbour_message(&[0u8; 16], &[], 1, NUD_INCOMPLETE);
+        let mut rows = Vec::new();
+
+        assert!(!parse_neighbours(&buffer, &mut rows).unwrap());
+        assert_eq!(rows[0].mac_address, None);
+    }
+
+    #[test]
+    fn truncated_neighbour_message_is_rejected() {
+        let buffer = neighbour_message(&[0u8; 16], &[], 1, NUD_REACHABLE);
+        let mut rows = Vec::new();
+
+        assert!(parse_neighbours(&buffer[..buffer.len() - 4], &mut rows).is_err());
+    }
+
+    #[test]
+    fn neighbour_states_prefer_the_highest_bit() {
+        assert_eq!(neighbour_state(NUD_PERMANENT | NUD_REACHABLE), NeighbourState::Permanent);
+        assert_eq!(neighbour_state(NUD_FAILED), NeighbourState::Unreachable);
+        assert_eq!(neighbour_state(NUD_DELAY), NeighbourState::Delay);
+        assert_eq!(neighbour_state(0), NeighbourState::Unknown);
+    }
+}
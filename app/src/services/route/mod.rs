@@ -0,0 +1,113 @@
+use std::{
+    net::IpAddr,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+
+use crate::models::{Neighbour, Route, RouteLookup};
+
+#[cfg(windows)]
+mod windows;
+#[cfg(windows)]
+use self::windows as platform;
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "linux")]
+use self::linux as platform;
+#[cfg(target_os = "linux")]
+pub use self::linux::{parse_ipv4_routes, parse_ipv6_routes};
+
+#[derive(Debug)]
+struct CacheEntry {
+    refreshed_on: Instant,
+    routes: Vec<Route>,
+    neighbours: Vec<Neighbour>,
+}
+
+pub struct RouteService {
+    query_interval: Duration,
+    cache: Arc<RwLock<CacheEntry>>,
+}
+
+impl RouteService {
+    pub fn new() -> Self {
+        let query_interval = Duration::from_secs(5);
+
+        Self {
+            query_interval,
+            cache: Arc::new(RwLock::new(CacheEntry {
+                refreshed_on: Instant::now() - query_interval,
+                routes: vec![],
+                neighbours: vec![],
+            })),
+        }
+    }
+
+    pub fn get_routes(&self) -> Result<Vec<Route>> {
+        self.refresh_if_stale()?;
+        let guard = self.cache.read().unwrap();
+        Ok(guard.routes.clone())
+    }
+
+    pub fn get_neighbours(&self) -> Result<Vec<Neighbour>> {
+        self.refresh_if_stale()?;
+        let guard = self.cache.read().unwrap();
+        Ok(guard.neighbours.clone())
+    }
+
+    /// Which route and interface a packet to `destination` would leave through
+    pub fn lookup(&self, destination: IpAddr) -> Result<RouteLookup> {
+        self.refresh_if_stale()?;
+        let guard = self.cache.read().unwrap();
+
+        let route = find_route(&guard.routes, destination).cloned();
+        let next_hop = route.as_ref().and_then(|route| {
+            let next_hop = route.gateway.unwrap_or(destination);
+
+            guard.neighbours
+                .iter()
+                .find(|neighbour| neighbour.ip_address == next_hop && neighbour.interface == route.interface)
+                .cloned()
+        });
+
+        Ok(RouteLookup {
+            destination,
+            route,
+            next_hop,
+        })
+    }
+
+    fn refresh_if_stale(&self) -> Result<()> {
+        {
+            let guard = self.cache.read().unwrap();
+            if guard.refreshed_on.elapsed() <= self.query_interval {
+                return Ok(());
+            }
+        }
+
+        let routes = platform::enumerate_routes()?;
+        let neighbours = platform::enumerate_neighbours()?;
+
+        let mut guard = self.cache.write().unwrap();
+        guard.routes = routes;
+        guard.neighbours = neighbours;
+        guard.refreshed_on = Instant::now();
+
+        Ok(())
+    }
+}
+
+/// Longest prefix match, the lowest metric wins between equally specific routes
+pub fn find_route(routes: &[Route], destination: IpAddr) -> Option<&Route> {
+    routes
+        .iter()
+        .filter(|route| route.destination.contains(destination))
+        .min_by(|a, b| {
+            b.destination.prefix()
+                .cmp(&a.destination.prefix())
+                .then_with(|| a.metric.cmp(&b.metric))
+        })
+}
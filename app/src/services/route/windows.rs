@@ -0,0 +1,159 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    ptr, slice,
+};
+
+use anyhow::{bail, Result};
+use ipnetwork::IpNetwork;
+use widestring::U16CStr;
+use winapi::shared::{
+    ifdef::NET_LUID,
+    netioapi::{ConvertInterfaceLuidToAlias, FreeMibTable, GetIpForwardTable2, GetIpNetTable2, PMIB_IPFORWARD_TABLE2, PMIB_IPNET_TABLE2},
+    nldef::{NlnsDelay, NlnsIncomplete, NlnsPermanent, NlnsProbe, NlnsReachable, NlnsStale, NlnsUnreachable, NL_NEIGHBOR_STATE},
+    winerror::NO_ERROR,
+    ws2def::{AF_INET, AF_INET6, AF_UNSPEC},
+    ws2ipdef::SOCKADDR_INET,
+};
+
+use crate::{models::{Neighbour, NeighbourState, Route}, utils::format_mac};
+
+pub fn enumerate_routes() -> Result<Vec<Route>> {
+    unsafe {
+        let mut table: PMIB_IPFORWARD_TABLE2 = ptr::null_mut();
+        let status = GetIpForwardTable2(AF_UNSPEC as u16, &mut table);
+
+        if status != NO_ERROR {
+            bail!("GetIpForwardTable2 failed: {}", status);
+        }
+
+        let rows = slice::from_raw_parts((*table).Table.as_ptr(), (*table).NumEntries as usize);
+        let mut aliases = HashMap::new();
+        let mut routes = Vec::with_capacity(rows.len());
+
+        for row in rows {
+            let Some(prefix) = sockaddr_inet_to_ip(&row.DestinationPrefix.Prefix) else {
+                continue;
+            };
+
+            let Ok(destination) = IpNetwork::new(prefix, row.DestinationPrefix.PrefixLength) else {
+                continue;
+            };
+
+            let gateway = sockaddr_inet_to_ip(&row.NextHop).filter(|ip| !ip.is_unspecified());
+
+            routes.push(Route {
+                destination,
+                gateway,
+                interface: interface_alias(&mut aliases, &row.InterfaceLuid),
+                metric: row.Metric,
+                protocol: Some(route_protocol_name(row.Protocol as u32).to_string()),
+            });
+        }
+
+        FreeMibTable(table as *mut _);
+
+        Ok(routes)
+    }
+}
+
+pub fn enumerate_neighbours() -> Result<Vec<Neighbour>> {
+    unsafe {
+        let mut table: PMIB_IPNET_TABLE2 = ptr::null_mut();
+        let status = GetIpNetTable2(AF_UNSPEC as u16, &mut table);
+
+        if status != NO_ERROR {
+            bail!("GetIpNetTable2 failed: {}", status);
+        }
+
+        let rows = slice::from_raw_parts((*table).Table.as_ptr(), (*table).NumEntries as usize);
+        let mut aliases = HashMap::new();
+        let mut neighbours = Vec::with_capacity(rows.len());
+
+        for row in rows {
+            let Some(ip_address) = sockaddr_inet_to_ip(&row.Address) else {
+                continue;
+            };
+
+            let mac_length = (row.PhysicalAddressLength as usize).min(row.PhysicalAddress.len());
+
+            neighbours.push(Neighbour {
+                ip_address,
+                mac_address: Some(format_mac(&row.PhysicalAddress[..mac_length])).filter(|mac| !mac.is_empty()),
+                interface: interface_alias(&mut aliases, &row.InterfaceLuid),
+                state: to_neighbour_state(row.State),
+            });
+        }
+
+        FreeMibTable(table as *mut _);
+
+        Ok(neighbours)
+    }
+}
+
+unsafe fn interface_alias(aliases: &mut HashMap<u64, String>, luid: &NET_LUID) -> String {
+    aliases
+        .entry(*luid.Value())
+        .or_insert_with(|| {
+            let mut buffer = [0u16; 257];
+
+            if ConvertInterfaceLuidToAlias(luid, buffer.as_mut_ptr(), buffer.len()) != NO_ERROR {
+                return format!("{:x}", luid.Value());
+            }
+
+            U16CStr::from_slice_truncate(&buffer)
+                .map(|alias| alias.to_string_lossy())
+                .unwrap_or_default()
+        })
+        .clone()
+}
+
+unsafe fn sockaddr_inet_to_ip(address: &SOCKADDR_INET) -> Option<IpAddr> {
+    match *address.si_family() as i32 {
+        AF_INET => Some(IpAddr::V4(Ipv4Addr::from(u32::from_be(*address.Ipv4().sin_addr.S_un.S_addr())))),
+        AF_INET6 => Some(IpAddr::V6(Ipv6Addr::from(*address.Ipv6().sin6_addr.u.Byte()))),
+        _ => None,
+    }
+}
+
+fn to_neighbour_state(state: NL_NEIGHBOR_STATE) -> NeighbourState {
+    match state {
+        NlnsUnreachable => NeighbourState::Unreachable,
+        NlnsIncomplete => NeighbourState::Incomplete,
+        NlnsProbe => NeighbourState::Probe,
+        NlnsDelay => NeighbourState::Delay,
+        NlnsStale => NeighbourState::Stale,
+        NlnsReachable => NeighbourState::Reachable,
+        NlnsPermanent => NeighbourState::Permanent,
+        _ => NeighbourState::Unknown,
+    }
+}
+
+/// NL_ROUTE_PROTOCOL / MIB_IPFORWARD_PROTO values
+fn route_protocol_name(protocol: u32) -> &'static str {
+    match protocol {
+        1 => "other",
+        2 => "local",
+        3 => "static",
+        4 => "icmp",
+        5 => "egp",
+        6 => "ggp",
+        7 => "hello",
+        8 => "rip",
+        9 => "is-is",
+        10 => "es-is",
+        11 => "cisco",
+        12 => "bbn",
+        13 => "ospf",
+        14 => "bgp",
+        15 => "idpr",
+        16 => "eigrp",
+        17 => "dvmrp",
+        18 => "rpl",
+        19 => "dhcp",
+        10002 => "autostatic",
+        10006 => "static",
+        10007 => "static (non dod)",
+        _ => "unknown",
+    }
+}
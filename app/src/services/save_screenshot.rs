@@ -1,6 +1,5 @@
-use std::{path::{Path, PathBuf}, ptr, sync::{Arc, RwLock}, time::{Duration, Instant}};
-use anyhow::{bail, Result};
-use log::*;
+use std::path::{Path, PathBuf};
+use anyhow::Result;
 use data_url::DataUrl;
 
 pub struct SaveScreenshotService {
@@ -23,7 +22,7 @@ impl SaveScreenshotService {
 
         // let engine = GeneralPurpose::new(&alphabet::STANDARD, GeneralPurposeConfig::new());
         // let decoded = engine.decode(data).unwrap();
-        std::fs::write("output.png", &decoded)?;
+        std::fs::write(self.base_path.join("output.png"), &decoded)?;
 
         Ok(())
     }
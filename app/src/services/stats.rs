@@ -0,0 +1,120 @@
+//! Trend fitting shared by the memory, handle and disk histories
+
+use std::time::Duration;
+
+/// Least squares line `y = slope * x + intercept`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Trend {
+    pub slope: f64,
+    pub intercept: f64,
+    pub r_squared: f64,
+}
+
+/// Least squares fit, `None` for fewer than two points or when every x is the same
+pub fn fit_trend(points: &[(f64, f64)]) -> Option<Trend> {
+    if points.len() < 2 {
+        return None;
+    }
+
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+    let covariance: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let variance_x: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let variance_y: f64 = points.iter().map(|(_, y)| (y - mean_y).powi(2)).sum();
+
+    if variance_x == 0.0 {
+        return None;
+    }
+
+    let slope = covariance / variance_x;
+    let intercept = mean_y - slope * mean_x;
+
+    // a flat series is perfectly explained by a flat line
+    let r_squared = if variance_y == 0.0 { 1.0 } else { covariance.powi(2) / (variance_x * variance_y) };
+
+    Some(Trend { slope, intercept, r_squared })
+}
+
+/// Time until `current` reaches `threshold` growing by `per_minute`
+pub fn time_to_threshold(current: u64, threshold: u64, per_minute: f64) -> Option<Duration> {
+    if per_minute <= 0.0 || !per_minute.is_finite() {
+        return None;
+    }
+
+    let remaining = threshold.saturating_sub(current) as f64;
+
+    Some(Duration::from_secs_f64(remaining / per_minute * 60.0))
+}
+
+/// Share of sample to sample deltas that are not negative
+pub fn steady_ratio<T: PartialOrd>(values: &[T]) -> f64 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+
+    let non_decreasing = values.windows(2).filter(|pair| pair[1] >= pair[0]).count();
+
+    non_decreasing as f64 / (values.len() - 1) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fit_trend_recovers_a_line() {
+        let trend = fit_trend(&[(0.0, 10.0), (1.0, 12.0), (2.0, 14.0), (3.0, 16.0)]).unwrap();
+
+        assert!((trend.slope - 2.0).abs() < 1e-9);
+        assert!((trend.intercept - 10.0).abs() < 1e-9);
+        assert!((trend.r_squared - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn fit_trend_of_flat_series_is_flat_and_exact() {
+        let trend = fit_trend(&[(0.0, 5.0), (1.0, 5.0), (2.0, 5.0)]).unwrap();
+
+        assert_eq!(trend, Trend { slope: 0.0, intercept: 5.0, r_squared: 1.0 });
+    }
+
+    #[test]
+    fn fit_trend_of_noisy_series_has_lower_r_squared() {
+        let trend = fit_trend(&[(0.0, 0.0), (1.0, 10.0), (2.0, 0.0), (3.0, 10.0)]).unwrap();
+
+        assert!(trend.slope > 0.0);
+        assert!(trend.r_squared < 0.5);
+    }
+
+    #[test]
+    fn fit_trend_needs_two_distinct_xs() {
+        assert_eq!(fit_trend(&[]), None);
+        assert_eq!(fit_trend(&[(1.0, 1.0)]), None);
+        assert_eq!(fit_trend(&[(1.0, 1.0), (1.0, 5.0)]), None);
+    }
+
+    #[test]
+    fn time_to_threshold_divides_remaining_by_rate() {
+        assert_eq!(time_to_threshold(100, 400, 100.0), Some(Duration::from_secs(180)));
+        // already past the threshold
+        assert_eq!(time_to_threshold(500, 400, 100.0), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn time_to_threshold_is_none_without_growth() {
+        assert_eq!(time_to_threshold(100, 400, 0.0), None);
+        assert_eq!(time_to_threshold(100, 400, -5.0), None);
+        assert_eq!(time_to_threshold(100, 400, f64::NAN), None);
+        assert_eq!(time_to_threshold(100, 400, f64::INFINITY), None);
+    }
+
+    #[test]
+    fn steady_ratio_counts_non_negative_deltas() {
+        assert_eq!(steady_ratio(&[1, 2, 2, 3]), 1.0);
+        assert_eq!(steady_ratio(&[1, 2, 1, 3, 2]), 0.5);
+        assert_eq!(steady_ratio(&[3, 2, 1]), 0.0);
+        assert_eq!(steady_ratio::<u64>(&[]), 0.0);
+        assert_eq!(steady_ratio(&[7]), 0.0);
+    }
+}
@@ -1,11 +1,7 @@
 use std::{ffi::OsString, os::windows::ffi::OsStringExt, path::PathBuf};
 
-use hashbrown::HashMap;
-use log::info;
 use winapi::{shared::minwindef::{DWORD, FALSE, FILETIME, MAX_PATH}, um::{processthreadsapi::{GetProcessTimes, ProcessIdToSessionId}, psapi::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS}, winbase::QueryFullProcessImageNameW}};
 
-use crate::models::{Process, ProcessNode};
-
 pub unsafe fn get_process_image_path(handle: winapi::shared::ntdef::HANDLE, _pid: DWORD) -> Option<String> {
     let mut buf: [u16; MAX_PATH] = [0; MAX_PATH];
     let mut size = buf.len() as u32;
@@ -81,40 +77,3 @@ pub fn filetime_to_u64(ft: &FILETIME) -> u64 {
 pub fn extract_icon_stub(_exe: &str) -> Option<PathBuf> {
     None
 }
-
-pub fn build_process_tree(processes: Vec<Process>) -> Vec<ProcessNode> {
-    use std::collections::HashMap;
-
-    let mut nodes: HashMap<u32, ProcessNode> = HashMap::new();
-    let mut roots = Vec::new();
-
-    for p in processes {
-        nodes.insert(p.id, ProcessNode { process: p, children: Vec::new() });
-    }
-
-    let all_nodes: Vec<ProcessNode> = nodes.values().cloned().collect();
-
-    for node in all_nodes {
-        let ppid = node.process.parent_id;
-        if let Some(parent) = nodes.get_mut(&ppid) {
-            parent.children.push(node);
-        } else {
-            roots.push(node.process.id);
-        }
-    }
-
-    let mut tree: Vec<ProcessNode> = roots.into_iter().filter_map(|pid| nodes.remove(&pid)).collect();
-
-    fn sort_recursive(node: &mut ProcessNode) {
-        node.children.sort_by(|a, b| a.process.name.to_lowercase().cmp(&b.process.name.to_lowercase()));
-        for child in &mut node.children {
-            sort_recursive(child);
-        }
-    }
-
-    for node in &mut tree {
-        sort_recursive(node);
-    }
-
-    tree
-}
\ No newline at end of file
@@ -0,0 +1,186 @@
+/// A parsed WMI object path as found in association classes such as `Win32_LogicalDiskToPartition`
+///
+/// ```text
+/// \\DESKTOP\root\cimv2:Win32_DiskPartition.DeviceID="Disk #0, Partition #1"
+/// Win32_LogicalDisk.DeviceID="C:"
+/// Win32_Service.Name="a\"b",State=4
+/// Win32_OperatingSystem=@
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WmiObjectPath {
+    pub server: Option<String>,
+    pub namespace: Option<String>,
+    pub class: String,
+    /// key properties in declaration order, empty for singletons
+    pub keys: Vec<(String, String)>,
+}
+
+impl WmiObjectPath {
+    pub fn key(&self, name: &str) -> Option<&str> {
+        self.keys
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+pub fn parse_object_path(path: &str) -> Option<WmiObjectPath> {
+    let path = path.trim();
+
+    // the namespace part ends at the first colon outside of the quoted key values
+    let quote = path.find('"').unwrap_or(path.len());
+    let (prefix, relative) = match path[..quote].find(':') {
+        Some(colon) => (Some(&path[..colon]), &path[colon + 1..]),
+        None => (None, path),
+    };
+
+    let (server, namespace) = match prefix {
+        Some(prefix) => match prefix.strip_prefix("\\\\").or_else(|| prefix.strip_prefix("//")) {
+            Some(rest) => {
+                let (server, namespace) = rest.split_once(['\\', '/'])?;
+                (Some(server.to_string()), Some(namespace.to_string()))
+            }
+            None => (None, Some(prefix.to_string())),
+        },
+        None => (None, None),
+    };
+
+    let class_end = relative.find(['.', '=']).unwrap_or(relative.len());
+    let class = relative[..class_end].trim();
+
+    if class.is_empty() {
+        return None;
+    }
+
+    let rest = &relative[class_end..];
+    let keys = if rest.is_empty() || rest == "=@" {
+        vec![]
+    } else {
+        parse_keys(rest.strip_prefix('.')?)?
+    };
+
+    Some(WmiObjectPath {
+        server,
+        namespace,
+        class: class.to_string(),
+        keys,
+    })
+}
+
+/// `Key1="quoted \"value\"",Key2=5`
+fn parse_keys(input: &str) -> Option<Vec<(String, String)>> {
+    let mut keys = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    loop {
+        let mut name = String::new();
+        while let Some(char) = chars.next_if(|char| *char != '=') {
+            name.push(char);
+        }
+
+        chars.next()?; // =
+
+        let mut value = String::new();
+
+        if chars.next_if_eq(&'"').is_some() {
+            loop {
+                match chars.next()? {
+                    '\\' => value.push(chars.next()?),
+                    '"' => break,
+                    char => value.push(char),
+                }
+            }
+        } else {
+            while let Some(char) = chars.next_if(|char| *char != ',') {
+                value.push(char);
+            }
+        }
+
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+
+        keys.push((name.to_string(), value));
+
+        match chars.next() {
+            Some(',') => continue,
+            None => return Some(keys),
+            Some(_) => return None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(class: &str, keys: &[(&str, &str)]) -> WmiObjectPath {
+        WmiObjectPath {
+            server: None,
+            namespace: None,
+            class: class.into(),
+            keys: keys.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect(),
+        }
+    }
+
+    #[test]
+    fn server_and_namespace_are_split_off() {
+        let parsed = parse_object_path(r#"\\DESKTOP\root\cimv2:Win32_DiskPartition.DeviceID="Disk #0, Partition #1""#).unwrap();
+
+        assert_eq!(parsed.server.as_deref(), Some("DESKTOP"));
+        assert_eq!(parsed.namespace.as_deref(), Some(r"root\cimv2"));
+        assert_eq!(parsed.class, "Win32_DiskPartition");
+        assert_eq!(parsed.key("deviceid"), Some("Disk #0, Partition #1"));
+    }
+
+    #[test]
+    fn namespace_without_server() {
+        let parsed = parse_object_path(r#"root\cimv2:Win32_LogicalDisk.DeviceID="C:""#).unwrap();
+
+        assert_eq!(parsed.server, None);
+        assert_eq!(parsed.namespace.as_deref(), Some(r"root\cimv2"));
+        assert_eq!(parsed.key("DeviceID"), Some("C:"));
+    }
+
+    #[test]
+    fn relative_path() {
+        assert_eq!(parse_object_path(r#"Win32_LogicalDisk.DeviceID="C:""#), Some(path("Win32_LogicalDisk", &[("DeviceID", "C:")])));
+    }
+
+    #[test]
+    fn escaped_quotes_and_backslashes() {
+        let parsed = parse_object_path(r#"Win32_Directory.Name="C:\\Program Files\\\"x\"""#).unwrap();
+
+        assert_eq!(parsed.key("Name"), Some(r#"C:\Program Files\"x""#));
+    }
+
+    #[test]
+    fn multiple_keys_and_unquoted_numbers() {
+        assert_eq!(
+            parse_object_path(r#"Win32_Service.Name="a\"b",State=4"#),
+            Some(path("Win32_Service", &[("Name", "a\"b"), ("State", "4")]))
+        );
+    }
+
+    #[test]
+    fn singleton() {
+        assert_eq!(parse_object_path("Win32_OperatingSystem=@"), Some(path("Win32_OperatingSystem", &[])));
+    }
+
+    #[test]
+    fn malformed_paths_are_rejected() {
+        for malformed in [
+            "",
+            r#".DeviceID="C:""#,
+            "Win32_LogicalDisk.",
+            r#"Win32_LogicalDisk.DeviceID="C:"#,
+            r#"Win32_LogicalDisk.DeviceID="C:"junk"#,
+            r#"Win32_LogicalDisk.="C:""#,
+            "Win32_LogicalDisk.DeviceID",
+            r#"\\DESKTOP:Win32_LogicalDisk.DeviceID="C:""#,
+        ] {
+            assert_eq!(parse_object_path(malformed), None, "{malformed}");
+        }
+    }
+}
@@ -5,6 +5,8 @@ use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
 use wmi::WMIConnection;
 
+use crate::services::parse_object_path;
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct CpuInfo {
     #[serde(rename = "Name")]
@@ -19,12 +21,41 @@ pub struct CpuInfo {
 pub struct WmiDisk {
     #[serde(rename = "DeviceID")]
     pub device_id: String,
+    #[serde(rename = "Index")]
+    pub index: u32,
     #[serde(rename = "Model")]
     pub model: Option<String>,
     #[serde(rename = "MediaType")]
     pub media_type: Option<String>,
     #[serde(rename = "InterfaceType")]
     pub interface_type: Option<String>,
+    #[serde(rename = "SerialNumber")]
+    pub serial_number: Option<String>,
+    #[serde(rename = "Size")]
+    pub size: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct WmiPartition {
+    #[serde(rename = "DeviceID")]
+    pub device_id: String,
+    #[serde(rename = "DiskIndex")]
+    pub disk_index: u32,
+    #[serde(rename = "Size")]
+    pub size: Option<u64>,
+}
+
+/// `MSFT_Disk` from the storage management namespace, sized in bytes rather than whole cylinders
+#[derive(Debug, Deserialize, Clone)]
+pub struct WmiStorageDisk {
+    /// same as `Win32_DiskDrive.Index`
+    #[serde(rename = "Number")]
+    pub number: u32,
+    #[serde(rename = "Size")]
+    pub size: u64,
+    /// bytes taken by partitions and the partition table
+    #[serde(rename = "AllocatedSize")]
+    pub allocated_size: u64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -35,11 +66,21 @@ struct LogicalDiskToPartition {
     dependent: String,
 }
 
+/// Physical disks, their partitions and the drive letters mounted on them
+#[derive(Debug, Clone, Default)]
+pub struct DiskLayout {
+    pub disks: Vec<WmiDisk>,
+    pub partitions: Vec<WmiPartition>,
+    /// empty where the storage management namespace is missing (before Windows 8)
+    pub storage_disks: Vec<WmiStorageDisk>,
+    /// drive letter such as `C:` to the partition `DeviceID`
+    pub logical_map: HashMap<String, String>,
+}
+
 #[derive(Debug, Clone)]
 struct CacheEntry {
     refreshed_on: Instant,
-    disks: Vec<WmiDisk>,
-    logical_map: HashMap<String, WmiDisk>,
+    layout: DiskLayout,
 }
 
 pub struct WmiService {
@@ -52,8 +93,7 @@ impl WmiService {
         Self {
             cache: Arc::new(RwLock::new(CacheEntry {
                 refreshed_on: Instant::now() - Duration::from_secs(3600),
-                disks: Vec::new(),
-                logical_map: HashMap::new(),
+                layout: DiskLayout::default(),
             })),
             cache_ttl: Duration::from_secs(3600),
         }
@@ -72,63 +112,48 @@ impl WmiService {
         Ok(info)
     } 
 
-    fn fetch_disks() -> Result<Vec<WmiDisk>> {
+    fn fetch_layout() -> Result<DiskLayout> {
         let connection = WMIConnection::new()?;
+
         let disks: Vec<WmiDisk> = connection.raw_query(
-            "SELECT DeviceID, Model, MediaType, InterfaceType FROM Win32_DiskDrive"
+            "SELECT DeviceID, Index, Model, MediaType, InterfaceType, SerialNumber, Size FROM Win32_DiskDrive"
+        )?;
+        let partitions: Vec<WmiPartition> = connection.raw_query(
+            "SELECT DeviceID, DiskIndex, Size FROM Win32_DiskPartition"
         )?;
-        Ok(disks)
-    }
-
-    fn fetch_logical_map(disks: &[WmiDisk]) -> Result<HashMap<String, WmiDisk>> {
-        let connection = WMIConnection::new()?;
         let links: Vec<LogicalDiskToPartition> = connection.raw_query(
             "SELECT Antecedent, Dependent FROM Win32_LogicalDiskToPartition"
         )?;
-
-        let mut logical_map = HashMap::new();
-
-        for link in links {
-            if let Some(disk_id) = link.antecedent.split("Disk #").nth(1) {
-                if let Some(index_str) = disk_id.split(',').next() {
-                    if let Ok(idx) = index_str.parse::<u32>() {
-                        if let Some(drive_letter) = link
-                            .dependent
-                            .split("DeviceID=")
-                            .nth(1)
-                            .map(|s| s.trim_matches('"'))
-                        {
-                            if let Some(disk) = disks.iter().find(|d| d.device_id.ends_with(&idx.to_string())) {
-                                logical_map.insert(drive_letter.to_string(), disk.clone());
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        Ok(logical_map)
+        let storage_disks: Vec<WmiStorageDisk> = WMIConnection::with_namespace_path("root\\Microsoft\\Windows\\Storage")
+            .and_then(|connection| connection.raw_query("SELECT Number, Size, AllocatedSize FROM MSFT_Disk"))
+            .unwrap_or_default();
+
+        Ok(DiskLayout {
+            disks,
+            partitions,
+            storage_disks,
+            logical_map: Self::logical_map(&links),
+        })
     }
 
-    pub fn get_disks(&self) -> Result<Vec<WmiDisk>> {
-        let mut cache = self.cache.write().unwrap();
-        if cache.refreshed_on.elapsed() >= self.cache_ttl {
-            let disks = Self::fetch_disks()?;
-            cache.disks = disks.clone();
-            cache.refreshed_on = Instant::now();
-        }
-        Ok(cache.disks.clone())
+    fn logical_map(links: &[LogicalDiskToPartition]) -> HashMap<String, String> {
+        links
+            .iter()
+            .filter_map(|link| {
+                let partition = parse_object_path(&link.antecedent)?;
+                let logical = parse_object_path(&link.dependent)?;
+
+                Some((logical.key("DeviceID")?.to_string(), partition.key("DeviceID")?.to_string()))
+            })
+            .collect()
     }
 
-    pub fn map_logical_to_physical(&self) -> Result<HashMap<String, WmiDisk>> {
+    pub fn get_disk_layout(&self) -> Result<DiskLayout> {
         let mut cache = self.cache.write().unwrap();
-        if cache.refreshed_on.elapsed() >= self.cache_ttl || cache.logical_map.is_empty() {
-            let disks = Self::fetch_disks()?;
-            let logical_map = Self::fetch_logical_map(&disks)?;
-            cache.disks = disks;
-            cache.logical_map = logical_map;
+        if cache.refreshed_on.elapsed() >= self.cache_ttl || cache.layout.disks.is_empty() {
+            cache.layout = Self::fetch_layout()?;
             cache.refreshed_on = Instant::now();
         }
-        Ok(cache.logical_map.clone())
+        Ok(cache.layout.clone())
     }
 }
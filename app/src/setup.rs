@@ -2,7 +2,7 @@ use std::{error::Error};
 
 use tauri::{App, Manager};
 
-use crate::{context::AppContext, notifier::SetupEndedNotifier, services::SaveScreenshotService, updater::{register_handlers, setup_updater}};
+use crate::{context::AppContext, notifier::SetupEndedNotifier, services::{BenchmarkService, CleanupService, DiskForecastService, DiskService, DiskUsageService, HandleManager, IntegrityService, MemoryLeakService, MemoryService, ProcessMemoryService, SaveScreenshotService}, updater::{register_handlers, setup_updater}};
 
 pub fn setup(app: &mut App) -> Result<(), Box<dyn Error>> {
     
@@ -10,10 +10,23 @@ pub fn setup(app: &mut App) -> Result<(), Box<dyn Error>> {
 
     let context = app_handle.state::<AppContext>();
 
-    let save_screenshot_service = SaveScreenshotService::new(&context.exec_path);
+    let save_screenshot_service = SaveScreenshotService::new(&context.exec_dir);
 
     app_handle.manage(save_screenshot_service);
 
+    let memory_leak_service = MemoryLeakService::new(
+        app_handle.clone(),
+        app_handle.state::<MemoryService>().inner().clone(),
+        app_handle.state::<ProcessMemoryService>().inner().clone(),
+    );
+
+    app_handle.manage(memory_leak_service);
+    app_handle.manage(DiskUsageService::new(app_handle.clone(), &context.exec_dir));
+    app_handle.manage(DiskForecastService::new(app_handle.clone(), app_handle.state::<DiskService>().inner().clone(), &context.exec_dir));
+    app_handle.manage(BenchmarkService::new(app_handle.clone(), app_handle.state::<DiskService>().inner().clone(), &context.exec_dir));
+    app_handle.manage(CleanupService::new(app_handle.state::<HandleManager>().inner().clone(), &context.exec_dir));
+    app_handle.manage(IntegrityService::new(app_handle.clone(), &context.exec_dir));
+
     let notifier = app_handle.state::<SetupEndedNotifier>();
     
     setup_updater(app_handle);
@@ -1,3 +1,4 @@
+#[cfg(windows)]
 pub fn widestr_to_string(ws: &[u16]) -> String {
     let len = ws.iter().position(|&c| c == 0).unwrap_or(ws.len());
     String::from_utf16_lossy(&ws[..len])
@@ -19,4 +20,12 @@ pub fn format_bytes(bytes: u64) -> String {
     } else {
         format!("{} B", bytes)
     }
-}
\ No newline at end of file
+}
+
+pub fn format_mac(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
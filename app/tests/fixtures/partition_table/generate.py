@@ -0,0 +1,126 @@
+#!/usr/bin/env python3
+"""Writes the partition table images used by the partition_table tests.
+
+    python3 generate.py
+
+mbr.img             bootable NTFS, Linux and FAT32 primary partitions
+ebr.img             extended partition with a chain of three logical partitions
+gpt.img             512 byte sectors, EFI system and basic data partitions
+gpt-bad-backup.img  gpt.img with a damaged backup header and backup entry array
+gpt-4k.img          4096 byte sectors
+"""
+
+import struct
+import uuid
+import zlib
+from pathlib import Path
+
+HERE = Path(__file__).parent
+
+DISK_GUID = uuid.UUID("5b7e3a52-6d1c-4b1e-9c3f-1f2e3d4c5b6a")
+EFI_SYSTEM = uuid.UUID("c12a7328-f81f-11d2-ba4b-00a0c93ec93b")
+BASIC_DATA = uuid.UUID("ebd0a0a2-b9e5-4433-87c0-68b6b72699c7")
+LINUX_FS = uuid.UUID("0fc63daf-8483-4772-8e79-3d69d8477de4")
+
+
+def mbr(entries):
+    """`entries` are (bootable, type, first_lba, sectors) tuples"""
+    sector = bytearray(512)
+    for slot, (bootable, kind, first_lba, sectors) in enumerate(entries):
+        struct.pack_into("<B3xB3xII", sector, 446 + slot * 16, 0x80 if bootable else 0, kind, first_lba, sectors)
+    sector[510:512] = b"\x55\xaa"
+    return sector
+
+
+def write_mbr_images():
+    image = bytearray(128 * 512)
+    image[0:512] = mbr([(True, 0x07, 8, 40), (False, 0x83, 48, 40), (False, 0x0B, 88, 40)])
+    (HERE / "mbr.img").write_bytes(image)
+
+    # EBRs at 0, 10 and 20 sectors into the extended partition starting at LBA 40
+    image = bytearray(128 * 512)
+    image[0:512] = mbr([(False, 0x0C, 8, 32), (False, 0x0F, 40, 80)])
+    chain = [(0, 8), (10, 8), (20, 4)]
+    for index, (offset, sectors) in enumerate(chain):
+        link = [(False, 0x05, chain[index + 1][0], 10)] if index + 1 < len(chain) else []
+        ebr = mbr([(False, 0x83, 2, sectors)] + link)
+        start = (40 + offset) * 512
+        image[start:start + 512] = ebr
+    (HERE / "ebr.img").write_bytes(image)
+
+
+def gpt_entries(partitions, count=128):
+    array = bytearray(count * 128)
+    for index, (kind, first_lba, last_lba, attributes, name) in enumerate(partitions):
+        unique = uuid.UUID(int=index + 1)
+        struct.pack_into("<16s16sQQQ", array, index * 128, kind.bytes_le, unique.bytes_le, first_lba, last_lba, attributes)
+        encoded = name.encode("utf-16-le")
+        array[index * 128 + 56:index * 128 + 56 + len(encoded)] = encoded
+    return array
+
+
+def gpt_header(sector_size, my_lba, alternate_lba, first_usable, last_usable, entries_lba, entries):
+    header = bytearray(sector_size)
+    struct.pack_into(
+        "<8sIII4xQQQQ16sQIII",
+        header,
+        0,
+        b"EFI PART",
+        0x00010000,
+        92,
+        0,
+        my_lba,
+        alternate_lba,
+        first_usable,
+        last_usable,
+        DISK_GUID.bytes_le,
+        entries_lba,
+        128,
+        128,
+        zlib.crc32(entries),
+    )
+    struct.pack_into("<I", header, 16, zlib.crc32(header[:92]))
+    return header
+
+
+def gpt_image(sector_size, sectors, partitions):
+    entries = gpt_entries(partitions)
+    entry_sectors = len(entries) // sector_size
+    last_lba = sectors - 1
+    first_usable = 2 + entry_sectors
+    last_usable = last_lba - 1 - entry_sectors
+
+    image = bytearray(sectors * sector_size)
+    image[0:512] = mbr([(False, 0xEE, 1, min(last_lba, 0xFFFFFFFF))])
+
+    def put(lba, data):
+        image[lba * sector_size:lba * sector_size + len(data)] = data
+
+    put(1, gpt_header(sector_size, 1, last_lba, first_usable, last_usable, 2, entries))
+    put(2, entries)
+    put(last_lba - entry_sectors, entries)
+    put(last_lba, gpt_header(sector_size, last_lba, 1, first_usable, last_usable, last_lba - entry_sectors, entries))
+    return image
+
+
+def write_gpt_images():
+    partitions = [
+        (EFI_SYSTEM, 34, 49, 0, "EFI system partition"),
+        (BASIC_DATA, 50, 89, 1 << 63, "data"),
+    ]
+    image = gpt_image(512, 128, partitions)
+    (HERE / "gpt.img").write_bytes(image)
+
+    # flip a byte in the backup header's disk GUID and in the backup entry array
+    damaged = bytearray(image)
+    damaged[127 * 512 + 60] ^= 0xFF
+    damaged[95 * 512 + 32] ^= 0xFF
+    (HERE / "gpt-bad-backup.img").write_bytes(damaged)
+
+    partitions = [(LINUX_FS, 6, 9, 0, "root")]
+    (HERE / "gpt-4k.img").write_bytes(gpt_image(4096, 16, partitions))
+
+
+if __name__ == "__main__":
+    write_mbr_images()
+    write_gpt_images()
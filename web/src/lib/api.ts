@@ -1,5 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { DashboardStats, GetNetTableArgs, GetProcessArgs, GetProgramsArgs, LoadResult, NetTableEntry, Paged, PagedProcessResult, Program, UpdateStatus } from "./types";
+import type { DashboardStats, GeoIpDatabase, GetNetTableAggregateArgs, GetNetTableArgs, NetTableAggregate, GetIpcEndpointsArgs, IpcEndpoint, HandleInfo, DangerousHandle, OpenFileProcess, GetSystemHandlesArgs, ProcessHandleCounts, HandleGrowth, HandleAccessDenied, GetProcessMemoryArgs, ProcessMemory, ProcessMemorySummary, MemoryGrowth, DiskIoStats, ProcessIoStats, ScanProgress, DiskUsageReport, UsageEntry, SavedScanInfo, DiskUsageDiff, FindDuplicatesArgs, DuplicateReport, ResolveDuplicatesArgs, ResolveDuplicatesResult, DiskForecast, PartitionTable, BenchmarkArgs, BenchmarkProgress, BenchmarkRun, BenchmarkComparison, CleanupCategory, CleanupPreview, CleanupResult, IntegrityChange, IntegrityStatus, IntegrityVerification, GetProcessArgs, GetProgramsArgs, LoadResult, Neighbour, Route, RouteLookup, NetTableEntry, NetworkInterface, Paged, PagedProcessResult, Program, UpdateStatus } from "./types";
 import { emit, listen, type UnlistenFn } from "@tauri-apps/api/event";
 import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
 
@@ -14,6 +14,16 @@ export const checkUpdates = (install: boolean): Promise<void> => emit("check-upd
 
 export const onUpdateStatusChange = (handler: (value: UpdateStatus) => void): Promise<UnlistenFn> => listen<UpdateStatus>("on-update", (event) => handler(event.payload));
 
+export const onMemoryLeak = (handler: (value: MemoryGrowth) => void): Promise<UnlistenFn> => listen<MemoryGrowth>("on-memory-leak", (event) => handler(event.payload));
+
+export const onDiskFullForecast = (handler: (value: DiskForecast) => void): Promise<UnlistenFn> => listen<DiskForecast>("on-disk-full-forecast", (event) => handler(event.payload));
+
+export const onDiskUsageProgress = (handler: (value: ScanProgress) => void): Promise<UnlistenFn> => listen<ScanProgress>("on-disk-usage-progress", (event) => handler(event.payload));
+
+export const onBenchmarkProgress = (handler: (value: BenchmarkProgress) => void): Promise<UnlistenFn> => listen<BenchmarkProgress>("on-benchmark-progress", (event) => handler(event.payload));
+
+export const onIntegrityChange = (handler: (value: IntegrityChange) => void): Promise<UnlistenFn> => listen<IntegrityChange>("on-integrity-change", (event) => handler(event.payload));
+
 export const onScreenshotRequest = (handler: () => void): Promise<UnlistenFn> => listen<UpdateStatus>("on-screenshot", (event) => handler());
 
 export const getProcesses = (args: GetProcessArgs): Promise<PagedProcessResult> => invoke("get_processes", { args });
@@ -30,10 +40,104 @@ export const getLoadedDrivers = (): Promise<any> => invoke("get_loaded_drivers")
 
 export const getMemoryInfo = (): Promise<any> => invoke("get_memory_info");
 
-export const getSystemHandles = (): Promise<any> => invoke("get_system_handles");
+export const getProcessMemory = (args: GetProcessMemoryArgs): Promise<Paged<ProcessMemory>> => invoke("get_process_memory", { args });
+
+export const getProcessMemorySummary = (top?: number): Promise<ProcessMemorySummary> => invoke("get_process_memory_summary", { top });
+
+export const getMemoryGrowth = (windowMinutes?: number, limit?: number): Promise<MemoryGrowth[]> => invoke("get_memory_growth", { windowMinutes, limit });
+
+export const setMemoryLeakNotifications = (enabled: boolean): Promise<void> => invoke("set_memory_leak_notifications", { enabled });
+
+export const getDiskIo = (): Promise<DiskIoStats[]> => invoke("get_disk_io");
+
+export const getProcessIo = (limit?: number): Promise<ProcessIoStats[]> => invoke("get_process_io", { limit });
+
+export const startDiskUsageScan = (root: string): Promise<string> => invoke("start_disk_usage_scan", { root });
+
+export const cancelDiskUsageScan = (scanId: string): Promise<void> => invoke("cancel_disk_usage_scan", { scanId });
+
+export const getDiskUsageReport = (scanId: string): Promise<DiskUsageReport> => invoke("get_disk_usage_report", { scanId });
+
+export const getDiskUsageChildren = (scanId: string, path: string): Promise<UsageEntry[]> => invoke("get_disk_usage_children", { scanId, path });
+
+export const getDiskUsageScans = (): Promise<SavedScanInfo[]> => invoke("get_disk_usage_scans");
+
+export const diffDiskUsageScans = (previousScanId: string, currentScanId: string, limit?: number): Promise<DiskUsageDiff> => invoke("diff_disk_usage_scans", { previousScanId, currentScanId, limit });
+
+export const deleteDiskUsageScan = (scanId: string): Promise<void> => invoke("delete_disk_usage_scan", { scanId });
+
+export const findDuplicateFiles = (args: FindDuplicatesArgs): Promise<DuplicateReport> => invoke("find_duplicate_files", { args });
+
+export const resolveDuplicateFiles = (args: ResolveDuplicatesArgs): Promise<ResolveDuplicatesResult> => invoke("resolve_duplicate_files", { args });
+
+export const getDiskForecasts = (): Promise<DiskForecast[]> => invoke("get_disk_forecasts");
+
+export const setDiskForecastNotifications = (enabled: boolean, horizonDays?: number): Promise<void> => invoke("set_disk_forecast_notifications", { enabled, horizonDays });
+
+export const getPartitionTable = (path: string): Promise<PartitionTable> => invoke("get_partition_table", { path });
+
+export const startBenchmark = (args: BenchmarkArgs): Promise<string> => invoke("start_benchmark", { args });
+
+export const cancelBenchmark = (runId: string): Promise<void> => invoke("cancel_benchmark", { runId });
+
+export const getBenchmarkRun = (runId: string): Promise<BenchmarkRun> => invoke("get_benchmark_run", { runId });
+
+export const getBenchmarkRuns = (): Promise<BenchmarkRun[]> => invoke("get_benchmark_runs");
+
+export const compareBenchmarkRuns = (previousRunId: string, currentRunId: string): Promise<BenchmarkComparison> => invoke("compare_benchmark_runs", { previousRunId, currentRunId });
+
+export const deleteBenchmarkRun = (runId: string): Promise<void> => invoke("delete_benchmark_run", { runId });
+
+export const getCleanupCategories = (): Promise<CleanupCategory[]> => invoke("get_cleanup_categories");
+
+export const previewCleanup = (categories: string[], limit?: number): Promise<CleanupPreview> => invoke("preview_cleanup", { categories, limit });
+
+export const runCleanup = (categories: string[]): Promise<CleanupResult> => invoke("run_cleanup", { categories });
+
+export const getIntegrityStatus = (): Promise<IntegrityStatus> => invoke("get_integrity_status");
+
+export const getDefaultIntegrityPaths = (): Promise<string[]> => invoke("get_default_integrity_paths");
+
+export const createIntegrityBaseline = (paths: string[]): Promise<IntegrityStatus> => invoke("create_integrity_baseline", { paths });
+
+export const deleteIntegrityBaseline = (): Promise<void> => invoke("delete_integrity_baseline");
+
+export const verifyIntegrity = (): Promise<IntegrityVerification> => invoke("verify_integrity");
+
+export const getIntegrityChanges = (limit?: number): Promise<IntegrityChange[]> => invoke("get_integrity_changes", { limit });
+
+export const clearIntegrityChanges = (): Promise<void> => invoke("clear_integrity_changes");
+
+export const getSystemHandles = (args: GetSystemHandlesArgs): Promise<Paged<HandleInfo>> => invoke("get_system_handles", { args });
+
+export const findOpenHandles = (query: string): Promise<OpenFileProcess[]> => invoke("find_open_handles", { query });
+
+export const getDangerousHandles = (): Promise<DangerousHandle[]> => invoke("get_dangerous_handles");
+
+export const getHandleCounts = (): Promise<ProcessHandleCounts[]> => invoke("get_handle_counts");
+
+export const getHandleTopGrowers = (windowMinutes?: number, limit?: number): Promise<HandleGrowth[]> => invoke("get_handle_top_growers", { windowMinutes, limit });
+
+export const getHandleAccessDenied = (): Promise<HandleAccessDenied[]> => invoke("get_handle_access_denied");
 
 export const getNetTable = (args: GetNetTableArgs): Promise<Paged<NetTableEntry>> => invoke("get_net_table", { args });
 
+export const getNetTableAggregate = (args: GetNetTableAggregateArgs): Promise<NetTableAggregate> => invoke("get_net_table_aggregate", { args });
+
+export const getNetworkInterfaces = (): Promise<NetworkInterface[]> => invoke("get_network_interfaces");
+
+export const getGeoIpDatabases = (): Promise<GeoIpDatabase[]> => invoke("get_geoip_databases");
+
+export const setGeoIpDatabases = (paths: string[]): Promise<GeoIpDatabase[]> => invoke("set_geoip_databases", { paths });
+
+export const getRoutes = (): Promise<Route[]> => invoke("get_routes");
+
+export const getNeighbours = (): Promise<Neighbour[]> => invoke("get_neighbours");
+
+export const lookupRoute = (destination: string): Promise<RouteLookup> => invoke("lookup_route", { destination });
+
+export const getIpcEndpoints = (args: GetIpcEndpointsArgs): Promise<Paged<IpcEndpoint>> => invoke("get_ipc_endpoints", { args });
+
 export const saveScreenshot = (dataUrl: string): Promise<never> => invoke("save_screenshot", { dataUrl });
 
 export const requestScreenshot = (): Promise<void> => emit("on-screenshot");
@@ -15,6 +15,14 @@ export interface DiskPartition {
 export type DiskInfo = {
     model?: string;
     diskType?: string;
+    serial?: string;
+    interfaceType?: string;
+    size?: number;
+    sizeFormatted?: string;
+    /** disk size not covered by any partition */
+    unpartitioned?: number;
+    unpartitionedFormatted?: string;
+    removable?: boolean;
     partitions: DiskPartition[];
 };
 
@@ -32,6 +40,28 @@ export interface MemoryInfo {
     availVirtual: number;
     availVirtualFormatted: string;
     memoryLoad: number; 
+    /** file cache, standby list on Windows */
+    cached: number | null;
+    cachedFormatted: string | null;
+    buffers: number | null;
+    buffersFormatted: string | null;
+    committed: number | null;
+    committedFormatted: string | null;
+    commitLimit: number | null;
+    commitLimitFormatted: string | null;
+    swapUsed: number | null;
+    swapUsedFormatted: string | null;
+    hugePagesTotal: number | null;
+    hugePagesTotalFormatted: string | null;
+    hugePagesFree: number | null;
+    hugePagesFreeFormatted: string | null;
+    pagedPool: number | null;
+    pagedPoolFormatted: string | null;
+    nonPagedPool: number | null;
+    nonPagedPoolFormatted: string | null;
+    /** compressed store on Windows, zswap pool on Linux */
+    compressed: number | null;
+    compressedFormatted: string | null;
 }
 
 export interface DashboardStats {
@@ -39,6 +69,43 @@ export interface DashboardStats {
     activeProcesses: number;
     memory: MemoryInfo;
     disks: DiskInfo[];
+    diskIo: DiskIoStats[];
+    network: NetworkThroughput;
+}
+
+export interface NetworkThroughput {
+    rxBytesPerSec: number;
+    rxFormatted: string;
+    txBytesPerSec: number;
+    txFormatted: string;
+}
+
+export type LinkState = "up" | "down" | "testing" | "dormant" | "notPresent" | "lowerLayerDown" | "unknown";
+
+export interface InterfaceCounters {
+    rxBytes: number;
+    txBytes: number;
+    rxErrors: number;
+    txErrors: number;
+    rxDropped: number;
+    txDropped: number;
+}
+
+export interface NetworkInterface {
+    name: string;
+    description: string | null;
+    macAddress: string | null;
+    mtu: number | null;
+    linkState: LinkState;
+    speedMbps: number | null;
+    isLoopback: boolean;
+    addresses: string[];
+    gateways: string[];
+    dnsServers: string[];
+    traffic: {
+        total: InterfaceCounters;
+        perSecond: InterfaceCounters;
+    };
 }
 
 export interface GetProcessArgs extends PageArgs {
@@ -50,13 +117,95 @@ export interface GetProgramsArgs extends PageArgs {
     
 }
 
-export interface GetNetTableArgs extends PageArgs {
+export type TcpState = "closed" | "listening" | "synSent" | "synReceived" | "established"
+    | "finWait1" | "finWait2" | "closeWait" | "closing" | "lastAck" | "timeWait" | "deleteTcb";
+
+export type PortFilter = number | { start: number; end: number; };
+
+export interface NetTableFilter {
+    processName?: string | null;
+    processIds?: number[] | null;
+    localPort?: PortFilter | null;
+    remotePort?: PortFilter | null;
+    /** single address or CIDR block, e.g. `10.0.0.0/8` */
+    localIpAddr?: string | null;
+    remoteIpAddr?: string | null;
+    states?: TcpState[] | null;
+    loopback?: boolean | null;
+    /** ISO country codes of the remote address */
+    countries?: string[] | null;
+    asns?: number[] | null;
+}
+
+export interface GeoInfo {
+    countryCode: string | null;
+    country: string | null;
+    city: string | null;
+    asn: number | null;
+    organization: string | null;
+}
+
+export interface GeoIpDatabase {
+    path: string;
+    databaseType: string;
+    buildEpoch: number;
+}
+
+export type NetTableColumn = "processId" | "processName" | "protocol" | "localIpAddress"
+    | "localPort" | "remoteIpAddress" | "remotePort" | "state";
+
+export interface GetNetTableArgs extends PageArgs, NetTableFilter {
     protocols: Array<"tcp" | "udp">;
-    processName: string | null;
-    localPort: number | null;
-    remotePort: number | null;
-    localIpAddr: string | null;
-    remoteIpAddr: string | null;
+    exclude?: NetTableFilter | null;
+    sort?: { column: NetTableColumn; descending: boolean; } | null;
+    resolveHostNames?: boolean;
+}
+
+export type NetTableGroupBy = "process" | "remoteAddress" | "remotePort" | "state" | "country" | "asn";
+
+export interface GetNetTableAggregateArgs extends NetTableFilter {
+    protocols: Array<"tcp" | "udp">;
+    exclude?: NetTableFilter | null;
+    groupBy: NetTableGroupBy;
+}
+
+export interface ConnectionGroup {
+    key: string;
+    label: string;
+    count: number;
+    processCount: number;
+    states: Partial<Record<TcpState, number>>;
+    /** sum over the connections that have one, missing when none has */
+    throughput: NetworkThroughput | null;
+}
+
+export type ConnectionNode = {
+    type: "process";
+    id: string;
+    processId: number;
+    processName: string;
+} | {
+    type: "endpoint";
+    id: string;
+    ipAddress: string;
+    port: number | null;
+    geo: GeoInfo | null;
+}
+
+export interface ConnectionEdge {
+    source: string;
+    target: string;
+    weight: number;
+    states: Partial<Record<TcpState, number>>;
+    throughput: NetworkThroughput | null;
+}
+
+export interface NetTableAggregate {
+    groups: ConnectionGroup[];
+    graph: {
+        nodes: ConnectionNode[];
+        edges: ConnectionEdge[];
+    };
 }
 
 export type PagedProcessResult = {
@@ -109,4 +258,587 @@ export interface ProcessNode {
     children: ProcessNode[];
 }
 
-export * from "./updater";
\ No newline at end of file
+export * from "./updater";
+export interface Route {
+    /** CIDR, e.g. `0.0.0.0/0` */
+    destination: string;
+    gateway: string | null;
+    interface: string;
+    metric: number;
+    protocol: string | null;
+}
+
+export type NeighbourState = "unreachable" | "incomplete" | "probe" | "delay" | "stale" | "reachable" | "permanent" | "unknown";
+
+export interface Neighbour {
+    ipAddress: string;
+    macAddress: string | null;
+    interface: string;
+    state: NeighbourState;
+}
+
+export interface RouteLookup {
+    destination: string;
+    route: Route | null;
+    nextHop: Neighbour | null;
+}
+
+export type IpcKind = "unixSocket" | "namedPipe";
+
+export type IpcState = "listening" | "connected" | "connecting" | "disconnecting" | "unconnected" | "unknown";
+
+export interface IpcPeer {
+    inode: number | null;
+    processId: number | null;
+    processName: string | null;
+}
+
+export interface IpcEndpoint {
+    kind: IpcKind;
+    /** socket path (`@` prefixed when abstract) or pipe name */
+    path: string | null;
+    socketType: string | null;
+    state: IpcState;
+    inode: number | null;
+    processId: number | null;
+    processName: string | null;
+    peers: IpcPeer[];
+    instances: number | null;
+    maxInstances: number | null;
+}
+
+export interface IpcFilter {
+    kinds?: IpcKind[];
+    path?: string;
+    processName?: string;
+    processIds?: number[];
+    states?: IpcState[];
+    connectedOnly?: boolean;
+}
+
+export interface GetIpcEndpointsArgs extends PageArgs, IpcFilter {}
+
+export interface HandleInfo {
+    processId: number;
+    processName: string;
+    handle: number;
+    objectType: number;
+    /** File, Key, Event, Section... */
+    typeName: string | null;
+    /** object name, DOS path for files */
+    objectName: string | null;
+    grantedAccess: number;
+    /** `grantedAccess` decoded for the object type, e.g. `PROCESS_VM_WRITE` */
+    accessRights: string[];
+    /** process a Process or Thread handle refers to */
+    targetProcessId: number | null;
+    /** `st_mode` of the open object on Linux, where `grantedAccess` holds the open flags */
+    fileMode: number | null;
+}
+
+export interface HandleAccessDenied {
+    processId: number;
+    processName: string;
+    reason: string;
+}
+
+export interface DangerousHandle {
+    handle: HandleInfo;
+    targetProcessName: string | null;
+    rights: string[];
+}
+
+export interface FileAccess {
+    read: boolean;
+    write: boolean;
+    append: boolean;
+    delete: boolean;
+}
+
+export interface OpenFileHandle {
+    /** handle value on Windows, descriptor number on Linux, `null` for the working directory */
+    handle: number | null;
+    path: string;
+    access: FileAccess;
+    isDirectory: boolean;
+}
+
+export interface OpenFileProcess {
+    processId: number;
+    processName: string;
+    handles: OpenFileHandle[];
+}
+
+export interface HandleFilter {
+    processIds?: number[];
+    processName?: string;
+    typeNames?: string[];
+    objectName?: string;
+    /** any of the decoded rights, e.g. `PROCESS_VM_WRITE` */
+    accessRights?: string[];
+    /** all of the bits */
+    accessMask?: number;
+    dangerousOnly?: boolean;
+}
+
+export interface GetSystemHandlesArgs extends PageArgs, HandleFilter {}
+
+export interface ProcessHandleCounts {
+    processId: number;
+    processName: string;
+    total: number;
+    byType: Record<string, number>;
+}
+
+export interface HandleCountPoint {
+    timestamp: string;
+    count: number;
+}
+
+export interface HandleTypeGrowth {
+    typeName: string;
+    firstCount: number;
+    lastCount: number;
+    growth: number;
+}
+
+export interface HandleGrowth {
+    processId: number;
+    processName: string;
+    firstCount: number;
+    lastCount: number;
+    growth: number;
+    slopePerMinute: number;
+    suspectedLeak: boolean;
+    growingTypes: HandleTypeGrowth[];
+    samples: HandleCountPoint[];
+}
+
+export interface ProcessMemory {
+    processId: number;
+    processName: string;
+    workingSet: number | null;
+    /** PrivateUsage on Windows, private resident pages on Linux */
+    privateBytes: number | null;
+    sharedBytes: number | null;
+    /** Pss on Linux, private working set on Windows */
+    proportionalBytes: number | null;
+    commitCharge: number | null;
+    peakWorkingSet: number | null;
+    pageFaults: number | null;
+}
+
+export type ProcessMemoryColumn = "id" | "name" | "workingSet" | "privateBytes" | "sharedBytes" | "proportionalBytes" | "commitCharge" | "peakWorkingSet" | "pageFaults";
+
+export interface GetProcessMemoryArgs extends PageArgs {
+    name?: string;
+    sort?: { column: ProcessMemoryColumn; descending?: boolean };
+}
+
+export interface MemoryContributor {
+    processId: number;
+    processName: string;
+    bytes: number;
+    bytesFormatted: string;
+    /** percentage of used physical memory */
+    share: number;
+}
+
+export interface ProcessMemorySummary {
+    usedPhys: number;
+    usedPhysFormatted: string;
+    attributed: number;
+    attributedFormatted: string;
+    unattributed: number;
+    unattributedFormatted: string;
+    top: MemoryContributor[];
+}
+
+export interface MemoryPoint {
+    timestamp: string;
+    bytes: number;
+}
+
+export interface MemoryGrowth {
+    processId: number;
+    processName: string;
+    current: number;
+    currentFormatted: string;
+    growth: number;
+    bytesPerMinute: number;
+    bytesPerMinuteFormatted: string;
+    rSquared: number;
+    suspectedLeak: boolean;
+    /** seconds until threshold at the current rate */
+    timeToThreshold: number | null;
+    threshold: number;
+    thresholdFormatted: string;
+    samples: MemoryPoint[];
+}
+
+export interface DiskIoStats {
+    name: string;
+    readBytesPerSec: number;
+    readFormatted: string;
+    writeBytesPerSec: number;
+    writeFormatted: string;
+    readIops: number;
+    writeIops: number;
+    queueDepth: number;
+    /** percent of the interval the disk was busy */
+    utilisation: number;
+}
+
+export interface ProcessIoStats {
+    processId: number;
+    processName: string;
+    readBytesPerSec: number;
+    readFormatted: string;
+    writeBytesPerSec: number;
+    writeFormatted: string;
+    readOpsPerSec: number;
+    writeOpsPerSec: number;
+    readBytes: number;
+    writeBytes: number;
+}
+
+export type ScanState = "running" | "completed" | "cancelled" | "failed";
+
+export interface ScanProgress {
+    scanId: string;
+    root: string;
+    state: ScanState;
+    files: number;
+    directories: number;
+    bytes: number;
+    bytesFormatted: string;
+    currentPath: string | null;
+    error: string | null;
+}
+
+export interface UsageEntry {
+    path: string;
+    bytes: number;
+    bytesFormatted: string;
+    files: number;
+}
+
+export interface ExtensionUsage {
+    extension: string;
+    bytes: number;
+    bytesFormatted: string;
+    files: number;
+}
+
+export interface DiskUsageReport {
+    scanId: string;
+    root: string;
+    state: ScanState;
+    startedOn: string;
+    finishedOn: string;
+    totalBytes: number;
+    totalBytesFormatted: string;
+    totalFiles: number;
+    totalDirectories: number;
+    errors: number;
+    topDirectories: UsageEntry[];
+    topFiles: UsageEntry[];
+    extensions: ExtensionUsage[];
+}
+
+export interface SavedScanInfo {
+    scanId: string;
+    root: string;
+    state: ScanState;
+    finishedOn: string;
+    totalBytes: number;
+    totalBytesFormatted: string;
+}
+
+export interface UsageChange {
+    path: string;
+    before: number | null;
+    after: number | null;
+    delta: number;
+    deltaFormatted: string;
+}
+
+export interface DiskUsageDiff {
+    previousScanId: string;
+    currentScanId: string;
+    totalDelta: number;
+    totalDeltaFormatted: string;
+    changes: UsageChange[];
+}
+
+export interface FindDuplicatesArgs {
+    roots: string[];
+    minSize?: number;
+}
+
+export interface DuplicateGroup {
+    hash: string;
+    size: number;
+    sizeFormatted: string;
+    files: string[];
+    wasted: number;
+    wastedFormatted: string;
+}
+
+export interface DuplicateReport {
+    groups: DuplicateGroup[];
+    filesScanned: number;
+    filesHashed: number;
+    totalWasted: number;
+    totalWastedFormatted: string;
+    errors: number;
+}
+
+export type DuplicateAction = "delete" | "hardLink";
+
+export interface ResolveDuplicatesArgs {
+    action: DuplicateAction;
+    groups: { keep: string; remove: string[] }[];
+    dryRun?: boolean;
+}
+
+export type ActionOutcome = "done" | "wouldDo" | "skipped" | "failed";
+
+export interface DuplicateActionResult {
+    path: string;
+    keep: string;
+    outcome: ActionOutcome;
+    reason: string | null;
+    bytesFreed: number;
+}
+
+export interface ResolveDuplicatesResult {
+    action: DuplicateAction;
+    dryRun: boolean;
+    results: DuplicateActionResult[];
+    bytesFreed: number;
+    bytesFreedFormatted: string;
+}
+
+export interface DiskUsagePoint {
+    timestamp: string;
+    used: number;
+    total: number;
+}
+
+export interface DiskForecast {
+    name: string;
+    total: number;
+    totalFormatted: string;
+    used: number;
+    usedFormatted: string;
+    free: number;
+    freeFormatted: string;
+    bytesPerDay: number;
+    bytesPerDayFormatted: string;
+    rSquared: number;
+    fullOn: string | null;
+    daysLeft: number | null;
+    withinHorizon: boolean;
+    samples: DiskUsagePoint[];
+}
+
+export type PartitionScheme = "mbr" | "gpt";
+
+export interface PartitionTableEntry {
+    number: number;
+    typeId: string;
+    typeName: string | null;
+    uniqueGuid: string | null;
+    firstLba: number;
+    lastLba: number;
+    size: number;
+    sizeFormatted: string;
+    attributes: number;
+    attributeNames: string[];
+    name: string | null;
+    bootable: boolean;
+    logical: boolean;
+}
+
+export interface PartitionTable {
+    scheme: PartitionScheme;
+    sectorSize: number;
+    diskSize: number | null;
+    diskSizeFormatted: string | null;
+    diskGuid: string | null;
+    firstUsableLba: number | null;
+    lastUsableLba: number | null;
+    partitions: PartitionTableEntry[];
+    issues: string[];
+}
+
+export type BenchmarkTest = "sequentialRead" | "sequentialWrite" | "randomRead" | "randomWrite";
+
+export type BenchmarkState = "preparing" | "running" | "completed" | "cancelled" | "failed";
+
+export interface BenchmarkArgs {
+    partition: string;
+    directory?: string;
+    fileSize?: number;
+    queueDepth?: number;
+    durationSecs?: number;
+    tests?: BenchmarkTest[];
+}
+
+export interface BenchmarkProgress {
+    runId: string;
+    partition: string;
+    state: BenchmarkState;
+    test: BenchmarkTest | null;
+    progress: number;
+    bytesPerSec: number;
+    bytesPerSecFormatted: string;
+    error: string | null;
+}
+
+export interface LatencyStats {
+    min: number;
+    mean: number;
+    p50: number;
+    p90: number;
+    p99: number;
+    p999: number;
+    max: number;
+}
+
+export interface BenchmarkResult {
+    test: BenchmarkTest;
+    blockSize: number;
+    queueDepth: number;
+    operations: number;
+    bytes: number;
+    durationMs: number;
+    bytesPerSec: number;
+    bytesPerSecFormatted: string;
+    iops: number;
+    latency: LatencyStats;
+}
+
+export interface BenchmarkRun {
+    runId: string;
+    partition: string;
+    state: BenchmarkState;
+    startedOn: string;
+    finishedOn: string;
+    fileSize: number;
+    fileSizeFormatted: string;
+    queueDepth: number;
+    durationSecs: number;
+    cacheBypassed: boolean;
+    results: BenchmarkResult[];
+    error: string | null;
+}
+
+export interface BenchmarkChange {
+    test: BenchmarkTest;
+    previousBytesPerSec: number;
+    currentBytesPerSec: number;
+    throughputChange: number;
+    previousIops: number;
+    currentIops: number;
+    previousP99: number;
+    currentP99: number;
+}
+
+export interface BenchmarkComparison {
+    previousRunId: string;
+    currentRunId: string;
+    changes: BenchmarkChange[];
+}
+
+export interface CleanupCategory {
+    id: string;
+    name: string;
+    description: string;
+    platforms: string[];
+    patterns: string[];
+    exclude: string[];
+    minAgeDays: number;
+    selected: boolean;
+}
+
+export interface CleanupEntry {
+    path: string;
+    size: number;
+    sizeFormatted: string;
+    modifiedOn: string | null;
+}
+
+export interface CleanupCategoryPreview {
+    id: string;
+    name: string;
+    files: number;
+    bytes: number;
+    bytesFormatted: string;
+    entries: CleanupEntry[];
+    inUse: string[];
+}
+
+export interface CleanupPreview {
+    categories: CleanupCategoryPreview[];
+    totalBytes: number;
+    totalBytesFormatted: string;
+}
+
+export interface CleanupFailure {
+    path: string;
+    error: string;
+}
+
+export interface CleanupCategoryResult {
+    id: string;
+    name: string;
+    deleted: number;
+    bytesFreed: number;
+    bytesFreedFormatted: string;
+    inUse: string[];
+    failed: CleanupFailure[];
+}
+
+export interface CleanupResult {
+    categories: CleanupCategoryResult[];
+    deleted: number;
+    bytesFreed: number;
+    bytesFreedFormatted: string;
+}
+
+export interface IntegrityEntry {
+    path: string;
+    size: number;
+    modifiedOn: string | null;
+    hash: string | null;
+    permissions: number;
+    securityHash: string | null;
+}
+
+export type IntegrityChangeKind = "added" | "modified" | "deleted" | "permissionsChanged";
+
+export interface IntegrityChange {
+    path: string;
+    kind: IntegrityChangeKind;
+    detectedOn: string;
+    beforeHash: string | null;
+    afterHash: string | null;
+    before: IntegrityEntry | null;
+    after: IntegrityEntry | null;
+}
+
+export interface IntegrityStatus {
+    roots: string[];
+    files: number;
+    createdOn: string | null;
+    watching: boolean;
+    changes: number;
+}
+
+export interface IntegrityVerification {
+    verifiedOn: string;
+    filesChecked: number;
+    changes: IntegrityChange[];
+}